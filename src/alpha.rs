@@ -0,0 +1,198 @@
+// File: src\alpha.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Alpha channel extraction, replacement and flattening
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::{imageops, DynamicImage, GenericImageView, ImageBuffer, ImageFormat, Luma, Rgb32FImage, Rgba, RgbaImage};
+use std::path::PathBuf;
+
+use crate::color::Color;
+use crate::draw::{blend, is_high_depth, restore_color_type, with_alpha};
+use crate::encode::{save_image, supports_16bit, supports_alpha};
+use crate::i18n::tr;
+use crate::{detect_format_from_path, load_image, print_info, print_success, print_warning};
+
+#[derive(Args, Debug)]
+pub struct AlphaArgs {
+    /// Save the alpha channel as a grayscale image
+    #[arg(long, value_name = "FILE")]
+    pub extract_alpha: Option<PathBuf>,
+
+    /// Replace the alpha channel with a grayscale mask (white = opaque)
+    #[arg(long, value_name = "FILE")]
+    pub apply_alpha: Option<PathBuf>,
+}
+
+impl AlphaArgs {
+    /// Whether an alpha mask is saved or applied
+    pub fn is_active(&self) -> bool {
+        self.extract_alpha.is_some() || self.apply_alpha.is_some()
+    }
+}
+
+/// Set the image's alpha from `--apply-alpha`, resizing the mask when needed
+pub fn apply_mask(img: DynamicImage, args: &AlphaArgs) -> Result<DynamicImage> {
+    let Some(mask_path) = &args.apply_alpha else {
+        return Ok(img);
+    };
+
+    let (mask, _) = load_image(mask_path)?;
+    let (width, height) = img.dimensions();
+    let mut mask = mask.to_luma16();
+    if mask.dimensions() != (width, height) {
+        print_info(&tr!(
+            "Resizing alpha mask from {}x{} to {}x{}",
+            mask.width(), mask.height(), width, height
+        ));
+        mask = imageops::resize(&mask, width, height, imageops::FilterType::Triangle);
+    }
+
+    let mut canvas = img.to_rgba16();
+    for (pixel, value) in canvas.pixels_mut().zip(mask.pixels()) {
+        pixel.0[3] = value.0[0];
+    }
+    Ok(restore_color_type(with_alpha(img.color()), canvas))
+}
+
+/// Write the alpha channel to `--extract-alpha` (opaque images give a white mask)
+pub fn extract(img: &DynamicImage, args: &AlphaArgs) -> Result<()> {
+    let Some(path) = &args.extract_alpha else {
+        return Ok(());
+    };
+
+    if !img.color().has_alpha() {
+        print_warning(&tr!("Image has no alpha channel, extracted mask is fully opaque"));
+    }
+    // Read at 16 bits so deeper sources keep every alpha level
+    let luma_alpha = img.to_luma_alpha16();
+    let mask = ImageBuffer::from_fn(luma_alpha.width(), luma_alpha.height(), |x, y| Luma([luma_alpha.get_pixel(x, y).0[1]]));
+    let mask = DynamicImage::ImageLuma16(mask);
+
+    let format = detect_format_from_path(path).unwrap_or(ImageFormat::Png);
+    let mask = match is_high_depth(img.color()) && supports_16bit(format) {
+        true => mask,
+        false => DynamicImage::ImageLuma8(mask.to_luma8()),
+    };
+    save_image(&mask, format, 100, path)?;
+    print_success(&tr!("Alpha channel saved to: {}", path.display()));
+    Ok(())
+}
+
+/// Composite onto `background` when `format` cannot store the image's transparency
+pub fn flatten_for_format(img: DynamicImage, format: ImageFormat, background: Color) -> DynamicImage {
+    if !img.color().has_alpha() || supports_alpha(format) {
+        return img;
+    }
+
+    let mut canvas = img.to_rgba8();
+    if canvas.pixels().all(|p| p.0[3] == 255) {
+        // Fully opaque, the encoder simply drops the channel
+        return img;
+    }
+
+    print_warning(&tr!(
+        "{} has no alpha channel, flattening transparency onto {}",
+        format!("{:?}", format), background
+    ));
+    if is_high_depth(img.color()) {
+        return flatten_high_depth(&img, background);
+    }
+    flatten_onto(&mut canvas, background);
+
+    let flattened = DynamicImage::ImageRgba8(canvas);
+    if img.color().has_color() {
+        DynamicImage::ImageRgb8(flattened.to_rgb8())
+    } else {
+        DynamicImage::ImageLuma8(flattened.to_luma8())
+    }
+}
+
+/// Flatten in floating point so 16-bit sources stay 16-bit
+fn flatten_high_depth(img: &DynamicImage, background: Color) -> DynamicImage {
+    let base = background.0 .0.map(|c| c as f32 / 255.0);
+    let mut rgb = Rgb32FImage::new(img.width(), img.height());
+    for (src, dst) in img.to_rgba32f().pixels().zip(rgb.pixels_mut()) {
+        let [r, g, b, a] = src.0;
+        dst.0 = [r * a + base[0] * (1.0 - a), g * a + base[1] * (1.0 - a), b * a + base[2] * (1.0 - a)];
+    }
+
+    let flattened = DynamicImage::ImageRgb32F(rgb);
+    if img.color().has_color() {
+        DynamicImage::ImageRgb16(flattened.to_rgb16())
+    } else {
+        DynamicImage::ImageLuma16(flattened.to_luma16())
+    }
+}
+
+/// Composite every pixel onto an opaque background color
+pub fn flatten_onto(canvas: &mut RgbaImage, background: Color) {
+    let base = Rgba([background.0[0], background.0[1], background.0[2], 255]);
+    for pixel in canvas.pixels_mut() {
+        let mut composite = base;
+        blend(&mut composite, *pixel, 1.0);
+        *pixel = composite;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_mask_replaces_alpha() {
+        let dir = std::env::temp_dir().join(format!("imgconv-alpha-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mask_path = dir.join("mask.png");
+        image::GrayImage::from_pixel(2, 2, Luma([64])).save(&mask_path).unwrap();
+
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(4, 4));
+        let args = AlphaArgs { extract_alpha: None, apply_alpha: Some(mask_path) };
+        let masked = apply_mask(img, &args).unwrap();
+        assert_eq!(masked.color(), image::ColorType::Rgba8);
+        assert_eq!(masked.to_rgba8().get_pixel(3, 3).0[3], 64);
+
+        // 16-bit color stays 16-bit rather than being cut down to 8 bits
+        let deep = DynamicImage::ImageRgb16(image::ImageBuffer::from_pixel(4, 4, image::Rgb([1000u16, 2000, 3000])));
+        let masked = apply_mask(deep, &args).unwrap();
+        assert_eq!(masked.color(), image::ColorType::Rgba16);
+        assert_eq!(masked.to_rgba16().get_pixel(3, 3).0, [1000, 2000, 3000, 64 * 257]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_keeps_16_bit_alpha() {
+        let dir = std::env::temp_dir().join(format!("imgconv-alpha-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let img = DynamicImage::ImageRgba16(image::ImageBuffer::from_pixel(2, 2, Rgba([0, 0, 0, 300])));
+        let args = AlphaArgs { extract_alpha: Some(dir.join("mask.png")), apply_alpha: None };
+        extract(&img, &args).unwrap();
+        assert_eq!(image::open(dir.join("mask.png")).unwrap().to_luma16().get_pixel(0, 0).0, [300]);
+
+        let args = AlphaArgs { extract_alpha: Some(dir.join("mask8.png")), apply_alpha: None };
+        extract(&DynamicImage::ImageRgba8(RgbaImage::from_pixel(2, 2, Rgba([0, 0, 0, 77]))), &args).unwrap();
+        let mask = image::open(dir.join("mask8.png")).unwrap();
+        assert_eq!((mask.color(), mask.to_luma8().get_pixel(0, 0).0), (image::ColorType::L8, [77]));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_flatten_onto() {
+        let mut canvas = RgbaImage::from_pixel(1, 1, Rgba([0, 0, 0, 0]));
+        flatten_onto(&mut canvas, Color(Rgba([255, 255, 255, 255])));
+        assert_eq!(canvas.get_pixel(0, 0), &Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_flatten_for_format() {
+        let half = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([0, 0, 0, 128])));
+        let flat = flatten_for_format(half.clone(), ImageFormat::Jpeg, Color(Rgba([255, 255, 255, 255])));
+        assert_eq!(flat.to_rgb8().get_pixel(0, 0).0, [127, 127, 127]);
+
+        let kept = flatten_for_format(half, ImageFormat::Png, Color(Rgba([255, 255, 255, 255])));
+        assert!(kept.color().has_alpha());
+    }
+}
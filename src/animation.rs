@@ -0,0 +1,66 @@
+// File: src\animation.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Detect animated GIF/WebP/APNG input, whose frames after the first are dropped
+// License: MIT
+
+use clap::Args;
+use image::codecs::{gif::GifDecoder, png::PngDecoder, webp::WebPDecoder};
+use image::{AnimationDecoder, ImageFormat};
+use std::io::{BufRead, Seek};
+use std::path::Path;
+
+use crate::i18n::tr;
+use crate::print_warning;
+
+#[derive(Args, Debug)]
+pub struct AnimationArgs {
+    /// Convert only the first frame of animated input without warning about it
+    #[arg(long)]
+    pub first_frame: bool,
+}
+
+/// Whether `reader` holds an animation with more than one frame
+pub fn is_animated<R: BufRead + Seek>(reader: R, format: Option<ImageFormat>) -> bool {
+    match format {
+        Some(ImageFormat::Gif) => GifDecoder::new(reader).is_ok_and(|decoder| decoder.into_frames().take(2).count() > 1),
+        Some(ImageFormat::WebP) => WebPDecoder::new(reader).is_ok_and(|decoder| decoder.has_animation()),
+        Some(ImageFormat::Png) => PngDecoder::new(reader).is_ok_and(|decoder| decoder.is_apng().unwrap_or(false)),
+        _ => false,
+    }
+}
+
+/// Warn that only the first frame of animated `source` is converted, unless
+/// --first-frame says that is intended
+pub fn check<R: BufRead + Seek>(source: &Path, reader: R, format: Option<ImageFormat>, args: &AnimationArgs) {
+    if !args.first_frame && is_animated(reader, format) {
+        print_warning(&tr!(
+            "{} is animated: only its first frame is converted, the rest are DROPPED (--first-frame to silence this)",
+            source.display()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::gif::GifEncoder;
+    use image::{Frame, RgbaImage};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_detects_animated_gif() {
+        let gif = |frames: usize| {
+            let mut data = Vec::new();
+            let mut encoder = GifEncoder::new(&mut data);
+            for _ in 0..frames {
+                encoder.encode_frame(Frame::new(RgbaImage::new(4, 4))).unwrap();
+            }
+            drop(encoder);
+            data
+        };
+        assert!(is_animated(Cursor::new(gif(3)), Some(ImageFormat::Gif)));
+        assert!(!is_animated(Cursor::new(gif(1)), Some(ImageFormat::Gif)));
+        assert!(!is_animated(Cursor::new(gif(3)), Some(ImageFormat::Png)));
+    }
+}
@@ -0,0 +1,203 @@
+// File: src\annotate.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Text annotation / caption rendering during conversion
+// License: MIT
+
+use ab_glyph::{point, Font, FontVec, PxScale, ScaleFont};
+use anyhow::{Context, Result};
+use clap::Args;
+use image::{DynamicImage, ImageBuffer, Pixel, Rgba};
+use std::path::{Path, PathBuf};
+
+use crate::color::Color;
+use crate::draw::{blend_at, restore_color_type, wide, Sample};
+use crate::geometry::Gravity;
+
+#[derive(Args, Debug)]
+pub struct TextArgs {
+    /// Draw text onto the image (use \n for multiple lines)
+    #[arg(long, value_name = "TEXT", requires = "font")]
+    pub text: Option<String>,
+
+    /// TrueType/OpenType font used for --text
+    #[arg(long, value_name = "FILE")]
+    pub font: Option<PathBuf>,
+
+    /// Font size in pixels
+    #[arg(long, default_value = "24", value_name = "PX")]
+    pub font_size: f32,
+
+    /// Text color (#rgb, #rrggbb, #rrggbbaa)
+    #[arg(long, default_value = "#ffffff", value_name = "COLOR")]
+    pub text_color: Color,
+
+    /// Where to anchor the text
+    #[arg(long, value_enum, default_value = "south", value_name = "GRAVITY")]
+    pub gravity: Gravity,
+}
+
+impl TextArgs {
+    /// Whether there is text to draw
+    pub fn is_active(&self) -> bool {
+        self.text.is_some()
+    }
+}
+
+/// Render `--text` onto the image, returning it unchanged when no text was requested
+pub fn apply(img: DynamicImage, args: &TextArgs) -> Result<DynamicImage> {
+    let (Some(text), Some(font_path)) = (&args.text, &args.font) else {
+        return Ok(img);
+    };
+    if args.font_size <= 0.0 {
+        anyhow::bail!("Font size must be greater than 0, got: {}", args.font_size);
+    }
+
+    let font = load_font(font_path)?;
+    let scale = PxScale::from(args.font_size);
+    let scaled = font.as_scaled(scale);
+    let lines: Vec<&str> = text.split("\\n").flat_map(|l| l.split('\n')).collect();
+
+    let line_height = scaled.height() + scaled.line_gap();
+    let line_widths: Vec<f32> = lines.iter().map(|line| line_width(&font, scale, line)).collect();
+    let block_width = line_widths.iter().cloned().fold(0.0, f32::max).ceil() as u32;
+    let block_height = (line_height * lines.len() as f32).ceil() as u32;
+
+    let color_type = img.color();
+    let mut canvas = img.to_rgba16();
+    let margin = (args.font_size / 2.0).round() as u32;
+    let (block_x, block_y) = args.gravity.place(canvas.dimensions(), (block_width, block_height), margin);
+
+    for (i, line) in lines.iter().enumerate() {
+        // Center each line within the block
+        let offset_x = (block_width as f32 - line_widths[i]) / 2.0;
+        let baseline = block_y as f32 + scaled.ascent() + line_height * i as f32;
+        draw_line(&mut canvas, &font, scale, line, block_x as f32 + offset_x, baseline, wide(args.text_color.0));
+    }
+
+    Ok(restore_color_type(color_type, canvas))
+}
+
+/// Read a TrueType/OpenType font from disk
+pub fn load_font(path: &Path) -> Result<FontVec> {
+    let data = std::fs::read(path)
+        .with_context(|| format!("Failed to read font file: {}", path.display()))?;
+    FontVec::try_from_vec(data)
+        .map_err(|_| anyhow::anyhow!("Invalid font file: {}", path.display()))
+}
+
+/// Draw one line of text starting at `x` on the given `baseline`
+pub fn draw_line<T: Sample>(
+    canvas: &mut ImageBuffer<Rgba<T>, Vec<T>>,
+    font: &FontVec,
+    scale: PxScale,
+    line: &str,
+    x: f32,
+    baseline: f32,
+    color: Rgba<T>,
+) where
+    Rgba<T>: Pixel<Subpixel = T>,
+{
+    let scaled = font.as_scaled(scale);
+    let mut caret = x;
+    let mut previous = None;
+
+    for ch in line.chars() {
+        let id = font.glyph_id(ch);
+        if let Some(prev) = previous {
+            caret += scaled.kern(prev, id);
+        }
+        let glyph = id.with_scale_and_position(scale, point(caret, baseline));
+        caret += scaled.h_advance(id);
+        previous = Some(id);
+
+        if let Some(outlined) = font.outline_glyph(glyph) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let px = bounds.min.x as i64 + gx as i64;
+                let py = bounds.min.y as i64 + gy as i64;
+                blend_at(canvas, px, py, color, coverage);
+            });
+        }
+    }
+}
+
+/// Advance width of a single line including kerning
+pub fn line_width(font: &FontVec, scale: PxScale, line: &str) -> f32 {
+    let scaled = font.as_scaled(scale);
+    let mut width = 0.0;
+    let mut previous = None;
+    for ch in line.chars() {
+        let id = font.glyph_id(ch);
+        if let Some(prev) = previous {
+            width += scaled.kern(prev, id);
+        }
+        width += scaled.h_advance(id);
+        previous = Some(id);
+    }
+    width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{LumaA, RgbaImage};
+
+    /// Text in the test font, whose every printable character is a solid box
+    /// 2-10px across and 14px tall at this size, so coverage is predictable
+    fn args(text: &str, gravity: Gravity) -> TextArgs {
+        TextArgs {
+            text: Some(text.to_string()),
+            font: Some(Path::new(env!("CARGO_MANIFEST_DIR")).join("src/testdata/box.ttf")),
+            font_size: 20.0,
+            text_color: "#ffffff".parse().unwrap(),
+            gravity,
+        }
+    }
+
+    /// Bounding box (x0, y0, x1, y1) of the pixels that are no longer black
+    fn inked(img: &RgbaImage) -> (u32, u32, u32, u32) {
+        img.enumerate_pixels().filter(|(_, _, p)| p.0[0] > 0).fold((u32::MAX, u32::MAX, 0, 0), |(x0, y0, x1, y1), (x, y, _)| {
+            (x0.min(x), y0.min(y), x1.max(x), y1.max(y))
+        })
+    }
+
+    #[test]
+    fn test_text_follows_gravity() {
+        let black = DynamicImage::ImageRgba8(RgbaImage::from_pixel(120, 80, Rgba([0, 0, 0, 255])));
+        let (x0, y0, x1, y1) = inked(&apply(black.clone(), &args("AB", Gravity::South)).unwrap().into_rgba8());
+        // Centered, and kept a margin above the bottom edge
+        assert!(y0 > 40 && y1 < 70, "{:?}", (x0, y0, x1, y1));
+        assert!((x0 as i64 + x1 as i64 - 120).abs() <= 4, "{:?}", (x0, x1));
+
+        let (x0, y0, _, _) = inked(&apply(black, &args("AB", Gravity::Northwest)).unwrap().into_rgba8());
+        assert!(x0 < 20 && y0 < 20, "{:?}", (x0, y0));
+    }
+
+    #[test]
+    fn test_depth_and_alpha_survive() {
+        // Levels between the 8-bit steps survive away from the text
+        let sixteen = DynamicImage::ImageRgba16(image::ImageBuffer::from_pixel(60, 40, image::Rgba([1000u16, 2000, 3000, 65535])));
+        let out = apply(sixteen, &args("A", Gravity::Center)).unwrap();
+        assert_eq!(out.color(), image::ColorType::Rgba16);
+        assert_eq!(out.to_rgba16().get_pixel(2, 2).0, [1000, 2000, 3000, 65535]);
+
+        let float = DynamicImage::ImageRgb32F(image::Rgb32FImage::new(60, 40));
+        assert_eq!(apply(float, &args("A", Gravity::Center)).unwrap().color(), image::ColorType::Rgb32F);
+
+        // Text lands on a transparent gray canvas without making the rest opaque
+        let clear = DynamicImage::ImageLumaA8(image::GrayAlphaImage::from_pixel(60, 40, LumaA([0, 0])));
+        let out = apply(clear, &args("A", Gravity::Center)).unwrap();
+        assert_eq!(out.color(), image::ColorType::La8);
+        let out = out.into_luma_alpha8();
+        assert_eq!(out.get_pixel(30, 20).0, [255, 255]);
+        assert_eq!(out.get_pixel(2, 2).0, [0, 0]);
+    }
+
+    #[test]
+    fn test_missing_font() {
+        let mut args = args("A", Gravity::South);
+        args.font = Some(PathBuf::from("no-such-font.ttf"));
+        assert!(apply(DynamicImage::new_rgb8(8, 8), &args).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+// File: src\appicons.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: `imgconv appicons` - iOS and Android app icon sets from one image
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::alpha::flatten_onto;
+use crate::color::Color;
+use crate::encode::{save_image, write_bytes};
+use crate::i18n::tr;
+use crate::{load_image, print_info, print_success, print_warning};
+
+/// Smallest source that is not upscaled for the 1024px App Store icon
+const RECOMMENDED_SIZE: u32 = 1024;
+
+#[derive(Args, Debug)]
+pub struct AppIconsArgs {
+    /// Square source icon, ideally 1024x1024 or larger
+    #[arg(value_name = "INPUT")]
+    input: PathBuf,
+
+    /// Platforms to generate, comma-separated
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "ios,android", value_name = "PLATFORMS")]
+    platform: Vec<Platform>,
+
+    /// Directory that receives ios/ and android/
+    #[arg(long, default_value = ".", value_name = "DIR")]
+    out: PathBuf,
+
+    /// Fill for transparent areas of iOS icons and the Android adaptive-icon background
+    #[arg(long, default_value = "#ffffff", value_name = "COLOR")]
+    background: Color,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Platform {
+    /// Xcode AppIcon.appiconset with Contents.json
+    Ios,
+    /// mipmap-* launcher icons plus adaptive-icon foreground and XML
+    Android,
+}
+
+/// One entry of an Xcode icon set: point size, scale and device family
+struct IosIcon {
+    idiom: &'static str,
+    size: f32,
+    scale: u32,
+}
+
+impl IosIcon {
+    fn pixels(&self) -> u32 {
+        (self.size * self.scale as f32).round() as u32
+    }
+
+    fn size_label(&self) -> String {
+        format!("{}x{}", self.size, self.size)
+    }
+
+    fn filename(&self) -> String {
+        format!("Icon-{}@{}x.png", self.size, self.scale)
+    }
+}
+
+const fn ios(idiom: &'static str, size: f32, scale: u32) -> IosIcon {
+    IosIcon { idiom, size, scale }
+}
+
+const IOS_ICONS: [IosIcon; 18] = [
+    ios("iphone", 20.0, 2), ios("iphone", 20.0, 3),
+    ios("iphone", 29.0, 2), ios("iphone", 29.0, 3),
+    ios("iphone", 40.0, 2), ios("iphone", 40.0, 3),
+    ios("iphone", 60.0, 2), ios("iphone", 60.0, 3),
+    ios("ipad", 20.0, 1), ios("ipad", 20.0, 2),
+    ios("ipad", 29.0, 1), ios("ipad", 29.0, 2),
+    ios("ipad", 40.0, 1), ios("ipad", 40.0, 2),
+    ios("ipad", 76.0, 1), ios("ipad", 76.0, 2),
+    ios("ipad", 83.5, 2),
+    ios("ios-marketing", 1024.0, 1),
+];
+
+/// Android density buckets with the legacy launcher icon size (48dp)
+const ANDROID_DENSITIES: [(&str, u32); 5] =
+    [("mdpi", 48), ("hdpi", 72), ("xhdpi", 96), ("xxhdpi", 144), ("xxxhdpi", 192)];
+
+/// Adaptive icon layers are 108dp, of which only the central 66dp is
+/// guaranteed to survive the launcher's mask
+const ADAPTIVE_SIZE_DP: u32 = 108;
+const ADAPTIVE_SAFE_DP: u32 = 66;
+
+#[derive(Serialize)]
+struct Contents {
+    images: Vec<ContentsImage>,
+    info: ContentsInfo,
+}
+
+#[derive(Serialize)]
+struct ContentsImage {
+    size: String,
+    idiom: &'static str,
+    filename: String,
+    scale: String,
+}
+
+#[derive(Serialize)]
+struct ContentsInfo {
+    version: u32,
+    author: &'static str,
+}
+
+pub fn run(args: &AppIconsArgs) -> Result<()> {
+    let (img, _) = load_image(&args.input)?;
+    let source = square(&img.to_rgba8());
+    if source.width() < RECOMMENDED_SIZE {
+        print_warning(&tr!(
+            "Source is {}px; icons larger than that will be upscaled ({}px or more recommended)",
+            source.width(),
+            RECOMMENDED_SIZE
+        ));
+    }
+
+    let mut count = 0;
+    if args.platform.contains(&Platform::Ios) {
+        count += write_ios(&source, args.background, &args.out.join("ios"))?;
+    }
+    if args.platform.contains(&Platform::Android) {
+        count += write_android(&source, args.background, &args.out.join("android"))?;
+    }
+    print_success(&tr!("Wrote {} icons to: {}", count, args.out.display()));
+    Ok(())
+}
+
+/// Pad a non-square source onto a transparent square canvas
+fn square(img: &RgbaImage) -> RgbaImage {
+    let (width, height) = img.dimensions();
+    if width == height {
+        return img.clone();
+    }
+    print_warning(&tr!("Source is {}x{}, padding it to a square", width, height));
+    let side = width.max(height);
+    let mut canvas = RgbaImage::new(side, side);
+    imageops::overlay(&mut canvas, img, ((side - width) / 2) as i64, ((side - height) / 2) as i64);
+    canvas
+}
+
+fn resized(source: &RgbaImage, size: u32) -> RgbaImage {
+    imageops::resize(source, size, size, FilterType::Lanczos3)
+}
+
+fn save_png(img: RgbaImage, path: &Path) -> Result<()> {
+    save_image(&DynamicImage::ImageRgba8(img), ImageFormat::Png, 100, path)
+}
+
+fn create_dir(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create directory: {}", dir.display()))
+}
+
+/// Write AppIcon.appiconset; the App Store rejects icons with transparency,
+/// so every size is flattened onto the background
+fn write_ios(source: &RgbaImage, background: Color, out: &Path) -> Result<usize> {
+    let dir = out.join("AppIcon.appiconset");
+    create_dir(&dir)?;
+
+    let mut written: Vec<String> = Vec::new();
+    for icon in &IOS_ICONS {
+        let filename = icon.filename();
+        // iPhone and iPad share some files, e.g. Icon-40@2x.png
+        if !written.contains(&filename) {
+            let mut img = resized(source, icon.pixels());
+            flatten_onto(&mut img, background);
+            save_png(img, &dir.join(&filename))?;
+            written.push(filename);
+        }
+    }
+    write_bytes(&dir.join("Contents.json"), contents_json()?.as_bytes())?;
+    print_info(&tr!("iOS: {} icons and Contents.json in {}", written.len(), dir.display()));
+    Ok(written.len())
+}
+
+fn contents_json() -> Result<String> {
+    let contents = Contents {
+        images: IOS_ICONS
+            .iter()
+            .map(|icon| ContentsImage {
+                size: icon.size_label(),
+                idiom: icon.idiom,
+                filename: icon.filename(),
+                scale: format!("{}x", icon.scale),
+            })
+            .collect(),
+        info: ContentsInfo { version: 1, author: "imgconv" },
+    };
+    Ok(serde_json::to_string_pretty(&contents).context("Failed to serialize Contents.json")? + "\n")
+}
+
+/// Write res/ with legacy, round and adaptive launcher icons plus the 512px
+/// Play Store icon
+fn write_android(source: &RgbaImage, background: Color, out: &Path) -> Result<usize> {
+    let res = out.join("res");
+    let mut count = 0;
+    for (density, size) in ANDROID_DENSITIES {
+        let dir = res.join(format!("mipmap-{}", density));
+        create_dir(&dir)?;
+        let icon = resized(source, size);
+        save_png(round(&icon), &dir.join("ic_launcher_round.png"))?;
+        save_png(icon, &dir.join("ic_launcher.png"))?;
+        let foreground_size = size * ADAPTIVE_SIZE_DP / 48;
+        save_png(adaptive_foreground(source, foreground_size), &dir.join("ic_launcher_foreground.png"))?;
+        count += 3;
+    }
+
+    let anydpi = res.join("mipmap-anydpi-v26");
+    create_dir(&anydpi)?;
+    for name in ["ic_launcher.xml", "ic_launcher_round.xml"] {
+        write_bytes(&anydpi.join(name), ADAPTIVE_ICON_XML.as_bytes())?;
+    }
+    let values = res.join("values");
+    create_dir(&values)?;
+    let [r, g, b, _] = background.0 .0;
+    let colors = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<resources>\n    \
+         <color name=\"ic_launcher_background\">#{:02X}{:02X}{:02X}</color>\n</resources>\n",
+        r, g, b
+    );
+    write_bytes(&values.join("ic_launcher_background.xml"), colors.as_bytes())?;
+
+    save_png(resized(source, 512), &out.join("ic_launcher-playstore.png"))?;
+    count += 1;
+    print_info(&tr!("Android: {} icons and adaptive-icon XML in {}", count, res.display()));
+    Ok(count)
+}
+
+const ADAPTIVE_ICON_XML: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<adaptive-icon xmlns:android=\"http://schemas.android.com/apk/res/android\">
+    <background android:drawable=\"@color/ic_launcher_background\" />
+    <foreground android:drawable=\"@mipmap/ic_launcher_foreground\" />
+</adaptive-icon>
+";
+
+/// The icon shrunk into the adaptive safe zone, centered on a transparent layer
+fn adaptive_foreground(source: &RgbaImage, size: u32) -> RgbaImage {
+    let inner = (size * ADAPTIVE_SAFE_DP / ADAPTIVE_SIZE_DP).max(1);
+    let mut layer = RgbaImage::new(size, size);
+    let offset = ((size - inner) / 2) as i64;
+    imageops::overlay(&mut layer, &resized(source, inner), offset, offset);
+    layer
+}
+
+/// Cut the icon to a circle with a one-pixel antialiased edge
+fn round(icon: &RgbaImage) -> RgbaImage {
+    let mut out = icon.clone();
+    let radius = icon.width() as f32 / 2.0;
+    for (x, y, pixel) in out.enumerate_pixels_mut() {
+        let dx = x as f32 + 0.5 - radius;
+        let dy = y as f32 + 0.5 - radius;
+        let coverage = (radius - (dx * dx + dy * dy).sqrt() + 0.5).clamp(0.0, 1.0);
+        let Rgba([r, g, b, a]) = *pixel;
+        *pixel = Rgba([r, g, b, (a as f32 * coverage).round() as u8]);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ios_matrix_and_contents() {
+        let json: serde_json::Value = serde_json::from_str(&contents_json().unwrap()).unwrap();
+        let images = json["images"].as_array().unwrap();
+        assert_eq!(images.len(), IOS_ICONS.len());
+        let marketing = images.iter().find(|i| i["idiom"] == "ios-marketing").unwrap();
+        assert_eq!(marketing["size"], "1024x1024");
+        assert_eq!(marketing["filename"], "Icon-1024@1x.png");
+        let ipad_pro = images.iter().find(|i| i["size"] == "83.5x83.5").unwrap();
+        assert_eq!(ipad_pro["filename"], "Icon-83.5@2x.png");
+        assert_eq!(IOS_ICONS.iter().find(|i| i.size == 83.5).unwrap().pixels(), 167);
+    }
+
+    #[test]
+    fn test_foreground_padding_and_round_mask() {
+        let source = RgbaImage::from_pixel(64, 64, Rgba([255, 0, 0, 255]));
+        let layer = adaptive_foreground(&source, 108);
+        assert_eq!(layer.get_pixel(54, 54).0, [255, 0, 0, 255]);
+        assert_eq!(layer.get_pixel(20, 20).0[3], 0); // outside the 66dp safe zone
+
+        let circle = round(&resized(&source, 48));
+        assert_eq!(circle.get_pixel(0, 0).0[3], 0);
+        assert_eq!(circle.get_pixel(24, 24).0[3], 255);
+    }
+}
@@ -0,0 +1,170 @@
+// File: src\archive.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: ZIP and TAR (.tar, .tar.gz, .tgz) archives as batch input, read entry by entry
+// License: MIT
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+
+use crate::detect_format_from_path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+/// Archive kind from the file name
+fn kind(path: &Path) -> Option<Kind> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    if name.ends_with(".zip") {
+        Some(Kind::Zip)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(Kind::TarGz)
+    } else if name.ends_with(".tar") {
+        Some(Kind::Tar)
+    } else {
+        None
+    }
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    kind(path).is_some()
+}
+
+/// An open archive; its entries are decompressed one at a time, never to disk
+pub enum Archive {
+    Zip(zip::ZipArchive<BufReader<File>>),
+    Tar(tar::Archive<Box<dyn Read>>),
+}
+
+impl Archive {
+    pub fn open(path: &Path) -> Result<Self> {
+        let kind = kind(path).with_context(|| format!("Not a ZIP or TAR archive: {}", path.display()))?;
+        let file = File::open(path).with_context(|| format!("Failed to open archive: {}", path.display()))?;
+        let file = BufReader::new(file);
+        Ok(match kind {
+            Kind::Zip => Archive::Zip(
+                zip::ZipArchive::new(file).with_context(|| format!("Failed to read ZIP archive: {}", path.display()))?,
+            ),
+            Kind::Tar => Archive::Tar(tar::Archive::new(Box::new(file))),
+            Kind::TarGz => Archive::Tar(tar::Archive::new(Box::new(flate2::read::MultiGzDecoder::new(file)))),
+        })
+    }
+
+    /// Reader over the entries; a TAR archive can be walked only once
+    pub fn entries(&mut self) -> Result<Entries<'_>> {
+        match self {
+            Archive::Zip(zip) => {
+                let index = (0..zip.len())
+                    .filter_map(|i| Some((entry_path(Path::new(zip.name_for_index(i)?))?, i)))
+                    .collect();
+                Ok(Entries::Zip { zip, index })
+            }
+            Archive::Tar(tar) => Ok(Entries::Tar(tar.entries().context("Failed to read TAR archive")?)),
+        }
+    }
+}
+
+pub enum Entries<'a> {
+    Zip { zip: &'a mut zip::ZipArchive<BufReader<File>>, index: HashMap<PathBuf, usize> },
+    Tar(tar::Entries<'a, Box<dyn Read>>),
+}
+
+impl Entries<'_> {
+    /// Contents of the entry at `name` (as returned by `list`); TAR entries
+    /// must be asked for in archive order
+    pub fn read(&mut self, name: &Path) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        match self {
+            Entries::Zip { zip, index } => {
+                let i = *index.get(name).with_context(|| format!("Not in the archive: {}", name.display()))?;
+                let mut file = zip.by_index(i).with_context(|| format!("Failed to read {} from the archive", name.display()))?;
+                file.read_to_end(&mut data)
+                    .with_context(|| format!("Failed to decompress {} from the archive", name.display()))?;
+            }
+            Entries::Tar(entries) => loop {
+                let mut entry = entries
+                    .next()
+                    .with_context(|| format!("Not in the archive: {}", name.display()))?
+                    .context("Failed to read TAR archive")?;
+                if entry.path().ok().and_then(|path| entry_path(&path)).as_deref() == Some(name) {
+                    entry.read_to_end(&mut data)
+                        .with_context(|| format!("Failed to read {} from the archive", name.display()))?;
+                    break;
+                }
+            },
+        }
+        Ok(data)
+    }
+}
+
+/// Image entries of the archive at `path` (by extension), in archive order
+pub fn list(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut names = Vec::new();
+    match Archive::open(path)? {
+        Archive::Zip(zip) => {
+            for name in zip.file_names().filter(|name| !name.ends_with('/')) {
+                names.extend(entry_path(Path::new(name)));
+            }
+        }
+        Archive::Tar(mut tar) => {
+            for entry in tar.entries().context("Failed to read TAR archive")? {
+                let entry = entry.with_context(|| format!("Failed to read TAR archive: {}", path.display()))?;
+                if entry.header().entry_type().is_file() {
+                    names.extend(entry.path().ok().and_then(|path| entry_path(&path)));
+                }
+            }
+        }
+    }
+    names.retain(|name| detect_format_from_path(name).is_some());
+    Ok(names)
+}
+
+/// An entry name as a relative path: "./" prefixes, root and ".." parts are
+/// dropped so outputs always land inside the output directory
+fn entry_path(name: &Path) -> Option<PathBuf> {
+    let path: PathBuf = name.components().filter(|c| matches!(c, Component::Normal(_))).collect();
+    (!path.as_os_str().is_empty()).then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_entry_names() {
+        assert!(is_archive(Path::new("Photos.TAR.GZ")) && is_archive(Path::new("a.tgz")) && is_archive(Path::new("a.zip")));
+        assert!(!is_archive(Path::new("a.gz")));
+        assert_eq!(entry_path(Path::new("./shots/a.png")), Some(PathBuf::from("shots/a.png")));
+        assert_eq!(entry_path(Path::new("/../../etc/a.png")), Some(PathBuf::from("etc/a.png")));
+        assert_eq!(entry_path(Path::new("./")), None);
+    }
+
+    #[test]
+    fn test_read_zip_entries() {
+        let path = std::env::temp_dir().join(format!("imgconv-archive-{}.zip", std::process::id()));
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.add_directory("shots/", options).unwrap();
+        for (name, data) in [("shots/b.png", b"bb"), ("notes.txt", b"tt"), ("./a.jpg", b"aa")] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let names = list(&path).unwrap();
+        assert_eq!(names, [PathBuf::from("shots/b.png"), PathBuf::from("a.jpg")]);
+        let mut archive = Archive::open(&path).unwrap();
+        let mut entries = archive.entries().unwrap();
+        assert_eq!(entries.read(&names[1]).unwrap(), b"aa");
+        assert_eq!(entries.read(&names[0]).unwrap(), b"bb");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
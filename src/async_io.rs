@@ -0,0 +1,71 @@
+// File: src\async_io.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Tokio variants of the library entry points (`async` feature)
+// License: MIT
+
+use image::ImageFormat;
+use std::path::Path;
+
+use crate::encode::temp_path;
+use crate::error::{ImgconvError, Result};
+use crate::Converter;
+
+impl Converter {
+    /// `run` for async code: files are read and written with tokio, and the
+    /// decode/resize/encode work runs on the blocking pool so the runtime's
+    /// worker threads stay free
+    pub async fn run_async(&self, src: impl AsRef<Path>, dst: impl AsRef<Path>) -> Result<(u32, u32)> {
+        let (src, dst) = (src.as_ref(), dst.as_ref());
+        let format = self.output_format(dst)?;
+        let data = tokio::fs::read(src).await.map_err(ImgconvError::io(format!("Failed to read {}", src.display())))?;
+        let (output, size) = self.convert_blocking(data, format, src.display().to_string()).await?;
+
+        // Same atomic replace as the blocking writer: temporary file, then rename
+        let temp = temp_path(dst);
+        let written = async {
+            tokio::fs::write(&temp, &output).await.map_err(ImgconvError::io(format!("Failed to write {}", dst.display())))?;
+            self.token.check()?;
+            tokio::fs::rename(&temp, dst).await.map_err(ImgconvError::io(format!("Failed to write {}", dst.display())))
+        }
+        .await;
+        if written.is_err() {
+            let _ = tokio::fs::remove_file(&temp).await;
+        }
+        written.map(|_| size)
+    }
+
+    /// `convert_bytes` on the blocking pool
+    pub async fn convert_bytes_async(&self, data: Vec<u8>, format: ImageFormat) -> Result<(Vec<u8>, (u32, u32))> {
+        self.check_format(format)?;
+        self.convert_blocking(data, format, "the image data".to_string()).await
+    }
+
+    async fn convert_blocking(&self, data: Vec<u8>, format: ImageFormat, input: String) -> Result<(Vec<u8>, (u32, u32))> {
+        let converter = self.clone();
+        tokio::task::spawn_blocking(move || converter.convert(&data, format, &input))
+            .await
+            .map_err(|error| ImgconvError::Io { context: "Conversion task failed".to_string(), source: error.into() })?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Resize;
+    use image::GenericImageView;
+
+    #[test]
+    fn test_run_async() {
+        let dir = std::env::temp_dir().join(format!("imgconv-async-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (src, dst) = (dir.join("in.png"), dir.join("out.webp"));
+        image::RgbImage::new(60, 30).save(&src).unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let size = runtime.block_on(Converter::new().resize(Resize::Fit(20, 20)).run_async(&src, &dst)).unwrap();
+        assert_eq!(size, (20, 10));
+        assert_eq!(image::open(&dst).unwrap().dimensions(), (20, 10));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,145 @@
+// File: src\autoquality.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Perceptual quality selection: the lowest quality that keeps SSIM above a threshold
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::{DynamicImage, GrayImage, ImageFormat};
+use std::io::Cursor;
+
+use crate::encode::{encode_image, supports_quality};
+use crate::i18n::tr;
+use crate::{print_info, print_warning};
+
+/// SSIM window edge and step (8x8 windows every 4 pixels)
+const WINDOW: u32 = 8;
+const STEP: u32 = 4;
+
+#[derive(Args, Debug)]
+pub struct AutoQualityArgs {
+    /// Pick the lowest JPEG quality whose output still looks like the source (see --min-ssim)
+    #[arg(long, conflicts_with = "estimate")]
+    pub auto_quality: bool,
+
+    /// Similarity --auto-quality must keep, as SSIM from 0 to 1 (higher = closer to the source)
+    #[arg(long, default_value = "0.98", value_name = "SSIM", requires = "auto_quality", value_parser = parse_ssim)]
+    pub min_ssim: f64,
+}
+
+fn parse_ssim(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(value) if value > 0.0 && value <= 1.0 => Ok(value),
+        _ => Err(format!("Invalid SSIM '{}', expected a number in (0, 1]", s)),
+    }
+}
+
+/// Quality to encode `img` with: the lowest meeting --min-ssim, or `quality`
+/// when --auto-quality is off or the format cannot be measured
+///
+/// Quality is bisected over 1-100, encoding and decoding the whole image at
+/// each step.
+pub fn quality(img: &DynamicImage, format: ImageFormat, args: &AutoQualityArgs, quality: u8) -> Result<u8> {
+    if !args.auto_quality {
+        return Ok(quality);
+    }
+    if !supports_quality(format) {
+        return Ok(quality);
+    }
+    if format != ImageFormat::Jpeg {
+        // Trial encodings must be decoded to compare, and this build only encodes
+        // AVIF; formats::check has already warned
+        return Ok(quality);
+    }
+
+    let source = img.to_luma8();
+    let similarity = |quality: u8| -> Result<f64> {
+        let mut buffer = Cursor::new(Vec::new());
+        encode_image(img, format, quality, &mut buffer)?;
+        let decoded = image::load_from_memory_with_format(buffer.get_ref(), format)
+            .context("Failed to decode trial encoding")?;
+        Ok(ssim(&source, &decoded.to_luma8()))
+    };
+
+    let (mut low, mut high) = (1u8, 100u8);
+    let mut best = (100, similarity(100)?);
+    if best.1 < args.min_ssim {
+        print_warning(&tr!("Even quality 100 only reaches SSIM {}, using it", format!("{:.4}", best.1)));
+        return Ok(100);
+    }
+    while low < high {
+        let quality = low + (high - low) / 2;
+        let score = similarity(quality)?;
+        if score >= args.min_ssim {
+            best = (quality, score);
+            high = quality;
+        } else {
+            low = quality + 1;
+        }
+    }
+    print_info(&tr!("Auto quality {} (SSIM {} >= {})", best.0, format!("{:.4}", best.1), args.min_ssim));
+    Ok(best.0)
+}
+
+/// Mean structural similarity of two equally sized grayscale images
+pub fn ssim(a: &GrayImage, b: &GrayImage) -> f64 {
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+    let (width, height) = a.dimensions();
+    let window = WINDOW.min(width).min(height).max(1);
+    let (mut total, mut count) = (0.0, 0u64);
+
+    let mut y = 0;
+    while y + window <= height {
+        let mut x = 0;
+        while x + window <= width {
+            let (mut sa, mut sb, mut saa, mut sbb, mut sab) = (0.0, 0.0, 0.0, 0.0, 0.0);
+            for dy in 0..window {
+                for dx in 0..window {
+                    let pa = a.get_pixel(x + dx, y + dy).0[0] as f64;
+                    let pb = b.get_pixel(x + dx, y + dy).0[0] as f64;
+                    sa += pa;
+                    sb += pb;
+                    saa += pa * pa;
+                    sbb += pb * pb;
+                    sab += pa * pb;
+                }
+            }
+            let n = (window * window) as f64;
+            let (ma, mb) = (sa / n, sb / n);
+            let (va, vb, cov) = (saa / n - ma * ma, sbb / n - mb * mb, sab / n - ma * mb);
+            total += ((2.0 * ma * mb + C1) * (2.0 * cov + C2)) / ((ma * ma + mb * mb + C1) * (va + vb + C2));
+            count += 1;
+            x += STEP;
+        }
+        y += STEP;
+    }
+    if count == 0 { 1.0 } else { total / count as f64 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    #[test]
+    fn test_ssim_identical_and_degraded() {
+        let img = GrayImage::from_fn(32, 32, |x, y| Luma([((x * 8) ^ (y * 5)) as u8]));
+        assert!((ssim(&img, &img) - 1.0).abs() < 1e-9);
+        let flat = GrayImage::from_pixel(32, 32, Luma([128]));
+        assert!(ssim(&img, &flat) < 0.2);
+    }
+
+    #[test]
+    fn test_auto_quality_meets_threshold() {
+        let img = DynamicImage::ImageLuma8(GrayImage::from_fn(64, 64, |x, y| Luma([(x * 4 + y) as u8])));
+        let args = AutoQualityArgs { auto_quality: true, min_ssim: 0.95 };
+        let chosen = quality(&img, ImageFormat::Jpeg, &args, 90).unwrap();
+        let mut buffer = Cursor::new(Vec::new());
+        encode_image(&img, ImageFormat::Jpeg, chosen, &mut buffer).unwrap();
+        let decoded = image::load_from_memory(buffer.get_ref()).unwrap().to_luma8();
+        assert!(ssim(&img.to_luma8(), &decoded) >= 0.95);
+        assert!(chosen < 100);
+    }
+}
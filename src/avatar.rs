@@ -0,0 +1,67 @@
+// File: src\avatar.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: One-flag profile picture recipe: square smart crop and resize
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::{DynamicImage, GenericImageView};
+use std::path::Path;
+
+use crate::crop::smart_crop_rect;
+use crate::i18n::tr;
+use crate::resize::Filter;
+use crate::{print_info, print_warning, Format};
+
+#[derive(Args, Debug)]
+pub struct AvatarArgs {
+    /// Profile picture of SIZExSIZE: square crop around the face/detail, resize (add --circle to mask)
+    #[arg(
+        long,
+        value_name = "SIZE",
+        value_parser = clap::value_parser!(u32).range(1..=4096),
+        conflicts_with_all = ["resize", "smart_crop", "social", "web_set"]
+    )]
+    pub avatar: Option<u32>,
+}
+
+impl AvatarArgs {
+    /// Whether --avatar was given
+    pub fn is_active(&self) -> bool {
+        self.avatar.is_some()
+    }
+}
+
+/// Crop `img` to its most detailed square and scale it to the avatar size
+pub fn apply(img: DynamicImage, args: &AvatarArgs, filter: Filter) -> Result<DynamicImage> {
+    let Some(size) = args.avatar else {
+        return Ok(img);
+    };
+    let rect = smart_crop_rect(&img, 1.0);
+    let (width, height) = img.dimensions();
+    if rect.width < size {
+        print_warning(&tr!("Source is {}x{}; the {}px avatar will be upscaled", width, height, size));
+    }
+    print_info(&tr!("Avatar {}x{} from the {}x{} square at {},{}", size, size, rect.width, rect.height, rect.x, rect.y));
+    Ok(img.crop_imm(rect.x, rect.y, rect.width, rect.height).resize_exact(size, size, filter.to_filter_type()))
+}
+
+/// Output format for an avatar whose output name has no extension: WebP
+pub fn default_format(args: &AvatarArgs, output: &Path) -> Option<Format> {
+    (args.avatar.is_some() && output.extension().is_none()).then_some(Format::Webp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_avatar_is_square() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(640, 480));
+        let args = AvatarArgs { avatar: Some(128) };
+        assert_eq!(apply(img, &args, Filter::Lanczos3).unwrap().dimensions(), (128, 128));
+        assert!(matches!(default_format(&args, Path::new("me")), Some(Format::Webp)));
+        assert!(default_format(&args, Path::new("me.png")).is_none());
+    }
+}
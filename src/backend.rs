@@ -0,0 +1,57 @@
+// File: src\backend.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Selectable JPEG codec backend (the SIMD encoder needs the "turbo" feature)
+// License: MIT
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use image::DynamicImage;
+use std::path::Path;
+
+#[derive(Args, Debug)]
+pub struct BackendArgs {
+    /// JPEG codec backend; turbo needs a build with `--features turbo`
+    #[arg(long, value_enum, default_value = "builtin", value_name = "BACKEND")]
+    pub backend: Backend,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Backend {
+    /// The image crate's codecs
+    Builtin,
+    /// SIMD JPEG encoder (jpeg-encoder), several times faster on large photos
+    Turbo,
+}
+
+/// Fail early when turbo was asked for but not compiled in
+pub fn check(args: &BackendArgs) -> Result<()> {
+    if args.backend == Backend::Turbo && !cfg!(feature = "turbo") {
+        anyhow::bail!(
+            "--backend turbo is not available in this build; reinstall with `cargo install imgconv --features turbo`"
+        );
+    }
+    Ok(())
+}
+
+/// Whether JPEG output should go through the turbo encoder
+pub fn use_turbo(args: &BackendArgs) -> bool {
+    cfg!(feature = "turbo") && args.backend == Backend::Turbo
+}
+
+/// Encode `img` as a 4:2:0 JPEG with the SIMD encoder
+pub fn save_jpeg(img: &DynamicImage, quality: u8, progressive: bool, path: &Path) -> Result<()> {
+    crate::encode::write_file(path, |writer| crate::encode::encode_turbo_jpeg(img, quality, progressive, writer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turbo_availability_matches_build() {
+        let args = BackendArgs { backend: Backend::Turbo };
+        assert_eq!(check(&args).is_ok(), cfg!(feature = "turbo"));
+        assert!(check(&BackendArgs { backend: Backend::Builtin }).is_ok());
+    }
+}
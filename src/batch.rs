@@ -0,0 +1,422 @@
+// File: src\batch.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Directory (batch) conversion with incremental rebuilds
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use image::ImageFormat;
+use imgconv::ImgconvError;
+use std::cmp::Ordering;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use crate::i18n::tr;
+use crate::cancel;
+use crate::names::sanitize_path;
+use crate::throttle::{Throttle, ThrottleArgs};
+use crate::{exifthumb, progress, template};
+use crate::{detect_format_from_path, format_to_main_extension, with_image_extension};
+use crate::{print_error, print_info, print_success};
+
+#[derive(Args, Debug)]
+pub struct BatchArgs {
+    /// Descend into subdirectories when the input is a directory
+    #[arg(short = 'r', long)]
+    pub recursive: bool,
+
+    /// Skip files whose output exists and is newer than the source
+    #[arg(long)]
+    pub newer_only: bool,
+
+    /// Sort batch outputs into subdirectories of the output directory
+    #[arg(long, value_enum, value_name = "KEY")]
+    pub organize_by: Option<OrganizeBy>,
+
+    /// Convert the files listed in FILE, one per line ("-" reads stdin)
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["input", "pos_input", "clipboard"])]
+    pub files_from: Option<PathBuf>,
+
+    /// Entries in --files-from are NUL-separated (as written by find -print0)
+    #[arg(short = '0', long, requires = "files_from")]
+    pub null: bool,
+
+    /// Make output file names URL-safe (no spaces or special characters, lowercase extension)
+    #[arg(long)]
+    pub sanitize_names: bool,
+
+    /// Order to convert a batch in, which also sets the {seq} numbers (default: by path)
+    #[arg(long, value_enum, value_name = "KEY")]
+    pub sort: Option<SortBy>,
+
+    /// Output file name template for batches; tokens: {name} {seq} {ext}
+    #[arg(long, value_name = "TEMPLATE", conflicts_with = "in_place")]
+    pub rename: Option<String>,
+
+    /// First {seq} number
+    #[arg(long, default_value = "1", value_name = "N", requires = "rename")]
+    pub seq_start: usize,
+
+    /// Digits {seq} is zero-padded to
+    #[arg(long, default_value = "4", value_name = "N", requires = "rename")]
+    pub seq_digits: usize,
+
+    #[command(flatten)]
+    pub throttle: ThrottleArgs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OrganizeBy {
+    /// Output format, e.g. out/webp/
+    Format,
+    /// Source modification month, e.g. out/2024-05/
+    Date,
+    /// Source dimensions, e.g. out/1920x1080/
+    Dimensions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortBy {
+    /// Path, with numbers in natural order (frame2 before frame10)
+    Name,
+    /// Source modification time, oldest first
+    Mtime,
+    /// EXIF capture date, oldest first; files without one go last
+    ExifDate,
+}
+
+/// Convert every file in `files`
+///
+/// `plan` maps a source file to its output path and format, `convert` does
+/// the work. Every file is planned first and the outputs about to be written
+/// are passed to `confirm`, which can stop the run before anything changes.
+/// A failing file is reported and the batch carries on; the run fails at the
+/// end if any file did.
+pub fn run(
+    files: &[PathBuf],
+    args: &BatchArgs,
+    plan: impl Fn(&Path) -> Result<(PathBuf, ImageFormat)>,
+    confirm: impl FnOnce(&[&Path]) -> Result<()>,
+    mut convert: impl FnMut(&Path, &Path, ImageFormat) -> Result<()>,
+) -> Result<()> {
+    if files.is_empty() {
+        anyhow::bail!(tr!("No images to convert"));
+    }
+    let planned: Vec<Result<(PathBuf, ImageFormat)>> = files.iter().map(|source| plan(source)).collect();
+    let outputs: Vec<&Path> = files
+        .iter()
+        .zip(&planned)
+        .filter_map(|(source, planned)| match planned {
+            Ok((output, _)) if !(args.newer_only && is_up_to_date(source, output)) => Some(output.as_path()),
+            _ => None,
+        })
+        .collect();
+    confirm(&outputs)?;
+    print_info(&tr!("{} files to convert", files.len()));
+    progress::start(files.len());
+
+    let (mut converted, mut skipped, mut failed) = (0, 0, 0);
+    let mut throttle = Throttle::new(&args.throttle);
+    for (source, planned) in files.iter().zip(planned) {
+        if cancel::process().is_cancelled() {
+            break;
+        }
+        let result = planned.and_then(|(output, format)| {
+            if args.newer_only && is_up_to_date(source, &output) {
+                print_info(&tr!("Up to date, skipping: {}", output.display()));
+                progress::file_skipped(&output);
+                return Ok(false);
+            }
+            throttle.wait_for_idle();
+            progress::file_started(Some(source), &output);
+            convert(source, &output, format)?;
+            progress::file_finished(&output);
+            throttle.account(source, &output);
+            Ok(true)
+        });
+        match result {
+            Ok(true) => converted += 1,
+            Ok(false) => skipped += 1,
+            Err(_) if cancel::process().is_cancelled() => break,
+            Err(e) => {
+                failed += 1;
+                progress::file_failed(Some(source), &e);
+                print_error(&format!("{}: {:#}", source.display(), e));
+            }
+        }
+    }
+
+    progress::done(converted, skipped, failed);
+    if cancel::process().is_cancelled() {
+        let error = anyhow::Error::new(ImgconvError::Cancelled);
+        return Err(error.context(tr!("Batch cancelled: {} converted, {} not converted", converted, files.len() - converted - skipped)));
+    }
+    if failed > 0 {
+        anyhow::bail!(tr!("{} of {} files failed to convert", failed, files.len()));
+    }
+    print_success(&tr!("Batch done: {} converted, {} up to date", converted, skipped));
+    Ok(())
+}
+
+/// Image files under `root` (by extension), in a stable order
+pub fn collect(root: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .with_context(|| tr!("Failed to read directory: {}", dir.display()))?;
+        for entry in entries {
+            let path = entry.with_context(|| tr!("Failed to read directory: {}", dir.display()))?.path();
+            if path.is_dir() {
+                if recursive {
+                    dirs.push(path);
+                }
+            } else if detect_format_from_path(&path).is_some() {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Order `files` by `key`; ties and undated files fall back to name order
+pub fn sort(files: &mut [PathBuf], key: SortBy) {
+    match key {
+        SortBy::Name => files.sort_by(|a, b| natural_cmp(a, b)),
+        SortBy::Mtime => {
+            let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+            let mut keyed: Vec<_> = files.iter().map(|path| (modified(path), path.clone())).collect();
+            keyed.sort_by(|(a, a_path), (b, b_path)| a.cmp(b).then_with(|| natural_cmp(a_path, b_path)));
+            files.iter_mut().zip(keyed).for_each(|(file, (_, path))| *file = path);
+        }
+        SortBy::ExifDate => {
+            let mut keyed: Vec<_> = files.iter().map(|path| (exifthumb::date_taken(path), path.clone())).collect();
+            keyed.sort_by(|(a, a_path), (b, b_path)| {
+                (a.is_none(), a).cmp(&(b.is_none(), b)).then_with(|| natural_cmp(a_path, b_path))
+            });
+            files.iter_mut().zip(keyed).for_each(|(file, (_, path))| *file = path);
+        }
+    }
+}
+
+/// Compare paths with runs of digits compared by value
+fn natural_cmp(a: &Path, b: &Path) -> Ordering {
+    let (a, b) = (a.to_string_lossy(), b.to_string_lossy());
+    let (mut a, mut b) = (a.as_ref(), b.as_ref());
+    loop {
+        let (Some(x), Some(y)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len());
+        };
+        let ordering = if x.is_ascii_digit() && y.is_ascii_digit() {
+            let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+            let (x, y) = (&a[..digits(a)], &b[..digits(b)]);
+            let (x_value, y_value) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+            let ordering = x_value.len().cmp(&y_value.len()).then(x_value.cmp(y_value)).then(x.len().cmp(&y.len()));
+            (a, b) = (&a[x.len()..], &b[y.len()..]);
+            ordering
+        } else {
+            (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+            x.cmp(&y)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Paths listed in `list` (or stdin for "-"), newline- or NUL-separated
+pub fn read_list(list: &Path, null: bool) -> Result<Vec<PathBuf>> {
+    let data = if list == Path::new("-") {
+        let mut data = Vec::new();
+        std::io::stdin().read_to_end(&mut data).with_context(|| tr!("Failed to read file list from stdin"))?;
+        data
+    } else {
+        std::fs::read(list).with_context(|| tr!("Failed to read file list: {}", list.display()))?
+    };
+    Ok(parse_list(&data, null))
+}
+
+fn parse_list(data: &[u8], null: bool) -> Vec<PathBuf> {
+    let separator = if null { b'\0' } else { b'\n' };
+    data.split(|b| *b == separator)
+        .map(|entry| if null { entry } else { entry.strip_suffix(b"\r").unwrap_or(entry) })
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| PathBuf::from(String::from_utf8_lossy(entry).into_owned()))
+        .collect()
+}
+
+/// Where `source` sits relative to the batch root; listed files outside it
+/// (absolute or `..` paths) fall back to their file name
+pub fn relative(root: &Path, source: &Path) -> PathBuf {
+    match source.strip_prefix(root) {
+        Ok(relative) if relative.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) => {
+            relative.to_path_buf()
+        }
+        _ => PathBuf::from(source.file_name().unwrap_or_default()),
+    }
+}
+
+/// Mirror `source` (under `root`) into `out_dir`, switching the extension to
+/// `format` or keeping the source format when none is given
+pub fn mirror(
+    root: &Path,
+    source: &Path,
+    out_dir: &Path,
+    format: Option<ImageFormat>,
+    seq: usize,
+    args: &BatchArgs,
+) -> Result<(PathBuf, ImageFormat)> {
+    let mut relative = relative(root, source);
+    let format = match format {
+        Some(format) => format,
+        None => detect_format_from_path(source)
+            .with_context(|| tr!("Unknown image format: {}", source.display()))?,
+    };
+
+    if detect_format_from_path(source) != Some(format) {
+        relative = with_image_extension(&relative, format_to_main_extension(&format));
+    }
+    if let Some(template) = &args.rename {
+        relative.set_file_name(file_name(template, &relative, seq, args)?);
+    }
+    if args.sanitize_names {
+        relative = sanitize_path(&relative);
+    }
+    let output = match args.organize_by {
+        Some(key) => out_dir.join(group(source, format, key)?).join(relative),
+        None => out_dir.join(relative),
+    };
+    Ok((output, format))
+}
+
+/// --rename `template` filled in for the output `relative`, numbered `seq`
+pub fn file_name(template: &str, relative: &Path, seq: usize, args: &BatchArgs) -> Result<String> {
+    let part = |part: Option<&std::ffi::OsStr>| part.unwrap_or_default().to_string_lossy().into_owned();
+    let vars = [
+        ("name", part(relative.file_stem())),
+        ("seq", format!("{:0width$}", seq, width = args.seq_digits)),
+        ("ext", part(relative.extension())),
+    ];
+    template::render(template, &vars)
+}
+
+/// Subdirectory name for `source` under --organize-by
+fn group(source: &Path, format: ImageFormat, key: OrganizeBy) -> Result<String> {
+    match key {
+        OrganizeBy::Format => Ok(format_to_main_extension(&format).to_string()),
+        OrganizeBy::Date => {
+            let modified = std::fs::metadata(source)
+                .and_then(|m| m.modified())
+                .with_context(|| tr!("Failed to read modification time: {}", source.display()))?;
+            let seconds = modified.duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+            let (year, month) = year_month(seconds / 86_400);
+            Ok(format!("{:04}-{:02}", year, month))
+        }
+        OrganizeBy::Dimensions => {
+            let (width, height) = image::image_dimensions(source)
+                .with_context(|| tr!("Failed to read dimensions: {}", source.display()))?;
+            Ok(format!("{}x{}", width, height))
+        }
+    }
+}
+
+/// Civil (UTC) year and month for a day count since 1970-01-01
+fn year_month(days: u64) -> (u64, u64) {
+    // Howard Hinnant's days-to-civil, shifted so years start in March
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month)
+}
+
+/// True when `output` exists and was modified after `source` (make semantics)
+pub fn is_up_to_date(source: &Path, output: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified());
+    match (modified(source), modified(output)) {
+        (Ok(source), Ok(output)) => output > source,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mirror_switches_extension() {
+        let (root, out) = (Path::new("in"), Path::new("out"));
+        let mut args = BatchArgs {
+            recursive: true,
+            newer_only: false,
+            organize_by: None,
+            files_from: None,
+            null: false,
+            sanitize_names: false,
+            sort: None,
+            rename: None,
+            seq_start: 1,
+            seq_digits: 4,
+            throttle: ThrottleArgs { throttle_mbps: None, idle_only: false },
+        };
+        let (output, format) = mirror(root, Path::new("in/a/b.png"), out, Some(ImageFormat::WebP), 1, &args).unwrap();
+        assert_eq!((output, format), (PathBuf::from("out/a/b.webp"), ImageFormat::WebP));
+        let (output, format) = mirror(root, Path::new("in/c.JPG"), out, None, 1, &args).unwrap();
+        assert_eq!((output, format), (PathBuf::from("out/c.JPG"), ImageFormat::Jpeg));
+
+        args.organize_by = Some(OrganizeBy::Format);
+        args.sanitize_names = true;
+        let (output, _) = mirror(root, Path::new("in/My Trip/c 1.JPG"), out, None, 1, &args).unwrap();
+        assert_eq!(output, PathBuf::from("out/jpg/My-Trip/c-1.jpg"));
+
+        args.rename = Some("frame_{seq}.{ext}".into());
+        let (output, _) = mirror(root, Path::new("in/My Trip/c 1.png"), out, Some(ImageFormat::WebP), 12, &args).unwrap();
+        assert_eq!(output, PathBuf::from("out/webp/My-Trip/frame_0012.webp"));
+        assert!(file_name("{index}", Path::new("a.png"), 1, &args).is_err());
+    }
+
+    #[test]
+    fn test_natural_sort() {
+        let mut files: Vec<PathBuf> = ["f10.png", "f2.png", "f02b.png", "a/f1.png", "F1.png"].iter().map(PathBuf::from).collect();
+        sort(&mut files, SortBy::Name);
+        assert_eq!(files, ["F1.png", "a/f1.png", "f2.png", "f02b.png", "f10.png"].map(PathBuf::from));
+    }
+
+    #[test]
+    fn test_file_lists() {
+        assert_eq!(parse_list(b"a.png\r\n\nb c.jpg\n", false), [PathBuf::from("a.png"), PathBuf::from("b c.jpg")]);
+        assert_eq!(parse_list(b"line\nbreak.png\0x.png\0", true), [PathBuf::from("line\nbreak.png"), PathBuf::from("x.png")]);
+        assert_eq!(relative(Path::new(""), Path::new("photos/a.png")), PathBuf::from("photos/a.png"));
+        assert_eq!(relative(Path::new(""), Path::new("/abs/a.png")), PathBuf::from("a.png"));
+        assert_eq!(relative(Path::new(""), Path::new("../up/a.png")), PathBuf::from("a.png"));
+    }
+
+    #[test]
+    fn test_year_month() {
+        assert_eq!(year_month(0), (1970, 1));
+        assert_eq!(year_month(19_844), (2024, 5)); // 2024-05-01
+        assert_eq!(year_month(19_782), (2024, 2)); // 2024-02-29
+    }
+
+    #[test]
+    fn test_up_to_date_needs_newer_output() {
+        let dir = std::env::temp_dir().join(format!("imgconv-batch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (source, output) = (dir.join("a.png"), dir.join("a.webp"));
+        std::fs::write(&source, b"").unwrap();
+        assert!(!is_up_to_date(&source, &output));
+
+        std::fs::write(&output, b"").unwrap();
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(10);
+        std::fs::File::options().write(true).open(&output).unwrap().set_modified(later).unwrap();
+        assert!(is_up_to_date(&source, &output));
+        assert!(!is_up_to_date(&output, &source));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
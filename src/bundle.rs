@@ -0,0 +1,116 @@
+// File: src\bundle.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Write batch outputs into a ZIP archive (--zip-output)
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::ImageFormat;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::i18n::tr;
+use crate::print_success;
+use crate::sink::Sink;
+
+#[derive(Args, Debug)]
+pub struct BundleArgs {
+    /// Write batch outputs into this ZIP archive instead of an output directory
+    #[arg(
+        long,
+        value_name = "FILE",
+        group = "sink",
+        conflicts_with_all = ["clipboard", "in_place", "output", "pos_output", "estimate", "resume"]
+    )]
+    pub zip_output: Option<PathBuf>,
+}
+
+/// A ZIP archive filled one converted file at a time
+///
+/// Files are streamed into the archive as they arrive; the archive itself is
+/// only created once the first file is added.
+pub struct Bundle {
+    path: PathBuf,
+    zip: Option<ZipWriter<BufWriter<File>>>,
+    entries: usize,
+}
+
+impl Bundle {
+    pub fn new(path: &Path) -> Self {
+        Self { path: path.to_path_buf(), zip: None, entries: 0 }
+    }
+}
+
+impl Sink for Bundle {
+    fn put(&mut self, output: &Path, name: &str, format: ImageFormat) -> Result<()> {
+        let zip = match &mut self.zip {
+            Some(zip) => zip,
+            None => {
+                if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(dir)
+                        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+                }
+                let file = File::create(&self.path).with_context(|| format!("Failed to create {}", self.path.display()))?;
+                self.zip.insert(ZipWriter::new(BufWriter::new(file)))
+            }
+        };
+        let options = SimpleFileOptions::default().compression_method(compression(format));
+        zip.start_file(name, options).with_context(|| format!("Failed to add {} to the archive", name))?;
+        let mut file = File::open(output).with_context(|| format!("Failed to read {}", output.display()))?;
+        std::io::copy(&mut file, zip).with_context(|| format!("Failed to add {} to the archive", name))?;
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Write the archive's directory; files added so far stay in even when
+    /// others in the batch failed
+    fn finish(&mut self) -> Result<()> {
+        let Some(zip) = self.zip.take() else {
+            return Ok(());
+        };
+        zip.finish().with_context(|| format!("Failed to write {}", self.path.display()))?;
+        print_success(&tr!("Wrote {} files into {}", self.entries, self.path.display()));
+        Ok(())
+    }
+}
+
+/// Already-compressed formats are stored as they are; deflating them only costs time
+fn compression(format: ImageFormat) -> CompressionMethod {
+    match format {
+        ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif | ImageFormat::WebP | ImageFormat::Avif => {
+            CompressionMethod::Stored
+        }
+        _ => CompressionMethod::Deflated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_bundle_writes_entries() {
+        let dir = std::env::temp_dir().join(format!("imgconv-bundle-{}", std::process::id()));
+        let path = dir.join("out.zip");
+        let output = dir.join("a.bmp");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&output, b"BM data").unwrap();
+        let mut bundle = Bundle::new(&path);
+        bundle.put(&output, "sub/a.bmp", ImageFormat::Bmp).unwrap();
+        bundle.finish().unwrap();
+
+        let mut zip = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut entry = zip.by_name("sub/a.bmp").unwrap();
+        assert_eq!(entry.compression(), CompressionMethod::Deflated);
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"BM data");
+        drop(entry);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
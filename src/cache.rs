@@ -0,0 +1,83 @@
+// File: src\cache.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Conversion cache keyed by source content and settings
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::ImageFormat;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::encode::write_bytes;
+use crate::format_to_main_extension;
+
+#[derive(Args, Debug)]
+pub struct CacheArgs {
+    /// Reuse earlier outputs for unchanged sources and settings
+    #[arg(long, value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
+}
+
+/// Cache slot for one source file under one set of settings
+#[derive(Debug)]
+pub struct Entry {
+    path: PathBuf,
+}
+
+impl Entry {
+    /// Hash `source`'s bytes together with `settings` and the output format
+    pub fn new(dir: &Path, source: &Path, settings: &str, format: ImageFormat) -> Result<Self> {
+        let data = std::fs::read(source)
+            .with_context(|| format!("Failed to read input file: {}", source.display()))?;
+        let key = key(&data, settings, format);
+        let path = dir.join(format!("{}.{}", key, format_to_main_extension(&format)));
+        Ok(Self { path })
+    }
+
+    /// Cached output bytes, if this source was converted with these settings before
+    pub fn lookup(&self) -> Option<Vec<u8>> {
+        std::fs::read(&self.path).ok()
+    }
+
+    /// Keep a copy of a freshly written output
+    pub fn store(&self, output: &Path) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+        }
+        let data = std::fs::read(output)
+            .with_context(|| format!("Failed to read output for caching: {}", output.display()))?;
+        write_bytes(&self.path, &data)
+    }
+}
+
+/// Hex SHA-256 over the imgconv version, settings, format and source bytes
+///
+/// The version is part of the key so encoder changes in a new release never
+/// serve stale output.
+fn key(data: &[u8], settings: &str, format: ImageFormat) -> String {
+    let mut hasher = Sha256::new();
+    for part in [env!("CARGO_PKG_VERSION"), settings, format_to_main_extension(&format)] {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    hasher.update(data);
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_depends_on_content_and_settings() {
+        let base = key(b"pixels", "quality: 80", ImageFormat::WebP);
+        assert_eq!(base.len(), 64);
+        assert_eq!(base, key(b"pixels", "quality: 80", ImageFormat::WebP));
+        assert_ne!(base, key(b"pixels!", "quality: 80", ImageFormat::WebP));
+        assert_ne!(base, key(b"pixels", "quality: 90", ImageFormat::WebP));
+        assert_ne!(base, key(b"pixels", "quality: 80", ImageFormat::Avif));
+    }
+}
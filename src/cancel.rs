@@ -0,0 +1,119 @@
+// File: src\cancel.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Cancellation tokens that stop conversions cleanly, without partial outputs
+// License: MIT
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use crate::error::{ImgconvError, Result};
+
+/// Shared flag that asks running conversions to stop
+///
+/// Clones share the flag, so one clone can be handed to a conversion and
+/// another kept to call `cancel` from a different thread. A cancelled
+/// conversion returns `ImgconvError::Cancelled` and leaves no output behind.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Whether the token was or still may be cancelled: a token nothing else
+    /// holds a clone of can never fire
+    pub fn can_be_cancelled(&self) -> bool {
+        self.is_cancelled() || Arc::strong_count(&self.0) > 1
+    }
+
+    /// `Err(Cancelled)` once `cancel` was called
+    pub fn check(&self) -> Result<()> {
+        match self.is_cancelled() {
+            true => Err(ImgconvError::Cancelled),
+            false => Ok(()),
+        }
+    }
+}
+
+/// The token Ctrl-C cancels in the command-line tool, which every file
+/// written with `encode::write_file` checks
+#[doc(hidden)]
+pub fn process() -> &'static CancellationToken {
+    static PROCESS: OnceLock<CancellationToken> = OnceLock::new();
+    PROCESS.get_or_init(CancellationToken::new)
+}
+
+/// Writer that fails once its token is cancelled, so an encoder stops at its
+/// next write instead of finishing the image
+pub struct CancelWriter<W> {
+    inner: W,
+    token: CancellationToken,
+}
+
+impl<W> CancelWriter<W> {
+    pub fn new(inner: W, token: CancellationToken) -> Self {
+        Self { inner, token }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for CancelWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Not ErrorKind::Interrupted: write_all would just retry
+        if self.token.is_cancelled() {
+            return Err(io::Error::other(ImgconvError::Cancelled));
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: io::Seek> io::Seek for CancelWriter<W> {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_writer_stops_writing() {
+        let token = CancellationToken::new();
+        let mut writer = CancelWriter::new(Vec::new(), token.clone());
+        writer.write_all(b"abc").unwrap();
+        token.cancel();
+        assert!(writer.write_all(b"def").is_err());
+        assert_eq!(writer.into_inner(), b"abc");
+        assert!(matches!(token.check(), Err(ImgconvError::Cancelled)));
+    }
+
+    #[test]
+    fn test_only_shared_tokens_can_be_cancelled() {
+        let token = CancellationToken::new();
+        assert!(!token.can_be_cancelled());
+        let clone = token.clone();
+        assert!(token.can_be_cancelled());
+        clone.cancel();
+        drop(clone);
+        assert!(token.can_be_cancelled());
+    }
+}
@@ -0,0 +1,197 @@
+// File: src\chromakey.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Background removal: a keyed color to transparency (--chroma-key) or an external matting model (--remove-bg)
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::{imageops, DynamicImage, GenericImageView, GrayImage, Luma};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::color::Color;
+use crate::i18n::tr;
+use crate::print_info;
+
+/// The matting program run when IMGCONV_REMOVE_BG doesn't name another
+const DEFAULT_PROGRAM: &str = "rembg";
+
+/// Numbers the scratch directories, as parallel jobs may run the model at once
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Args, Debug)]
+pub struct ChromaKeyArgs {
+    /// Make the background COLOR transparent, e.g. "#00ff00" for a green screen
+    #[arg(long, value_name = "COLOR")]
+    pub chroma_key: Option<Color>,
+
+    /// How far colors may be from --chroma-key and still be keyed out, as a
+    /// percentage of the largest color difference; as wide a band beyond fades in
+    #[arg(long, value_name = "PERCENT", default_value = "10%", value_parser = parse_fuzz, requires = "chroma_key")]
+    pub fuzz: f32,
+
+    /// Soften the keyed edge over this many pixels
+    #[arg(long, value_name = "PIXELS", default_value_t = 1.0, requires = "chroma_key")]
+    pub feather: f32,
+
+    /// Cut out the subject with an external matting model (runs rembg, or the
+    /// program named by IMGCONV_REMOVE_BG, as PROGRAM i INPUT OUTPUT)
+    #[arg(long, conflicts_with = "chroma_key")]
+    pub remove_bg: bool,
+}
+
+impl ChromaKeyArgs {
+    /// Whether a background is keyed out or removed
+    pub fn is_active(&self) -> bool {
+        self.chroma_key.is_some() || self.remove_bg
+    }
+}
+
+fn parse_fuzz(s: &str) -> Result<f32, String> {
+    let percent: f32 = s.trim_end_matches('%').parse().map_err(|_| format!("Expected a percentage such as 12%, got: {}", s))?;
+    if !(0.0..=100.0).contains(&percent) {
+        return Err(format!("Fuzz must be between 0% and 100%, got: {}", s));
+    }
+    Ok(percent / 100.0)
+}
+
+/// Apply --chroma-key, returning the image unchanged when not requested
+///
+/// Edge pixels that are partly the key color get it taken back out, so no
+/// green fringe is left around the subject.
+pub fn apply_chroma_key(img: DynamicImage, args: &ChromaKeyArgs) -> Result<DynamicImage> {
+    let Some(Color(key)) = args.chroma_key else {
+        return Ok(img);
+    };
+    if !(0.0..=100.0).contains(&args.feather) {
+        anyhow::bail!("Feather must be between 0 and 100 pixels, got: {}", args.feather);
+    }
+    let key = [0, 1, 2].map(|c| key.0[c] as f32 / 255.0);
+    let mut canvas = img.to_rgba32f();
+    let (width, height) = canvas.dimensions();
+    let largest = 3f32.sqrt();
+    let matte = GrayImage::from_fn(width, height, |x, y| {
+        let pixel = canvas.get_pixel(x, y).0;
+        let distance = (0..3).map(|c| (pixel[c] - key[c]).powi(2)).sum::<f32>().sqrt() / largest;
+        let opacity = if args.fuzz > 0.0 { (distance / args.fuzz - 1.0).clamp(0.0, 1.0) } else { (distance > 0.0) as u8 as f32 };
+        Luma([(opacity * 255.0).round() as u8])
+    });
+    // Feathering only fades the subject's edge in; the background stays clear
+    let feathered = if args.feather > 0.0 { imageops::blur(&matte, args.feather / 2.0) } else { matte.clone() };
+    let mut keyed = 0u64;
+    for ((pixel, matte), feathered) in canvas.pixels_mut().zip(matte.pixels()).zip(feathered.pixels()) {
+        let opacity = matte.0[0] as f32 / 255.0;
+        if opacity > 0.0 && opacity < 1.0 {
+            for (value, key) in pixel.0.iter_mut().zip(key) {
+                *value = ((*value - (1.0 - opacity) * key) / opacity).clamp(0.0, 1.0);
+            }
+        }
+        let alpha = matte.0[0].min(feathered.0[0]);
+        if alpha < 255 {
+            keyed += 1;
+        }
+        pixel.0[3] *= alpha as f32 / 255.0;
+    }
+    print_info(&tr!("Keyed out {}% of the image", format!("{:.1}", keyed as f64 * 100.0 / (width as f64 * height as f64).max(1.0))));
+    Ok(with_alpha(DynamicImage::ImageRgba32F(canvas), img.color()))
+}
+
+/// Apply --remove-bg, returning the image unchanged when not requested
+pub fn apply_remove_bg(img: DynamicImage, args: &ChromaKeyArgs) -> Result<DynamicImage> {
+    if !args.remove_bg {
+        return Ok(img);
+    }
+    let program = std::env::var_os("IMGCONV_REMOVE_BG").map(PathBuf::from).unwrap_or_else(|| DEFAULT_PROGRAM.into());
+    let matte = run(&img, &program).context(
+        "Background removal failed; install rembg (pip install \"rembg[cli]\") or set IMGCONV_REMOVE_BG to a program run as PROGRAM i INPUT OUTPUT",
+    )?;
+    let mut matte = matte.to_luma_alpha8();
+    if matte.dimensions() != img.dimensions() {
+        matte = imageops::resize(&matte, img.width(), img.height(), imageops::FilterType::Triangle);
+    }
+    // Only the model's matte is used, so the pixels keep their full depth
+    let mut canvas = img.to_rgba32f();
+    for (pixel, matte) in canvas.pixels_mut().zip(matte.pixels()) {
+        pixel.0[3] *= matte.0[1] as f32 / 255.0;
+    }
+    print_info(&tr!("Background removed with {}", program.display()));
+    Ok(with_alpha(DynamicImage::ImageRgba32F(canvas), img.color()))
+}
+
+/// Run the matting program on `img`, returning its cutout
+fn run(img: &DynamicImage, program: &std::path::Path) -> Result<DynamicImage> {
+    let dir = std::env::temp_dir().join(format!("imgconv-bg-{}-{}", std::process::id(), RUNS.fetch_add(1, Ordering::Relaxed)));
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let result = (|| {
+        let (input, output) = (dir.join("in.png"), dir.join("out.png"));
+        DynamicImage::ImageRgb8(img.to_rgb8()).save(&input).context("Failed to write the model's input")?;
+        let result = Command::new(program)
+            .arg("i")
+            .arg(&input)
+            .arg(&output)
+            .stdin(Stdio::null())
+            .output()
+            .with_context(|| format!("could not run {}", program.display()))?;
+        if !result.status.success() {
+            let message = String::from_utf8_lossy(&result.stderr).trim().lines().last().unwrap_or_default().to_string();
+            anyhow::bail!("{} failed: {}", program.display(), message);
+        }
+        image::open(&output).context("Failed to read the model's output")
+    })();
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+/// `img` back in the layout of `original`, with an alpha channel added if it had none
+fn with_alpha(img: DynamicImage, original: image::ColorType) -> DynamicImage {
+    use image::ColorType::*;
+    match original {
+        L8 | La8 => DynamicImage::ImageLumaA8(img.to_luma_alpha8()),
+        L16 | La16 => DynamicImage::ImageLumaA16(img.to_luma_alpha16()),
+        Rgb16 | Rgba16 => DynamicImage::ImageRgba16(img.to_rgba16()),
+        Rgb32F | Rgba32F => img,
+        _ => DynamicImage::ImageRgba8(img.to_rgba8()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn args(feather: f32) -> ChromaKeyArgs {
+        ChromaKeyArgs { chroma_key: Some("#00ff00".parse().unwrap()), fuzz: 0.12, feather, remove_bg: false }
+    }
+
+    #[test]
+    fn test_chroma_key() {
+        // A red subject on the left of a slightly uneven green screen
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(8, 4, |x, y| if x < 4 { Rgb([200, 30, 30]) } else { Rgb([10, 240 + y as u8, 20]) }));
+        let out = apply_chroma_key(img, &args(0.0)).unwrap();
+        assert_eq!(out.color(), image::ColorType::Rgba8);
+        let out = out.into_rgba8();
+        assert_eq!(out.get_pixel(6, 1).0[3], 0);
+        assert_eq!(out.get_pixel(1, 1).0, [200, 30, 30, 255]);
+        assert_eq!(parse_fuzz("12%"), Ok(0.12));
+        assert!(parse_fuzz("120%").is_err());
+    }
+
+    #[test]
+    fn test_feathered_edge_loses_the_key_color() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(16, 4, |x, _| if x < 8 { Rgb([200, 30, 30]) } else { Rgb([0, 255, 0]) }));
+        let out = apply_chroma_key(img, &args(3.0)).unwrap().into_rgba8();
+        let edge = out.get_pixel(7, 1).0;
+        assert!(edge[3] > 0 && edge[3] < 255, "{:?}", edge);
+        assert_eq!(edge[..3], [200, 30, 30]);
+        // The screen itself stays fully transparent
+        assert_eq!(out.get_pixel(8, 1).0[3], 0);
+    }
+
+    #[test]
+    fn test_missing_program() {
+        let img = DynamicImage::ImageRgb8(RgbImage::new(4, 4));
+        assert!(run(&img, std::path::Path::new("imgconv-no-such-program")).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+// File: src\clipboard.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Clipboard image decoding: padded RGBA rows and Windows DIB/PNG fallbacks
+// License: MIT
+
+use anyhow::{Context, Result};
+use image::{DynamicImage, ImageFormat, RgbaImage};
+
+/// An RGBA image from clipboard pixels whose rows may be padded past `width * 4` bytes
+pub fn rgba_from_raw(width: usize, height: usize, bytes: &[u8]) -> Result<RgbaImage> {
+    let row = width * 4;
+    if height == 0 || !bytes.len().is_multiple_of(height) || bytes.len() / height < row {
+        anyhow::bail!("Clipboard image data has {} bytes, too few for {}x{} RGBA", bytes.len(), width, height);
+    }
+    let stride = bytes.len() / height;
+    let pixels = if stride == row {
+        bytes.to_vec()
+    } else {
+        bytes.chunks_exact(stride).flat_map(|line| &line[..row]).copied().collect()
+    };
+    RgbaImage::from_raw(width as u32, height as u32, pixels).context("Failed to create image from clipboard data")
+}
+
+/// Decode a CF_DIB/CF_DIBV5 payload (a bitmap header and pixels, without the
+/// file header), honouring its channel masks, row padding and bottom-up order
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn decode_dib(dib: &[u8]) -> Result<DynamicImage> {
+    // Compression values that put colour masks after a BITMAPINFOHEADER
+    const BI_BITFIELDS: u32 = 3;
+    const BI_ALPHABITFIELDS: u32 = 6;
+
+    let u16_at = |pos: usize| dib.get(pos..pos + 2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+    let u32_at = |pos: usize| dib.get(pos..pos + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    let (Some(header_size), Some(bit_count), Some(compression)) = (u32_at(0), u16_at(14), u32_at(16)) else {
+        anyhow::bail!("Clipboard bitmap is truncated");
+    };
+    let colors = match u32_at(32).unwrap_or(0) {
+        0 if bit_count <= 8 => 1 << bit_count,
+        used => used,
+    };
+    // A plain BITMAPINFOHEADER is followed by the masks; V4/V5 headers contain them
+    let masks = match compression {
+        BI_BITFIELDS if header_size == 40 => 12,
+        BI_ALPHABITFIELDS if header_size == 40 => 16,
+        _ => 0,
+    };
+    let offset = 14 + header_size + masks + colors * 4;
+    let mut bmp = Vec::with_capacity(14 + dib.len());
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&(14 + dib.len() as u32).to_le_bytes());
+    bmp.extend_from_slice(&[0; 4]);
+    bmp.extend_from_slice(&offset.to_le_bytes());
+    bmp.extend_from_slice(dib);
+    image::load_from_memory_with_format(&bmp, ImageFormat::Bmp).context("Failed to decode the clipboard bitmap")
+}
+
+/// Read the clipboard's "PNG" format, else CF_DIBV5 or CF_DIB, for when
+/// arboard's RGBA conversion fails
+#[cfg(windows)]
+pub fn windows_fallback() -> Result<(DynamicImage, Option<ImageFormat>)> {
+    use clipboard_win::formats::{RawData, CF_DIB, CF_DIBV5};
+    use clipboard_win::{get_clipboard, is_format_avail, register_format};
+
+    let read = |format: u32| get_clipboard::<Vec<u8>, _>(RawData(format)).ok().filter(|data| !data.is_empty());
+    if let Some(png) = register_format("PNG").and_then(|format| read(format.get())) {
+        let img = image::load_from_memory_with_format(&png, ImageFormat::Png)
+            .context("Failed to decode the clipboard's PNG data")?;
+        return Ok((img, Some(ImageFormat::Png)));
+    }
+    for format in [CF_DIBV5, CF_DIB] {
+        if let Some(dib) = is_format_avail(format).then(|| read(format)).flatten() {
+            return Ok((decode_dib(&dib)?, Some(ImageFormat::Bmp)));
+        }
+    }
+    anyhow::bail!("The clipboard has no PNG or bitmap data")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_padded_rows() {
+        // 1x2 image with rows padded to 8 bytes
+        let bytes = [1, 2, 3, 4, 0, 0, 0, 0, 5, 6, 7, 8, 0, 0, 0, 0];
+        let img = rgba_from_raw(1, 2, &bytes).unwrap();
+        assert_eq!(img.into_raw(), [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(rgba_from_raw(3, 2, &bytes).is_err());
+    }
+
+    #[test]
+    fn test_decode_dib_bgra() {
+        // 2x1, 32 bpp, BI_BITFIELDS with BGRA masks after a BITMAPINFOHEADER
+        let mut dib = Vec::new();
+        for value in [40u32, 2, 1] {
+            dib.extend_from_slice(&value.to_le_bytes());
+        }
+        dib.extend_from_slice(&1u16.to_le_bytes());
+        dib.extend_from_slice(&32u16.to_le_bytes());
+        dib.extend_from_slice(&3u32.to_le_bytes()); // BI_BITFIELDS
+        dib.extend_from_slice(&[0; 20]);
+        for mask in [0x00FF_0000u32, 0x0000_FF00, 0x0000_00FF] {
+            dib.extend_from_slice(&mask.to_le_bytes());
+        }
+        dib.extend_from_slice(&[0x30, 0x20, 0x10, 0xFF, 0xFF, 0x00, 0x00, 0xFF]); // B G R A per pixel
+        let img = decode_dib(&dib).unwrap().to_rgba8();
+        assert_eq!(img.get_pixel(0, 0).0[..3], [0x10, 0x20, 0x30]);
+        assert_eq!(img.get_pixel(1, 0).0[..3], [0x00, 0x00, 0xFF]);
+    }
+}
@@ -0,0 +1,137 @@
+// File: src\cmyk.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: CMYK/YCCK JPEG decoding with Adobe inversion and ICC conversion
+// License: MIT
+
+use anyhow::{Context, Result};
+use image::{DynamicImage, RgbImage};
+use moxcms::{ColorProfile, DataColorSpace, Layout, TransformOptions};
+use zune_core::bytestream::ZCursor;
+use zune_core::colorspace::ColorSpace;
+use zune_core::options::DecoderOptions;
+use zune_jpeg::JpegDecoder;
+
+use crate::i18n::tr;
+use crate::{print_info, print_warning};
+
+/// Decode a four-component JPEG to sRGB, None for ordinary gray/YCbCr files
+pub fn decode(data: &[u8]) -> Result<Option<DynamicImage>> {
+    let mut probe = JpegDecoder::new(ZCursor::new(data));
+    probe.decode_headers().map_err(|e| anyhow::anyhow!("{:?}", e)).context("Failed to read JPEG header")?;
+    let source = match probe.input_colorspace() {
+        Some(space @ (ColorSpace::CMYK | ColorSpace::YCCK)) => space,
+        _ => return Ok(None),
+    };
+    let (width, height) = probe.dimensions().context("Failed to read JPEG dimensions")?;
+    let icc = probe.icc_profile();
+
+    // Ask for the stored components untouched; we do the color math ourselves
+    let options = DecoderOptions::default().set_strict_mode(false).jpeg_set_out_colorspace(source);
+    let mut decoder = JpegDecoder::new_with_options(ZCursor::new(data), options);
+    let raw = decoder.decode().map_err(|e| anyhow::anyhow!("{:?}", e)).context("Failed to decode CMYK JPEG")?;
+
+    let inverted = adobe_transform(data).is_some();
+    let ink = to_ink(&raw, source, inverted);
+    let kind = if source == ColorSpace::YCCK { "YCCK" } else { "CMYK" };
+    print_info(&if inverted { tr!("{} JPEG (Adobe inverted)", kind) } else { tr!("{} JPEG", kind) });
+
+    let rgb = match icc.as_deref().map(|icc| icc_to_srgb(icc, &ink)) {
+        Some(Ok(rgb)) => {
+            print_info(&tr!("Converted CMYK using the embedded ICC profile"));
+            rgb
+        }
+        Some(Err(e)) => {
+            print_warning(&tr!("Embedded ICC profile unusable ({}), using naive CMYK conversion", format!("{:#}", e)));
+            naive_to_rgb(&ink)
+        }
+        None => naive_to_rgb(&ink),
+    };
+
+    let img = RgbImage::from_raw(width as u32, height as u32, rgb).context("CMYK JPEG has an unexpected buffer size")?;
+    Ok(Some(DynamicImage::ImageRgb8(img)))
+}
+
+/// Transform byte of the Adobe APP14 marker, if present
+///
+/// Photoshop and most print tools write CMYK inverted (0 = full ink) and tag
+/// it with this marker; 0 = CMYK, 2 = YCCK.
+fn adobe_transform(data: &[u8]) -> Option<u8> {
+    let mut pos = 2; // skip SOI
+    while pos + 4 <= data.len() && data[pos] == 0xFF {
+        let marker = data[pos + 1];
+        if marker == 0xDA || marker == 0xD9 {
+            break; // start of scan / end of image: no more headers
+        }
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let payload = data.get(pos + 4..pos + 2 + length)?;
+        if marker == 0xEE && payload.len() >= 12 && payload.starts_with(b"Adobe") {
+            return Some(payload[11]);
+        }
+        pos += 2 + length;
+    }
+    None
+}
+
+/// Normalise stored samples to ink amounts (0 = no ink, 255 = full coverage)
+fn to_ink(raw: &[u8], source: ColorSpace, inverted: bool) -> Vec<u8> {
+    let mut ink = raw.to_vec();
+    for px in ink.chunks_exact_mut(4) {
+        if source == ColorSpace::YCCK {
+            // YCC encodes the inverted CMY as if it were RGB; K is stored as is
+            let (y, cb, cr) = (px[0] as f32, px[1] as f32 - 128.0, px[2] as f32 - 128.0);
+            let r = (y + 1.402 * cr).round().clamp(0.0, 255.0) as u8;
+            let g = (y - 0.344_136 * cb - 0.714_136 * cr).round().clamp(0.0, 255.0) as u8;
+            let b = (y + 1.772 * cb).round().clamp(0.0, 255.0) as u8;
+            px[..3].copy_from_slice(&[255 - r, 255 - g, 255 - b]);
+        }
+        if inverted {
+            px.iter_mut().for_each(|v| *v = 255 - *v);
+        }
+    }
+    ink
+}
+
+/// Simple subtractive model used when no profile is embedded
+fn naive_to_rgb(ink: &[u8]) -> Vec<u8> {
+    ink.chunks_exact(4)
+        .flat_map(|px| {
+            let white = 255 - px[3] as u32;
+            [0, 1, 2].map(|c| ((255 - px[c] as u32) * white / 255) as u8)
+        })
+        .collect()
+}
+
+/// Convert ink values to sRGB through an embedded CMYK ICC profile
+fn icc_to_srgb(icc: &[u8], ink: &[u8]) -> Result<Vec<u8>> {
+    let profile = ColorProfile::new_from_slice(icc).map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    if profile.color_space != DataColorSpace::Cmyk {
+        anyhow::bail!("profile is {:?}, not CMYK", profile.color_space);
+    }
+    // CMYK travels through the four-channel Rgba layout
+    let transform = profile
+        .create_transform_8bit(Layout::Rgba, &ColorProfile::new_srgb(), Layout::Rgb, TransformOptions::default())
+        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    let mut rgb = vec![0u8; ink.len() / 4 * 3];
+    transform.transform(ink, &mut rgb).map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    Ok(rgb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_naive_to_rgb() {
+        assert_eq!(naive_to_rgb(&[0, 0, 0, 0, 255, 0, 0, 0, 0, 0, 0, 255]), vec![255, 255, 255, 0, 255, 255, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_adobe_marker() {
+        let mut data = vec![0xFF, 0xD8, 0xFF, 0xEE, 0x00, 0x0E];
+        data.extend_from_slice(b"Adobe\x00\x64\x00\x00\x00\x00\x02");
+        data.extend_from_slice(&[0xFF, 0xDA]);
+        assert_eq!(adobe_transform(&data), Some(2));
+        assert_eq!(adobe_transform(&[0xFF, 0xD8, 0xFF, 0xDA]), None);
+    }
+}
@@ -0,0 +1,77 @@
+// File: src\color.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Color arguments ("#rgb", "#rrggbbaa", named colors)
+// License: MIT
+
+use image::Rgba;
+use std::fmt;
+use std::str::FromStr;
+
+/// RGBA color parsed from a hex string or a handful of common names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color(pub Rgba<u8>);
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [r, g, b, a] = self.0 .0;
+        if a == 255 {
+            write!(f, "#{:02x}{:02x}{:02x}", r, g, b)
+        } else {
+            write!(f, "#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let named = match s.to_lowercase().as_str() {
+            "black" => Some([0, 0, 0, 255]),
+            "white" => Some([255, 255, 255, 255]),
+            "red" => Some([255, 0, 0, 255]),
+            "green" => Some([0, 255, 0, 255]),
+            "blue" => Some([0, 0, 255, 255]),
+            "gray" | "grey" => Some([128, 128, 128, 255]),
+            "transparent" => Some([0, 0, 0, 0]),
+            _ => None,
+        };
+        if let Some(rgba) = named {
+            return Ok(Color(Rgba(rgba)));
+        }
+
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        let invalid = || format!("Invalid color '{}', expected #rgb, #rgba, #rrggbb or #rrggbbaa", s);
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        // Short forms repeat each digit: #f80 -> #ff8800
+        let expanded: String = match hex.len() {
+            3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 | 8 => hex.to_string(),
+            _ => return Err(invalid()),
+        };
+
+        let channel = |i: usize| u8::from_str_radix(&expanded[i * 2..i * 2 + 2], 16).map_err(|_| invalid());
+        let alpha = if expanded.len() == 8 { channel(3)? } else { 255 };
+        Ok(Color(Rgba([channel(0)?, channel(1)?, channel(2)?, alpha])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!("#000".parse::<Color>(), Ok(Color(Rgba([0, 0, 0, 255]))));
+        assert_eq!("#ffffffcc".parse::<Color>(), Ok(Color(Rgba([255, 255, 255, 0xcc]))));
+        assert_eq!("ff8000".parse::<Color>(), Ok(Color(Rgba([255, 128, 0, 255]))));
+        assert_eq!("White".parse::<Color>(), Ok(Color(Rgba([255, 255, 255, 255]))));
+        assert!("#12345".parse::<Color>().is_err());
+        assert!("#gggggg".parse::<Color>().is_err());
+        assert_eq!("#FFF".parse::<Color>().unwrap().to_string(), "#ffffff");
+    }
+}
@@ -0,0 +1,112 @@
+// File: src\colorspace.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: sRGB / linear-light conversions for processing stages
+// License: MIT
+
+use image::{ColorType, DynamicImage, Rgba32FImage};
+
+/// Encoding a processing stage expects its pixels in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colorspace {
+    /// Gamma-encoded values as stored in the file (the default for most stages)
+    Srgb,
+    /// Linear light, where averaging pixels matches how light mixes
+    Linear,
+}
+
+/// Run `stage` on `img` in `space`, handing back the original pixel layout
+///
+/// Stages that average or blend pixels (resampling, blurs) should opt into
+/// `Colorspace::Linear`; in `Srgb` the image is passed through unchanged.
+pub fn run_in(space: Colorspace, img: DynamicImage, stage: impl FnOnce(DynamicImage) -> DynamicImage) -> DynamicImage {
+    match space {
+        Colorspace::Srgb => stage(img),
+        Colorspace::Linear => {
+            let color_type = img.color();
+            let linear = DynamicImage::ImageRgba32F(to_linear(&img));
+            from_linear(stage(linear).to_rgba32f(), color_type)
+        }
+    }
+}
+
+/// Decode sRGB color channels to linear light; alpha is left as is
+pub fn to_linear(img: &DynamicImage) -> Rgba32FImage {
+    let mut canvas = img.to_rgba32f();
+    for pixel in canvas.pixels_mut() {
+        for c in &mut pixel.0[..3] {
+            *c = srgb_to_linear(*c);
+        }
+    }
+    canvas
+}
+
+/// Encode linear light back to sRGB in the layout of `original`
+pub fn from_linear(mut canvas: Rgba32FImage, original: ColorType) -> DynamicImage {
+    for pixel in canvas.pixels_mut() {
+        for c in &mut pixel.0[..3] {
+            *c = linear_to_srgb(c.clamp(0.0, 1.0));
+        }
+    }
+
+    with_color_type(DynamicImage::ImageRgba32F(canvas), original)
+}
+
+/// `img` converted to the pixel layout `original` describes
+pub fn with_color_type(img: DynamicImage, original: ColorType) -> DynamicImage {
+    match original {
+        ColorType::L8 => DynamicImage::ImageLuma8(img.to_luma8()),
+        ColorType::La8 => DynamicImage::ImageLumaA8(img.to_luma_alpha8()),
+        ColorType::Rgb8 => DynamicImage::ImageRgb8(img.to_rgb8()),
+        ColorType::Rgba8 => DynamicImage::ImageRgba8(img.to_rgba8()),
+        ColorType::L16 => DynamicImage::ImageLuma16(img.to_luma16()),
+        ColorType::La16 => DynamicImage::ImageLumaA16(img.to_luma_alpha16()),
+        ColorType::Rgb16 => DynamicImage::ImageRgb16(img.to_rgb16()),
+        ColorType::Rgba16 => DynamicImage::ImageRgba16(img.into_rgba16()),
+        ColorType::Rgb32F => DynamicImage::ImageRgb32F(img.to_rgb32f()),
+        ColorType::Rgba32F => DynamicImage::ImageRgba32F(img.into_rgba32f()),
+        // Layouts newer than this list keep whatever the stage produced
+        _ => img,
+    }
+}
+
+/// sRGB transfer function, encoded value to linear (0.0-1.0)
+pub fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.040_45 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// sRGB transfer function, linear value to encoded (0.0-1.0)
+pub fn linear_to_srgb(v: f32) -> f32 {
+    if v <= 0.003_130_8 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_transfer_round_trip() {
+        for v in [0.0, 0.02, 0.2, 0.5, 1.0] {
+            assert!((linear_to_srgb(srgb_to_linear(v)) - v).abs() < 1e-5);
+        }
+        // Mid-gray in sRGB is about 21% linear light
+        assert!((srgb_to_linear(0.5) - 0.214).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_run_in_linear_keeps_layout() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 1, Rgb([10, 128, 250])));
+        let out = run_in(Colorspace::Linear, img, |linear| linear);
+        assert_eq!(out.color(), ColorType::Rgb8);
+        assert_eq!(out.to_rgb8().get_pixel(0, 0), &Rgb([10, 128, 250]));
+    }
+}
@@ -0,0 +1,122 @@
+// File: src\confirm.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Summary and confirmation prompt before destructive runs (--yes skips it)
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use crate::dispose::DisposeArgs;
+use crate::i18n::tr;
+use crate::inplace::InPlaceArgs;
+use crate::print_warning;
+
+/// Overwriting fewer existing outputs than this is routine and not asked about
+const MANY_OVERWRITES: usize = 10;
+
+/// Existing outputs named in the summary
+const EXAMPLES: usize = 3;
+
+#[derive(Args, Debug)]
+pub struct ConfirmArgs {
+    /// Don't ask before overwriting many files, deleting sources or converting in place without a backup
+    #[arg(short = 'y', long)]
+    pub yes: bool,
+}
+
+/// What a run is about to do, collected before anything is written
+#[derive(Debug, Default)]
+pub struct Plan {
+    files: usize,
+    overwrites: Vec<PathBuf>,
+    in_place_without_backup: bool,
+    delete_sources: bool,
+}
+
+impl Plan {
+    pub fn new(in_place: &InPlaceArgs, dispose: &DisposeArgs) -> Self {
+        Self {
+            in_place_without_backup: in_place.in_place && in_place.no_backup,
+            delete_sources: dispose.delete_source,
+            ..Self::default()
+        }
+    }
+
+    /// Count one file about to be converted to `output`
+    pub fn add(&mut self, output: &Path) {
+        self.files += 1;
+        // In-place runs always replace their input, which the backup choice covers
+        if !self.in_place_without_backup && output.exists() {
+            self.overwrites.push(output.to_path_buf());
+        }
+    }
+
+    /// Lines describing the destructive parts of the plan; empty when there are none
+    fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.overwrites.len() >= MANY_OVERWRITES {
+            let examples: Vec<String> = self.overwrites.iter().take(EXAMPLES).map(|p| p.display().to_string()).collect();
+            lines.push(format!("overwrite {} existing files ({}, ...)", self.overwrites.len(), examples.join(", ")));
+        }
+        if self.in_place_without_backup {
+            lines.push(format!("replace {} originals in place without a backup", self.files));
+        }
+        if self.delete_sources {
+            lines.push(format!("delete {} source files after converting them", self.files));
+        }
+        lines
+    }
+
+    /// Ask before a destructive run; declining aborts it
+    ///
+    /// Nothing is asked with --yes or when stdin is not a terminal (scripts,
+    /// `--files-from -`), so unattended runs behave as before.
+    pub fn confirm(&self, args: &ConfirmArgs) -> Result<()> {
+        let summary = self.summary();
+        if summary.is_empty() || args.yes || !std::io::stdin().is_terminal() {
+            return Ok(());
+        }
+        print_warning(&tr!("This run will:"));
+        for line in &summary {
+            eprintln!("    - {}", line);
+        }
+        eprint!("Continue? [y/N] ");
+        std::io::stderr().flush().ok();
+        let mut answer = String::new();
+        std::io::stdin().lock().read_line(&mut answer).context("Failed to read the answer")?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            anyhow::bail!("Cancelled, nothing was converted (pass --yes to skip this question)");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_lists_destructive_steps() {
+        let dir = std::env::temp_dir().join(format!("imgconv-confirm-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut plan = Plan { delete_sources: true, ..Plan::default() };
+        for i in 0..MANY_OVERWRITES {
+            let output = dir.join(format!("{}.webp", i));
+            std::fs::write(&output, b"x").unwrap();
+            plan.add(&output);
+        }
+        plan.add(&dir.join("new.webp"));
+        let summary = plan.summary();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(summary.len(), 2);
+        assert!(summary[0].starts_with("overwrite 10 existing files ("));
+        assert_eq!(summary[1], "delete 11 source files after converting them");
+
+        let mut routine = Plan::default();
+        routine.add(Path::new("Cargo.toml"));
+        assert!(routine.summary().is_empty());
+    }
+}
@@ -0,0 +1,343 @@
+// File: src\converter.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Builder-style conversion API for programs embedding imgconv
+// License: MIT
+
+use image::{DynamicImage, GenericImageView, ImageDecoder, ImageFormat, ImageReader};
+use std::fs::File;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::cancel::{CancelWriter, CancellationToken};
+use crate::encode::{self, Metadata};
+use crate::encoder::{EncoderOptions, Encoders};
+use crate::error::{BoxError, ImgconvError, Result};
+use crate::geometry::Gravity;
+use crate::pipeline::{Op, Pipeline, Rotation};
+use crate::resize::{self, Filter, ResizeArgs};
+use crate::transport::{ImageSink, ImageSource};
+use crate::{cmyk, formats};
+
+/// How `Converter::resize` changes the image size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resize {
+    /// Scale to fit within width x height, keeping the aspect ratio
+    Fit(u32, u32),
+    /// Scale to exactly width x height
+    Exact(u32, u32),
+}
+
+/// A conversion configured in code, the library counterpart of the command line
+///
+/// Starts out like `imgconv` without options: quality 90, no resizing,
+/// Lanczos3 filtering, output format from the destination extension and
+/// metadata stripped.
+#[derive(Debug, Clone)]
+pub struct Converter {
+    encoders: Encoders,
+    pipeline: Pipeline<'static>,
+    filter: Filter,
+    format: Option<ImageFormat>,
+    strip_metadata: bool,
+    pub(crate) token: CancellationToken,
+}
+
+impl Default for Converter {
+    fn default() -> Self {
+        Self {
+            encoders: Encoders::new(90),
+            pipeline: Pipeline::new(),
+            filter: Filter::Lanczos3,
+            format: None,
+            strip_metadata: true,
+            token: CancellationToken::new(),
+        }
+    }
+}
+
+impl Converter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encoder quality (1-100) for JPEG and AVIF, unless `encoder` sets
+    /// one for the format
+    pub fn quality(mut self, quality: u8) -> Self {
+        self.encoders.quality = quality;
+        self
+    }
+
+    /// Options for one format's encoder, replacing earlier ones for it
+    pub fn encoder(mut self, options: EncoderOptions) -> Self {
+        self.encoders.set(options);
+        self
+    }
+
+    pub fn resize(self, resize: Resize) -> Self {
+        let filter = self.filter;
+        self.op(Op::Resize { to: resize, filter, linear: false, gpu: false })
+    }
+
+    /// Resampling filter used by `resize`
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        for op in &mut self.pipeline.0 {
+            if let Op::Resize { filter: resize_filter, .. } = op {
+                *resize_filter = filter;
+            }
+        }
+        self
+    }
+
+    pub fn crop(self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        self.op(Op::Crop { x, y, width, height })
+    }
+
+    pub fn rotate(self, rotation: Rotation) -> Self {
+        self.op(Op::Rotate(rotation))
+    }
+
+    /// Add `brightness` (-255 to 255) and change the contrast by `contrast` percent
+    pub fn adjust(self, brightness: i32, contrast: f32) -> Self {
+        self.op(Op::ColorAdjust { brightness, contrast })
+    }
+
+    /// Overlay `image` at `gravity`, `margin` pixels from the edges, at `opacity` (0 to 1)
+    pub fn watermark(self, image: DynamicImage, gravity: Gravity, margin: u32, opacity: f32) -> Self {
+        self.op(Op::Watermark { image: Arc::new(image), gravity, margin, opacity })
+    }
+
+    /// Add any step; steps run in the order they were added
+    pub fn op(mut self, op: Op<'static>) -> Self {
+        self.pipeline.push(op);
+        self
+    }
+
+    /// The steps configured so far
+    pub fn pipeline(&self) -> &Pipeline<'static> {
+        &self.pipeline
+    }
+
+    /// Output format, instead of guessing it from the destination extension
+    pub fn format(mut self, format: ImageFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Drop the color profile and EXIF block (the default); with `false`
+    /// they are kept where the output format can hold them
+    pub fn strip_metadata(mut self, strip: bool) -> Self {
+        self.strip_metadata = strip;
+        self
+    }
+
+    /// Stop with `ImgconvError::Cancelled`, writing nothing, once `token` is
+    /// cancelled
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Convert `src` into `dst`, returning the size of the written image
+    pub fn run(&self, src: impl AsRef<Path>, dst: impl AsRef<Path>) -> Result<(u32, u32)> {
+        let (src, dst) = (src.as_ref(), dst.as_ref());
+        let format = self.output_format(dst)?;
+        let data = std::fs::read(src).map_err(ImgconvError::io(format!("Failed to read {}", src.display())))?;
+        let (output, size) = self.convert(&data, format, &src.display().to_string())?;
+        write_atomic(dst, &output, &self.token)?;
+        Ok(size)
+    }
+
+    /// Convert the image `source` yields and hand it to `sink`, in the format
+    /// set with `format` or else the one `sink`'s name ends in
+    pub fn convert_io(&self, source: &mut dyn ImageSource, sink: &mut dyn ImageSink) -> Result<(u32, u32)> {
+        let format = self.output_format(Path::new(&sink.name()))?;
+        let data = source.read()?;
+        let (output, size) = self.convert(&data, format, &source.name())?;
+        self.token.check()?;
+        sink.write(&output, format)?;
+        Ok(size)
+    }
+
+    /// Check the options and pick the format to write `dst` in
+    pub(crate) fn output_format(&self, dst: &Path) -> Result<ImageFormat> {
+        let format = match self.format {
+            Some(format) => format,
+            None => dst
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(formats::from_extension)
+                .ok_or_else(|| {
+                    ImgconvError::UnsupportedFormat(format!("Cannot tell the output format from {}", dst.display()))
+                })?,
+        };
+        self.check_format(format)?;
+        Ok(format)
+    }
+
+    pub(crate) fn check_format(&self, format: ImageFormat) -> Result<()> {
+        self.encoders.validate().map_err(ImgconvError::InvalidOption)?;
+        if !format.writing_enabled() {
+            return Err(ImgconvError::UnsupportedFormat(format!("Writing {:?} is not supported in this build", format)));
+        }
+        Ok(())
+    }
+
+    /// Convert an encoded image held in memory to `format`, returning the
+    /// encoded output and its size
+    pub fn convert_bytes(&self, data: &[u8], format: ImageFormat) -> Result<(Vec<u8>, (u32, u32))> {
+        self.check_format(format)?;
+        self.convert(data, format, "the image data")
+    }
+
+    /// Decode `data` (named `input` in errors), apply the options and encode
+    /// the result as `format`
+    pub(crate) fn convert(&self, data: &[u8], format: ImageFormat, input: &str) -> Result<(Vec<u8>, (u32, u32))> {
+        self.token.check()?;
+        let (img, metadata) = self.decode(data).map_err(|source| ImgconvError::Decode { input: input.to_string(), source })?;
+        self.token.check()?;
+        let img = storable(self.transform(img, format)?, format);
+        self.token.check()?;
+        let mut output = Cursor::new(Vec::new());
+        // A clone held here would make even an unshared token look cancellable
+        let encoded = if self.token.can_be_cancelled() {
+            let mut writer = CancelWriter::new(&mut output, self.token.clone());
+            encode::encode_image_with_metadata(&img, format, &self.encoders, &metadata, &self.token, &mut writer)
+        } else {
+            encode::encode_image_with_metadata(&img, format, &self.encoders, &metadata, &self.token, &mut output)
+        };
+        encoded.map_err(|source| match self.token.check() {
+            Err(cancelled) => cancelled,
+            Ok(()) => ImgconvError::Encode { format, source: source.into() },
+        })?;
+        Ok((output.into_inner(), img.dimensions()))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<(DynamicImage, Metadata), BoxError> {
+        let reader = ImageReader::new(Cursor::new(data)).with_guessed_format()?;
+        if reader.format() == Some(ImageFormat::Jpeg) && self.strip_metadata {
+            // Handles CMYK JPEGs, which the plain decoder rejects
+            if let Some(img) = cmyk::decode(data)? {
+                return Ok((img, Metadata::default()));
+            }
+        }
+        let mut decoder = reader.into_decoder()?;
+        let metadata = if self.strip_metadata {
+            Metadata::default()
+        } else {
+            Metadata { icc_profile: decoder.icc_profile()?, exif: decoder.exif_metadata()? }
+        };
+        Ok((DynamicImage::from_decoder(decoder)?, metadata))
+    }
+
+    fn transform(&self, img: DynamicImage, format: ImageFormat) -> Result<DynamicImage> {
+        let img = self.pipeline.apply(img)?;
+        let args = ResizeArgs { resize: None, filter: self.filter, linear_resize: false, gpu: false, no_auto_downscale: false };
+        Ok(resize::fit_ico(img, &args, format))
+    }
+}
+
+/// Convert the image read from `reader` and write it to `writer`, in the
+/// format set with `Converter::format`, without touching the filesystem
+///
+/// Decoders and encoders need to seek, so the input and the output are each
+/// held in memory once. Returns the size of the written image.
+pub fn convert_stream(mut reader: impl Read, mut writer: impl Write, options: &Converter) -> Result<(u32, u32)> {
+    let format = options.format.ok_or_else(|| {
+        ImgconvError::UnsupportedFormat("convert_stream needs an output format, set it with Converter::format".to_string())
+    })?;
+    options.check_format(format)?;
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).map_err(ImgconvError::io("Failed to read the input stream".to_string()))?;
+    let (output, size) = options.convert(&data, format, "the input stream")?;
+    writer
+        .write_all(&output)
+        .and_then(|_| writer.flush())
+        .map_err(ImgconvError::io("Failed to write the output stream".to_string()))?;
+    Ok(size)
+}
+
+/// Write `data` to `dst` through a temporary file, so a failure never leaves
+/// a truncated output behind
+pub(crate) fn write_atomic(dst: &Path, data: &[u8], token: &CancellationToken) -> Result<()> {
+    let temp = encode::temp_path(dst);
+    let written = File::create(&temp)
+        .and_then(|mut file| file.write_all(data).and_then(|_| file.sync_all()))
+        .map_err(ImgconvError::io(format!("Failed to write {}", dst.display())))
+        .and_then(|_| token.check())
+        .and_then(|_| std::fs::rename(&temp, dst).map_err(ImgconvError::io(format!("Failed to write {}", dst.display()))));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    written
+}
+
+/// `img` in pixels `format` can hold: alpha is dropped where unsupported
+/// and 16-bit or float samples are reduced to 8 bits
+fn storable(img: DynamicImage, format: ImageFormat) -> DynamicImage {
+    let color = img.color();
+    let alpha = color.has_alpha() && encode::supports_alpha(format);
+    let deep = color.bytes_per_pixel() > color.channel_count();
+    if format == ImageFormat::Hdr || (alpha == color.has_alpha() && (!deep || encode::supports_16bit(format))) {
+        return img;
+    }
+    match (alpha, color.has_color()) {
+        (true, _) => DynamicImage::ImageRgba8(img.to_rgba8()),
+        (false, true) => DynamicImage::ImageRgb8(img.to_rgb8()),
+        (false, false) => DynamicImage::ImageLuma8(img.to_luma8()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder::{PngOpts, WebpOpts};
+    use crate::transport::{MemorySink, MemorySource};
+
+    #[test]
+    fn test_convert_with_options() {
+        let dir = std::env::temp_dir().join(format!("imgconv-converter-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (src, dst) = (dir.join("in.png"), dir.join("out.JPG"));
+        DynamicImage::ImageRgba8(image::RgbaImage::new(400, 200)).save(&src).unwrap();
+
+        let size = Converter::new().quality(80).resize(Resize::Fit(100, 100)).run(&src, &dst).unwrap();
+        assert_eq!(size, (100, 50));
+        assert_eq!(image::open(&dst).unwrap().dimensions(), (100, 50));
+        assert!(matches!(Converter::new().quality(0).run(&src, &dst), Err(ImgconvError::InvalidOption(_))));
+        let webp = Converter::new().encoder(EncoderOptions::WebP(WebpOpts));
+        assert!(webp.run(&src, dir.join("out.webp")).is_ok());
+        let data = std::fs::read(&src).unwrap();
+        let png = |level| Converter::new().encoder(EncoderOptions::Png(PngOpts { level: Some(level) })).convert_bytes(&data, ImageFormat::Png);
+        assert!(png(0).unwrap().0.len() > png(9).unwrap().0.len());
+        assert!(matches!(Converter::new().run(&src, dir.join("out.xyz")), Err(ImgconvError::UnsupportedFormat(_))));
+        assert!(Converter::new().run(&dst, dir.join("b.png")).is_ok());
+        assert!(matches!(Converter::new().run(dir.join("none.png"), &dst), Err(ImgconvError::Io { .. })));
+        std::fs::write(&src, b"not an image").unwrap();
+        assert!(matches!(Converter::new().run(&src, &dst), Err(ImgconvError::Decode { .. })));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_convert_stream() {
+        let mut input = Vec::new();
+        image::RgbImage::new(30, 20).write_to(&mut Cursor::new(&mut input), ImageFormat::Png).unwrap();
+
+        let mut output = Vec::new();
+        let options = Converter::new().format(ImageFormat::Bmp).resize(Resize::Exact(10, 10));
+        assert_eq!(convert_stream(input.as_slice(), &mut output, &options).unwrap(), (10, 10));
+        assert_eq!(image::guess_format(&output).unwrap(), ImageFormat::Bmp);
+        assert!(convert_stream(input.as_slice(), Vec::new(), &Converter::new()).is_err());
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let mut sink = MemorySink::default();
+        assert_eq!(options.convert_io(&mut MemorySource(input.clone()), &mut sink).unwrap(), (10, 10));
+        assert_eq!(sink.format, Some(ImageFormat::Bmp));
+
+        let cancelled = convert_stream(input.as_slice(), Vec::new(), &options.cancellation(token));
+        assert!(matches!(cancelled, Err(ImgconvError::Cancelled)));
+    }
+}
@@ -0,0 +1,110 @@
+// File: src\convolve.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Square convolution kernels and applying them to images or single planes
+// License: MIT
+
+use image::{Rgba, Rgba32FImage};
+
+/// A square kernel of odd size, applied as correlation (the weights are not
+/// flipped), so it reads the way it is written: row by row, top left first
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kernel {
+    size: usize,
+    weights: Vec<f32>,
+}
+
+impl Kernel {
+    /// A kernel from its weights, `size` x `size` of them
+    pub fn new(size: usize, weights: Vec<f32>) -> Result<Self, String> {
+        if size.is_multiple_of(2) || weights.len() != size * size {
+            return Err(format!("A kernel needs an odd size and size² weights, got {} weights for size {}", weights.len(), size));
+        }
+        Ok(Kernel { size, weights })
+    }
+
+    /// Horizontal Sobel gradient, positive where it gets brighter to the right
+    pub fn sobel_x() -> Self {
+        Kernel { size: 3, weights: vec![-1.0, 0.0, 1.0, -2.0, 0.0, 2.0, -1.0, 0.0, 1.0] }
+    }
+
+    /// Vertical Sobel gradient, positive where it gets brighter downwards
+    pub fn sobel_y() -> Self {
+        Kernel { size: 3, weights: vec![-1.0, -2.0, -1.0, 0.0, 0.0, 0.0, 1.0, 2.0, 1.0] }
+    }
+
+    /// Normalized Gaussian blur reaching three sigmas out
+    pub fn gaussian(sigma: f32) -> Self {
+        let radius = (sigma * 3.0).ceil().max(1.0) as i64;
+        let size = (radius * 2 + 1) as usize;
+        let mut weights: Vec<f32> = (-radius..=radius)
+            .flat_map(|dy| (-radius..=radius).map(move |dx| (-((dx * dx + dy * dy) as f32) / (2.0 * sigma * sigma)).exp()))
+            .collect();
+        let total: f32 = weights.iter().sum();
+        weights.iter_mut().for_each(|weight| *weight /= total);
+        Kernel { size, weights }
+    }
+
+    /// Weighted sum around (x, y), reading pixels through `sample` with
+    /// coordinates clamped to the `width` x `height` edges
+    fn sum(&self, x: u32, y: u32, width: u32, height: u32, mut sample: impl FnMut(u32, u32) -> f32) -> f32 {
+        let radius = (self.size / 2) as i64;
+        let mut total = 0.0;
+        for (i, weight) in self.weights.iter().enumerate().filter(|(_, weight)| **weight != 0.0) {
+            let nx = (x as i64 + i as i64 % self.size as i64 - radius).clamp(0, width as i64 - 1) as u32;
+            let ny = (y as i64 + i as i64 / self.size as i64 - radius).clamp(0, height as i64 - 1) as u32;
+            total += weight * sample(nx, ny);
+        }
+        total
+    }
+}
+
+/// Convolve the color channels of `img`, keeping alpha; results are not
+/// clamped, so signed kernels keep their sign
+pub fn convolve(img: &Rgba32FImage, kernel: &Kernel) -> Rgba32FImage {
+    let (width, height) = img.dimensions();
+    Rgba32FImage::from_fn(width, height, |x, y| {
+        let mut pixel = [0.0, 0.0, 0.0, img.get_pixel(x, y).0[3]];
+        for (channel, value) in pixel.iter_mut().take(3).enumerate() {
+            *value = kernel.sum(x, y, width, height, |nx, ny| img.get_pixel(nx, ny).0[channel]);
+        }
+        Rgba(pixel)
+    })
+}
+
+/// Convolve a single `width` x `height` plane of values, stored row by row
+pub fn convolve_plane(plane: &[f32], width: u32, height: u32, kernel: &Kernel) -> Vec<f32> {
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| kernel.sum(x, y, width, height, |nx, ny| plane[(ny * width + nx) as usize]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kernels() {
+        assert!(Kernel::new(2, vec![0.0; 4]).is_err());
+        assert!(Kernel::new(3, vec![0.0; 8]).is_err());
+        let gaussian = Kernel::gaussian(1.0);
+        assert_eq!(gaussian.size, 7);
+        assert!((gaussian.weights.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+
+        // A step up to the right: the horizontal gradient sees it, the vertical doesn't
+        let step: Vec<f32> = (0..16).map(|i| if i % 4 < 2 { 0.0 } else { 1.0 }).collect();
+        let gx = convolve_plane(&step, 4, 4, &Kernel::sobel_x());
+        let gy = convolve_plane(&step, 4, 4, &Kernel::sobel_y());
+        assert_eq!((gx[5], gx[4]), (4.0, 0.0));
+        assert!(gy.iter().all(|value| *value == 0.0));
+    }
+
+    #[test]
+    fn test_convolve_keeps_alpha() {
+        let img = Rgba32FImage::from_pixel(3, 3, Rgba([0.2, 0.4, 0.6, 0.5]));
+        let double = Kernel::new(1, vec![2.0]).unwrap();
+        let pixel = convolve(&img, &double).get_pixel(1, 1).0;
+        assert!((pixel[2] - 1.2).abs() < 1e-6 && pixel[3] == 0.5);
+    }
+}
@@ -0,0 +1,154 @@
+// File: src\crop.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Content-aware crop to an aspect ratio
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::{imageops, DynamicImage, GenericImageView, GrayImage};
+
+use crate::geometry::{AspectRatio, Rect};
+use crate::i18n::tr;
+use crate::print_info;
+
+/// Longest edge of the saliency map; larger images are analysed downscaled
+const ANALYSIS_SIZE: u32 = 256;
+
+#[derive(Args, Debug)]
+pub struct CropArgs {
+    /// Crop to the W x H area whose top-left corner is at X,Y
+    #[arg(long, value_name = "X,Y,W,H")]
+    pub crop: Option<Rect>,
+
+    /// Crop to an aspect ratio around the most detailed region (e.g. 1:1, 16:9)
+    #[arg(long, value_name = "W:H")]
+    pub smart_crop: Option<AspectRatio>,
+}
+
+impl CropArgs {
+    /// Whether --crop or --smart-crop was given
+    pub fn is_active(&self) -> bool {
+        self.crop.is_some() || self.smart_crop.is_some()
+    }
+}
+
+/// Apply `--smart-crop` (`--crop` is a typed pipeline step), returning the image unchanged when not requested
+pub fn apply(img: DynamicImage, args: &CropArgs) -> Result<DynamicImage> {
+    let Some(aspect) = args.smart_crop else {
+        return Ok(img);
+    };
+
+    let rect = smart_crop_rect(&img, aspect.ratio());
+    print_info(&tr!(
+        "Smart crop to {}x{} at {},{}",
+        rect.width, rect.height, rect.x, rect.y
+    ));
+    Ok(img.crop_imm(rect.x, rect.y, rect.width, rect.height))
+}
+
+/// Largest window of `ratio` that covers the most edge energy
+pub fn smart_crop_rect(img: &DynamicImage, ratio: f64) -> Rect {
+    let (width, height) = img.dimensions();
+    let (crop_w, crop_h) = if width as f64 / height as f64 > ratio {
+        (((height as f64 * ratio).round() as u32).clamp(1, width), height)
+    } else {
+        (width, ((width as f64 / ratio).round() as u32).clamp(1, height))
+    };
+    if (crop_w, crop_h) == (width, height) {
+        return Rect { x: 0, y: 0, width, height };
+    }
+
+    // Analyse a downscaled copy, then map the best offset back
+    let scale = (ANALYSIS_SIZE as f64 / width.max(height) as f64).min(1.0);
+    let small_w = ((width as f64 * scale).round() as u32).max(1);
+    let small_h = ((height as f64 * scale).round() as u32).max(1);
+    let luma = imageops::resize(&img.to_luma8(), small_w, small_h, imageops::FilterType::Triangle);
+    let energy = edge_energy(&luma);
+
+    let horizontal = crop_w < width;
+    let profile: Vec<f64> = if horizontal {
+        (0..small_w).map(|x| (0..small_h).map(|y| energy[(y * small_w + x) as usize]).sum()).collect()
+    } else {
+        (0..small_h).map(|y| (0..small_w).map(|x| energy[(y * small_w + x) as usize]).sum()).collect()
+    };
+    let window = if horizontal {
+        ((crop_w as f64 * scale).round() as usize).clamp(1, profile.len())
+    } else {
+        ((crop_h as f64 * scale).round() as usize).clamp(1, profile.len())
+    };
+    let best = best_window(&profile, window);
+
+    if horizontal {
+        let x = ((best as f64 / scale).round() as u32).min(width - crop_w);
+        Rect { x, y: 0, width: crop_w, height: crop_h }
+    } else {
+        let y = ((best as f64 / scale).round() as u32).min(height - crop_h);
+        Rect { x: 0, y, width: crop_w, height: crop_h }
+    }
+}
+
+/// Gradient magnitude per pixel (central differences)
+fn edge_energy(luma: &GrayImage) -> Vec<f64> {
+    let (width, height) = luma.dimensions();
+    let at = |x: i64, y: i64| {
+        let x = x.clamp(0, width as i64 - 1) as u32;
+        let y = y.clamp(0, height as i64 - 1) as u32;
+        luma.get_pixel(x, y).0[0] as f64
+    };
+
+    let mut energy = Vec::with_capacity((width * height) as usize);
+    for y in 0..height as i64 {
+        for x in 0..width as i64 {
+            let dx = at(x + 1, y) - at(x - 1, y);
+            let dy = at(x, y + 1) - at(x, y - 1);
+            energy.push((dx * dx + dy * dy).sqrt());
+        }
+    }
+    energy
+}
+
+/// Start index of the `window`-wide span with the highest sum; ties prefer the center
+fn best_window(profile: &[f64], window: usize) -> usize {
+    let positions = profile.len() - window + 1;
+    let center = (positions - 1) as f64 / 2.0;
+    let mut sum: f64 = profile[..window].iter().sum();
+    let mut best = (sum, -center, 0);
+
+    for start in 1..positions {
+        sum += profile[start + window - 1] - profile[start - 1];
+        let candidate = (sum, -(start as f64 - center).abs(), start);
+        if candidate.0 > best.0 + 1e-9 || ((candidate.0 - best.0).abs() <= 1e-9 && candidate.1 > best.1) {
+            best = candidate;
+        }
+    }
+    best.2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_best_window_prefers_energy_then_center() {
+        assert_eq!(best_window(&[0.0, 0.0, 5.0, 5.0, 0.0], 2), 2);
+        assert_eq!(best_window(&[1.0; 5], 3), 1);
+    }
+
+    #[test]
+    fn test_smart_crop_finds_detail() {
+        // Flat image with a checkerboard patch on the right
+        let mut img = RgbImage::from_pixel(300, 100, Rgb([128, 128, 128]));
+        for y in 0..100 {
+            for x in 220..290 {
+                if (x + y) % 2 == 0 {
+                    img.put_pixel(x, y, Rgb([255, 255, 255]));
+                }
+            }
+        }
+        let rect = smart_crop_rect(&DynamicImage::ImageRgb8(img), 1.0);
+        assert_eq!((rect.width, rect.height), (100, 100));
+        assert!(rect.x >= 190 && rect.x <= 220, "x = {}", rect.x);
+    }
+}
@@ -0,0 +1,187 @@
+// File: src\curves.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Tone curves (--curve) and solarizing (--solarize)
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::DynamicImage;
+use std::str::FromStr;
+
+use crate::draw::restore_color_type;
+
+#[derive(Args, Debug)]
+pub struct CurvesArgs {
+    /// Tone curve through IN:OUT points (0-255), e.g. "0:0,128:160,255:255";
+    /// prefix with r=, g= or b= for one channel. Repeatable
+    #[arg(long, value_name = "[CH=]IN:OUT,...")]
+    pub curve: Vec<Curve>,
+
+    /// Invert the tones above THRESHOLD (0-255), like an overexposed print
+    #[arg(long, value_name = "THRESHOLD")]
+    pub solarize: Option<u8>,
+}
+
+impl CurvesArgs {
+    /// Whether a curve or solarization is applied
+    pub fn is_active(&self) -> bool {
+        !self.curve.is_empty() || self.solarize.is_some()
+    }
+}
+
+/// A tone curve as given to --curve, for all channels or just one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Curve {
+    /// 0 = red, 1 = green, 2 = blue; None for all three
+    pub channel: Option<usize>,
+    /// Control points sorted by input level
+    pub points: Vec<(u8, u8)>,
+}
+
+impl FromStr for Curve {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (channel, spec) = match s.split_once('=') {
+            Some((channel, spec)) => {
+                let channel = match channel.trim().to_ascii_lowercase().as_str() {
+                    "r" | "red" => 0,
+                    "g" | "green" => 1,
+                    "b" | "blue" => 2,
+                    other => return Err(format!("Unknown curve channel '{}', expected r, g or b", other)),
+                };
+                (Some(channel), spec)
+            }
+            None => (None, s),
+        };
+        let invalid = || format!("Expected IN:OUT points from 0 to 255 such as \"0:0,128:160,255:255\", got: {}", s);
+        let mut points = spec
+            .split(',')
+            .map(|point| {
+                let (x, y) = point.split_once(':')?;
+                Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+            })
+            .collect::<Option<Vec<(u8, u8)>>>()
+            .ok_or_else(invalid)?;
+        points.sort_unstable();
+        if points.len() < 2 || points.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(format!("A curve needs at least two points with different inputs, got: {}", s));
+        }
+        Ok(Curve { channel, points })
+    }
+}
+
+impl Curve {
+    /// Output level for every input level, along a monotone cubic through
+    /// the points (so it never overshoots between them), flat past the ends
+    pub fn table(&self) -> [u8; 256] {
+        let points: Vec<(f32, f32)> = self.points.iter().map(|&(x, y)| (x as f32, y as f32)).collect();
+        let slopes: Vec<f32> = points.windows(2).map(|pair| (pair[1].1 - pair[0].1) / (pair[1].0 - pair[0].0)).collect();
+        // Fritsch-Carlson tangents
+        let tangents: Vec<f32> = (0..points.len())
+            .map(|i| match (i.checked_sub(1).map(|i| slopes[i]), slopes.get(i)) {
+                (None, Some(&next)) => next,
+                (Some(previous), None) => previous,
+                (Some(previous), Some(&next)) if previous * next > 0.0 => {
+                    3.0 * (previous + next) / ((2.0 * next + previous) / previous + (next + 2.0 * previous) / next)
+                }
+                _ => 0.0,
+            })
+            .collect();
+        let mut table = [0; 256];
+        for (level, out) in table.iter_mut().enumerate() {
+            let x = level as f32;
+            let segment = points.windows(2).position(|pair| x <= pair[1].0);
+            let y = match segment {
+                _ if x <= points[0].0 => points[0].1,
+                None => points[points.len() - 1].1,
+                Some(i) => {
+                    let ((x0, y0), (x1, y1)) = (points[i], points[i + 1]);
+                    let h = x1 - x0;
+                    let t = (x - x0) / h;
+                    let (t2, t3) = (t * t, t * t * t);
+                    (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+                        + (t3 - 2.0 * t2 + t) * h * tangents[i]
+                        + (-2.0 * t3 + 3.0 * t2) * y1
+                        + (t3 - t2) * h * tangents[i + 1]
+                }
+            };
+            *out = y.round().clamp(0.0, 255.0) as u8;
+        }
+        table
+    }
+}
+
+/// Apply the --curve tone curves in order, then --solarize
+pub fn apply(img: DynamicImage, args: &CurvesArgs) -> Result<DynamicImage> {
+    if args.curve.is_empty() && args.solarize.is_none() {
+        return Ok(img);
+    }
+    let mut tables = [std::array::from_fn::<u8, 256, _>(|level| level as u8); 3];
+    for curve in &args.curve {
+        let table = curve.table();
+        for (channel, lut) in tables.iter_mut().enumerate() {
+            if curve.channel.is_none_or(|only| only == channel) {
+                *lut = lut.map(|level| table[level as usize]);
+            }
+        }
+    }
+    if let Some(threshold) = args.solarize {
+        for lut in &mut tables {
+            *lut = lut.map(|level| if level > threshold { 255 - level } else { level });
+        }
+    }
+    let color_type = img.color();
+    let mut canvas = img.to_rgba16();
+    for pixel in canvas.pixels_mut() {
+        for (channel, lut) in tables.iter().enumerate() {
+            pixel.0[channel] = lookup(lut, pixel.0[channel]);
+        }
+    }
+    Ok(restore_color_type(color_type, canvas))
+}
+
+/// Map a 16-bit level through an 8-bit table, interpolating between its
+/// entries so 16-bit images keep their smooth gradients
+fn lookup(table: &[u8; 256], value: u16) -> u16 {
+    let position = value as u32 * 255;
+    let (level, fraction) = ((position / 65535) as usize, (position % 65535) as f32 / 65535.0);
+    let (low, high) = (table[level] as f32, table[(level + 1).min(255)] as f32);
+    ((low + (high - low) * fraction) * 257.0).round() as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn test_parse_and_table() {
+        let curve: Curve = "0:0,128:160,255:255".parse().unwrap();
+        let table = curve.table();
+        assert_eq!((table[0], table[128], table[255]), (0, 160, 255));
+        assert!(table.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!("g=0:255,255:0".parse::<Curve>().unwrap().channel, Some(1));
+        assert!("0:0".parse::<Curve>().is_err());
+        assert!("x=0:0,255:255".parse::<Curve>().is_err());
+        assert!("0:0,300:255".parse::<Curve>().is_err());
+    }
+
+    #[test]
+    fn test_solarize_and_channel_curve() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(2, 2, Rgba([100, 200, 50, 128])));
+        let args = CurvesArgs { curve: vec!["b=0:255,255:0".parse().unwrap()], solarize: Some(150) };
+        let out = apply(img, &args).unwrap().to_rgba8();
+        assert_eq!(*out.get_pixel(0, 0), Rgba([100, 55, 50, 128]));
+    }
+
+    #[test]
+    fn test_16bit_levels_are_interpolated() {
+        let invert: Curve = "0:255,255:0".parse().unwrap();
+        let table = invert.table();
+        assert_eq!(lookup(&table, 0), 65535);
+        assert_eq!(lookup(&table, 1000), 64535);
+        assert_eq!(lookup(&table, 65535), 0);
+    }
+}
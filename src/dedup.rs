@@ -0,0 +1,150 @@
+// File: src\dedup.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Convert identical batch inputs once (--dedup)
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use image::ImageFormat;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::create_parent_dir;
+use crate::i18n::tr;
+use crate::{print_info, print_success};
+
+#[derive(Args, Debug)]
+pub struct DedupArgs {
+    /// Convert batch inputs with identical content once, then link, copy or skip the repeats
+    #[arg(long, value_enum, value_name = "MODE", conflicts_with = "in_place")]
+    pub dedup: Option<Mode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Mode {
+    /// Hard-link the first output (copied where links aren't possible)
+    Link,
+    /// Copy the first output
+    Copy,
+    /// Write no output for repeats, only report them
+    Skip,
+}
+
+/// What became of a batch input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reused {
+    /// Not seen before: convert it, then `record` the output
+    No,
+    /// Its output was linked or copied from an earlier one
+    Output,
+    /// Skipped as a duplicate
+    Skipped,
+}
+
+/// Outputs of the batch so far, by source content and output format
+pub struct Seen {
+    mode: Mode,
+    outputs: HashMap<([u8; 32], ImageFormat), PathBuf>,
+    pending: Option<([u8; 32], ImageFormat)>,
+    duplicates: usize,
+}
+
+impl Seen {
+    pub fn new(args: &DedupArgs) -> Option<Self> {
+        args.dedup.map(|mode| Self { mode, outputs: HashMap::new(), pending: None, duplicates: 0 })
+    }
+
+    /// Links and copies need the first output to stay where it was written
+    pub fn keeps_outputs(&self) -> bool {
+        self.mode != Mode::Skip
+    }
+
+    /// Reuse the output of an earlier identical source for `output`, if there is one
+    ///
+    /// `data` is the source's content when already in memory (archive entries);
+    /// otherwise `source` is read to hash it.
+    pub fn reuse(&mut self, source: &Path, data: Option<&[u8]>, format: ImageFormat, output: &Path) -> Result<Reused> {
+        let digest: [u8; 32] = match data {
+            Some(data) => Sha256::digest(data).into(),
+            None => {
+                let data = std::fs::read(source).with_context(|| format!("Failed to read input file: {}", source.display()))?;
+                Sha256::digest(&data).into()
+            }
+        };
+        let Some(first) = self.outputs.get(&(digest, format)) else {
+            self.pending = Some((digest, format));
+            return Ok(Reused::No);
+        };
+        self.duplicates += 1;
+        match self.mode {
+            Mode::Skip => {
+                print_info(&tr!("Duplicate of {}, skipping: {}", first.display(), source.display()));
+                Ok(Reused::Skipped)
+            }
+            Mode::Link | Mode::Copy if first == output => Ok(Reused::Output),
+            mode => {
+                create_parent_dir(output)?;
+                if output.exists() {
+                    std::fs::remove_file(output).with_context(|| format!("Failed to replace {}", output.display()))?;
+                }
+                let linked = mode == Mode::Link && std::fs::hard_link(first, output).is_ok();
+                if !linked {
+                    std::fs::copy(first, output)
+                        .with_context(|| format!("Failed to copy {} to {}", first.display(), output.display()))?;
+                }
+                print_success(&tr!(
+                    "Duplicate of {}, {}: {}",
+                    first.display(),
+                    if linked { "linked" } else { "copied" },
+                    output.display()
+                ));
+                Ok(Reused::Output)
+            }
+        }
+    }
+
+    /// Remember `output` as the result for the source last passed to `reuse`
+    pub fn record(&mut self, output: &Path) {
+        if let Some(key) = self.pending.take() {
+            self.outputs.insert(key, output.to_path_buf());
+        }
+    }
+
+    pub fn report(&self) {
+        if self.duplicates > 0 {
+            print_info(&tr!("{} duplicate inputs were not converted again", self.duplicates));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicates_reuse_first_output() {
+        let dir = std::env::temp_dir().join(format!("imgconv-dedup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (a, b, c) = (dir.join("a.png"), dir.join("b.png"), dir.join("c.png"));
+        std::fs::write(&a, b"same").unwrap();
+        std::fs::write(&b, b"same").unwrap();
+        std::fs::write(&c, b"other").unwrap();
+        let (out_a, out_b) = (dir.join("out/a.webp"), dir.join("out/sub/b.webp"));
+
+        let mut seen = Seen::new(&DedupArgs { dedup: Some(Mode::Link) }).unwrap();
+        assert_eq!(seen.reuse(&a, None, ImageFormat::WebP, &out_a).unwrap(), Reused::No);
+        std::fs::create_dir_all(out_a.parent().unwrap()).unwrap();
+        std::fs::write(&out_a, b"encoded").unwrap();
+        seen.record(&out_a);
+        assert_eq!(seen.reuse(&b, None, ImageFormat::WebP, &out_b).unwrap(), Reused::Output);
+        assert_eq!(std::fs::read(&out_b).unwrap(), b"encoded");
+        assert_eq!(seen.reuse(&b, None, ImageFormat::Png, &dir.join("b2.png")).unwrap(), Reused::No);
+        assert_eq!(seen.reuse(&c, Some(b"same"), ImageFormat::WebP, &out_b).unwrap(), Reused::Output);
+
+        seen.mode = Mode::Skip;
+        assert_eq!(seen.reuse(&b, None, ImageFormat::WebP, &out_b).unwrap(), Reused::Skipped);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
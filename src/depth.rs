@@ -0,0 +1,235 @@
+// File: src\depth.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Output bit depth control with dithered reduction
+// License: MIT
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use image::{ColorType, DynamicImage, GrayImage, ImageBuffer, ImageFormat, Luma, LumaA, Pixel, Rgb, Rgba};
+
+use crate::alpha::flatten_onto;
+use crate::color::Color;
+use crate::draw::is_high_depth;
+use crate::encode::supports_16bit;
+use crate::i18n::tr;
+use crate::{print_info, print_warning};
+
+#[derive(Args, Debug)]
+pub struct DepthArgs {
+    /// Bits per sample for PNG, TIFF and PNM output (1 = black and white)
+    #[arg(long, value_enum, value_name = "BITS")]
+    pub bit_depth: Option<BitDepth>,
+
+    /// Dithering used when reducing bit depth
+    #[arg(long, value_enum, default_value = "floyd-steinberg", value_name = "MODE")]
+    pub dither: Dither,
+}
+
+impl DepthArgs {
+    /// Whether --bit-depth was given (--dither alone changes nothing)
+    pub fn is_active(&self) -> bool {
+        self.bit_depth.is_some()
+    }
+}
+
+impl DepthArgs {
+    /// Whether the output must go through the 1-bit encoder
+    pub fn is_bilevel(&self) -> bool {
+        self.bit_depth == Some(BitDepth::One)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BitDepth {
+    #[value(name = "1")]
+    One,
+    #[value(name = "8")]
+    Eight,
+    #[value(name = "16")]
+    Sixteen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Dither {
+    /// Round to the nearest level
+    None,
+    /// Error diffusion
+    FloydSteinberg,
+}
+
+/// Convert `img` to the requested depth; 1-bit results are 0/255 grayscale for `encode::save_bilevel`
+pub fn apply(img: DynamicImage, args: &DepthArgs, format: ImageFormat, background: Color) -> Result<DynamicImage> {
+    let Some(depth) = args.bit_depth else {
+        return Ok(keep_depth_if_possible(img, args.dither, format));
+    };
+
+    let supported = match depth {
+        BitDepth::One => matches!(format, ImageFormat::Png | ImageFormat::Pnm),
+        _ => matches!(format, ImageFormat::Png | ImageFormat::Tiff | ImageFormat::Pnm),
+    };
+    if !supported {
+        anyhow::bail!(tr!("--bit-depth {} is not supported for {} output", bits(depth), format!("{:?}", format)));
+    }
+
+    let channels = img.color().channel_count() as usize;
+    let source_bits = img.color().bits_per_pixel() as usize / channels;
+    print_info(&tr!("Bit depth: {} -> {}", source_bits, bits(depth)));
+
+    Ok(match depth {
+        BitDepth::One => {
+            let mut canvas = img.to_rgba8();
+            flatten_onto(&mut canvas, background);
+            let luma = DynamicImage::ImageRgba8(canvas).to_luma8();
+            DynamicImage::ImageLuma8(to_bilevel(&luma, args.dither))
+        }
+        BitDepth::Eight if source_bits > 8 => reduce_to_8bit(&img, args.dither),
+        BitDepth::Eight => match (channels, img.color().has_alpha()) {
+            (1, _) => DynamicImage::ImageLuma8(img.to_luma8()),
+            (2, _) => DynamicImage::ImageLumaA8(img.to_luma_alpha8()),
+            (_, false) => DynamicImage::ImageRgb8(img.to_rgb8()),
+            (_, true) => DynamicImage::ImageRgba8(img.to_rgba8()),
+        },
+        BitDepth::Sixteen => match (channels, img.color().has_alpha()) {
+            (1, _) => DynamicImage::ImageLuma16(img.to_luma16()),
+            (2, _) => DynamicImage::ImageLumaA16(img.to_luma_alpha16()),
+            (_, false) => DynamicImage::ImageRgb16(img.to_rgb16()),
+            (_, true) => DynamicImage::ImageRgba16(img.to_rgba16()),
+        },
+    })
+}
+
+/// 16-bit images stay 16-bit unless the output format cannot store them
+fn keep_depth_if_possible(img: DynamicImage, dither: Dither, format: ImageFormat) -> DynamicImage {
+    let sixteen_bit = matches!(img.color(), ColorType::L16 | ColorType::La16 | ColorType::Rgb16 | ColorType::Rgba16);
+    if !sixteen_bit || supports_16bit(format) {
+        return img;
+    }
+    print_warning(&tr!("{} only stores 8 bits per channel, reducing 16-bit image", format!("{:?}", format)));
+    reduce_to_8bit(&img, dither)
+}
+
+/// Warn that `option` brings an image of `color` down to 8 bits per channel
+pub fn warn_reduction(color: ColorType, option: &str) {
+    if is_high_depth(color) {
+        let bits = color.bits_per_pixel() / color.channel_count() as u16;
+        print_warning(&tr!("{} works with 8 bits per channel, reducing {}-bit image", option, bits));
+    }
+}
+
+fn bits(depth: BitDepth) -> u8 {
+    match depth {
+        BitDepth::One => 1,
+        BitDepth::Eight => 8,
+        BitDepth::Sixteen => 16,
+    }
+}
+
+/// Threshold grayscale to pure black and white
+fn to_bilevel(luma: &GrayImage, dither: Dither) -> GrayImage {
+    let (width, height) = luma.dimensions();
+    let mut plane: Vec<f32> = luma.pixels().map(|p| p.0[0] as f32 / 255.0).collect();
+    quantize_plane(&mut plane, width as usize, 2, dither);
+    GrayImage::from_raw(width, height, plane.iter().map(|&v| (v * 255.0).round() as u8).collect())
+        .expect("plane matches image size")
+}
+
+/// 16-bit/float to 8-bit per channel, keeping the channel layout
+fn reduce_to_8bit(img: &DynamicImage, dither: Dither) -> DynamicImage {
+    match (img.color().channel_count(), img.color().has_alpha()) {
+        (1, _) => DynamicImage::ImageLuma8(reduce::<Luma<u16>, Luma<u8>>(&img.to_luma16(), dither)),
+        (2, _) => DynamicImage::ImageLumaA8(reduce::<LumaA<u16>, LumaA<u8>>(&img.to_luma_alpha16(), dither)),
+        (_, false) => DynamicImage::ImageRgb8(reduce::<Rgb<u16>, Rgb<u8>>(&img.to_rgb16(), dither)),
+        (_, true) => DynamicImage::ImageRgba8(reduce::<Rgba<u16>, Rgba<u8>>(&img.to_rgba16(), dither)),
+    }
+}
+
+fn reduce<P, Q>(img: &ImageBuffer<P, Vec<u16>>, dither: Dither) -> ImageBuffer<Q, Vec<u8>>
+where
+    P: Pixel<Subpixel = u16>,
+    Q: Pixel<Subpixel = u8>,
+{
+    let (width, height) = img.dimensions();
+    let channels = P::CHANNEL_COUNT as usize;
+    let samples = img.as_raw();
+    let mut out = vec![0u8; samples.len()];
+
+    for channel in 0..channels {
+        let mut plane: Vec<f32> = samples.iter().skip(channel).step_by(channels).map(|&v| v as f32 / 65535.0).collect();
+        quantize_plane(&mut plane, width as usize, 256, dither);
+        for (i, v) in plane.iter().enumerate() {
+            out[i * channels + channel] = (v * 255.0).round() as u8;
+        }
+    }
+    ImageBuffer::from_raw(width, height, out).expect("buffer matches image size")
+}
+
+/// Snap a 0.0-1.0 plane to `levels` evenly spaced values, diffusing the error if requested
+fn quantize_plane(plane: &mut [f32], width: usize, levels: u32, dither: Dither) {
+    let steps = (levels - 1) as f32;
+    let snap = |v: f32| (v.clamp(0.0, 1.0) * steps).round() / steps;
+
+    if dither == Dither::None {
+        plane.iter_mut().for_each(|v| *v = snap(*v));
+        return;
+    }
+
+    let height = plane.len() / width.max(1);
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            let old = plane[i];
+            let new = snap(old);
+            plane[i] = new;
+            let error = old - new;
+
+            if x + 1 < width {
+                plane[i + 1] += error * 7.0 / 16.0;
+            }
+            if y + 1 < height {
+                if x > 0 {
+                    plane[i + width - 1] += error * 3.0 / 16.0;
+                }
+                plane[i + width] += error * 5.0 / 16.0;
+                if x + 1 < width {
+                    plane[i + width + 1] += error / 16.0;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bilevel_dither_preserves_tone() {
+        let gray = GrayImage::from_pixel(16, 16, Luma([128]));
+        let dithered = to_bilevel(&gray, Dither::FloydSteinberg);
+        let white = dithered.pixels().filter(|p| p.0[0] == 255).count();
+        assert!(dithered.pixels().all(|p| p.0[0] == 0 || p.0[0] == 255));
+        assert!((112..=144).contains(&white), "white pixels: {}", white);
+
+        let thresholded = to_bilevel(&gray, Dither::None);
+        assert!(thresholded.pixels().all(|p| p.0[0] == 255));
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        let args = DepthArgs { bit_depth: Some(BitDepth::Sixteen), dither: Dither::None };
+        let img = DynamicImage::new_rgb8(2, 2);
+        assert!(apply(img.clone(), &args, ImageFormat::Jpeg, Color(Rgba([255; 4]))).is_err());
+        let out = apply(img, &args, ImageFormat::Png, Color(Rgba([255; 4]))).unwrap();
+        assert_eq!(out.color(), ColorType::Rgb16);
+    }
+
+    #[test]
+    fn test_16bit_kept_or_reduced() {
+        let args = DepthArgs { bit_depth: None, dither: Dither::FloydSteinberg };
+        let img = DynamicImage::new_rgba16(2, 2);
+        let background = Color(Rgba([255; 4]));
+        assert_eq!(apply(img.clone(), &args, ImageFormat::Png, background).unwrap().color(), ColorType::Rgba16);
+        assert_eq!(apply(img, &args, ImageFormat::WebP, background).unwrap().color(), ColorType::Rgba8);
+    }
+}
@@ -0,0 +1,220 @@
+// File: src\desktop.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Linux desktop entry and Nautilus/Dolphin right-click actions (install-desktop)
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use std::path::{Path, PathBuf};
+
+use crate::explorer::{label, name};
+use crate::i18n::tr;
+use crate::{print_info, print_success, print_warning, Format};
+
+#[derive(Args, Debug)]
+pub struct InstallArgs {
+    /// Formats offered in the "Convert to" actions
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "png,jpeg,webp", value_name = "FORMATS")]
+    pub formats: Vec<Format>,
+}
+
+/// A file written under the user's data directory
+#[derive(Debug)]
+struct File {
+    path: PathBuf,
+    content: String,
+    executable: bool,
+}
+
+pub fn install(args: &InstallArgs) -> Result<()> {
+    let data = data_dir()?;
+    let exe = std::env::current_exe().context("Cannot locate the imgconv executable")?;
+    let formats: Vec<String> = args.formats.iter().map(name).collect();
+    for file in files(&data, &exe, &formats) {
+        if let Some(dir) = file.path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        }
+        std::fs::write(&file.path, &file.content)
+            .with_context(|| format!("Failed to write {}", file.path.display()))?;
+        if file.executable {
+            make_executable(&file.path)?;
+        }
+        print_info(&tr!("Installed: {}", file.path.display()));
+    }
+    print_success(&tr!("Added imgconv to the application menu and the Nautilus/Dolphin right-click menus"));
+    print_info(&tr!("Restart the file manager (nautilus -q) if the actions do not show up yet; remove with `imgconv uninstall-desktop`"));
+    Ok(())
+}
+
+pub fn uninstall() -> Result<()> {
+    let data = data_dir()?;
+    let mut removed = 0;
+    for path in installed_paths(&data) {
+        let result = if path.is_dir() { std::fs::remove_dir_all(&path) } else { std::fs::remove_file(&path) };
+        if result.is_ok() {
+            removed += 1;
+            print_info(&tr!("Removed: {}", path.display()));
+        }
+    }
+    if removed == 0 {
+        print_warning(&tr!("No imgconv desktop integration was installed"));
+    } else {
+        print_success(&tr!("Removed the imgconv desktop integration"));
+    }
+    Ok(())
+}
+
+/// $XDG_DATA_HOME, or ~/.local/share
+fn data_dir() -> Result<PathBuf> {
+    if cfg!(windows) || cfg!(target_os = "macos") {
+        anyhow::bail!("Desktop integration needs a freedesktop.org desktop (GNOME, KDE, ...); on Windows use install-shell-integration");
+    }
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local").join("share")))
+        .context("Neither XDG_DATA_HOME nor HOME is set")
+}
+
+/// Everything install writes; uninstall removes these
+fn installed_paths(data: &Path) -> Vec<PathBuf> {
+    vec![
+        data.join("imgconv").join("convert.sh"),
+        data.join("applications").join("imgconv.desktop"),
+        data.join("nautilus").join("scripts").join("imgconv"),
+        data.join("kio").join("servicemenus").join("imgconv.desktop"),
+        data.join("kservices5").join("ServiceMenus").join("imgconv.desktop"),
+    ]
+}
+
+/// The helper script all actions call, the application entry, one Nautilus
+/// script per format and the Dolphin service menu (KDE 6 and 5 locations)
+fn files(data: &Path, exe: &Path, formats: &[String]) -> Vec<File> {
+    let paths = installed_paths(data);
+    let helper = &paths[0];
+    let exec_helper = exec_quote(&helper.display().to_string());
+
+    let mut files = vec![File { path: helper.clone(), content: helper_script(exe), executable: true }];
+
+    let mut entry = format!(
+        "[Desktop Entry]\nType=Application\nName=imgconv\nComment=Convert images between formats\n\
+         Exec={} tui\nTerminal=true\nIcon=image-x-generic\nCategories=Graphics;Utility;\nActions={};\n",
+        exec_quote(&exe.display().to_string()),
+        formats.join(";")
+    );
+    for format in formats {
+        entry += &format!("\n[Desktop Action {}]\nName=Convert to {}\nExec={} {} %F\n", format, label(format), exec_helper, format);
+    }
+    files.push(File { path: paths[1].clone(), content: entry, executable: false });
+
+    for format in formats {
+        let script = format!(
+            "#!/bin/sh\n# Installed by imgconv install-desktop\nexec {} {} \"$@\"\n",
+            shell_quote(&helper.display().to_string()),
+            format
+        );
+        files.push(File { path: paths[2].join(format!("Convert to {}", label(format))), content: script, executable: true });
+    }
+
+    let mut menu = format!(
+        "[Desktop Entry]\nType=Service\nMimeType=image/*;inode/directory;\nActions={};\n\
+         X-KDE-ServiceTypes=KonqPopupMenu/Plugin\nX-KDE-Submenu=Convert with imgconv\n",
+        formats.join(";")
+    );
+    for format in formats {
+        menu += &format!(
+            "\n[Desktop Action {}]\nName=Convert to {}\nIcon=image-x-generic\nExec={} {} %F\n",
+            format,
+            label(format),
+            exec_helper,
+            format
+        );
+    }
+    files.push(File { path: paths[3].clone(), content: menu.clone(), executable: true });
+    files.push(File { path: paths[4].clone(), content: menu, executable: true });
+    files
+}
+
+/// Converts each argument: a file next to itself, a folder (recursively)
+/// into a sibling FOLDER-FORMAT folder, like the Windows context menu
+fn helper_script(exe: &Path) -> String {
+    format!(
+        r#"#!/bin/sh
+# Installed by imgconv install-desktop
+imgconv={}
+format="$1"
+shift
+failed=0
+for path in "$@"; do
+    if [ -d "$path" ]; then
+        "$imgconv" "$path" "$path-$format" -f "$format" -r --yes || failed=$((failed + 1))
+    else
+        # Files already in the target format are left alone
+        ext=$(printf '%s' "${{path##*.}}" | tr '[:upper:]' '[:lower:]')
+        if [ "$ext" = "$format" ] || {{ [ "$format" = jpeg ] && [ "$ext" = jpg ]; }}; then
+            continue
+        fi
+        "$imgconv" "$path" "$path" -f "$format" --yes || failed=$((failed + 1))
+    fi
+done
+if command -v notify-send >/dev/null 2>&1; then
+    if [ "$failed" -eq 0 ]; then
+        notify-send -i image-x-generic imgconv "Converted $# item(s) to $format"
+    else
+        notify-send -u critical -i image-x-generic imgconv "$failed of $# item(s) failed to convert to $format"
+    fi
+fi
+exit "$failed"
+"#,
+        shell_quote(&exe.display().to_string())
+    )
+}
+
+/// Quote for a desktop entry Exec line (double quotes; `"`, `` ` ``, `$` and `\` escaped)
+fn exec_quote(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            // The backslash itself is doubled again by the desktop entry string escaping
+            quoted.push_str("\\\\");
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desktop_files() {
+        let formats = vec!["png".to_string(), "webp".to_string()];
+        let files = files(Path::new("/home/me/.local/share"), Path::new("/opt/my tools/imgconv"), &formats);
+        assert_eq!(files.len(), 6);
+        assert!(files[0].content.contains("imgconv='/opt/my tools/imgconv'"));
+        assert!(files[1].content.contains("Exec=\"/opt/my tools/imgconv\" tui\n"));
+        assert!(files[1].content.contains("[Desktop Action webp]\nName=Convert to WebP\nExec=\"/home/me/.local/share/imgconv/convert.sh\" webp %F\n"));
+        assert_eq!(files[3].path, Path::new("/home/me/.local/share/nautilus/scripts/imgconv/Convert to WebP"));
+        assert!(files[4].content.contains("X-KDE-Submenu=Convert with imgconv"));
+        assert_eq!(exec_quote("/a$b"), "\"/a\\\\$b\"");
+    }
+}
@@ -0,0 +1,114 @@
+// File: src\dispose.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Delete or archive source files once their output is safely written
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::ImageFormat;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+use crate::verify::ensure_decodes;
+use crate::{print_info, print_warning};
+
+#[derive(Args, Debug)]
+pub struct DisposeArgs {
+    /// Delete each source once its output is written and verified
+    #[arg(long, conflicts_with_all = ["in_place", "estimate"])]
+    pub delete_source: bool,
+
+    /// Move each source into DIR once its output is written and verified
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["delete_source", "in_place", "estimate"])]
+    pub move_source_to: Option<PathBuf>,
+}
+
+impl DisposeArgs {
+    pub fn is_enabled(&self) -> bool {
+        self.delete_source || self.move_source_to.is_some()
+    }
+}
+
+/// Delete or move `source` after checking that `output` decodes
+///
+/// `relative` is where the source lands under --move-source-to, so batch runs
+/// keep their directory layout and same-named files don't collide.
+pub fn apply(source: &Path, relative: &Path, output: &Path, format: ImageFormat, args: &DisposeArgs) -> Result<()> {
+    if !args.is_enabled() {
+        return Ok(());
+    }
+    if same_file(source, output) {
+        anyhow::bail!("Output overwrote the source, refusing to remove it: {}", source.display());
+    }
+
+    if !ensure_decodes(output)? {
+        // Encode-only formats can only be checked for content
+        let size = std::fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+        if size == 0 {
+            anyhow::bail!("Output is missing or empty: {}", output.display());
+        }
+        print_warning(&tr!("Cannot decode {} to verify, checked the file size only", format!("{:?}", format)));
+    }
+
+    if let Some(dir) = &args.move_source_to {
+        let destination = dir.join(relative);
+        move_file(source, &destination)?;
+        print_info(&tr!("Source moved to: {}", destination.display()));
+    } else {
+        std::fs::remove_file(source)
+            .with_context(|| format!("Failed to delete source: {}", source.display()))?;
+        print_info(&tr!("Source deleted: {}", source.display()));
+    }
+    Ok(())
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Rename, falling back to copy + delete across filesystems; never overwrites
+fn move_file(source: &Path, destination: &Path) -> Result<()> {
+    if destination.exists() {
+        anyhow::bail!("Refusing to overwrite archived file: {}", destination.display());
+    }
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    if std::fs::rename(source, destination).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(source, destination)
+        .with_context(|| format!("Failed to move {} to {}", source.display(), destination.display()))?;
+    std::fs::remove_file(source).with_context(|| format!("Failed to delete source: {}", source.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_move_after_verified_output() {
+        let dir = std::env::temp_dir().join(format!("imgconv-dispose-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (source, output, archive) = (dir.join("in.png"), dir.join("out.png"), dir.join("done"));
+        std::fs::write(&source, b"source").unwrap();
+        let args = DisposeArgs { delete_source: false, move_source_to: Some(archive.clone()) };
+
+        // A corrupt output keeps the source where it is
+        std::fs::write(&output, b"not a png").unwrap();
+        assert!(apply(&source, Path::new("a/in.png"), &output, ImageFormat::Png, &args).is_err());
+        assert!(source.exists());
+
+        RgbImage::from_pixel(2, 2, Rgb([1, 2, 3])).save(&output).unwrap();
+        apply(&source, Path::new("a/in.png"), &output, ImageFormat::Png, &args).unwrap();
+        assert!(!source.exists());
+        assert_eq!(std::fs::read(archive.join("a/in.png")).unwrap(), b"source");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
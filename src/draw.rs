@@ -0,0 +1,130 @@
+// File: src\draw.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Pixel blending helpers shared by the drawing effects
+// License: MIT
+
+use image::{ColorType, DynamicImage, ImageBuffer, Pixel, Rgba};
+
+use crate::colorspace::with_color_type;
+
+/// The canvas the drawing effects work on: 16 bits per channel, so 16-bit
+/// sources keep their precision and 8-bit ones round-trip exactly
+pub type Canvas = ImageBuffer<Rgba<u16>, Vec<u16>>;
+
+/// Channel types the blending helpers work on
+pub trait Sample: Copy {
+    fn to_unit(self) -> f32;
+    fn from_unit(value: f32) -> Self;
+}
+
+impl Sample for u8 {
+    fn to_unit(self) -> f32 {
+        self as f32 / 255.0
+    }
+
+    fn from_unit(value: f32) -> Self {
+        (value * 255.0).round().clamp(0.0, 255.0) as u8
+    }
+}
+
+impl Sample for u16 {
+    fn to_unit(self) -> f32 {
+        self as f32 / 65535.0
+    }
+
+    fn from_unit(value: f32) -> Self {
+        (value * 65535.0).round().clamp(0.0, 65535.0) as u16
+    }
+}
+
+/// An 8-bit color (as given on the command line) for drawing on a `Canvas`
+pub fn wide(color: Rgba<u8>) -> Rgba<u16> {
+    Rgba(color.0.map(|c| c as u16 * 257))
+}
+
+/// Source-over blend `color` onto `pixel`, scaled by `coverage` (0.0 - 1.0)
+pub fn blend<T: Sample>(pixel: &mut Rgba<T>, color: Rgba<T>, coverage: f32) {
+    let src_a = color.0[3].to_unit() * coverage.clamp(0.0, 1.0);
+    if src_a <= 0.0 {
+        return;
+    }
+    let dst_a = pixel.0[3].to_unit();
+    let out_a = src_a + dst_a * (1.0 - src_a);
+
+    for c in 0..3 {
+        let src = color.0[c].to_unit();
+        let dst = pixel.0[c].to_unit();
+        pixel.0[c] = T::from_unit((src * src_a + dst * dst_a * (1.0 - src_a)) / out_a);
+    }
+    pixel.0[3] = T::from_unit(out_a);
+}
+
+/// Blend `color` at signed coordinates, ignoring points outside the canvas
+pub fn blend_at<T: Sample>(canvas: &mut ImageBuffer<Rgba<T>, Vec<T>>, x: i64, y: i64, color: Rgba<T>, coverage: f32)
+where
+    Rgba<T>: Pixel<Subpixel = T>,
+{
+    if x < 0 || y < 0 || x >= canvas.width() as i64 || y >= canvas.height() as i64 {
+        return;
+    }
+    blend(canvas.get_pixel_mut(x as u32, y as u32), color, coverage);
+}
+
+/// Convert a drawn canvas back to the layout and depth of the source image
+pub fn restore_color_type(original: ColorType, canvas: Canvas) -> DynamicImage {
+    with_color_type(DynamicImage::ImageRgba16(canvas), original)
+}
+
+/// The layout `original` takes on once a stage gives it transparency
+pub fn with_alpha(original: ColorType) -> ColorType {
+    match original {
+        ColorType::L8 => ColorType::La8,
+        ColorType::Rgb8 => ColorType::Rgba8,
+        ColorType::L16 => ColorType::La16,
+        ColorType::Rgb16 => ColorType::Rgba16,
+        ColorType::Rgb32F => ColorType::Rgba32F,
+        other => other,
+    }
+}
+
+/// Whether `color` stores more than 8 bits per channel
+pub fn is_high_depth(color: ColorType) -> bool {
+    color.bytes_per_pixel() > color.channel_count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blend() {
+        let mut pixel = Rgba([0u8, 0, 0, 255]);
+        blend(&mut pixel, Rgba([255, 255, 255, 255]), 0.5);
+        assert_eq!(pixel, Rgba([128, 128, 128, 255]));
+
+        let mut transparent = Rgba([0u8, 0, 0, 0]);
+        blend(&mut transparent, Rgba([255, 0, 0, 255]), 1.0);
+        assert_eq!(transparent, Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_blend_16bit() {
+        let mut pixel = Rgba([0u16, 0, 0, 65535]);
+        blend(&mut pixel, wide(Rgba([255, 255, 255, 255])), 0.5);
+        assert_eq!(pixel, Rgba([32768, 32768, 32768, 65535]));
+    }
+
+    #[test]
+    fn test_restore_keeps_16bit() {
+        // A level an 8-bit canvas could not hold
+        let canvas = Canvas::from_pixel(1, 1, Rgba([1000, 0, 0, 65535]));
+        let img = restore_color_type(ColorType::Rgb16, canvas.clone());
+        assert_eq!(img.color(), ColorType::Rgb16);
+        assert_eq!(img.to_rgb16().get_pixel(0, 0).0, [1000, 0, 0]);
+        assert_eq!(restore_color_type(ColorType::Rgb8, canvas).color(), ColorType::Rgb8);
+        assert_eq!(with_alpha(ColorType::L16), ColorType::La16);
+        assert!(is_high_depth(img.color()));
+        assert!(!is_high_depth(ColorType::Rgba8));
+    }
+}
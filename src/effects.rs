@@ -0,0 +1,206 @@
+// File: src\effects.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Drop shadow and vignette post-processing effects
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::{imageops, DynamicImage, GrayImage, Luma, Rgba};
+use std::str::FromStr;
+
+use crate::color::Color;
+use crate::draw::{blend, restore_color_type, wide, with_alpha, Canvas};
+
+#[derive(Args, Debug)]
+pub struct EffectsArgs {
+    /// Drop shadow as BLURxSPREAD+X+Y[#COLOR], e.g. "8x8+0+4#00000080"
+    #[arg(long, value_name = "SPEC", allow_hyphen_values = true)]
+    pub shadow: Option<Shadow>,
+
+    /// Darken the edges of the image (0.0 - 1.0)
+    #[arg(long, value_name = "STRENGTH")]
+    pub vignette: Option<f32>,
+}
+
+impl EffectsArgs {
+    /// Whether a shadow or vignette is added
+    pub fn is_active(&self) -> bool {
+        self.shadow.is_some() || self.vignette.is_some()
+    }
+}
+
+/// Parsed `--shadow` specification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    pub blur: u32,
+    pub spread: u32,
+    pub offset_x: i32,
+    pub offset_y: i32,
+    pub color: Color,
+}
+
+impl FromStr for Shadow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Expected BLURxSPREAD+X+Y[#COLOR], got: {}", s);
+
+        let (geometry, color) = match s.find('#') {
+            Some(i) => (&s[..i], s[i..].parse::<Color>()?),
+            None => (s, Color(Rgba([0, 0, 0, 128]))),
+        };
+
+        // Split "8x8+0-4" into size "8x8" and signed offsets "+0", "-4"
+        let offset_start = geometry.find(['+', '-']).ok_or_else(invalid)?;
+        let (size, offsets) = geometry.split_at(offset_start);
+        let (blur, spread) = size.split_once('x').ok_or_else(invalid)?;
+
+        let mut signed = Vec::new();
+        let mut current = String::new();
+        for ch in offsets.chars() {
+            if (ch == '+' || ch == '-') && !current.is_empty() {
+                signed.push(current.clone());
+                current.clear();
+            }
+            current.push(ch);
+        }
+        signed.push(current);
+        if signed.len() != 2 {
+            return Err(invalid());
+        }
+
+        Ok(Shadow {
+            blur: blur.parse().map_err(|_| invalid())?,
+            spread: spread.parse().map_err(|_| invalid())?,
+            offset_x: signed[0].parse().map_err(|_| invalid())?,
+            offset_y: signed[1].parse().map_err(|_| invalid())?,
+            color,
+        })
+    }
+}
+
+/// Darken pixels toward the corners with a smooth radial falloff
+pub fn apply_vignette(img: DynamicImage, args: &EffectsArgs) -> Result<DynamicImage> {
+    let Some(strength) = args.vignette else {
+        return Ok(img);
+    };
+    if !(0.0..=1.0).contains(&strength) {
+        anyhow::bail!("Vignette strength must be between 0.0 and 1.0, got: {}", strength);
+    }
+
+    let color_type = img.color();
+    let mut canvas = img.to_rgba16();
+    let (cx, cy) = (canvas.width() as f32 / 2.0, canvas.height() as f32 / 2.0);
+    let max_distance = (cx * cx + cy * cy).sqrt().max(1.0);
+
+    for (x, y, pixel) in canvas.enumerate_pixels_mut() {
+        let dx = x as f32 + 0.5 - cx;
+        let dy = y as f32 + 0.5 - cy;
+        let distance = (dx * dx + dy * dy).sqrt() / max_distance;
+        // Untouched center, smoothstep falloff from 50% of the radius outwards
+        let t = ((distance - 0.5) / 0.5).clamp(0.0, 1.0);
+        let factor = 1.0 - strength * t * t * (3.0 - 2.0 * t);
+        for c in 0..3 {
+            pixel.0[c] = (pixel.0[c] as f32 * factor).round() as u16;
+        }
+    }
+
+    Ok(restore_color_type(color_type, canvas))
+}
+
+/// Draw a blurred drop shadow behind the image, growing the canvas to fit it
+pub fn apply_shadow(img: DynamicImage, args: &EffectsArgs) -> Result<DynamicImage> {
+    let Some(shadow) = args.shadow else {
+        return Ok(img);
+    };
+
+    let color_type = img.color();
+    let source = img.to_rgba16();
+    let (width, height) = source.dimensions();
+    let reach = shadow.blur * 2 + shadow.spread;
+    let pad_left = reach + (-shadow.offset_x).max(0) as u32;
+    let pad_right = reach + shadow.offset_x.max(0) as u32;
+    let pad_top = reach + (-shadow.offset_y).max(0) as u32;
+    let pad_bottom = reach + shadow.offset_y.max(0) as u32;
+
+    // Shadow mask: the source alpha, offset, grown by spread and blurred
+    let mut mask = GrayImage::new(width + pad_left + pad_right, height + pad_top + pad_bottom);
+    let mask_x = (pad_left as i64 + shadow.offset_x as i64) as u32;
+    let mask_y = (pad_top as i64 + shadow.offset_y as i64) as u32;
+    for (x, y, pixel) in source.enumerate_pixels() {
+        mask.put_pixel(mask_x + x, mask_y + y, Luma([(pixel.0[3] as f32 / 257.0).round() as u8]));
+    }
+    let mask = dilate(&mask, shadow.spread);
+    let mask = if shadow.blur > 0 { imageops::blur(&mask, shadow.blur as f32 / 2.0) } else { mask };
+
+    let mut canvas = Canvas::new(mask.width(), mask.height());
+    for (x, y, pixel) in canvas.enumerate_pixels_mut() {
+        let coverage = mask.get_pixel(x, y).0[0] as f32 / 255.0;
+        blend(pixel, wide(shadow.color.0), coverage);
+    }
+    for (x, y, pixel) in source.enumerate_pixels() {
+        blend(canvas.get_pixel_mut(pad_left + x, pad_top + y), *pixel, 1.0);
+    }
+
+    Ok(restore_color_type(with_alpha(color_type), canvas))
+}
+
+/// Grow bright areas of a mask by `radius` pixels (separable max filter)
+fn dilate(mask: &GrayImage, radius: u32) -> GrayImage {
+    if radius == 0 {
+        return mask.clone();
+    }
+    let (width, height) = mask.dimensions();
+    let r = radius as i64;
+    let max_along = |src: &GrayImage, horizontal: bool| {
+        GrayImage::from_fn(width, height, |x, y| {
+            let value = (-r..=r)
+                .filter_map(|d| {
+                    let (sx, sy) = if horizontal { (x as i64 + d, y as i64) } else { (x as i64, y as i64 + d) };
+                    (sx >= 0 && sy >= 0 && sx < width as i64 && sy < height as i64)
+                        .then(|| src.get_pixel(sx as u32, sy as u32).0[0])
+                })
+                .max()
+                .unwrap_or(0);
+            Luma([value])
+        })
+    };
+    let horizontal = max_along(mask, true);
+    max_along(&horizontal, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shadow() {
+        let shadow: Shadow = "8x8+0+4#00000080".parse().unwrap();
+        assert_eq!((shadow.blur, shadow.spread, shadow.offset_x, shadow.offset_y), (8, 8, 0, 4));
+        assert_eq!(shadow.color, Color(Rgba([0, 0, 0, 0x80])));
+
+        let shadow: Shadow = "4x0-3+2".parse().unwrap();
+        assert_eq!((shadow.offset_x, shadow.offset_y), (-3, 2));
+        assert!("8x8".parse::<Shadow>().is_err());
+    }
+
+    #[test]
+    fn test_shadow_keeps_depth() {
+        let img = DynamicImage::ImageRgb16(image::ImageBuffer::from_pixel(4, 4, image::Rgb([1000u16, 2000, 3000])));
+        let args = EffectsArgs { shadow: Some("2x0+2+2".parse().unwrap()), vignette: None };
+        let out = apply_shadow(img, &args).unwrap();
+        assert_eq!(out.color(), image::ColorType::Rgba16);
+        assert_eq!(out.width(), 4 + 4 + 2 + 4);
+        assert_eq!(out.to_rgba16().get_pixel(4, 4).0, [1000, 2000, 3000, 65535]);
+    }
+
+    #[test]
+    fn test_dilate() {
+        let mut mask = GrayImage::new(5, 5);
+        mask.put_pixel(2, 2, Luma([255]));
+        let grown = dilate(&mask, 1);
+        assert_eq!(grown.get_pixel(1, 1).0[0], 255);
+        assert_eq!(grown.get_pixel(0, 0).0[0], 0);
+    }
+}
@@ -0,0 +1,422 @@
+// File: src\encode.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Format-aware image encoding shared by file output and estimation
+// License: MIT
+
+use anyhow::{Context, Result};
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::gif::GifEncoder;
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::codecs::pnm::{PnmEncoder, PnmSubtype, SampleEncoding};
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ExtendedColorType, GrayImage, ImageEncoder, ImageFormat, Rgba};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufWriter, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+
+use crate::cancel::{self, CancelWriter, CancellationToken};
+use crate::encoder::{self, Encoders};
+use crate::formats::capabilities;
+use crate::i18n::tr;
+use crate::print_info;
+
+/// Whether `--quality` has any effect when encoding to `format`
+pub fn supports_quality(format: ImageFormat) -> bool {
+    capabilities(format).is_some_and(|format| format.quality)
+}
+
+/// Whether `format` can store an alpha channel
+pub fn supports_alpha(format: ImageFormat) -> bool {
+    capabilities(format).is_none_or(|format| format.alpha)
+}
+
+/// Whether `format` can store 16 bits per channel
+pub fn supports_16bit(format: ImageFormat) -> bool {
+    capabilities(format).is_some_and(|format| format.sixteen_bit)
+}
+
+/// Color profile and EXIF block to carry over into the output
+#[derive(Debug, Clone, Default)]
+pub struct Metadata {
+    pub icc_profile: Option<Vec<u8>>,
+    pub exif: Option<Vec<u8>>,
+}
+
+/// Encode `img` as `format` into `writer`, honoring `quality` where supported
+/// and the process's other encoder options
+pub fn encode_image<W: Write + Seek>(
+    img: &DynamicImage,
+    format: ImageFormat,
+    quality: u8,
+    writer: &mut W,
+) -> Result<()> {
+    let encoders = encoder::process().at_quality(quality);
+    encode_image_with_metadata(img, format, &encoders, &Metadata::default(), cancel::process(), writer)
+}
+
+/// `encode_image` with the given encoder options, also embedding `metadata`
+/// in formats that can hold it (JPEG, PNG, WebP; other formats drop it) and
+/// giving up once `token` is cancelled
+pub fn encode_image_with_metadata<W: Write + Seek>(
+    img: &DynamicImage,
+    format: ImageFormat,
+    encoders: &Encoders,
+    metadata: &Metadata,
+    token: &CancellationToken,
+    writer: &mut W,
+) -> Result<()> {
+    let quality = encoders.quality_for(format);
+    match format {
+        ImageFormat::Jpeg if encoders.jpeg.progressive => encode_turbo_jpeg(img, quality, true, writer)?,
+        ImageFormat::Jpeg => {
+            let mut encoder = JpegEncoder::new_with_quality(writer, quality);
+            attach(&mut encoder, metadata);
+            match jpeg_compatible(img).as_ref() {
+                DynamicImage::ImageLuma8(gray) => encoder.encode_image(gray),
+                DynamicImage::ImageRgb8(rgb) => encoder.encode_image(rgb),
+                img => encoder.encode_image(img),
+            }
+            .with_context(|| tr!("Failed to encode JPEG image"))?;
+        }
+        ImageFormat::Png => {
+            let compression = match encoders.png.level {
+                None => CompressionType::default(),
+                Some(0) => CompressionType::Uncompressed,
+                Some(level) => CompressionType::Level(level),
+            };
+            let mut encoder = PngEncoder::new_with_quality(writer, compression, FilterType::default());
+            attach(&mut encoder, metadata);
+            img.write_with_encoder(encoder)
+                .with_context(|| tr!("Failed to encode PNG image"))?;
+        }
+        ImageFormat::WebP => {
+            let mut encoder = WebPEncoder::new_lossless(writer);
+            attach(&mut encoder, metadata);
+            img.write_with_encoder(encoder)
+                .with_context(|| tr!("Failed to encode WebP image"))?;
+        }
+        ImageFormat::Gif => {
+            img.write_with_encoder(GifEncoder::new_with_speed(writer, encoders.gif.speed))
+                .with_context(|| tr!("Failed to encode GIF image"))?;
+        }
+        ImageFormat::Avif => encode_avif(img, quality, encoders.avif.speed, token, writer)?,
+        ImageFormat::Hdr => {
+            // The Radiance encoder only accepts 32-bit float RGB
+            DynamicImage::ImageRgb32F(img.to_rgb32f()).write_to(writer, format)
+                .with_context(|| tr!("Failed to encode Hdr image"))?;
+        }
+        _ => {
+            img.write_to(writer, format)
+                .with_context(|| tr!("Failed to encode {} image", format!("{:?}", format)))?;
+        }
+    }
+    Ok(())
+}
+
+/// AVIF encoding is slow and can't be stopped part-way, so when `token` can
+/// be cancelled it runs on its own thread, on a copy of `img`
+///
+/// Cancelling returns at once and discards the result, but the abandoned
+/// thread keeps a core busy in the background until it has encoded the
+/// whole image. A token nothing else holds can't fire, so then the image is
+/// encoded in place, without the thread or the copy.
+fn encode_avif<W: Write>(img: &DynamicImage, quality: u8, speed: u8, token: &CancellationToken, writer: &mut W) -> Result<()> {
+    if !token.can_be_cancelled() {
+        return img
+            .write_with_encoder(AvifEncoder::new_with_speed_quality(writer, speed, quality))
+            .with_context(|| tr!("Failed to encode AVIF image"));
+    }
+    token.check()?;
+    let img = img.clone();
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut data = Vec::new();
+        let result = img.write_with_encoder(AvifEncoder::new_with_speed_quality(&mut data, speed, quality));
+        let _ = sender.send(result.map(|_| data));
+    });
+    loop {
+        match receiver.recv_timeout(Duration::from_millis(100)) {
+            Ok(result) => {
+                let data = result.with_context(|| tr!("Failed to encode AVIF image"))?;
+                return writer.write_all(&data).with_context(|| tr!("Failed to encode AVIF image"));
+            }
+            Err(RecvTimeoutError::Timeout) => token.check()?,
+            Err(RecvTimeoutError::Disconnected) => anyhow::bail!(tr!("Failed to encode AVIF image: the encoder stopped")),
+        }
+    }
+}
+
+/// Encode `img` as a 4:2:0 JPEG with the SIMD encoder, which unlike the
+/// builtin one can write progressive scans
+#[cfg(feature = "turbo")]
+pub fn encode_turbo_jpeg<W: Write>(img: &DynamicImage, quality: u8, progressive: bool, writer: &mut W) -> Result<()> {
+    use jpeg_encoder::{ColorType, Encoder};
+
+    let (width, height) = (img.width(), img.height());
+    let (width, height) = match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(width), Ok(height)) => (width, height),
+        _ => anyhow::bail!(tr!("JPEG images are limited to 65535x65535, got {}x{}", width, height)),
+    };
+    // JPEG has no alpha; callers flatten transparency before saving
+    let (data, color) = match img {
+        DynamicImage::ImageLuma8(gray) => (gray.as_raw().clone(), ColorType::Luma),
+        _ => (img.to_rgb8().into_raw(), ColorType::Rgb),
+    };
+    let mut encoder = Encoder::new(writer, quality);
+    encoder.set_progressive(progressive);
+    encoder.encode(&data, width, height, color).with_context(|| tr!("Failed to encode JPEG image"))
+}
+
+#[cfg(not(feature = "turbo"))]
+pub fn encode_turbo_jpeg<W: Write>(_img: &DynamicImage, _quality: u8, _progressive: bool, _writer: &mut W) -> Result<()> {
+    anyhow::bail!(tr!("The SIMD JPEG encoder is not available in this build; reinstall with `cargo install imgconv --features turbo`"))
+}
+
+/// Hand `metadata` to an encoder; ones that can't store a part just skip it
+fn attach(encoder: &mut impl ImageEncoder, metadata: &Metadata) {
+    if let Some(profile) = &metadata.icc_profile {
+        let _ = encoder.set_icc_profile(profile.clone());
+    }
+    if let Some(exif) = &metadata.exif {
+        let _ = encoder.set_exif_metadata(exif.clone());
+    }
+}
+
+/// `img` as the 8-bit gray or RGB pixels a JPEG stores
+///
+/// Gray sources stay single-channel. Alpha is dropped (transparency is
+/// flattened before this, so it is opaque); deeper samples are rounded to
+/// 8 bits, with a note as that loses precision.
+fn jpeg_compatible(img: &DynamicImage) -> Cow<'_, DynamicImage> {
+    let color = img.color();
+    if matches!(img, DynamicImage::ImageLuma8(_) | DynamicImage::ImageRgb8(_)) {
+        return Cow::Borrowed(img);
+    }
+    let converted = if color.has_color() {
+        DynamicImage::ImageRgb8(img.to_rgb8())
+    } else {
+        DynamicImage::ImageLuma8(img.to_luma8())
+    };
+    if color.bytes_per_pixel() > color.channel_count() {
+        print_info(&tr!("JPEG stores 8 bits per channel, converting {} image to {}", format!("{:?}", color), format!("{:?}", converted.color())));
+    }
+    Cow::Owned(converted)
+}
+
+/// Encode a black-and-white image (pixels 0 or 255) at 1 bit per pixel
+pub fn encode_bilevel<W: Write + Seek>(img: &GrayImage, format: ImageFormat, writer: &mut W) -> Result<()> {
+    let (width, height) = img.dimensions();
+    match format {
+        ImageFormat::Png => {
+            let mut encoder = png::Encoder::new(writer, width, height);
+            encoder.set_color(png::ColorType::Grayscale);
+            encoder.set_depth(png::BitDepth::One);
+            let mut png_writer = encoder.write_header()
+                .with_context(|| tr!("Failed to encode PNG image"))?;
+
+            // Rows are padded to whole bytes, most significant bit first, 1 = white
+            let mut data = Vec::with_capacity((width as usize).div_ceil(8) * height as usize);
+            for row in img.rows() {
+                for chunk in row.collect::<Vec<_>>().chunks(8) {
+                    let byte = chunk.iter().enumerate()
+                        .filter(|(_, p)| p.0[0] >= 128)
+                        .fold(0u8, |byte, (i, _)| byte | (0x80 >> i));
+                    data.push(byte);
+                }
+            }
+            png_writer.write_image_data(&data)
+                .with_context(|| tr!("Failed to encode PNG image"))?;
+            png_writer.finish()
+                .with_context(|| tr!("Failed to encode PNG image"))?;
+        }
+        ImageFormat::Pnm => {
+            // PBM samples: 0 = black, 1 = white
+            let samples: Vec<u8> = img.pixels().map(|p| (p.0[0] >= 128) as u8).collect();
+            PnmEncoder::new(writer)
+                .with_subtype(PnmSubtype::Bitmap(SampleEncoding::Binary))
+                .write_image(&samples, width, height, ExtendedColorType::L8)
+                .with_context(|| tr!("Failed to encode PBM image"))?;
+        }
+        _ => anyhow::bail!(tr!("1-bit output is not supported for {}", format!("{:?}", format))),
+    }
+    Ok(())
+}
+
+/// Bits per index needed for a palette of `colors` entries (1, 2, 4 or 8)
+pub fn palette_bits(colors: usize) -> u8 {
+    match colors {
+        0..=2 => 1,
+        3..=4 => 2,
+        5..=16 => 4,
+        _ => 8,
+    }
+}
+
+/// Pack one index per pixel into rows of `bits`-wide samples, as PNG stores them
+pub fn pack_indices(indices: &[u8], width: u32, bits: u8) -> Vec<u8> {
+    let per_byte = 8 / bits as usize;
+    let mut data = Vec::with_capacity(indices.len().div_ceil(per_byte));
+    for row in indices.chunks(width as usize) {
+        for chunk in row.chunks(per_byte) {
+            let byte = chunk.iter().enumerate()
+                .fold(0u8, |byte, (i, &index)| byte | (index << (8 - bits as usize * (i + 1))));
+            data.push(byte);
+        }
+    }
+    data
+}
+
+/// Encode palette indices as an indexed (PNG8) image, using the smallest bit depth that fits
+pub fn encode_indexed<W: Write>(
+    palette: &[Rgba<u8>],
+    indices: &[u8],
+    (width, height): (u32, u32),
+    writer: &mut W,
+) -> Result<()> {
+    let bits = palette_bits(palette.len());
+    let mut encoder = png::Encoder::new(writer, width, height);
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::from_u8(bits).with_context(|| tr!("Invalid PNG bit depth"))?);
+    encoder.set_palette(palette.iter().flat_map(|c| [c.0[0], c.0[1], c.0[2]]).collect::<Vec<u8>>());
+
+    // tRNS only needs entries up to the last non-opaque color
+    if let Some(last) = palette.iter().rposition(|c| c.0[3] < 255) {
+        encoder.set_trns(palette[..=last].iter().map(|c| c.0[3]).collect::<Vec<u8>>());
+    }
+
+    let data = pack_indices(indices, width, bits);
+    let mut png_writer = encoder.write_header()
+        .with_context(|| tr!("Failed to encode PNG image"))?;
+    png_writer.write_image_data(&data)
+        .with_context(|| tr!("Failed to encode PNG image"))?;
+    png_writer.finish()
+        .with_context(|| tr!("Failed to encode PNG image"))?;
+    Ok(())
+}
+
+/// Create `path` atomically, running `encode` against a buffered writer
+///
+/// Data goes to a hidden temporary file in the destination directory that is
+/// renamed over `path` only once it is complete, so an interrupted or failed
+/// conversion never leaves a truncated file behind.
+pub fn write_file(path: &Path, encode: impl FnOnce(&mut BufWriter<CancelWriter<File>>) -> Result<()>) -> Result<()> {
+    let token = cancel::process();
+    let temp = temp_path(path);
+    let result = (|| {
+        let file = File::create(&temp)
+            .with_context(|| tr!("Failed to create output file: {}", path.display()))?;
+        let mut writer = BufWriter::new(CancelWriter::new(file, token.clone()));
+        encode(&mut writer)?;
+        let file = writer.into_inner()
+            .map_err(|e| e.into_error())
+            .with_context(|| tr!("Failed to save image to: {}", path.display()))?
+            .into_inner();
+        file.sync_all()
+            .with_context(|| tr!("Failed to save image to: {}", path.display()))?;
+        token.check()?;
+        std::fs::rename(&temp, path)
+            .with_context(|| tr!("Failed to move output into place: {}", path.display()))
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    // Report a cancelled encoder as cancelled, not as the write error it stopped with
+    match token.check() {
+        Err(cancelled) if result.is_err() => Err(cancelled.into()),
+        _ => result,
+    }
+}
+
+/// Write raw bytes (JSON, palettes) to `path` atomically
+pub fn write_bytes(path: &Path, data: &[u8]) -> Result<()> {
+    write_file(path, |writer| {
+        writer.write_all(data)
+            .with_context(|| tr!("Failed to write: {}", path.display()))
+    })
+}
+
+/// Hidden sibling of `path` used while writing, e.g. ".photo.png.1234.tmp"
+pub fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
+
+/// Encode `img` into a new file at `path`
+pub fn save_image(img: &DynamicImage, format: ImageFormat, quality: u8, path: &Path) -> Result<()> {
+    write_file(path, |writer| encode_image(img, format, quality, writer))
+}
+
+/// Encode palette indices into a new indexed PNG at `path`
+pub fn save_indexed(palette: &[Rgba<u8>], indices: &[u8], size: (u32, u32), path: &Path) -> Result<()> {
+    write_file(path, |writer| encode_indexed(palette, indices, size, writer))
+}
+
+/// Encode a black-and-white image into a new file at `path` at 1 bit per pixel
+pub fn save_bilevel(img: &GrayImage, format: ImageFormat, path: &Path) -> Result<()> {
+    write_file(path, |writer| encode_bilevel(img, format, writer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_write_keeps_existing_file() {
+        let dir = std::env::temp_dir().join(format!("imgconv-atomic-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.txt");
+        std::fs::write(&path, "original").unwrap();
+
+        assert!(write_file(&path, |_| anyhow::bail!("encoder failed")).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "original");
+        write_bytes(&path, b"replaced").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "replaced");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1, "temporary file left behind");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_avif_in_place_or_on_a_thread() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::from_fn(16, 16, |x, y| image::Rgb([x as u8 * 16, y as u8 * 16, 128])));
+        let encode = |token: &CancellationToken| {
+            let mut data = Vec::new();
+            encode_avif(&img, 60, 10, token, &mut data).map(|_| data)
+        };
+        let unshared = CancellationToken::new();
+        let shared = CancellationToken::new();
+        let handle = shared.clone();
+        assert_eq!(encode(&unshared).unwrap(), encode(&shared).unwrap());
+        handle.cancel();
+        assert!(encode(&shared).is_err());
+    }
+
+    #[test]
+    fn test_jpeg_from_gray_and_16_bit() {
+        let gray = DynamicImage::ImageLuma16(image::ImageBuffer::from_pixel(8, 8, image::Luma([40_000u16])));
+        let rgba = DynamicImage::ImageRgba16(image::ImageBuffer::from_pixel(8, 8, image::Rgba([65_535, 0, 0, 65_535])));
+        for (img, expected) in [(gray, image::ColorType::L8), (rgba, image::ColorType::Rgb8)] {
+            let mut buffer = std::io::Cursor::new(Vec::new());
+            encode_image(&img, ImageFormat::Jpeg, 90, &mut buffer).unwrap();
+            let decoded = image::load_from_memory_with_format(buffer.get_ref(), ImageFormat::Jpeg).unwrap();
+            assert_eq!(decoded.color(), expected);
+        }
+    }
+
+    #[test]
+    fn test_indexed_png_round_trip() {
+        let palette = [Rgba([255, 0, 0, 255]), Rgba([0, 0, 255, 128]), Rgba([0, 255, 0, 255])];
+        let indices = [0, 1, 2, 1, 0, 2];
+        let mut png = Vec::new();
+        encode_indexed(&palette, &indices, (3, 2), &mut png).unwrap();
+
+        let img = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(*img.get_pixel(1, 0), Rgba([0, 0, 255, 128]));
+        assert_eq!(*img.get_pixel(2, 1), Rgba([0, 255, 0, 255]));
+    }
+}
@@ -0,0 +1,258 @@
+// File: src\encoder.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Per-format encoder options (--jpeg.*, --png.*, --avif.*, --gif.*) and their library types
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::ImageFormat;
+use std::sync::OnceLock;
+
+#[derive(Args, Debug, Default)]
+pub struct EncoderArgs {
+    /// JPEG quality (1-100), overriding -q for JPEG output
+    #[arg(long = "jpeg.quality", value_name = "N", value_parser = clap::value_parser!(u8).range(1..=100))]
+    pub jpeg_quality: Option<u8>,
+
+    /// Write progressive JPEG (needs a build with `--features turbo`)
+    #[arg(long = "jpeg.progressive")]
+    pub jpeg_progressive: bool,
+
+    /// PNG compression level, 0 (none) to 9 (smallest, slowest)
+    #[arg(long = "png.level", value_name = "N", value_parser = clap::value_parser!(u8).range(0..=9))]
+    pub png_level: Option<u8>,
+
+    /// AVIF quality (1-100), overriding -q for AVIF output
+    #[arg(long = "avif.quality", value_name = "N", value_parser = clap::value_parser!(u8).range(1..=100))]
+    pub avif_quality: Option<u8>,
+
+    /// AVIF encoder speed, 1 (smallest, slowest) to 10 (fastest)
+    #[arg(long = "avif.speed", value_name = "N", value_parser = clap::value_parser!(u8).range(1..=10))]
+    pub avif_speed: Option<u8>,
+
+    /// GIF palette speed, 1 (best colors) to 30 (fastest)
+    #[arg(long = "gif.speed", value_name = "N", value_parser = clap::value_parser!(i32).range(1..=30))]
+    pub gif_speed: Option<i32>,
+}
+
+impl EncoderArgs {
+    /// The encoder settings these flags describe, with `quality` (-q) for
+    /// formats without their own
+    pub fn encoders(&self, quality: u8) -> Encoders {
+        let mut encoders = Encoders::new(quality);
+        encoders.jpeg = JpegOpts { quality: self.jpeg_quality, progressive: self.jpeg_progressive };
+        encoders.png.level = self.png_level;
+        encoders.avif.quality = self.avif_quality;
+        encoders.avif.speed = self.avif_speed.unwrap_or(encoders.avif.speed);
+        encoders.gif.speed = self.gif_speed.unwrap_or(encoders.gif.speed);
+        encoders
+    }
+
+    /// Which flags were given, for checking against the output format
+    pub fn given(&self) -> [(&'static str, bool); 6] {
+        [
+            ("--jpeg.quality", self.jpeg_quality.is_some()),
+            ("--jpeg.progressive", self.jpeg_progressive),
+            ("--png.level", self.png_level.is_some()),
+            ("--avif.quality", self.avif_quality.is_some()),
+            ("--avif.speed", self.avif_speed.is_some()),
+            ("--gif.speed", self.gif_speed.is_some()),
+        ]
+    }
+
+    /// Fail early on options this build can't honour
+    pub fn check(&self) -> Result<()> {
+        self.encoders(90).validate().map_err(anyhow::Error::msg)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JpegOpts {
+    /// Quality (1-100); None uses the converter's quality
+    pub quality: Option<u8>,
+    /// Progressive scans, which needs the "turbo" feature
+    pub progressive: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PngOpts {
+    /// Compression level, 0 (none) to 9; None is the encoder's fast default
+    pub level: Option<u8>,
+}
+
+/// WebP has nothing to set yet: the encoder in this build is lossless only
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WebpOpts;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AvifOpts {
+    /// Quality (1-100); None uses the converter's quality
+    pub quality: Option<u8>,
+    /// 1 (smallest, slowest) to 10 (fastest)
+    pub speed: u8,
+}
+
+impl Default for AvifOpts {
+    fn default() -> Self {
+        Self { quality: None, speed: 4 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GifOpts {
+    /// Palette speed, 1 (best colors) to 30 (fastest)
+    pub speed: i32,
+}
+
+impl Default for GifOpts {
+    fn default() -> Self {
+        Self { speed: 1 }
+    }
+}
+
+/// Options for one output format's encoder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncoderOptions {
+    Jpeg(JpegOpts),
+    Png(PngOpts),
+    WebP(WebpOpts),
+    Avif(AvifOpts),
+    Gif(GifOpts),
+}
+
+impl EncoderOptions {
+    /// The format these options apply to
+    pub fn format(&self) -> ImageFormat {
+        match self {
+            Self::Jpeg(_) => ImageFormat::Jpeg,
+            Self::Png(_) => ImageFormat::Png,
+            Self::WebP(_) => ImageFormat::WebP,
+            Self::Avif(_) => ImageFormat::Avif,
+            Self::Gif(_) => ImageFormat::Gif,
+        }
+    }
+}
+
+/// The options of every format's encoder, plus the quality used where a
+/// format doesn't set its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Encoders {
+    pub quality: u8,
+    pub jpeg: JpegOpts,
+    pub png: PngOpts,
+    pub webp: WebpOpts,
+    pub avif: AvifOpts,
+    pub gif: GifOpts,
+}
+
+impl Default for Encoders {
+    fn default() -> Self {
+        Self::new(90)
+    }
+}
+
+impl Encoders {
+    pub fn new(quality: u8) -> Self {
+        Self {
+            quality,
+            jpeg: JpegOpts::default(),
+            png: PngOpts::default(),
+            webp: WebpOpts,
+            avif: AvifOpts::default(),
+            gif: GifOpts::default(),
+        }
+    }
+
+    /// Replace the options of the format `options` is for
+    pub fn set(&mut self, options: EncoderOptions) {
+        match options {
+            EncoderOptions::Jpeg(options) => self.jpeg = options,
+            EncoderOptions::Png(options) => self.png = options,
+            EncoderOptions::WebP(options) => self.webp = options,
+            EncoderOptions::Avif(options) => self.avif = options,
+            EncoderOptions::Gif(options) => self.gif = options,
+        }
+    }
+
+    /// The quality `format` is encoded at
+    pub fn quality_for(&self, format: ImageFormat) -> u8 {
+        match format {
+            ImageFormat::Jpeg => self.jpeg.quality,
+            ImageFormat::Avif => self.avif.quality,
+            _ => None,
+        }
+        .unwrap_or(self.quality)
+    }
+
+    /// These options with every format encoded at `quality`, for a quality
+    /// that was already chosen per format (e.g. by --auto-quality)
+    pub fn at_quality(mut self, quality: u8) -> Self {
+        self.quality = quality;
+        self.jpeg.quality = None;
+        self.avif.quality = None;
+        self
+    }
+
+    /// Describe the first option out of range or unavailable in this build
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, quality) in [("Quality", Some(self.quality)), ("JPEG quality", self.jpeg.quality), ("AVIF quality", self.avif.quality)] {
+            if let Some(quality) = quality.filter(|quality| !(1..=100).contains(quality)) {
+                return Err(format!("{} must be between 1 and 100, got {}", name, quality));
+            }
+        }
+        if self.jpeg.progressive && !cfg!(feature = "turbo") {
+            return Err("Progressive JPEG needs a build with `--features turbo`".to_string());
+        }
+        if let Some(level) = self.png.level.filter(|level| *level > 9) {
+            return Err(format!("PNG compression level must be between 0 and 9, got {}", level));
+        }
+        if !(1..=10).contains(&self.avif.speed) {
+            return Err(format!("AVIF speed must be between 1 and 10, got {}", self.avif.speed));
+        }
+        if !(1..=30).contains(&self.gif.speed) {
+            return Err(format!("GIF speed must be between 1 and 30, got {}", self.gif.speed));
+        }
+        Ok(())
+    }
+}
+
+static PROCESS: OnceLock<Encoders> = OnceLock::new();
+
+/// Use `encoders` for everything this process encodes without its own settings
+#[doc(hidden)]
+pub fn set_process(encoders: Encoders) {
+    let _ = PROCESS.set(encoders);
+}
+
+/// The settings from `set_process`, or the defaults
+#[doc(hidden)]
+pub fn process() -> Encoders {
+    PROCESS.get().copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_per_format_quality() {
+        let args = EncoderArgs { jpeg_quality: Some(70), avif_speed: Some(8), ..Default::default() };
+        let encoders = args.encoders(85);
+        assert_eq!(encoders.quality_for(ImageFormat::Jpeg), 70);
+        assert_eq!(encoders.quality_for(ImageFormat::Avif), 85);
+        assert_eq!(encoders.avif.speed, 8);
+        assert_eq!(encoders.at_quality(40).quality_for(ImageFormat::Jpeg), 40);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut encoders = Encoders::new(90);
+        assert!(encoders.validate().is_ok());
+        encoders.set(EncoderOptions::Png(PngOpts { level: Some(12) }));
+        assert!(encoders.validate().unwrap_err().contains("PNG"));
+        encoders.set(EncoderOptions::Png(PngOpts::default()));
+        encoders.set(EncoderOptions::Jpeg(JpegOpts { quality: None, progressive: true }));
+        assert_eq!(encoders.validate().is_ok(), cfg!(feature = "turbo"));
+    }
+}
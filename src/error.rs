@@ -0,0 +1,57 @@
+// File: src\error.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Error type returned by the library API
+// License: MIT
+
+use image::ImageFormat;
+
+/// The underlying cause of a decode or encode failure
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+pub type Result<T, E = ImgconvError> = std::result::Result<T, E>;
+
+/// Why a conversion failed, for embedders to match on
+///
+/// The cause is kept as the error source, so `{:#}` through anyhow (as the
+/// command line prints it) still shows the whole chain.
+#[derive(Debug, thiserror::Error)]
+pub enum ImgconvError {
+    #[error("Failed to decode {input}")]
+    Decode { input: String, #[source] source: BoxError },
+
+    #[error("Failed to encode {format:?} image")]
+    Encode { format: ImageFormat, #[source] source: BoxError },
+
+    /// A pipeline step failed
+    #[error("Failed to {step}")]
+    Transform { step: String, #[source] source: BoxError },
+
+    /// No output format could be picked, or this build cannot write it
+    #[error("{0}")]
+    UnsupportedFormat(String),
+
+    /// An option is out of range
+    #[error("{0}")]
+    InvalidOption(String),
+
+    /// The conversion's `CancellationToken` was cancelled; nothing was written
+    #[error("Cancelled")]
+    Cancelled,
+
+    /// A source or sink failed, such as a download or an upload
+    #[error("Failed to transfer {name}")]
+    Transport { name: String, #[source] source: BoxError },
+
+    #[error("No image found in clipboard")]
+    ClipboardEmpty,
+
+    #[error("{context}")]
+    Io { context: String, #[source] source: std::io::Error },
+}
+
+impl ImgconvError {
+    pub(crate) fn io(context: String) -> impl FnOnce(std::io::Error) -> Self {
+        move |source| Self::Io { context, source }
+    }
+}
@@ -0,0 +1,152 @@
+// File: src\estimate.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Projected output size per quality without writing to disk
+// License: MIT
+
+use anyhow::Result;
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use std::io::Cursor;
+
+use crate::encode::{encode_image, supports_quality};
+use crate::i18n::tr;
+use crate::print_info;
+
+/// Qualities tried when `--estimate` is given without a list
+pub const DEFAULT_CANDIDATES: [&str; 6] = ["50", "60", "70", "80", "90", "95"];
+
+/// Images above this many pixels are estimated from sampled tiles
+const SAMPLE_THRESHOLD: u64 = 8_000_000;
+
+/// Edge length of each sampled tile
+const TILE_SIZE: u32 = 512;
+
+/// Tiles sampled per axis (the grid is TILES_PER_AXIS x TILES_PER_AXIS)
+const TILES_PER_AXIS: u32 = 3;
+
+/// Print the projected encoded size of `img` for each candidate quality
+pub fn run(img: &DynamicImage, format: ImageFormat, qualities: &[u8]) -> Result<()> {
+    let (width, height) = img.dimensions();
+    let total_pixels = width as u64 * height as u64;
+
+    let tiles = if total_pixels > SAMPLE_THRESHOLD {
+        let tiles = sample_tiles(width, height);
+        print_info(&tr!(
+            "Large image ({}x{}), estimating from {} sampled tiles",
+            width, height, tiles.len()
+        ));
+        tiles
+    } else {
+        vec![(0, 0, width, height)]
+    };
+    let sampled_pixels: u64 = tiles.iter().map(|&(_, _, w, h)| w as u64 * h as u64).sum();
+
+    // Lossless formats ignore quality, so a single pass is enough
+    let qualities: Vec<u8> = if supports_quality(format) {
+        qualities.to_vec()
+    } else {
+        print_info(&tr!("{} ignores quality, estimating a single size", format!("{:?}", format)));
+        vec![qualities.first().copied().unwrap_or(90)]
+    };
+
+    for quality in qualities {
+        let mut encoded: u64 = 0;
+        for &(x, y, w, h) in &tiles {
+            let tile = if tiles.len() == 1 { img.clone() } else { img.crop_imm(x, y, w, h) };
+            let mut buffer = Cursor::new(Vec::new());
+            encode_image(&tile, format, quality, &mut buffer)?;
+            encoded += buffer.get_ref().len() as u64;
+        }
+        let projected = encoded * total_pixels / sampled_pixels.max(1);
+
+        if supports_quality(format) {
+            eprintln!("  quality {:>3}  ~{}", quality, format_size(projected));
+        } else {
+            eprintln!("  ~{}", format_size(projected));
+        }
+    }
+
+    Ok(())
+}
+
+/// Highest quality up to `max_quality` whose encoding of `img` fits in
+/// `limit` bytes, with that size; None when even quality 1 is too large
+///
+/// Quality is searched by bisection, encoding the whole image each step.
+pub fn quality_within(img: &DynamicImage, format: ImageFormat, max_quality: u8, limit: u64) -> Result<Option<(u8, u64)>> {
+    let encoded_size = |quality: u8| -> Result<u64> {
+        let mut buffer = Cursor::new(Vec::new());
+        encode_image(img, format, quality, &mut buffer)?;
+        Ok(buffer.get_ref().len() as u64)
+    };
+    let (mut low, mut high) = (1, max_quality.max(1));
+    let mut best = None;
+    while low <= high {
+        let quality = low + (high - low) / 2;
+        let size = encoded_size(quality)?;
+        if size <= limit {
+            best = Some((quality, size));
+            low = quality + 1;
+        } else if quality == 1 {
+            break;
+        } else {
+            high = quality - 1;
+        }
+    }
+    Ok(best)
+}
+
+/// Evenly spread a grid of tiles across the image
+fn sample_tiles(width: u32, height: u32) -> Vec<(u32, u32, u32, u32)> {
+    let tile_w = TILE_SIZE.min(width);
+    let tile_h = TILE_SIZE.min(height);
+    let mut tiles = Vec::new();
+
+    for row in 0..TILES_PER_AXIS {
+        for col in 0..TILES_PER_AXIS {
+            let x = (width - tile_w) * col / (TILES_PER_AXIS - 1);
+            let y = (height - tile_h) * row / (TILES_PER_AXIS - 1);
+            tiles.push((x, y, tile_w, tile_h));
+        }
+    }
+
+    tiles
+}
+
+/// Human readable byte size
+pub fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.2} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_tiles_cover_corners() {
+        let tiles = sample_tiles(4000, 3000);
+        assert_eq!(tiles.len(), 9);
+        assert_eq!(tiles[0], (0, 0, 512, 512));
+        assert_eq!(tiles[8], (4000 - 512, 3000 - 512, 512, 512));
+    }
+
+    #[test]
+    fn test_quality_within_limit() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::from_fn(128, 128, |x, y| {
+            image::Rgb([((x * 7) ^ (y * 13)) as u8, (x * y) as u8, (x + y * 3) as u8])
+        }));
+        let (quality, size) = quality_within(&img, ImageFormat::Jpeg, 95, 6 * 1024).unwrap().unwrap();
+        assert!(size <= 6 * 1024 && quality < 95);
+        assert!(quality_within(&img, ImageFormat::Jpeg, 95, 100).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.00 MB");
+    }
+}
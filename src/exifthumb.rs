@@ -0,0 +1,321 @@
+// File: src\exifthumb.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: EXIF-embedded JPEG thumbnails and capture dates, read without decoding the full image
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::{DynamicImage, ImageFormat};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::i18n::tr;
+use crate::print_info;
+
+/// How much of the file is read to find the thumbnail (EXIF sits at the start)
+const HEADER_BYTES: u64 = 256 * 1024;
+
+#[derive(Args, Debug)]
+pub struct EmbeddedArgs {
+    /// Use the EXIF-embedded JPEG thumbnail when present instead of decoding the full image
+    #[arg(long)]
+    pub embedded_thumbnail: bool,
+}
+
+/// Decode the EXIF thumbnail of `path` (JPEG, or TIFF-based RAW), None when
+/// it has none and the caller should decode the full image
+///
+/// Only the header and the thumbnail itself are read, so this stays fast on
+/// large photos.
+pub fn load(path: &Path) -> Result<Option<DynamicImage>> {
+    let Some(data) = read(path)? else {
+        print_info(&tr!("No embedded thumbnail in {}, decoding the full image", path.display()));
+        return Ok(None);
+    };
+    let img = image::load_from_memory_with_format(&data, ImageFormat::Jpeg)
+        .with_context(|| format!("Failed to decode the embedded thumbnail of {}", path.display()))?;
+    print_info(&tr!("Using the embedded {}x{} EXIF thumbnail", img.width(), img.height()));
+    Ok(Some(img))
+}
+
+/// The EXIF thumbnail of `path` as stored (a small JPEG), None when it has none
+pub fn read(path: &Path) -> Result<Option<Vec<u8>>> {
+    let mut file = File::open(path).with_context(|| format!("Failed to open input file: {}", path.display()))?;
+    let mut header = Vec::new();
+    (&mut file).take(HEADER_BYTES).read_to_end(&mut header)
+        .with_context(|| format!("Failed to read input file: {}", path.display()))?;
+
+    let Some((offset, length)) = locate(&header) else {
+        return Ok(None);
+    };
+    let mut data = vec![0; length];
+    file.seek(SeekFrom::Start(offset as u64))
+        .and_then(|_| file.read_exact(&mut data))
+        .with_context(|| format!("Embedded thumbnail of {} is truncated", path.display()))?;
+    Ok(Some(data))
+}
+
+/// A copy of `jpeg` with `thumbnail` (a JPEG) as its EXIF thumbnail, in
+/// place of any it had; the image data and other metadata are kept as they are
+pub fn embed(jpeg: &[u8], thumbnail: &[u8]) -> Result<Vec<u8>> {
+    if !jpeg.starts_with(&[0xFF, 0xD8]) {
+        anyhow::bail!("EXIF thumbnails can only be embedded in JPEG files");
+    }
+    let segment = exif_app1(jpeg);
+    // Without EXIF data, start from an empty little-endian IFD0
+    let mut tiff = match segment {
+        Some((start, end)) => jpeg[start + 10..end].to_vec(),
+        None => b"II\x2a\x00\x08\x00\x00\x00\x00\x00\x00\x00\x00\x00".to_vec(),
+    };
+    let (big_endian, next_ifd, old_end) = {
+        let (_, parsed) = Tiff::find(&tiff).context("The EXIF data is not valid TIFF")?;
+        let ifd0 = parsed.u32_at(4).context("The EXIF data is truncated")? as usize;
+        let next_ifd = ifd0 + 2 + 12 * parsed.u16_at(ifd0).context("The EXIF data is truncated")? as usize;
+        let ifd1 = parsed.u32_at(next_ifd).context("The EXIF data is truncated")? as usize;
+        // Usually the old IFD1 and its thumbnail close the block; they can then be
+        // dropped, otherwise they are just left unreferenced
+        let old_end = locate(&tiff).filter(|(offset, length)| offset + length == tiff.len()).map(|(offset, _)| {
+            let ifd1_end = parsed.u16_at(ifd1).map(|count| ifd1 + 2 + 12 * count as usize + 4);
+            if ifd1 > 0 && ifd1_end == Some(offset) { ifd1 } else { offset }
+        });
+        (parsed.big_endian, next_ifd, old_end)
+    };
+    if let Some(end) = old_end {
+        tiff.truncate(end);
+    }
+    tiff.resize(tiff.len() + tiff.len() % 2, 0);
+    let u16_bytes = |v: u16| if big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+    let u32_bytes = |v: u32| if big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+
+    // A fresh IFD1: compression (6 = JPEG), then the thumbnail's offset and length
+    let ifd1 = tiff.len() as u32;
+    tiff[next_ifd..next_ifd + 4].copy_from_slice(&u32_bytes(ifd1));
+    tiff.extend_from_slice(&u16_bytes(3));
+    for (tag, kind, value) in [(0x0103, 3, 6), (0x0201, 4, ifd1 + 42), (0x0202, 4, thumbnail.len() as u32)] {
+        tiff.extend_from_slice(&u16_bytes(tag));
+        tiff.extend_from_slice(&u16_bytes(kind));
+        tiff.extend_from_slice(&u32_bytes(1));
+        // SHORT values sit in the first two bytes of the field
+        match kind {
+            3 => tiff.extend_from_slice(&[u16_bytes(value as u16), [0, 0]].concat()),
+            _ => tiff.extend_from_slice(&u32_bytes(value)),
+        }
+    }
+    tiff.extend_from_slice(&[0; 4]);
+    tiff.extend_from_slice(thumbnail);
+
+    let length = tiff.len() + 8;
+    if length > u16::MAX as usize {
+        anyhow::bail!("The EXIF data with a {} byte thumbnail exceeds the 64 KB a JPEG segment holds", thumbnail.len());
+    }
+    let (before, after) = match segment {
+        Some((start, end)) => (start, end),
+        // EXIF goes first, after the JFIF header when there is one
+        None => match jpeg.get(2..4) {
+            Some([0xFF, 0xE0]) => {
+                let end = 4 + u16::from_be_bytes([jpeg[4], jpeg[5]]) as usize;
+                (end, end)
+            }
+            _ => (2, 2),
+        },
+    };
+    let mut out = Vec::with_capacity(jpeg.len() + length);
+    out.extend_from_slice(&jpeg[..before]);
+    out.extend_from_slice(&[0xFF, 0xE1]);
+    out.extend_from_slice(&(length as u16).to_be_bytes());
+    out.extend_from_slice(b"Exif\0\0");
+    out.extend_from_slice(&tiff);
+    out.extend_from_slice(&jpeg[after..]);
+    Ok(out)
+}
+
+/// When the photo in `path` was taken, as EXIF's "YYYY:MM:DD HH:MM:SS"
+/// (DateTimeOriginal, else DateTime), None without EXIF data
+pub fn date_taken(path: &Path) -> Option<String> {
+    let mut header = Vec::new();
+    File::open(path).ok()?.take(HEADER_BYTES).read_to_end(&mut header).ok()?;
+    date_in(&header)
+}
+
+pub(crate) fn date_in(header: &[u8]) -> Option<String> {
+    let (_, tiff) = Tiff::find(header)?;
+    let ifd0 = tiff.u32_at(4)? as usize;
+    let ascii = |pos: usize| {
+        // A 20-byte ASCII value ("YYYY:MM:DD HH:MM:SS\0") always sits at the offset
+        let offset = tiff.u32_at(pos + 8)? as usize;
+        let text = std::str::from_utf8(tiff.data.get(offset..offset + 19)?).ok()?;
+        // Cameras without a clock write "0000:00:00 00:00:00"
+        (!text.trim_matches(|c: char| c == ' ' || c == ':' || c == '0').is_empty()).then(|| text.to_string())
+    };
+    let exif_ifd = tiff.entries(ifd0)?.find(|(tag, _)| *tag == 0x8769).and_then(|(_, pos)| tiff.u32_at(pos + 8));
+    let original = exif_ifd
+        .and_then(|ifd| tiff.entries(ifd as usize))
+        .and_then(|mut entries| entries.find(|(tag, _)| *tag == 0x9003)) // DateTimeOriginal
+        .and_then(|(_, pos)| ascii(pos));
+    original.or_else(|| tiff.entries(ifd0)?.find(|(tag, _)| *tag == 0x0132).and_then(|(_, pos)| ascii(pos)))
+}
+
+/// The TIFF structure holding EXIF data
+pub(crate) struct Tiff<'a> {
+    pub(crate) data: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    /// The TIFF structure in `header` (a JPEG APP1 segment, or a TIFF
+    /// container as used by RAW files) and where it starts
+    pub(crate) fn find(header: &'a [u8]) -> Option<(usize, Self)> {
+        let start = if header.starts_with(&[0xFF, 0xD8]) { exif_segment(header)? } else { 0 };
+        let data = &header[start..];
+        let big_endian = match data.get(..4)? {
+            [b'I', b'I', 42, 0] => false,
+            [b'M', b'M', 0, 42] => true,
+            _ => return None,
+        };
+        Some((start, Self { data, big_endian }))
+    }
+
+    pub(crate) fn u16_at(&self, pos: usize) -> Option<u16> {
+        let b = self.data.get(pos..pos + 2)?;
+        Some(if self.big_endian { u16::from_be_bytes([b[0], b[1]]) } else { u16::from_le_bytes([b[0], b[1]]) })
+    }
+
+    pub(crate) fn u32_at(&self, pos: usize) -> Option<u32> {
+        let b = self.data.get(pos..pos + 4)?;
+        let b = [b[0], b[1], b[2], b[3]];
+        Some(if self.big_endian { u32::from_be_bytes(b) } else { u32::from_le_bytes(b) })
+    }
+
+    /// Tag and position of each entry in the IFD at `ifd`
+    pub(crate) fn entries(&self, ifd: usize) -> Option<impl Iterator<Item = (u16, usize)> + '_> {
+        let count = self.u16_at(ifd)? as usize;
+        Some((0..count).filter_map(move |entry| {
+            let pos = ifd + 2 + 12 * entry;
+            Some((self.u16_at(pos)?, pos))
+        }))
+    }
+
+    /// The text of the ASCII entry at `pos`, without its trailing NULs
+    pub(crate) fn ascii(&self, pos: usize) -> Option<String> {
+        let count = self.u32_at(pos + 4)? as usize;
+        // Values of up to 4 bytes are stored in the entry itself
+        let offset = if count <= 4 { pos + 8 } else { self.u32_at(pos + 8)? as usize };
+        let text = std::str::from_utf8(self.data.get(offset..offset + count)?).ok()?;
+        Some(text.trim_end_matches('\0').trim().to_string()).filter(|text| !text.is_empty())
+    }
+}
+
+/// File offset and length of the thumbnail JPEG described by the EXIF data in `header`
+fn locate(header: &[u8]) -> Option<(usize, usize)> {
+    let (tiff_start, tiff) = Tiff::find(header)?;
+
+    // IFD0 describes the main image; the thumbnail lives in the IFD after it
+    let ifd0 = tiff.u32_at(4)? as usize;
+    let ifd1 = tiff.u32_at(ifd0 + 2 + 12 * tiff.u16_at(ifd0)? as usize)? as usize;
+    if ifd1 == 0 {
+        return None;
+    }
+    let (mut offset, mut length) = (None, None);
+    for (tag, pos) in tiff.entries(ifd1)? {
+        match tag {
+            0x0201 => offset = tiff.u32_at(pos + 8), // JPEGInterchangeFormat
+            0x0202 => length = tiff.u32_at(pos + 8), // JPEGInterchangeFormatLength
+            _ => {}
+        }
+    }
+    match (offset?, length?) {
+        (_, 0) => None,
+        (offset, length) => Some((tiff_start + offset as usize, length as usize)),
+    }
+}
+
+/// Start of the TIFF structure inside a JPEG's "Exif" APP1 segment
+fn exif_segment(jpeg: &[u8]) -> Option<usize> {
+    exif_app1(jpeg).map(|(start, _)| start + 10)
+}
+
+/// Where a JPEG's "Exif" APP1 segment starts (at its marker) and ends
+fn exif_app1(jpeg: &[u8]) -> Option<(usize, usize)> {
+    let mut pos = 2; // skip SOI
+    while pos + 4 <= jpeg.len() && jpeg[pos] == 0xFF {
+        let marker = jpeg[pos + 1];
+        if marker == 0xDA || marker == 0xD9 {
+            return None; // start of scan / end of image: no more headers
+        }
+        let length = u16::from_be_bytes([jpeg[pos + 2], jpeg[pos + 3]]) as usize;
+        if marker == 0xE1 && jpeg.get(pos + 4..pos + 10) == Some(b"Exif\0\0") {
+            return Some((pos, (pos + 2 + length).min(jpeg.len())));
+        }
+        pos += 2 + length;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_thumbnail_in_jpeg() {
+        // Little-endian TIFF: empty IFD0 pointing at an IFD1 with offset 44 and length 4
+        let mut tiff = b"II\x2a\x00\x08\x00\x00\x00".to_vec();
+        tiff.extend_from_slice(&[0, 0, 14, 0, 0, 0]); // IFD0: no entries, next IFD at 14
+        tiff.extend_from_slice(&[2, 0]);
+        tiff.extend_from_slice(&[0x01, 0x02, 4, 0, 1, 0, 0, 0, 44, 0, 0, 0]);
+        tiff.extend_from_slice(&[0x02, 0x02, 4, 0, 1, 0, 0, 0, 4, 0, 0, 0]);
+        tiff.extend_from_slice(&[0, 0, 0, 0]);
+        tiff.extend_from_slice(b"\xFF\xD8\xFF\xD9");
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+        jpeg.extend_from_slice(b"Exif\0\0");
+        jpeg.extend_from_slice(&tiff);
+        assert_eq!(locate(&jpeg), Some((12 + 44, 4)));
+        assert_eq!(&jpeg[56..60], b"\xFF\xD8\xFF\xD9");
+        assert_eq!(locate(&tiff), Some((44, 4)));
+        assert_eq!(locate(b"\xFF\xD8\xFF\xDA"), None);
+    }
+
+    #[test]
+    fn test_date_taken() {
+        // Big-endian TIFF: IFD0 with DateTime and an Exif IFD holding DateTimeOriginal
+        let mut tiff = b"MM\x00\x2a\x00\x00\x00\x08".to_vec();
+        tiff.extend_from_slice(&[0, 2]);
+        tiff.extend_from_slice(&[0x01, 0x32, 0, 2, 0, 0, 0, 20, 0, 0, 0, 52]);
+        tiff.extend_from_slice(&[0x87, 0x69, 0, 4, 0, 0, 0, 1, 0, 0, 0, 38]);
+        tiff.extend_from_slice(&[0, 0, 0, 0]);
+        tiff.extend_from_slice(&[0, 1, 0x90, 0x03, 0, 2, 0, 0, 0, 20, 0, 0, 0, 72]);
+        tiff.extend_from_slice(b"2024:01:02 03:04:05\0");
+        tiff.extend_from_slice(b"2023:07:08 09:10:11\0");
+        assert_eq!(date_in(&tiff).as_deref(), Some("2023:07:08 09:10:11"));
+
+        tiff[40..42].copy_from_slice(&[0x90, 0x04]); // no DateTimeOriginal: fall back to DateTime
+        assert_eq!(date_in(&tiff).as_deref(), Some("2024:01:02 03:04:05"));
+        assert_eq!(date_in(b"\xFF\xD8\xFF\xDA"), None);
+    }
+
+    #[test]
+    fn test_embed_thumbnail() {
+        let jpeg = |size: u32| {
+            let mut data = std::io::Cursor::new(Vec::new());
+            DynamicImage::new_rgb8(size, size).write_to(&mut data, ImageFormat::Jpeg).unwrap();
+            data.into_inner()
+        };
+        let (photo, thumbnail) = (jpeg(64), jpeg(8));
+        assert_eq!(locate(&photo), None);
+        let embedded = embed(&photo, &thumbnail).unwrap();
+        let (offset, length) = locate(&embedded).unwrap();
+        assert_eq!(&embedded[offset..offset + length], &thumbnail[..]);
+        assert_eq!(image::load_from_memory(&embedded).unwrap().width(), 64);
+
+        // Embedding again replaces the thumbnail rather than piling them up
+        let smaller = jpeg(4);
+        let again = embed(&embedded, &smaller).unwrap();
+        let (offset, length) = locate(&again).unwrap();
+        assert_eq!(&again[offset..offset + length], &smaller[..]);
+        assert_eq!(again.len(), embedded.len() - thumbnail.len() + smaller.len());
+        assert!(embed(b"GIF89a", &smaller).is_err());
+    }
+}
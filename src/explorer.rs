@@ -0,0 +1,192 @@
+// File: src\explorer.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Windows Explorer context-menu entries (install-shell-integration)
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use std::process::Command;
+
+use crate::i18n::tr;
+use crate::{print_info, print_success, print_warning, Format};
+
+/// Per-user classes, so no administrator rights are needed
+const CLASSES: &str = r"HKCU\Software\Classes";
+
+/// Keys owned by imgconv, removed again on uninstall
+const ROOTS: [&str; 3] = [
+    r"SystemFileAssociations\image\shell\imgconv",
+    r"Directory\shell\imgconv",
+    r"Directory\Background\shell\imgconv",
+];
+
+#[derive(Args, Debug)]
+pub struct InstallArgs {
+    /// Formats offered in the "Convert to" menus
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "png,jpeg,webp", value_name = "FORMATS")]
+    pub formats: Vec<Format>,
+}
+
+/// One registry value: `name` None is the key's default value
+#[derive(Debug, Clone, PartialEq)]
+struct Value {
+    key: String,
+    name: Option<&'static str>,
+    data: String,
+}
+
+impl Value {
+    fn new(key: impl Into<String>, name: Option<&'static str>, data: impl Into<String>) -> Self {
+        Self { key: key.into(), name, data: data.into() }
+    }
+}
+
+pub fn install(args: &InstallArgs) -> Result<()> {
+    ensure_windows()?;
+    let exe = std::env::current_exe().context("Cannot locate the imgconv executable")?;
+    let values = values(&exe.display().to_string(), &args.formats);
+    for value in &values {
+        let key = format!(r"{}\{}", CLASSES, value.key);
+        let mut reg = Command::new("reg");
+        reg.args(["add", &key]);
+        match value.name {
+            Some(name) => reg.args(["/v", name]),
+            None => reg.arg("/ve"),
+        };
+        let output = reg.args(["/d", &value.data, "/f"]).output().context("Failed to run reg.exe")?;
+        if !output.status.success() {
+            anyhow::bail!("reg add {} failed: {}", key, String::from_utf8_lossy(&output.stderr).trim());
+        }
+    }
+    print_success(&tr!("Added \"Convert with imgconv\" to the Explorer context menu ({} registry values)", values.len()));
+    print_info(&tr!("Right-click an image, a folder or a folder background; remove with `imgconv uninstall-shell-integration`"));
+    Ok(())
+}
+
+pub fn uninstall() -> Result<()> {
+    ensure_windows()?;
+    let mut removed = 0;
+    for root in ROOTS {
+        let key = format!(r"{}\{}", CLASSES, root);
+        let output = Command::new("reg").args(["delete", &key, "/f"]).output().context("Failed to run reg.exe")?;
+        if output.status.success() {
+            removed += 1;
+        }
+    }
+    if removed == 0 {
+        print_warning(&tr!("No imgconv context-menu entries were installed"));
+    } else {
+        print_success(&tr!("Removed the imgconv context-menu entries"));
+    }
+    Ok(())
+}
+
+fn ensure_windows() -> Result<()> {
+    if !cfg!(windows) {
+        anyhow::bail!("Explorer integration is only available on Windows");
+    }
+    Ok(())
+}
+
+/// Registry values for the three menus: "Convert to" on image files, "Convert
+/// folder to" on folders (a recursive batch into FOLDER-FORMAT next to it) and
+/// "Paste clipboard image here" on the folder background
+fn values(exe: &str, formats: &[Format]) -> Vec<Value> {
+    let mut values = Vec::new();
+    let mut menu = |root: &str, title: &str| {
+        values.push(Value::new(root, Some("MUIVerb"), title));
+        values.push(Value::new(root, Some("SubCommands"), ""));
+        values.push(Value::new(root, Some("Icon"), exe));
+    };
+    menu(ROOTS[0], "Convert with imgconv");
+    menu(ROOTS[1], "Convert images with imgconv");
+
+    for format in formats {
+        let name = &name(format);
+        let file = format!(r"{}\shell\{}", ROOTS[0], name);
+        values.push(Value::new(&file, Some("MUIVerb"), format!("Convert to {}", label(name))));
+        // Hide "Convert to PNG" on PNG files and so on
+        let extensions: Vec<String> = extensions(name).iter().map(|ext| format!("System.FileExtension:=.{}", ext)).collect();
+        values.push(Value::new(&file, Some("AppliesTo"), format!("NOT ({})", extensions.join(" OR "))));
+        values.push(Value::new(format!(r"{}\command", file), None, command(exe, &format!("\"%1\" \"%1\" -f {}", name))));
+
+        let folder = format!(r"{}\shell\{}", ROOTS[1], name);
+        values.push(Value::new(&folder, Some("MUIVerb"), format!("Convert folder to {}", label(name))));
+        values.push(Value::new(
+            format!(r"{}\command", folder),
+            None,
+            command(exe, &format!("\"%1\" \"%1-{}\" -f {} -r", name, name)),
+        ));
+    }
+
+    values.push(Value::new(ROOTS[2], Some("MUIVerb"), "Paste clipboard image here (PNG)"));
+    values.push(Value::new(ROOTS[2], Some("Icon"), exe));
+    values.push(Value::new(format!(r"{}\command", ROOTS[2]), None, command(exe, "-c \"%V\\clipboard.png\"")));
+    values
+}
+
+/// Run through cmd so the console stays open with the error when a conversion fails
+fn command(exe: &str, arguments: &str) -> String {
+    format!("cmd.exe /c \"\"{}\" {} || pause\"", exe, arguments)
+}
+
+/// The `--format` value, e.g. "webp" (aliases like "jpg" map to their main name)
+pub fn name(format: &Format) -> String {
+    let name = format.to_possible_value().map(|value| value.get_name().to_string()).unwrap_or_default();
+    match name.as_str() {
+        "jpg" => "jpeg".into(),
+        "tif" => "tiff".into(),
+        _ => name,
+    }
+}
+
+/// Menu label for a format name, e.g. "WebP"
+pub fn label(name: &str) -> String {
+    match name {
+        "webp" => "WebP".into(),
+        "farbfeld" => "Farbfeld".into(),
+        _ => name.to_uppercase(),
+    }
+}
+
+fn extensions(name: &str) -> &'static [&'static str] {
+    match name {
+        "jpeg" => &["jpg", "jpeg"],
+        "tiff" => &["tif", "tiff"],
+        "png" => &["png"],
+        "gif" => &["gif"],
+        "bmp" => &["bmp"],
+        "ico" => &["ico"],
+        "webp" => &["webp"],
+        "avif" => &["avif"],
+        "pnm" => &["pnm", "ppm", "pgm", "pbm"],
+        "tga" => &["tga"],
+        "dds" => &["dds"],
+        "hdr" => &["hdr"],
+        _ => &["ff"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_values() {
+        let values = values(r"C:\Tools\imgconv.exe", &[Format::Jpg, Format::Webp]);
+        let command = values
+            .iter()
+            .find(|v| v.key == r"SystemFileAssociations\image\shell\imgconv\shell\jpeg\command")
+            .unwrap();
+        assert_eq!(command.name, None);
+        assert_eq!(command.data, r#"cmd.exe /c ""C:\Tools\imgconv.exe" "%1" "%1" -f jpeg || pause""#);
+        assert!(values.contains(&Value::new(
+            r"SystemFileAssociations\image\shell\imgconv\shell\jpeg",
+            Some("AppliesTo"),
+            "NOT (System.FileExtension:=.jpg OR System.FileExtension:=.jpeg)"
+        )));
+        assert!(values.contains(&Value::new(r"Directory\shell\imgconv\shell\webp", Some("MUIVerb"), "Convert folder to WebP")));
+        assert!(values.iter().all(|v| ROOTS.iter().any(|root| v.key.starts_with(root))));
+    }
+}
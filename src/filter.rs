@@ -0,0 +1,200 @@
+// File: src\filter.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Select batch inputs by dimensions, file size, format and date
+// License: MIT
+
+use clap::Args;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::i18n::tr;
+use crate::limits::ByteSize;
+use crate::{detect_format_from_path, print_info, Format};
+
+#[derive(Args, Debug)]
+pub struct FilterArgs {
+    /// Only convert batch inputs at least this many pixels wide
+    #[arg(long, value_name = "PX")]
+    pub min_width: Option<u32>,
+
+    /// Only convert batch inputs at most this many pixels wide
+    #[arg(long, value_name = "PX")]
+    pub max_width: Option<u32>,
+
+    /// Only convert batch inputs at least this many pixels high
+    #[arg(long, value_name = "PX")]
+    pub min_height: Option<u32>,
+
+    /// Only convert batch inputs at most this many pixels high
+    #[arg(long, value_name = "PX")]
+    pub max_height: Option<u32>,
+
+    /// Only convert batch inputs of at least this file size (e.g. 200K)
+    #[arg(long, value_name = "SIZE")]
+    pub min_size: Option<ByteSize>,
+
+    /// Only convert batch inputs of at most this file size (e.g. 5MB)
+    #[arg(long, value_name = "SIZE")]
+    pub max_size: Option<ByteSize>,
+
+    /// Only convert batch inputs in these formats (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "FORMATS")]
+    pub only_format: Vec<Format>,
+
+    /// Only convert batch inputs modified on or after this day (YYYY-MM-DD, UTC)
+    #[arg(long, value_name = "DATE")]
+    pub modified_since: Option<Date>,
+
+    /// Only convert batch inputs modified before this day (YYYY-MM-DD, UTC)
+    #[arg(long, value_name = "DATE")]
+    pub modified_before: Option<Date>,
+}
+
+/// A calendar day, as midnight UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date(pub SystemTime);
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid date '{}', expected YYYY-MM-DD", s);
+        let parts: Vec<&str> = s.trim().split('-').collect();
+        let [year, month, day] = parts[..] else {
+            return Err(invalid());
+        };
+        let (year, month, day): (u64, u64, u64) =
+            (year.parse().map_err(|_| invalid())?, month.parse().map_err(|_| invalid())?, day.parse().map_err(|_| invalid())?);
+        if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return Err(invalid());
+        }
+        Ok(Date(UNIX_EPOCH + Duration::from_secs(days_from_civil(year, month, day) * 86_400)))
+    }
+}
+
+/// Days since 1970-01-01 of a civil (proleptic Gregorian) date
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    // Howard Hinnant's days-from-civil, the inverse of batch::year_month
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+impl FilterArgs {
+    pub fn is_enabled(&self) -> bool {
+        self.needs_files() || !self.only_format.is_empty()
+    }
+
+    /// Whether a filter looks at the file on disk rather than its name
+    pub fn needs_files(&self) -> bool {
+        self.min_width.is_some()
+            || self.max_width.is_some()
+            || self.min_height.is_some()
+            || self.max_height.is_some()
+            || self.min_size.is_some()
+            || self.max_size.is_some()
+            || self.modified_since.is_some()
+            || self.modified_before.is_some()
+    }
+
+    /// Whether `path` passes every filter given; unreadable files don't
+    fn matches(&self, path: &Path) -> bool {
+        if !self.only_format.is_empty() {
+            let format = detect_format_from_path(path);
+            if !self.only_format.iter().any(|wanted| Some(wanted.to_image_format()) == format) {
+                return false;
+            }
+        }
+        if self.min_size.is_some() || self.max_size.is_some() || self.modified_since.is_some() || self.modified_before.is_some() {
+            let Ok(metadata) = std::fs::metadata(path) else {
+                return false;
+            };
+            let size = metadata.len();
+            if self.min_size.is_some_and(|min| size < min.0) || self.max_size.is_some_and(|max| size > max.0) {
+                return false;
+            }
+            let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+            if self.modified_since.is_some_and(|since| modified < since.0)
+                || self.modified_before.is_some_and(|before| modified >= before.0)
+            {
+                return false;
+            }
+        }
+        if self.min_width.is_some() || self.max_width.is_some() || self.min_height.is_some() || self.max_height.is_some() {
+            // Only the header is read, not the whole image
+            let Ok((width, height)) = image::image_dimensions(path) else {
+                return false;
+            };
+            let outside = |value: u32, min: Option<u32>, max: Option<u32>| {
+                min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max)
+            };
+            if outside(width, self.min_width, self.max_width) || outside(height, self.min_height, self.max_height) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// The batch inputs in `files` that match the filters, reporting how many were left out
+pub fn apply(files: Vec<PathBuf>, args: &FilterArgs) -> Vec<PathBuf> {
+    if !args.is_enabled() {
+        return files;
+    }
+    let total = files.len();
+    let files: Vec<PathBuf> = files.into_iter().filter(|path| args.matches(path)).collect();
+    if files.len() < total {
+        print_info(&tr!("Skipping {} of {} files that don't match the filters", total - files.len(), total));
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        let day = |s: &str| s.parse::<Date>().map(|date| date.0.duration_since(UNIX_EPOCH).unwrap().as_secs() / 86_400);
+        assert_eq!(day("1970-01-01"), Ok(0));
+        assert_eq!(day("2024-05-01"), Ok(19_844));
+        assert_eq!(day("2024-02-29"), Ok(19_782));
+        assert!(day("2024-13-01").is_err());
+        assert!(day("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_filters() {
+        let dir = std::env::temp_dir().join(format!("imgconv-filter-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (wide, small) = (dir.join("wide.png"), dir.join("small.jpg"));
+        image::RgbImage::new(40, 10).save(&wide).unwrap();
+        image::RgbImage::new(8, 8).save(&small).unwrap();
+        let files = vec![wide.clone(), small.clone()];
+
+        let mut args = FilterArgs {
+            min_width: Some(20),
+            max_width: None,
+            min_height: None,
+            max_height: None,
+            min_size: None,
+            max_size: None,
+            only_format: Vec::new(),
+            modified_since: None,
+            modified_before: None,
+        };
+        assert_eq!(apply(files.clone(), &args), [wide]);
+        args.min_width = None;
+        args.only_format = vec![Format::Jpg];
+        assert_eq!(apply(files.clone(), &args), [small]);
+        args.modified_since = Some("2999-01-01".parse().unwrap());
+        assert!(apply(files, &args).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,153 @@
+// File: src\formats.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Format capability table (`imgconv formats`) and checks of options against it
+// License: MIT
+
+use anyhow::Result;
+use image::ImageFormat;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use crate::i18n::tr;
+use crate::print_warning;
+
+/// What imgconv can do with one format
+pub struct Capabilities {
+    pub format: ImageFormat,
+    pub extensions: &'static [&'static str],
+    /// -q changes the output
+    pub quality: bool,
+    pub alpha: bool,
+    pub sixteen_bit: bool,
+    /// Animated files are recognised (only the first frame is converted)
+    pub animation: bool,
+    /// Output options that only apply to some formats
+    pub options: &'static [&'static str],
+}
+
+/// Every format imgconv converts, in the order they are listed
+pub const TABLE: [Capabilities; 13] = [
+    entry(ImageFormat::Png, &["png"], false, true, true, true, &["--optimize", "--png-palette", "--bit-depth", "--stream", "--png.level"]),
+    entry(ImageFormat::Jpeg, &["jpg", "jpeg"], true, false, false, false, &["--auto-quality", "--backend", "--jpeg.quality", "--jpeg.progressive"]),
+    entry(ImageFormat::Gif, &["gif"], false, true, false, true, &["--gif.speed"]),
+    entry(ImageFormat::Bmp, &["bmp"], false, true, false, false, &[]),
+    entry(ImageFormat::Ico, &["ico"], false, true, false, false, &[]),
+    entry(ImageFormat::Tiff, &["tiff", "tif"], false, true, true, false, &["--bit-depth", "--stream"]),
+    entry(ImageFormat::WebP, &["webp"], false, true, false, true, &[]),
+    entry(ImageFormat::Avif, &["avif"], true, true, false, false, &["--avif.quality", "--avif.speed"]),
+    entry(ImageFormat::Pnm, &["pnm", "pbm", "pgm", "ppm"], false, false, true, false, &["--bit-depth", "--stream"]),
+    entry(ImageFormat::Tga, &["tga"], false, true, false, false, &[]),
+    entry(ImageFormat::Dds, &["dds"], false, true, false, false, &[]),
+    entry(ImageFormat::Hdr, &["hdr"], false, false, false, false, &[]),
+    entry(ImageFormat::Farbfeld, &["ff"], false, true, false, false, &[]),
+];
+
+const fn entry(
+    format: ImageFormat,
+    extensions: &'static [&'static str],
+    quality: bool,
+    alpha: bool,
+    sixteen_bit: bool,
+    animation: bool,
+    options: &'static [&'static str],
+) -> Capabilities {
+    Capabilities { format, extensions, quality, alpha, sixteen_bit, animation, options }
+}
+
+/// Options that make the conversion fail for other formats; the rest are
+/// just ignored, so they only warn (unless --strict)
+const REQUIRED_BY_FORMAT: [&str; 3] = ["--png-palette", "--bit-depth", "--stream"];
+
+/// Options and formats already warned about, so a batch warns once per format rather than per file
+static WARNED: Mutex<Option<HashSet<(&'static str, ImageFormat)>>> = Mutex::new(None);
+
+pub fn capabilities(format: ImageFormat) -> Option<&'static Capabilities> {
+    TABLE.iter().find(|capabilities| capabilities.format == format)
+}
+
+/// The format an extension stands for, in any case
+pub fn from_extension(ext: &str) -> Option<ImageFormat> {
+    TABLE
+        .iter()
+        .find(|entry| entry.extensions.iter().any(|known| known.eq_ignore_ascii_case(ext)))
+        .map(|entry| entry.format)
+}
+
+/// `imgconv formats`: what each format reads, writes and which options apply to it
+pub fn run() -> Result<()> {
+    let yes_no = |value: bool| if value { "yes" } else { "no" };
+    println!(
+        "{:<9} {:<20} {:<5} {:<6} {:<8} {:<6} {:<7} {:<10} OPTIONS",
+        "FORMAT", "EXTENSIONS", "READ", "WRITE", "QUALITY", "ALPHA", "16-BIT", "ANIMATED"
+    );
+    for entry in &TABLE {
+        let extensions: Vec<String> = entry.extensions.iter().map(|ext| format!(".{}", ext)).collect();
+        println!(
+            "{:<9} {:<20} {:<5} {:<6} {:<8} {:<6} {:<7} {:<10} {}",
+            format!("{:?}", entry.format),
+            extensions.join(" "),
+            yes_no(entry.format.reading_enabled()),
+            yes_no(entry.format.writing_enabled()),
+            yes_no(entry.quality),
+            yes_no(entry.alpha),
+            yes_no(entry.sixteen_bit),
+            if entry.animation { "1st frame" } else { "no" },
+            if entry.options.is_empty() { "-".to_string() } else { entry.options.join(" ") }
+        );
+    }
+    Ok(())
+}
+
+/// Check the options that were `given` against what `format` can do
+///
+/// -q and format-specific options that `format` would ignore warn (or fail
+/// with `strict`); ones it cannot honour at all fail.
+pub fn check(format: ImageFormat, given: &[(&'static str, bool)], strict: bool) -> Result<()> {
+    let Some(capabilities) = capabilities(format) else {
+        return Ok(());
+    };
+    for &(option, _) in given.iter().filter(|(_, given)| *given) {
+        let applies = |entry: &Capabilities| match option {
+            "-q" => entry.quality,
+            option => entry.options.contains(&option),
+        };
+        if applies(capabilities) {
+            continue;
+        }
+        let supported: Vec<String> = TABLE.iter().filter(|entry| applies(entry)).map(|entry| format!("{:?}", entry.format)).collect();
+        if REQUIRED_BY_FORMAT.contains(&option) {
+            anyhow::bail!(tr!("{} cannot be used with {} output, only with {}", option, format!("{:?}", format), supported.join(", ")));
+        }
+        let name = if option == "-q" { "-q/--quality" } else { option };
+        let message = tr!("{} has no effect on {} output, only on {}", name, format!("{:?}", format), supported.join(", "));
+        if strict {
+            anyhow::bail!(tr!("{} (--strict)", message));
+        }
+        if WARNED.lock().unwrap().get_or_insert_with(HashSet::new).insert((option, format)) {
+            print_warning(&tr!("{}; see `imgconv formats`", message));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_options() {
+        assert!(check(ImageFormat::Png, &[("-q", true)], false).is_ok());
+        assert!(check(ImageFormat::Png, &[("-q", true)], true).is_err());
+        assert!(check(ImageFormat::Png, &[("-q", false)], true).is_ok());
+        assert!(check(ImageFormat::Jpeg, &[("-q", true), ("--backend", true)], true).is_ok());
+        let error = check(ImageFormat::Jpeg, &[("--bit-depth", true)], false).unwrap_err();
+        assert_eq!(error.to_string(), "--bit-depth cannot be used with Jpeg output, only with Png, Tiff, Pnm");
+    }
+
+    #[test]
+    fn test_table_covers_each_format_once() {
+        let formats: HashSet<ImageFormat> = TABLE.iter().map(|entry| entry.format).collect();
+        assert_eq!(formats.len(), TABLE.len());
+    }
+}
@@ -0,0 +1,146 @@
+// File: src\frame.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Border and frame drawing around the image
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::{imageops, DynamicImage, Rgba};
+use std::str::FromStr;
+
+use crate::color::Color;
+use crate::draw::{restore_color_type, wide, Canvas};
+use crate::i18n::tr;
+use crate::limits::PixelCount;
+
+#[derive(Args, Debug)]
+pub struct FrameArgs {
+    /// Add a solid border of this width (pixels) around the image
+    #[arg(long, value_name = "PX")]
+    pub border: Option<u32>,
+
+    /// Border color (also the outer band of --frame)
+    #[arg(long, default_value = "#000000", value_name = "COLOR")]
+    pub border_color: Color,
+
+    /// Add a two-band frame: outer band in --border-color, inner mat in --frame-color
+    #[arg(long, value_name = "OUTER,INNER", conflicts_with = "border")]
+    pub frame: Option<FrameWidths>,
+
+    /// Color of the inner mat of --frame
+    #[arg(long, default_value = "#ffffff", value_name = "COLOR")]
+    pub frame_color: Color,
+}
+
+impl FrameArgs {
+    /// Whether a border or frame is drawn
+    pub fn is_active(&self) -> bool {
+        self.border.is_some() || self.frame.is_some()
+    }
+}
+
+/// Outer and inner band widths of `--frame`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameWidths {
+    pub outer: u32,
+    pub inner: u32,
+}
+
+impl FromStr for FrameWidths {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Expected OUTER,INNER widths in pixels, got: {}", s);
+        let (outer, inner) = s.split_once(',').ok_or_else(invalid)?;
+        Ok(FrameWidths {
+            outer: outer.trim().parse().map_err(|_| invalid())?,
+            inner: inner.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Apply `--border` or `--frame`, returning the image unchanged when neither is set
+///
+/// The framed size is checked against --max-pixels before anything is drawn,
+/// since a wide border asks for far more pixels than the image itself.
+pub fn apply(img: DynamicImage, args: &FrameArgs, max_pixels: PixelCount) -> Result<DynamicImage> {
+    let bands: Vec<(u32, Rgba<u8>)> = match (args.border, args.frame) {
+        (Some(width), _) => vec![(width, args.border_color.0)],
+        (None, Some(frame)) => vec![(frame.inner, args.frame_color.0), (frame.outer, args.border_color.0)],
+        (None, None) => return Ok(img),
+    };
+
+    let mut size = (img.width(), img.height());
+    for &(width, _) in &bands {
+        size = grown(size, width)?;
+    }
+    let pixels = size.0 as u64 * size.1 as u64;
+    if max_pixels.0 > 0 && pixels > max_pixels.0 {
+        anyhow::bail!(tr!(
+            "The framed image would be {}x{} ({} pixels), over the --max-pixels limit of {}",
+            size.0,
+            size.1,
+            pixels,
+            max_pixels.0
+        ));
+    }
+
+    // Bands are applied inside-out so the outer one ends up on the edge
+    let color_type = img.color();
+    let mut canvas = img.to_rgba16();
+    for (width, color) in bands {
+        canvas = add_band(&canvas, width, wide(color))?;
+    }
+
+    Ok(restore_color_type(color_type, canvas))
+}
+
+/// `(width, height)` with a band of `band` pixels on every side
+fn grown((width, height): (u32, u32), band: u32) -> Result<(u32, u32)> {
+    let grow = |side: u32| band.checked_mul(2).and_then(|both| side.checked_add(both));
+    match (grow(width), grow(height)) {
+        (Some(width), Some(height)) => Ok((width, height)),
+        _ => anyhow::bail!(tr!("A {} pixel band around a {}x{} image is over {} pixels wide", band, width, height, u32::MAX)),
+    }
+}
+
+/// Surround `img` with a solid band of `width` pixels
+fn add_band(img: &Canvas, width: u32, color: Rgba<u16>) -> Result<Canvas> {
+    if width == 0 {
+        return Ok(img.clone());
+    }
+    let (canvas_width, canvas_height) = grown(img.dimensions(), width)?;
+    let mut canvas = Canvas::from_pixel(canvas_width, canvas_height, color);
+    imageops::replace(&mut canvas, img, width as i64, width as i64);
+    Ok(canvas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_frame_widths() {
+        assert_eq!("10, 4".parse::<FrameWidths>(), Ok(FrameWidths { outer: 10, inner: 4 }));
+        assert!("10".parse::<FrameWidths>().is_err());
+    }
+
+    #[test]
+    fn test_add_band() {
+        let img = Canvas::from_pixel(2, 2, Rgba([1000, 2000, 3000, 65535]));
+        let framed = add_band(&img, 3, wide(Rgba([0, 0, 0, 255]))).unwrap();
+        assert_eq!(framed.dimensions(), (8, 8));
+        assert_eq!(framed.get_pixel(0, 0), &Rgba([0, 0, 0, 65535]));
+        assert_eq!(framed.get_pixel(3, 3), &Rgba([1000, 2000, 3000, 65535]));
+    }
+
+    #[test]
+    fn test_oversized_border_is_refused() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(4, 4));
+        let border = |width| FrameArgs { border: Some(width), border_color: Color(Rgba([0, 0, 0, 255])), frame: None, frame_color: Color(Rgba([255; 4])) };
+        assert!(apply(img.clone(), &border(3_000_000_000), PixelCount(0)).is_err());
+        assert!(apply(img.clone(), &border(100_000), PixelCount(100_000_000)).is_err());
+        assert_eq!(apply(img, &border(2), PixelCount(100)).unwrap().width(), 8);
+    }
+}
@@ -0,0 +1,217 @@
+// File: src\geometry.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Point, size, rectangle, aspect ratio and gravity arguments
+// License: MIT
+
+use clap::ValueEnum;
+use std::str::FromStr;
+
+/// Pixel coordinate given as "X,Y"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+}
+
+/// Rectangle given as "X,Y,W,H"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    /// Clip the rectangle to an image of the given size, None if nothing is left
+    pub fn clamp_to(&self, width: u32, height: u32) -> Option<Rect> {
+        if self.x >= width || self.y >= height {
+            return None;
+        }
+        let clipped = Rect {
+            x: self.x,
+            y: self.y,
+            width: self.width.min(width - self.x),
+            height: self.height.min(height - self.y),
+        };
+        (clipped.width > 0 && clipped.height > 0).then_some(clipped)
+    }
+}
+
+/// Dimensions given as "WxH"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl FromStr for Size {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Expected WxH, got: {}", s);
+        let lower = s.to_lowercase();
+        let (width, height) = lower.split_once('x').ok_or_else(invalid)?;
+        let size = Size {
+            width: width.trim().parse().map_err(|_| invalid())?,
+            height: height.trim().parse().map_err(|_| invalid())?,
+        };
+        if size.width == 0 || size.height == 0 {
+            return Err(format!("Width and height must be greater than 0, got: {}", s));
+        }
+        Ok(size)
+    }
+}
+
+impl Size {
+    /// Dimensions of a `width`x`height` image scaled to fit inside this size,
+    /// rounded the way `DynamicImage::resize` does
+    pub fn fit(&self, width: u32, height: u32) -> (u32, u32) {
+        let ratio = (self.width as f64 / width as f64).min(self.height as f64 / height as f64);
+        let scale = |v: u32| ((v as f64 * ratio).round() as u32).max(1);
+        (scale(width), scale(height))
+    }
+}
+
+/// Aspect ratio given as "W:H"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AspectRatio {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl AspectRatio {
+    pub fn ratio(&self) -> f64 {
+        self.width / self.height
+    }
+}
+
+impl FromStr for AspectRatio {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Expected an aspect ratio like 16:9, got: {}", s);
+        let (width, height) = s.split_once(':').ok_or_else(invalid)?;
+        let width: f64 = width.trim().parse().map_err(|_| invalid())?;
+        let height: f64 = height.trim().parse().map_err(|_| invalid())?;
+        if width <= 0.0 || height <= 0.0 {
+            return Err(invalid());
+        }
+        Ok(AspectRatio { width, height })
+    }
+}
+
+/// Anchor position of an overlay within the image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "lowercase")]
+pub enum Gravity {
+    Northwest,
+    North,
+    Northeast,
+    West,
+    Center,
+    East,
+    Southwest,
+    South,
+    Southeast,
+}
+
+impl Gravity {
+    /// Top-left offset of an `inner` box anchored inside an `outer` box with `margin`
+    pub fn place(&self, outer: (u32, u32), inner: (u32, u32), margin: u32) -> (i64, i64) {
+        let (ow, oh) = (outer.0 as i64, outer.1 as i64);
+        let (iw, ih) = (inner.0 as i64, inner.1 as i64);
+        let margin = margin as i64;
+
+        let x = match self {
+            Gravity::Northwest | Gravity::West | Gravity::Southwest => margin,
+            Gravity::North | Gravity::Center | Gravity::South => (ow - iw) / 2,
+            Gravity::Northeast | Gravity::East | Gravity::Southeast => ow - iw - margin,
+        };
+        let y = match self {
+            Gravity::Northwest | Gravity::North | Gravity::Northeast => margin,
+            Gravity::West | Gravity::Center | Gravity::East => (oh - ih) / 2,
+            Gravity::Southwest | Gravity::South | Gravity::Southeast => oh - ih - margin,
+        };
+        (x, y)
+    }
+}
+
+fn parse_numbers(s: &str, count: usize, shape: &str) -> Result<Vec<u32>, String> {
+    let numbers = s
+        .split(',')
+        .map(|part| part.trim().parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("Expected {}, got: {}", shape, s))?;
+    if numbers.len() != count {
+        return Err(format!("Expected {}, got: {}", shape, s));
+    }
+    Ok(numbers)
+}
+
+impl FromStr for Point {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let n = parse_numbers(s, 2, "X,Y")?;
+        Ok(Point { x: n[0], y: n[1] })
+    }
+}
+
+impl FromStr for Rect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let n = parse_numbers(s, 4, "X,Y,W,H")?;
+        if n[2] == 0 || n[3] == 0 {
+            return Err(format!("Width and height must be greater than 0, got: {}", s));
+        }
+        Ok(Rect { x: n[0], y: n[1], width: n[2], height: n[3] })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_point_and_rect() {
+        assert_eq!("120,45".parse::<Point>(), Ok(Point { x: 120, y: 45 }));
+        assert!("120".parse::<Point>().is_err());
+        assert_eq!(
+            "0, 0, 10, 20".parse::<Rect>(),
+            Ok(Rect { x: 0, y: 0, width: 10, height: 20 })
+        );
+        assert!("0,0,0,10".parse::<Rect>().is_err());
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!("256x128".parse::<Size>(), Ok(Size { width: 256, height: 128 }));
+        assert_eq!("64X64".parse::<Size>(), Ok(Size { width: 64, height: 64 }));
+        assert!("256".parse::<Size>().is_err());
+        assert!("0x10".parse::<Size>().is_err());
+    }
+
+    #[test]
+    fn test_parse_aspect_ratio() {
+        assert_eq!("16:9".parse::<AspectRatio>().unwrap().ratio(), 16.0 / 9.0);
+        assert!("1:0".parse::<AspectRatio>().is_err());
+        assert!("square".parse::<AspectRatio>().is_err());
+    }
+
+    #[test]
+    fn test_gravity_place() {
+        assert_eq!(Gravity::Northwest.place((100, 50), (20, 10), 5), (5, 5));
+        assert_eq!(Gravity::South.place((100, 50), (20, 10), 5), (40, 35));
+        assert_eq!(Gravity::Center.place((100, 50), (20, 10), 5), (40, 20));
+    }
+
+    #[test]
+    fn test_rect_clamp() {
+        let rect = Rect { x: 90, y: 0, width: 20, height: 20 };
+        assert_eq!(rect.clamp_to(100, 10), Some(Rect { x: 90, y: 0, width: 10, height: 10 }));
+        assert_eq!(rect.clamp_to(50, 50), None);
+    }
+}
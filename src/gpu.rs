@@ -0,0 +1,426 @@
+// File: src\gpu.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: wgpu compute backend for resizing, with the sRGB/linear conversion done on the GPU
+// License: MIT
+
+use anyhow::Result;
+use image::{imageops::FilterType, DynamicImage};
+
+/// Resize `img` to exactly `width`x`height` on the GPU, sampling like
+/// `imageops::resize` with `filter`, in linear light when `linear`
+///
+/// Fails when the build has no GPU support, no adapter is found or the image
+/// doesn't fit in the adapter's buffers; callers then resize on the CPU.
+#[cfg(feature = "gpu")]
+pub fn resize(img: &DynamicImage, width: u32, height: u32, filter: FilterType, linear: bool) -> Result<DynamicImage> {
+    let gpu = backend::Gpu::get()?;
+    let canvas = gpu.resize(img, width, height, filter, linear)?;
+    Ok(crate::colorspace::with_color_type(DynamicImage::ImageRgba32F(canvas), img.color()))
+}
+
+#[cfg(not(feature = "gpu"))]
+pub fn resize(_img: &DynamicImage, _width: u32, _height: u32, _filter: FilterType, _linear: bool) -> Result<DynamicImage> {
+    Err(unavailable())
+}
+
+/// Name and backend of the adapter --gpu runs on, once one was found
+#[cfg(feature = "gpu")]
+pub fn adapter() -> Result<String> {
+    Ok(backend::Gpu::get()?.name.clone())
+}
+
+#[cfg(not(feature = "gpu"))]
+pub fn adapter() -> Result<String> {
+    Err(unavailable())
+}
+
+#[cfg(not(feature = "gpu"))]
+fn unavailable() -> anyhow::Error {
+    anyhow::anyhow!("GPU support is not available in this build; reinstall with `cargo install imgconv --features gpu`")
+}
+
+#[cfg(feature = "gpu")]
+mod backend {
+    use anyhow::{anyhow, Context, Result};
+    use image::{imageops::FilterType, DynamicImage, Rgba32FImage};
+    use std::borrow::Cow;
+    use std::sync::{mpsc, OnceLock};
+    use wgpu::util::DeviceExt;
+
+    /// Two passes like `imageops::resize`: down the columns into a
+    /// `src_width` x `dst_height` buffer, then along its rows
+    const SHADER: &str = r#"
+struct Params {
+    src_width: u32,
+    src_height: u32,
+    dst_width: u32,
+    dst_height: u32,
+    method: u32,
+    packed: u32,
+    linear: u32,
+    support: f32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> source: array<u32>;
+@group(0) @binding(2) var<storage, read_write> columns: array<vec4<f32>>;
+@group(0) @binding(3) var<storage, read_write> resized: array<vec4<f32>>;
+
+const PI: f32 = 3.14159265;
+
+fn sinc(t: f32) -> f32 {
+    if t == 0.0 {
+        return 1.0;
+    }
+    return sin(t * PI) / (t * PI);
+}
+
+fn kernel(x: f32) -> f32 {
+    let a = abs(x);
+    switch params.method {
+        case 0u: {
+            return 1.0;
+        }
+        case 1u: {
+            return max(1.0 - a, 0.0);
+        }
+        case 2u: {
+            // Catmull-Rom: the cubic spline with B = 0, C = 0.5
+            if a < 1.0 {
+                return (9.0 * a * a * a - 15.0 * a * a + 6.0) / 6.0;
+            }
+            if a < 2.0 {
+                return (-3.0 * a * a * a + 15.0 * a * a - 24.0 * a + 12.0) / 6.0;
+            }
+            return 0.0;
+        }
+        case 3u: {
+            // Standard deviation 0.5
+            return exp(-2.0 * x * x) / (sqrt(2.0 * PI) * 0.5);
+        }
+        default: {
+            if a < 3.0 {
+                return sinc(x) * sinc(x / 3.0);
+            }
+            return 0.0;
+        }
+    }
+}
+
+fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        return v / 12.92;
+    }
+    return pow((v + 0.055) / 1.055, 2.4);
+}
+
+fn linear_to_srgb(v: f32) -> f32 {
+    if v <= 0.0031308 {
+        return v * 12.92;
+    }
+    return 1.055 * pow(v, 1.0 / 2.4) - 0.055;
+}
+
+fn load(i: u32) -> vec4<f32> {
+    var pixel: vec4<f32>;
+    if params.packed == 1u {
+        pixel = unpack4x8unorm(source[i]);
+    } else {
+        pixel = vec4<f32>(
+            bitcast<f32>(source[i * 4u]),
+            bitcast<f32>(source[i * 4u + 1u]),
+            bitcast<f32>(source[i * 4u + 2u]),
+            bitcast<f32>(source[i * 4u + 3u]),
+        );
+    }
+    if params.linear == 1u {
+        pixel = vec4<f32>(srgb_to_linear(pixel.r), srgb_to_linear(pixel.g), srgb_to_linear(pixel.b), pixel.a);
+    }
+    return pixel;
+}
+
+// The source rows `out` samples from, and its center among them
+struct Window {
+    first: u32,
+    end: u32,
+    center: f32,
+    scale: f32,
+}
+
+fn window(out: u32, size_in: u32, size_out: u32) -> Window {
+    let ratio = f32(size_in) / f32(size_out);
+    let scale = max(ratio, 1.0);
+    let center = (f32(out) + 0.5) * ratio;
+    let first = u32(clamp(floor(center - params.support * scale), 0.0, f32(size_in - 1u)));
+    let end = u32(clamp(ceil(center + params.support * scale), f32(first + 1u), f32(size_in)));
+    return Window(first, end, center - 0.5, scale);
+}
+
+@compute @workgroup_size(8, 8)
+fn vertical(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.src_width || id.y >= params.dst_height {
+        return;
+    }
+    let w = window(id.y, params.src_height, params.dst_height);
+    var sum = vec4<f32>(0.0);
+    var total = 0.0;
+    for (var y = w.first; y < w.end; y++) {
+        let weight = kernel((f32(y) - w.center) / w.scale);
+        sum += load(y * params.src_width + id.x) * weight;
+        total += weight;
+    }
+    columns[id.y * params.src_width + id.x] = sum / total;
+}
+
+@compute @workgroup_size(8, 8)
+fn horizontal(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.dst_width || id.y >= params.dst_height {
+        return;
+    }
+    let w = window(id.x, params.src_width, params.dst_width);
+    var sum = vec4<f32>(0.0);
+    var total = 0.0;
+    for (var x = w.first; x < w.end; x++) {
+        let weight = kernel((f32(x) - w.center) / w.scale);
+        sum += columns[id.y * params.src_width + x] * weight;
+        total += weight;
+    }
+    var pixel = clamp(sum / total, vec4<f32>(0.0), vec4<f32>(1.0));
+    if params.linear == 1u {
+        pixel = vec4<f32>(linear_to_srgb(pixel.r), linear_to_srgb(pixel.g), linear_to_srgb(pixel.b), pixel.a);
+    }
+    resized[id.y * params.dst_width + id.x] = pixel;
+}
+"#;
+
+    /// Invocations per workgroup along each axis, as in the shader
+    const WORKGROUP: u32 = 8;
+
+    /// The device and compiled passes, set up once per process
+    pub struct Gpu {
+        pub name: String,
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        layout: wgpu::BindGroupLayout,
+        vertical: wgpu::ComputePipeline,
+        horizontal: wgpu::ComputePipeline,
+    }
+
+    impl Gpu {
+        /// The shared device; a failed setup is remembered, so a batch
+        /// without a usable GPU only looks for one once
+        pub fn get() -> Result<&'static Gpu> {
+            static GPU: OnceLock<Result<Gpu, String>> = OnceLock::new();
+            GPU.get_or_init(|| Gpu::new().map_err(|e| format!("{:#}", e))).as_ref().map_err(|e| anyhow!("{}", e))
+        }
+
+        fn new() -> Result<Self> {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+            let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                ..Default::default()
+            }))
+            .context("No GPU adapter found")?;
+            // The adapter's own limits, as large images need more than the defaults allow
+            let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+                label: Some("imgconv"),
+                required_limits: adapter.limits(),
+                ..Default::default()
+            }))
+            .context("Failed to open the GPU")?;
+            let info = adapter.get_info();
+            let (layout, vertical, horizontal) = checked(&device, || Self::passes(&device))?;
+            Ok(Gpu { name: format!("{} ({:?})", info.name, info.backend), device, queue, layout, vertical, horizontal })
+        }
+
+        /// The bind group layout and the two compiled passes
+        fn passes(device: &wgpu::Device) -> (wgpu::BindGroupLayout, wgpu::ComputePipeline, wgpu::ComputePipeline) {
+            let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+                binding,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            };
+            let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("resize"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    storage(1, true),
+                    storage(2, false),
+                    storage(3, false),
+                ],
+            });
+            let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("resize"),
+                bind_group_layouts: &[Some(&layout)],
+                immediate_size: 0,
+            });
+            let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("resize"),
+                source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+            });
+            let pass = |entry_point| {
+                device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: Some(entry_point),
+                    layout: Some(&pipeline_layout),
+                    module: &module,
+                    entry_point: Some(entry_point),
+                    compilation_options: Default::default(),
+                    cache: None,
+                })
+            };
+            let (vertical, horizontal) = (pass("vertical"), pass("horizontal"));
+            (layout, vertical, horizontal)
+        }
+
+        pub fn resize(&self, img: &DynamicImage, width: u32, height: u32, filter: FilterType, linear: bool) -> Result<Rgba32FImage> {
+            let (src_width, src_height) = (img.width(), img.height());
+            if (src_width, src_height) == (width, height) || src_width == 0 || src_height == 0 {
+                return Ok(img.to_rgba32f());
+            }
+            // 8-bit images go up as they are, four bytes a pixel; deeper ones as floats
+            let (source, packed) = match img {
+                DynamicImage::ImageRgba8(rgba) => (Cow::Borrowed(rgba.as_raw().as_slice()), true),
+                DynamicImage::ImageRgb8(_) | DynamicImage::ImageLuma8(_) | DynamicImage::ImageLumaA8(_) => (Cow::Owned(img.to_rgba8().into_raw()), true),
+                _ => (Cow::Owned(img.to_rgba32f().into_raw().iter().flat_map(|value| value.to_ne_bytes()).collect()), false),
+            };
+            let columns_size = src_width as u64 * height as u64 * 16;
+            let target_size = width as u64 * height as u64 * 16;
+            let limits = self.device.limits();
+            let largest = (source.len() as u64).max(columns_size).max(target_size);
+            if largest > limits.max_storage_buffer_binding_size.min(limits.max_buffer_size) {
+                anyhow::bail!("{}x{} is too large for the GPU's buffers", src_width, src_height);
+            }
+            let groups = |size: u32| size.div_ceil(WORKGROUP);
+            if [groups(src_width), groups(width), groups(height)].iter().any(|&count| count > limits.max_compute_workgroups_per_dimension) {
+                anyhow::bail!("{}x{} is too large for the GPU's dispatch limits", src_width, src_height);
+            }
+
+            // Support of each filter, as in `imageops::resize`
+            let (filter, support) = match filter {
+                FilterType::Nearest => (0, 0.0),
+                FilterType::Triangle => (1, 1.0),
+                FilterType::CatmullRom => (2, 2.0),
+                FilterType::Gaussian => (3, 3.0),
+                FilterType::Lanczos3 => (4, 3.0f32),
+            };
+            let params: Vec<u8> = [src_width, src_height, width, height, filter, packed as u32, linear as u32, support.to_bits()]
+                .iter()
+                .flat_map(|value| value.to_ne_bytes())
+                .collect();
+            let readback = checked(&self.device, || {
+                let buffer = |label, contents: &[u8], usage| {
+                    self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor { label: Some(label), contents, usage })
+                };
+                let params = buffer("params", &params, wgpu::BufferUsages::UNIFORM);
+                let source = buffer("source", &source, wgpu::BufferUsages::STORAGE);
+                let storage = |label, size, usage| {
+                    self.device.create_buffer(&wgpu::BufferDescriptor { label: Some(label), size, usage, mapped_at_creation: false })
+                };
+                let columns = storage("columns", columns_size, wgpu::BufferUsages::STORAGE);
+                let target = storage("target", target_size, wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC);
+                let readback = storage("readback", target_size, wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST);
+                let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("resize"),
+                    layout: &self.layout,
+                    entries: &[
+                        wgpu::BindGroupEntry { binding: 0, resource: params.as_entire_binding() },
+                        wgpu::BindGroupEntry { binding: 1, resource: source.as_entire_binding() },
+                        wgpu::BindGroupEntry { binding: 2, resource: columns.as_entire_binding() },
+                        wgpu::BindGroupEntry { binding: 3, resource: target.as_entire_binding() },
+                    ],
+                });
+
+                let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("resize") });
+                {
+                    let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: Some("resize"), timestamp_writes: None });
+                    pass.set_bind_group(0, &bind_group, &[]);
+                    pass.set_pipeline(&self.vertical);
+                    pass.dispatch_workgroups(groups(src_width), groups(height), 1);
+                    pass.set_pipeline(&self.horizontal);
+                    pass.dispatch_workgroups(groups(width), groups(height), 1);
+                }
+                encoder.copy_buffer_to_buffer(&target, 0, &readback, 0, target_size);
+                self.queue.submit([encoder.finish()]);
+                readback
+            })?;
+
+            let (sender, receiver) = mpsc::channel();
+            readback.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+            self.device.poll(wgpu::PollType::wait_indefinitely()).context("The GPU stopped responding")?;
+            receiver.recv().context("The GPU dropped the resized image")?.context("Failed to read the resized image back")?;
+            let view = readback.slice(..).get_mapped_range().context("Failed to read the resized image back")?;
+            let pixels: Vec<f32> = view
+                .chunks_exact(4)
+                .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect();
+            Rgba32FImage::from_raw(width, height, pixels).context("The GPU returned a truncated image")
+        }
+    }
+
+    /// Run `work` on `device`, returning the validation or out-of-memory
+    /// error it raised instead of letting wgpu panic
+    fn checked<T>(device: &wgpu::Device, work: impl FnOnce() -> T) -> Result<T> {
+        let memory = device.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
+        let validation = device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let value = work();
+        let errors = [pollster::block_on(validation.pop()), pollster::block_on(memory.pop())];
+        match errors.into_iter().flatten().next() {
+            Some(error) => Err(anyhow!("{}", error)),
+            None => Ok(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colorspace::{run_in, Colorspace};
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_matches_the_cpu_or_reports_no_gpu() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(40, 30, |x, y| Rgb([(x * 6) as u8, (y * 8) as u8, ((x + y) * 3) as u8])));
+        if adapter().is_err() {
+            assert!(resize(&img, 10, 8, FilterType::Lanczos3, false).is_err());
+            return;
+        }
+        let filters = [FilterType::Nearest, FilterType::Triangle, FilterType::CatmullRom, FilterType::Gaussian, FilterType::Lanczos3];
+        for (filter, linear) in filters.into_iter().flat_map(|filter| [(filter, false), (filter, true)]) {
+            let gpu = resize(&img, 10, 8, filter, linear).unwrap();
+            assert_eq!(gpu.color(), image::ColorType::Rgb8);
+            let space = if linear { Colorspace::Linear } else { Colorspace::Srgb };
+            let cpu = run_in(space, img.clone(), |img| img.resize_exact(10, 8, filter)).into_rgb8();
+            for (gpu, cpu) in gpu.into_rgb8().pixels().zip(cpu.pixels()) {
+                assert!(gpu.0.iter().zip(cpu.0).all(|(a, b)| a.abs_diff(b) <= 1), "{:?}: {:?} vs {:?}", filter, gpu, cpu);
+            }
+        }
+        // Deeper images go up as floats and keep their depth
+        let deep = DynamicImage::ImageRgba16(image::ImageBuffer::from_fn(40, 30, |x, y| image::Rgba([x as u16 * 1600, y as u16 * 2000, 30_000, 65_535])));
+        let gpu = resize(&deep, 10, 8, FilterType::CatmullRom, false).unwrap();
+        assert_eq!(gpu.color(), image::ColorType::Rgba16);
+        let cpu = deep.resize_exact(10, 8, FilterType::CatmullRom).into_rgba16();
+        assert!(gpu.into_rgba16().pixels().zip(cpu.pixels()).all(|(a, b)| a.0.iter().zip(b.0).all(|(a, b)| a.abs_diff(b) <= 2)));
+
+        // Linear light brightens the average of black and white
+        let stripes = DynamicImage::ImageRgb8(RgbImage::from_fn(8, 2, |x, _| if x % 2 == 0 { Rgb([0; 3]) } else { Rgb([255; 3]) }));
+        let linear = resize(&stripes, 1, 1, FilterType::Triangle, true).unwrap().into_rgb8();
+        assert!(linear.get_pixel(0, 0).0[0] > 180, "{:?}", linear.get_pixel(0, 0));
+    }
+}
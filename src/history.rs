@@ -0,0 +1,194 @@
+// File: src\history.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: History of successful conversions and `imgconv again` to repeat the last one
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::encode::write_bytes;
+
+/// Older entries are dropped once the history grows past this
+const MAX_ENTRIES: usize = 500;
+
+#[derive(Args, Debug)]
+pub struct AgainArgs {
+    /// Run the recipe on this input instead of the recorded one
+    #[arg(short, long, value_name = "FILE")]
+    pub input: Option<PathBuf>,
+
+    /// Output for the new input (default: named after it, next to the recorded output)
+    #[arg(short, long, value_name = "FILE", requires = "input")]
+    pub output: Option<PathBuf>,
+
+    /// Print the command instead of running it
+    #[arg(long)]
+    pub print: bool,
+}
+
+/// One successful invocation: the arguments as given, plus which of them
+/// were the input and output so they can be swapped out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Seconds since the Unix epoch
+    time: u64,
+    cwd: PathBuf,
+    arguments: Vec<String>,
+    input: Option<String>,
+    output: Option<String>,
+    output_is_dir: bool,
+}
+
+/// History file: $IMGCONV_HISTORY (empty disables it), else the per-user state directory
+fn path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("IMGCONV_HISTORY") {
+        return (!path.is_empty()).then(|| PathBuf::from(path));
+    }
+    let dir = if cfg!(windows) {
+        PathBuf::from(std::env::var_os("LOCALAPPDATA")?)
+    } else {
+        std::env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local").join("state")))?
+    };
+    Some(dir.join("imgconv").join("history.jsonl"))
+}
+
+/// Append a finished conversion to the history
+pub fn record(arguments: &[String], input: Option<&Path>, output: Option<&Path>) -> Result<()> {
+    let Some(path) = path() else {
+        return Ok(());
+    };
+    let entry = Entry {
+        time: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        cwd: std::env::current_dir().unwrap_or_default(),
+        arguments: arguments.to_vec(),
+        input: input.map(|p| p.display().to_string()),
+        output: output.map(|p| p.display().to_string()),
+        output_is_dir: output.is_some_and(Path::is_dir),
+    };
+
+    let existing = std::fs::read_to_string(&path).unwrap_or_default();
+    let mut lines: Vec<&str> = existing.lines().filter(|line| !line.trim().is_empty()).collect();
+    let line = serde_json::to_string(&entry)?;
+    lines.push(&line);
+    let keep = lines.len().saturating_sub(MAX_ENTRIES);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create history directory: {}", dir.display()))?;
+    }
+    write_bytes(&path, (lines[keep..].join("\n") + "\n").as_bytes())
+}
+
+/// Arguments repeating the last recorded conversion, adjusted for `args`
+pub fn again(args: &AgainArgs) -> Result<Vec<String>> {
+    let path = path().context("History is disabled (IMGCONV_HISTORY is empty)")?;
+    let data = std::fs::read_to_string(&path)
+        .with_context(|| format!("No conversion recorded yet ({} not found)", path.display()))?;
+    let last = data.lines().rev().find(|line| !line.trim().is_empty()).context("No conversion recorded yet")?;
+    let entry: Entry = serde_json::from_str(last)
+        .with_context(|| format!("Invalid history file: {}", path.display()))?;
+    rebuild(&entry, args.input.as_deref(), args.output.as_deref())
+}
+
+/// `entry`'s arguments with the input (and output) replaced
+///
+/// Without an explicit output, a file output is renamed after the new input
+/// and keeps its directory and extension; a directory output is reused.
+fn rebuild(entry: &Entry, input: Option<&Path>, output: Option<&Path>) -> Result<Vec<String>> {
+    let Some(input) = input else {
+        return Ok(entry.arguments.clone());
+    };
+    let Some(old_input) = &entry.input else {
+        anyhow::bail!("The last conversion had no input file (clipboard or --files-from), so --input cannot replace it");
+    };
+    let mut arguments = substitute(&entry.arguments, old_input, &input.display().to_string());
+
+    let new_output = match (output, &entry.output) {
+        (Some(output), _) => Some(output.to_path_buf()),
+        (None, Some(old)) if !entry.output_is_dir => {
+            let old = Path::new(old);
+            let stem = input.file_stem().context("--input has no file name")?;
+            // Appended rather than set, so dots in the stem (`v1.2`) survive
+            let mut name = stem.to_os_string();
+            if let Some(ext) = old.extension() {
+                name.push(".");
+                name.push(ext);
+            }
+            Some(old.with_file_name(name))
+        }
+        _ => None,
+    };
+    if let (Some(new_output), Some(old_output)) = (new_output, &entry.output) {
+        arguments = substitute(&arguments, old_output, &new_output.display().to_string());
+    }
+    Ok(arguments)
+}
+
+/// Replace the argument `old` (alone or as `--option=old`) with `new`
+fn substitute(arguments: &[String], old: &str, new: &str) -> Vec<String> {
+    arguments
+        .iter()
+        .map(|argument| match argument.strip_suffix(old) {
+            Some("") => new.to_string(),
+            Some(prefix) if prefix.starts_with('-') && prefix.ends_with('=') => format!("{}{}", prefix, new),
+            _ => argument.clone(),
+        })
+        .collect()
+}
+
+/// Shell-style command line for display, quoting arguments with spaces
+pub fn command_line(arguments: &[String]) -> String {
+    std::iter::once("imgconv".to_string())
+        .chain(arguments.iter().map(|argument| {
+            if argument.is_empty() || argument.contains([' ', '"', '\'']) {
+                format!("\"{}\"", argument.replace('"', "\\\""))
+            } else {
+                argument.clone()
+            }
+        }))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(arguments: &[&str], input: &str, output: &str, output_is_dir: bool) -> Entry {
+        Entry {
+            time: 0,
+            cwd: PathBuf::new(),
+            arguments: arguments.iter().map(|s| s.to_string()).collect(),
+            input: Some(input.into()),
+            output: Some(output.into()),
+            output_is_dir,
+        }
+    }
+
+    #[test]
+    fn test_rebuild_swaps_input_and_output() {
+        let last = entry(&["shots/a.png", "web/a.webp", "-q", "70"], "shots/a.png", "web/a.webp", false);
+        let again = rebuild(&last, Some(Path::new("b.jpg")), None).unwrap();
+        assert_eq!(again, ["b.jpg", "web/b.webp", "-q", "70"]);
+        let again = rebuild(&last, Some(Path::new("b.jpg")), Some(Path::new("c.avif"))).unwrap();
+        assert_eq!(again, ["b.jpg", "c.avif", "-q", "70"]);
+
+        let batch = entry(&["--input=photos", "-o", "out", "-f", "webp"], "photos", "out", true);
+        assert_eq!(rebuild(&batch, Some(Path::new("more")), None).unwrap(), ["--input=more", "-o", "out", "-f", "webp"]);
+        assert_eq!(rebuild(&batch, None, None).unwrap(), batch.arguments);
+    }
+
+    #[test]
+    fn test_command_line_quotes() {
+        let arguments = vec!["my photo.png".to_string(), "out.jpg".to_string()];
+        assert_eq!(command_line(&arguments), "imgconv \"my photo.png\" out.jpg");
+    }
+}
@@ -0,0 +1,125 @@
+// File: src\hooks.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Per-file shell command and end-of-run webhook notifications
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::i18n::tr;
+use crate::{print_info, print_warning, template};
+
+#[derive(Args, Debug)]
+pub struct HookArgs {
+    /// Run a shell command after each converted file; tokens: {input} {output} {name}
+    #[arg(long, value_name = "CMD", conflicts_with = "estimate")]
+    pub on_complete: Option<String>,
+
+    /// POST a JSON summary to URL when the run finishes (uses curl)
+    #[arg(long, value_name = "URL", conflicts_with = "estimate")]
+    pub webhook: Option<String>,
+}
+
+/// Run --on-complete for one written output; failures are reported but do
+/// not undo the conversion
+pub fn on_complete(args: &HookArgs, input: Option<&Path>, output: &Path) -> Result<()> {
+    let Some(command) = &args.on_complete else {
+        return Ok(());
+    };
+    let vars = [
+        ("input", input.map(|path| quote(&path.display().to_string())).unwrap_or_else(|| quote(""))),
+        ("output", quote(&output.display().to_string())),
+        ("name", quote(&output.file_name().unwrap_or_default().to_string_lossy())),
+    ];
+    let command = template::render(command, &vars)?;
+    let status = shell(&command)
+        .status()
+        .with_context(|| format!("Failed to run --on-complete command: {}", command))?;
+    if !status.success() {
+        print_warning(&tr!("--on-complete command failed ({}): {}", status, command));
+    }
+    Ok(())
+}
+
+/// POST `payload` to --webhook; a failed delivery is only a warning
+pub fn notify(args: &HookArgs, payload: &serde_json::Value) {
+    let Some(url) = &args.webhook else {
+        return;
+    };
+    match post_json(url, payload) {
+        Ok(()) => print_info(&tr!("Webhook notified: {}", url)),
+        Err(e) => print_warning(&tr!("Webhook failed: {}", format!("{:#}", e))),
+    }
+}
+
+/// Send through curl, which handles HTTPS and proxies on every platform
+fn post_json(url: &str, payload: &serde_json::Value) -> Result<()> {
+    let mut child = Command::new("curl")
+        .args(["-fsS", "-o", if cfg!(windows) { "NUL" } else { "/dev/null" }, "-X", "POST"])
+        .args(["-H", "Content-Type: application/json", "--data-binary", "@-", url])
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("Could not run curl (is it installed and on PATH?)")?;
+    child.stdin.take().context("curl stdin unavailable")?.write_all(payload.to_string().as_bytes())
+        .context("Failed to send the webhook payload to curl")?;
+    let status = child.wait().context("curl did not finish")?;
+    if !status.success() {
+        anyhow::bail!("curl exited with {} posting to {}", status, url);
+    }
+    Ok(())
+}
+
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// Quote a token value so paths with spaces or quotes reach the command intact
+fn quote(value: &str) -> String {
+    if cfg!(windows) {
+        cmd_quote(value)
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Quote for cmd.exe, which expands `%VAR%` even inside double quotes
+///
+/// Each `%` is taken out of the quotes as `^%`: the caret makes the would-be
+/// variable name one that is never defined, so cmd leaves it alone, then
+/// drops the caret. Carets inside the quotes are already literal.
+fn cmd_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\"").replace('%', "\"^%\""))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_complete_quotes_paths() {
+        assert_eq!(quote("it's here.png"), "'it'\\''s here.png'");
+        assert_eq!(cmd_quote("100%PATH%^x.png"), "\"100\"^%\"PATH\"^%\"^x.png\"");
+        let dir = std::env::temp_dir().join(format!("imgconv-hooks-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("log.txt");
+        let args = HookArgs {
+            on_complete: Some(format!("printf '%s|%s' {{name}} {{input}} > '{}'", log.display())),
+            webhook: None,
+        };
+        on_complete(&args, Some(Path::new("my photo.png")), &dir.join("my photo.webp")).unwrap();
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "my photo.webp|my photo.png");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,509 @@
+// File: src\i18n.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Message catalog for localized output (--lang, locale detection)
+// License: MIT
+
+use clap::{Args, ValueEnum};
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+#[derive(Args, Debug)]
+pub struct LangArgs {
+    /// Language of status messages (default: from LC_ALL, LC_MESSAGES or LANG)
+    #[arg(long, value_enum, value_name = "LANG", global = true)]
+    pub lang: Option<Lang>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Lang {
+    /// English
+    En,
+    /// Bahasa Indonesia
+    Id,
+}
+
+impl Lang {
+    /// Language of a POSIX locale such as `id_ID.UTF-8`; None for unsupported ones
+    fn from_locale(locale: &str) -> Option<Self> {
+        let code = locale.split(['_', '-', '.', '@']).next()?.to_lowercase();
+        match code.as_str() {
+            "en" | "c" | "posix" => Some(Lang::En),
+            // "in" is the pre-1989 code some systems still report
+            "id" | "in" => Some(Lang::Id),
+            _ => None,
+        }
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::En => &[],
+            Lang::Id => INDONESIAN,
+        }
+    }
+}
+
+/// Choose the output language once, before anything is printed
+pub fn init(args: &LangArgs) {
+    let lang = args.lang.unwrap_or_else(|| {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+            .next()
+            .and_then(|locale| Lang::from_locale(&locale))
+            .unwrap_or(Lang::En)
+    });
+    let _ = LANG.set(lang);
+}
+
+/// Translate `template` into the current language and fill its `{}`
+/// placeholders with `args` in order
+///
+/// The English template is the lookup key, so a message missing from a
+/// catalog is printed in English instead of failing.
+pub fn translate(template: &str, args: &[&dyn Display]) -> String {
+    let lang = LANG.get().copied().unwrap_or(Lang::En);
+    fill(lookup(lang, template), args)
+}
+
+fn lookup(lang: Lang, template: &str) -> &str {
+    lang.catalog()
+        .iter()
+        .find(|(english, _)| *english == template)
+        .map_or(template, |(_, translated)| translated)
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    out.push_str(parts.next().unwrap_or_default());
+    for part in parts {
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+/// `tr!("Reading image from: {}", path.display())` - a localized `format!`
+/// with plain `{}` placeholders (format values with `format!` first)
+#[doc(hidden)]
+#[macro_export]
+macro_rules! tr {
+    ($template:literal $(, $arg:expr)* $(,)?) => {
+        $crate::i18n::translate($template, &[$(&$arg as &dyn std::fmt::Display),*])
+    };
+}
+pub use crate::tr;
+
+const INDONESIAN: &[(&str, &str)] = &[
+    ("Resuming batch: imgconv {}", "Melanjutkan batch: imgconv {}"),
+    ("Quality must be between 1 and 100, got: {}", "Kualitas harus antara 1 dan 100, didapat: {}"),
+    ("Batch converting images listed in: {}", "Konversi batch gambar yang tercantum di: {}"),
+    ("Running jobs from: {}", "Menjalankan job dari: {}"),
+    ("Skipping {} of {} files that don't match the filters", "Melewati {} dari {} file yang tidak cocok dengan filter"),
+    ("Reading image from clipboard...", "Membaca gambar dari clipboard..."),
+    (
+        "Input file is required. Usage: imgconv <input> <output> OR imgconv -c <output>",
+        "File input wajib diisi. Penggunaan: imgconv <input> <output> ATAU imgconv -c <output>",
+    ),
+    ("Input file not found: {}", "File input tidak ditemukan: {}"),
+    ("Batch converting images from: {}", "Konversi batch gambar dari: {}"),
+    ("Batch converting images from archive: {}", "Konversi batch gambar dari arsip: {}"),
+    ("Up to date, skipping: {}", "Sudah terbaru, dilewati: {}"),
+    (
+        "Output file is required. Usage: imgconv <input> <output> OR imgconv -c <output>",
+        "File output wajib diisi. Penggunaan: imgconv <input> <output> ATAU imgconv -c <output>",
+    ),
+    ("Image loaded: {}x{} pixels, format: {}", "Gambar dimuat: {}x{} piksel, format: {}"),
+    ("Image loaded: {}x{} pixels", "Gambar dimuat: {}x{} piksel"),
+    ("Restored from cache: {}", "Dipulihkan dari cache: {}"),
+    ("Reading image from: {}", "Membaca gambar dari: {}"),
+    ("Successfully converted to: {}", "Berhasil dikonversi ke: {}"),
+    ("Estimating output size for format: {}", "Memperkirakan ukuran output untuk format: {}"),
+    ("Converting to format: {}", "Mengonversi ke format: {}"),
+    ("{} quality: {}", "Kualitas {}: {}"),
+    ("Failed to open input file: {}", "Gagal membuka file input: {}"),
+    ("Failed to read input file: {}", "Gagal membaca file input: {}"),
+    ("Failed to decode image", "Gagal mendekode gambar"),
+    ("Failed to access clipboard", "Gagal mengakses clipboard"),
+    (
+        "No image found in clipboard. Please copy an image first.",
+        "Tidak ada gambar di clipboard. Salin gambar terlebih dahulu.",
+    ),
+    (
+        "Correcting extension from .{} to .{} (conversion mode)",
+        "Memperbaiki ekstensi dari .{} ke .{} (mode konversi)",
+    ),
+    (
+        "Output extension .{} doesn't match clipboard format .{}, correcting...",
+        "Ekstensi output .{} tidak cocok dengan format clipboard .{}, diperbaiki...",
+    ),
+    ("Auto-adding extension: .{}", "Menambahkan ekstensi otomatis: .{}"),
+    ("No images to convert", "Tidak ada gambar untuk dikonversi"),
+    ("{} files to convert", "{} file akan dikonversi"),
+    ("{} of {} files failed to convert", "{} dari {} file gagal dikonversi"),
+    ("Batch done: {} converted, {} up to date", "Batch selesai: {} dikonversi, {} sudah terbaru"),
+    ("Batch cancelled: {} converted, {} not converted", "Batch dibatalkan: {} dikonversi, {} tidak dikonversi"),
+    ("Cancelling, press Ctrl-C again to quit immediately", "Membatalkan, tekan Ctrl-C lagi untuk langsung keluar"),
+    ("Saved {}%", "Hemat {}%"),
+    ("Grew {}%", "Bertambah {}%"),
+    ("Size report saved to: {}", "Laporan ukuran disimpan ke: {}"),
+    ("Salvaged image written to: {}", "Gambar yang diselamatkan ditulis ke: {}"),
+    ("Partially decodable, use --repair to salvage", "Sebagian dapat didekode, gunakan --repair untuk menyelamatkannya"),
+    ("Nothing salvageable, header could not be read", "Tidak ada yang bisa diselamatkan, header tidak dapat dibaca"),
+    ("{} of {} files failed verification", "{} dari {} file gagal diverifikasi"),
+    ("All {} files verified", "Semua {} file terverifikasi"),
+    ("Resizing alpha mask from {}x{} to {}x{}", "Mengubah ukuran mask alfa dari {}x{} ke {}x{}"),
+    (
+        "Image has no alpha channel, extracted mask is fully opaque",
+        "Gambar tidak memiliki kanal alfa, mask yang diekstrak sepenuhnya buram",
+    ),
+    ("Alpha channel saved to: {}", "Kanal alfa disimpan ke: {}"),
+    ("{} has no alpha channel, flattening transparency onto {}", "{} tidak memiliki kanal alfa, transparansi diratakan ke {}"),
+    (
+        "{} is animated: only its first frame is converted, the rest are DROPPED (--first-frame to silence this)",
+        "{} beranimasi: hanya frame pertama yang dikonversi, sisanya DIBUANG (--first-frame untuk membungkam ini)",
+    ),
+    (
+        "Source is {}px; icons larger than that will be upscaled ({}px or more recommended)",
+        "Sumber berukuran {}px; ikon yang lebih besar akan diperbesar (disarankan {}px atau lebih)",
+    ),
+    ("Wrote {} icons to: {}", "Menulis {} ikon ke: {}"),
+    ("Source is {}x{}, padding it to a square", "Sumber berukuran {}x{}, diberi bantalan menjadi persegi"),
+    ("iOS: {} icons and Contents.json in {}", "iOS: {} ikon dan Contents.json di {}"),
+    ("Android: {} icons and adaptive-icon XML in {}", "Android: {} ikon dan XML adaptive-icon di {}"),
+    ("Even quality 100 only reaches SSIM {}, using it", "Bahkan kualitas 100 hanya mencapai SSIM {}, kualitas itu dipakai"),
+    ("Auto quality {} (SSIM {} >= {})", "Kualitas otomatis {} (SSIM {} >= {})"),
+    ("Source is {}x{}; the {}px avatar will be upscaled", "Sumber berukuran {}x{}; avatar {}px akan diperbesar"),
+    ("Avatar {}x{} from the {}x{} square at {},{}", "Avatar {}x{} dari persegi {}x{} di {},{}"),
+    ("Wrote {} files into {}", "Menulis {} file ke {}"),
+    ("Keyed out {}% of the image", "{}% gambar dijadikan transparan"),
+    ("Background removed with {}", "Latar belakang dihapus dengan {}"),
+    ("{} JPEG (Adobe inverted)", "JPEG {} (terbalik Adobe)"),
+    ("{} JPEG", "JPEG {}"),
+    ("Converted CMYK using the embedded ICC profile", "CMYK dikonversi dengan profil ICC yang tertanam"),
+    (
+        "Embedded ICC profile unusable ({}), using naive CMYK conversion",
+        "Profil ICC tertanam tidak dapat dipakai ({}), memakai konversi CMYK sederhana",
+    ),
+    ("This run will:", "Proses ini akan:"),
+    ("Smart crop to {}x{} at {},{}", "Potong cerdas ke {}x{} di {},{}"),
+    ("Duplicate of {}, skipping: {}", "Duplikat dari {}, dilewati: {}"),
+    ("Duplicate of {}, {}: {}", "Duplikat dari {}, {}: {}"),
+    ("{} duplicate inputs were not converted again", "{} input duplikat tidak dikonversi ulang"),
+    ("Bit depth: {} -> {}", "Kedalaman bit: {} -> {}"),
+    ("{} only stores 8 bits per channel, reducing 16-bit image", "{} hanya menyimpan 8 bit per kanal, gambar 16-bit diturunkan"),
+    ("{} works with 8 bits per channel, reducing {}-bit image", "{} bekerja dengan 8 bit per kanal, gambar {}-bit diturunkan"),
+    ("Installed: {}", "Terpasang: {}"),
+    (
+        "Added imgconv to the application menu and the Nautilus/Dolphin right-click menus",
+        "imgconv ditambahkan ke menu aplikasi dan menu klik kanan Nautilus/Dolphin",
+    ),
+    (
+        "Restart the file manager (nautilus -q) if the actions do not show up yet; remove with `imgconv uninstall-desktop`",
+        "Mulai ulang pengelola file (nautilus -q) jika aksinya belum muncul; hapus dengan `imgconv uninstall-desktop`",
+    ),
+    ("Removed: {}", "Dihapus: {}"),
+    ("No imgconv desktop integration was installed", "Tidak ada integrasi desktop imgconv yang terpasang"),
+    ("Removed the imgconv desktop integration", "Integrasi desktop imgconv dihapus"),
+    (
+        "Cannot decode {} to verify, checked the file size only",
+        "Tidak dapat mendekode {} untuk verifikasi, hanya ukuran file yang diperiksa",
+    ),
+    ("Source moved to: {}", "Sumber dipindahkan ke: {}"),
+    ("Source deleted: {}", "Sumber dihapus: {}"),
+    ("JPEG stores 8 bits per channel, converting {} image to {}", "JPEG menyimpan 8 bit per kanal, gambar {} dikonversi ke {}"),
+    ("Large image ({}x{}), estimating from {} sampled tiles", "Gambar besar ({}x{}), memperkirakan dari {} petak sampel"),
+    ("{} ignores quality, estimating a single size", "{} mengabaikan kualitas, memperkirakan satu ukuran saja"),
+    ("No embedded thumbnail in {}, decoding the full image", "Tidak ada thumbnail tertanam di {}, mendekode gambar penuh"),
+    ("Using the embedded {}x{} EXIF thumbnail", "Memakai thumbnail EXIF tertanam {}x{}"),
+    (
+        "Added \"Convert with imgconv\" to the Explorer context menu ({} registry values)",
+        "\"Convert with imgconv\" ditambahkan ke menu konteks Explorer ({} nilai registry)",
+    ),
+    (
+        "Right-click an image, a folder or a folder background; remove with `imgconv uninstall-shell-integration`",
+        "Klik kanan gambar, folder, atau latar folder; hapus dengan `imgconv uninstall-shell-integration`",
+    ),
+    ("No imgconv context-menu entries were installed", "Tidak ada entri menu konteks imgconv yang terpasang"),
+    ("Removed the imgconv context-menu entries", "Entri menu konteks imgconv dihapus"),
+    ("{}; see `imgconv formats`", "{}; lihat `imgconv formats`"),
+    ("--on-complete command failed ({}): {}", "Perintah --on-complete gagal ({}): {}"),
+    ("Webhook notified: {}", "Webhook diberi tahu: {}"),
+    ("Webhook failed: {}", "Webhook gagal: {}"),
+    ("Overwriting {} without a backup", "Menimpa {} tanpa cadangan"),
+    ("Replacing existing backup: {}", "Mengganti cadangan yang ada: {}"),
+    ("Original backed up to: {}", "Berkas asli dicadangkan ke: {}"),
+    ("Joining {} images into {}x{}", "Menggabungkan {} gambar menjadi {}x{}"),
+    ("Joined image saved to: {}", "Gambar gabungan disimpan ke: {}"),
+    ("Auto levels: {}", "Level otomatis: {}"),
+    ("Repeating: {}", "Mengulang: {}"),
+    ("Could not update history: {}", "Tidak dapat memperbarui riwayat: {}"),
+    ("{}; converting in strips", "{}; mengonversi per lajur"),
+    (
+        "Streaming resize uses area averaging; --filter and --linear-resize are ignored",
+        "Resize streaming memakai rata-rata area; --filter dan --linear-resize diabaikan",
+    ),
+    ("Pipeline for {} output:", "Pipeline untuk output {}:"),
+    ("{}; writing {}", "{}; menulis {}"),
+    ("Manifest with {} files written to: {}", "Manifest berisi {} file ditulis ke: {}"),
+    ("Skipping {}: {}", "Melewati {}: {}"),
+    ("Building contact sheet of {} images", "Membuat lembar kontak dari {} gambar"),
+    ("Contact sheet saved to: {} ({}x{})", "Lembar kontak disimpan ke: {} ({}x{})"),
+    ("Denoised ({}, strength {})", "Derau dikurangi ({}, kekuatan {})"),
+    ("Optimized PNG: {} ({})", "PNG dioptimalkan: {} ({})"),
+    ("Extracted {} colors ({})", "Mengekstrak {} warna ({})"),
+    ("Palette saved to: {}", "Palet disimpan ke: {}"),
+    ("Could not preserve owner of {} ({})", "Tidak dapat mempertahankan pemilik {} ({})"),
+    ("--preserve-owner is only supported on Unix", "--preserve-owner hanya didukung di Unix"),
+    ("Uploaded: {} -> {}", "Diunggah: {} -> {}"),
+    ("Uploaded {} files to {}", "{} file diunggah ke {}"),
+    ("Encoder threads limited to {}", "Thread encoder dibatasi hingga {}"),
+    ("Could not lower process priority ({})", "Tidak dapat menurunkan prioritas proses ({})"),
+    ("--low-priority is only supported on Unix", "--low-priority hanya didukung di Unix"),
+    ("Quantized to {} colors ({})", "Dikuantisasi menjadi {} warna ({})"),
+    ("Indexed PNG with {} colors ({})", "PNG terindeks dengan {} warna ({})"),
+    ("Redaction region {},{},{},{} is outside the {}x{} image", "Area sensor {},{},{},{} berada di luar gambar {}x{}"),
+    ("Redacted {} region(s)", "{} area disensor"),
+    ("Resized {}x{} -> {}x{} ({}, {})", "Ukuran diubah {}x{} -> {}x{} ({}, {})"),
+    (
+        "ICO entries are at most {}: downscaled {}x{} -> {}x{}, padded to {} (--no-auto-downscale to keep the size)",
+        "Entri ICO paling besar {}: diperkecil {}x{} -> {}x{}, diberi bantalan ke {} (--no-auto-downscale untuk mempertahankan ukuran)",
+    ),
+    ("Decoded at 1/{} scale: {}x{} -> {}x{}", "Didekode pada skala 1/{}: {}x{} -> {}x{}"),
+    ("Resizing on the GPU: {}", "Mengubah ukuran di GPU: {}"),
+    ("Resizing on the CPU instead: {}", "Mengubah ukuran di CPU sebagai gantinya: {}"),
+    ("Resuming: {} files already converted", "Melanjutkan: {} file sudah dikonversi"),
+    (
+        "Progress saved; run `imgconv --resume {}` to retry the remaining files",
+        "Progres disimpan; jalankan `imgconv --resume {}` untuk mencoba ulang file yang tersisa",
+    ),
+    ("Batch complete, removed state file: {}", "Batch selesai, file status dihapus: {}"),
+    ("Uploaded: s3://{}/{}", "Diunggah: s3://{}/{}"),
+    ("Uploaded {} files to s3://{}/{}", "{} file diunggah ke s3://{}/{}"),
+    ("No skew found", "Tidak ditemukan kemiringan"),
+    ("Deskewed by {}°", "Kemiringan diluruskan sebesar {}°"),
+    ("Uploaded: {}:{}", "Diunggah: {}:{}"),
+    ("Uploaded {} files to {}:{}", "{} file diunggah ke {}:{}"),
+    ("Source is {}x{}, smaller than {}x{}; it will be upscaled", "Sumber berukuran {}x{}, lebih kecil dari {}x{}; akan diperbesar"),
+    ("Social preset {}: {}x{} ({})", "Preset sosial {}: {}x{} ({})"),
+    ("Packing {} sprites into {}x{} sheet", "Mengemas {} sprite ke lembar {}x{}"),
+    ("Sprite sheet saved to: {}", "Lembar sprite disimpan ke: {}"),
+    ("Atlas saved to: {}", "Atlas disimpan ke: {}"),
+    ("Streaming reduces 16-bit samples to 8 bits", "Streaming menurunkan sampel 16-bit menjadi 8 bit"),
+    ("Output: {}{}", "Output: {}{}"),
+    (
+        "Super-resolution unavailable ({}); using the --upscale-filter instead",
+        "Super-resolusi tidak tersedia ({}); memakai --upscale-filter sebagai gantinya",
+    ),
+    ("Quality {} fits the {} budget ({})", "Kualitas {} muat dalam anggaran {} ({})"),
+    (
+        "{} is {}, over the {} budget; {} is lossless here, save as JPEG or AVIF to let the quality adapt",
+        "{} berukuran {}, melebihi anggaran {}; {} lossless di sini, simpan sebagai JPEG atau AVIF agar kualitas bisa menyesuaikan",
+    ),
+    ("System busy ({}% CPU), pausing the batch", "Sistem sibuk ({}% CPU), batch dijeda"),
+    ("System idle again, resuming the batch", "Sistem kembali senggang, batch dilanjutkan"),
+    ("--idle-only is only supported on Linux", "--idle-only hanya didukung di Linux"),
+    ("Saved the {} byte EXIF thumbnail of {} to {}", "Thumbnail EXIF {} byte dari {} disimpan ke {}"),
+    ("Embedded a new {}x{} thumbnail ({} bytes) in {}", "Thumbnail baru {}x{} ({} byte) ditanam di {}"),
+    ("Splitting {}x{} image into {} tiles", "Membagi gambar {}x{} menjadi {} petak"),
+    ("Wrote {} tiles ({} rows x {} columns)", "Menulis {} petak ({} baris x {} kolom)"),
+    ("Timings for {}: {}{}", "Waktu untuk {}: {}{}"),
+    ("Timings for {} files: {}{}", "Waktu untuk {} file: {}{}"),
+    (
+        "--tonemap/--exposure only apply to HDR input written to a non-HDR format",
+        "--tonemap/--exposure hanya berlaku untuk input HDR yang ditulis ke format non-HDR",
+    ),
+    ("Tone mapping with {} at {} EV", "Tone mapping dengan {} pada {} EV"),
+    (
+        "HDR input is clipped to display range, use --tonemap for better highlights",
+        "Input HDR dipangkas ke rentang tampilan, gunakan --tonemap untuk sorotan yang lebih baik",
+    ),
+    ("Upscaled {}x{} -> {}x{} ({})", "Diperbesar {}x{} -> {}x{} ({})"),
+    (
+        "--upscale-filter scale2x needs a factor of 2x, 3x, 4x, 6x, 8x or 9x, got {}x (xbr takes any whole factor)",
+        "--upscale-filter scale2x memerlukan faktor 2x, 3x, 4x, 6x, 8x atau 9x, didapat {}x (xbr menerima faktor bulat apa pun)",
+    ),
+    (
+        "--upscale-filter xbr needs a whole factor such as 2x, 3x or 4x, got {}x",
+        "--upscale-filter xbr memerlukan faktor bulat seperti 2x, 3x atau 4x, didapat {}x",
+    ),
+    ("{}: OK ({}x{}, {})", "{}: OK ({}x{}, {})"),
+    ("Source is only {}px wide; larger widths are not upscaled", "Lebar sumber hanya {}px; lebar yang lebih besar tidak diperbesar"),
+    ("Wrote {} ({}x{})", "Menulis {} ({}x{})"),
+    ("Wrote {} images and {} to: {}", "Menulis {} gambar dan {} ke: {}"),
+    ("White balance gains: R {}, G {}, B {}", "Penguatan white balance: R {}, G {}, B {}"),
+    (
+        "--organize-by sorts batch outputs and needs a directory as input",
+        "--organize-by mengurutkan output batch dan memerlukan direktori sebagai input",
+    ),
+    (
+        "--min-width, --max-size, --only-format and the other filters select batch inputs and need a directory as input",
+        "--min-width, --max-size, --only-format dan filter lainnya memilih input batch dan memerlukan direktori sebagai input",
+    ),
+    (
+        "--sort and --rename order and name batch outputs and need a directory as input",
+        "--sort dan --rename mengurutkan dan menamai output batch dan memerlukan direktori sebagai input",
+    ),
+    (
+        "{} collects batch outputs and needs a directory, an archive or --files-from as input",
+        "{} mengumpulkan output batch dan memerlukan direktori, arsip, atau --files-from sebagai input",
+    ),
+    (
+        "--resume tracks batch progress and needs a directory or --files-from as input",
+        "--resume melacak kemajuan batch dan memerlukan direktori atau --files-from sebagai input",
+    ),
+    ("Failed to create staging directory: {}", "Gagal membuat direktori sementara: {}"),
+    ("Failed to read {}", "Gagal membaca {}"),
+    (
+        "{} needs a source file on disk and does not work with {} as input",
+        "{} memerlukan file sumber di disk dan tidak bekerja dengan {} sebagai input",
+    ),
+    (
+        "{} needs source files on disk and does not work with archive input",
+        "{} memerlukan file sumber di disk dan tidak bekerja dengan input arsip",
+    ),
+    ("--estimate works on a single file, not a batch", "--estimate bekerja pada satu file, bukan batch"),
+    ("--web-set works on a single file, not a batch", "--web-set bekerja pada satu file, bukan batch"),
+    (
+        "{} removes each output once delivered, so only --dedup skip works with it",
+        "{} menghapus setiap output setelah dikirim, jadi hanya --dedup skip yang bisa dipakai bersamanya",
+    ),
+    ("Job {} ({}): {}", "Tugas {} ({}): {}"),
+    ("Job {} ({}): {} cannot be used in a job file", "Tugas {} ({}): {} tidak dapat digunakan dalam file tugas"),
+    (
+        "--embedded-thumbnail replaces the photo with its small preview and cannot be combined with --redact, --crop, --smart-crop, --apply-alpha or other options that run before resizing",
+        "--embedded-thumbnail mengganti foto dengan pratinjau kecilnya dan tidak dapat digabung dengan --redact, --crop, --smart-crop, --apply-alpha atau opsi lain yang berjalan sebelum pengubahan ukuran",
+    ),
+    ("{} needs about {}, over the --max-memory budget of {}", "{} memerlukan sekitar {}, melebihi anggaran --max-memory sebesar {}"),
+    ("--stream was given", "--stream diberikan"),
+    (
+        "{}, but streaming only supports PNG, TIFF and PNM input and output with an optional --resize",
+        "{}, tetapi streaming hanya mendukung input dan output PNG, TIFF dan PNM dengan --resize opsional",
+    ),
+    ("Failed to create directory: {}", "Gagal membuat direktori: {}"),
+    ("Estimate quality must be between 1 and 100, got: {}", "Kualitas estimasi harus antara 1 dan 100, didapat: {}"),
+    ("Failed to detect image format from: {}", "Gagal mendeteksi format gambar dari: {}"),
+    ("Unknown extension: {}", "Ekstensi tidak dikenal: {}"),
+    (
+        "Could not determine output format from '{}'. Please specify --format or use a recognized extension",
+        "Tidak dapat menentukan format output dari '{}'. Tentukan --format atau gunakan ekstensi yang dikenali",
+    ),
+    ("Output extension .{} is {}, but -f asks for {}", "Ekstensi output .{} adalah {}, tetapi -f meminta {}"),
+    ("{} (--strict)", "{} (--strict)"),
+    (
+        "{} is {}x{} ({} pixels), over the --max-pixels limit of {}",
+        "{} berukuran {}x{} ({} piksel), melebihi batas --max-pixels sebesar {}",
+    ),
+    (
+        "The framed image would be {}x{} ({} pixels), over the --max-pixels limit of {}",
+        "Gambar berbingkai akan berukuran {}x{} ({} piksel), melebihi batas --max-pixels sebesar {}",
+    ),
+    ("A {} pixel band around a {}x{} image is over {} pixels wide", "Pita {} piksel di sekitar gambar {}x{} lebih lebar dari {} piksel"),
+    ("{}: {} (--strict)", "{}: {} (--strict)"),
+    ("{}: {}", "{}: {}"),
+    ("decoded {}x{}, but the header says {}x{}", "didekode {}x{}, tetapi header menyebut {}x{}"),
+    (
+        "no end-of-image marker after the image data, the file looks truncated",
+        "tidak ada penanda akhir gambar setelah data gambar, file tampaknya terpotong",
+    ),
+    ("no GIF trailer at the end, the file looks truncated", "tidak ada trailer GIF di akhir, file tampaknya terpotong"),
+    ("no IEND chunk, the file looks truncated", "tidak ada chunk IEND, file tampaknya terpotong"),
+    ("the animation announces {} frames, but {} are present", "animasi menyebut {} frame, tetapi hanya ada {}"),
+    ("{} cannot be used with {} output, only with {}", "{} tidak dapat digunakan dengan output {}, hanya dengan {}"),
+    ("{} has no effect on {} output, only on {}", "{} tidak berpengaruh pada output {}, hanya pada {}"),
+    ("--bit-depth {} is not supported for {} output", "--bit-depth {} tidak didukung untuk output {}"),
+    (
+        "Cannot fit the image in {}, even at quality 1; resize it first",
+        "Gambar tidak muat dalam {}, bahkan pada kualitas 1; ubah ukurannya terlebih dahulu",
+    ),
+    ("The JPEG decoder returned a truncated {}x{} image", "Dekoder JPEG mengembalikan gambar {}x{} yang terpotong"),
+    ("Failed to read directory: {}", "Gagal membaca direktori: {}"),
+    ("Failed to read file list from stdin", "Gagal membaca daftar file dari stdin"),
+    ("Failed to read file list: {}", "Gagal membaca daftar file: {}"),
+    ("Unknown image format: {}", "Format gambar tidak dikenal: {}"),
+    ("Failed to read modification time: {}", "Gagal membaca waktu modifikasi: {}"),
+    ("Failed to read dimensions: {}", "Gagal membaca dimensi: {}"),
+    ("Failed to encode JPEG image", "Gagal mengenkode gambar JPEG"),
+    ("Failed to encode PNG image", "Gagal mengenkode gambar PNG"),
+    ("Failed to encode WebP image", "Gagal mengenkode gambar WebP"),
+    ("Failed to encode GIF image", "Gagal mengenkode gambar GIF"),
+    ("Failed to encode Hdr image", "Gagal mengenkode gambar Hdr"),
+    ("Failed to encode {} image", "Gagal mengenkode gambar {}"),
+    ("Failed to encode AVIF image", "Gagal mengenkode gambar AVIF"),
+    ("Failed to encode AVIF image: the encoder stopped", "Gagal mengenkode gambar AVIF: enkoder berhenti"),
+    ("JPEG images are limited to 65535x65535, got {}x{}", "Gambar JPEG dibatasi hingga 65535x65535, didapat {}x{}"),
+    (
+        "The SIMD JPEG encoder is not available in this build; reinstall with `cargo install imgconv --features turbo`",
+        "Enkoder JPEG SIMD tidak tersedia di build ini; instal ulang dengan `cargo install imgconv --features turbo`",
+    ),
+    ("Failed to encode PBM image", "Gagal mengenkode gambar PBM"),
+    ("1-bit output is not supported for {}", "Output 1-bit tidak didukung untuk {}"),
+    ("Invalid PNG bit depth", "Kedalaman bit PNG tidak valid"),
+    ("Failed to create output file: {}", "Gagal membuat file output: {}"),
+    ("Failed to save image to: {}", "Gagal menyimpan gambar ke: {}"),
+    ("Failed to move output into place: {}", "Gagal memindahkan output ke tempatnya: {}"),
+    ("Failed to write: {}", "Gagal menulis: {}"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_and_fill() {
+        assert_eq!(Lang::from_locale("id_ID.UTF-8"), Some(Lang::Id));
+        assert_eq!(Lang::from_locale("en-US"), Some(Lang::En));
+        assert_eq!(Lang::from_locale("fr_FR"), None);
+        let template = lookup(Lang::Id, "Image loaded: {}x{} pixels");
+        assert_eq!(fill(template, &[&300, &200]), "Gambar dimuat: 300x200 piksel");
+        assert_eq!(lookup(Lang::Id, "Not in the catalog"), "Not in the catalog");
+    }
+
+    #[test]
+    fn test_catalog_keeps_placeholders() {
+        for (english, translated) in INDONESIAN {
+            assert_eq!(english.matches("{}").count(), translated.matches("{}").count(), "{}", english);
+        }
+    }
+
+    #[test]
+    fn test_every_message_is_translated() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut missing = Vec::new();
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|extension| extension != "rs") {
+                continue;
+            }
+            let source = std::fs::read_to_string(path).unwrap();
+            // Spelled in two parts so this test doesn't find itself
+            for call in source.split(concat!("tr", "!(")).skip(1) {
+                let Some(literal) = call.trim_start().strip_prefix('"') else {
+                    continue;
+                };
+                let (mut template, mut chars) = (String::new(), literal.chars());
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => template.push(match chars.next() {
+                            Some('n') => '\n',
+                            Some(other) => other,
+                            None => break,
+                        }),
+                        _ => template.push(c),
+                    }
+                }
+                if !INDONESIAN.iter().any(|(english, _)| *english == template) {
+                    missing.push(template);
+                }
+            }
+        }
+        assert!(missing.is_empty(), "No Indonesian entry for: {:#?}", missing);
+    }
+}
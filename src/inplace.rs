@@ -0,0 +1,151 @@
+// File: src\inplace.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: In-place conversion with an explicit backup choice
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::{ImageFormat, ImageReader};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+use crate::{print_info, print_warning};
+
+#[derive(Args, Debug)]
+pub struct InPlaceArgs {
+    /// Overwrite the input file with the result (e.g. to recompress JPEGs)
+    #[arg(long, conflicts_with_all = ["output", "pos_output", "clipboard", "format", "extension"])]
+    pub in_place: bool,
+
+    /// Keep a copy of the original next to it before overwriting
+    #[arg(long, requires = "in_place")]
+    pub backup: bool,
+
+    /// Overwrite the original without keeping a copy
+    #[arg(long, requires = "in_place", conflicts_with_all = ["backup", "backup_suffix"])]
+    pub no_backup: bool,
+
+    /// Suffix appended to the backup file name (implies --backup)
+    #[arg(long, value_name = "SUFFIX", requires = "in_place")]
+    pub backup_suffix: Option<String>,
+}
+
+impl InPlaceArgs {
+    /// Suffix for the backup copy, None when running with --no-backup
+    fn suffix(&self) -> Result<Option<&str>> {
+        match (&self.backup_suffix, self.backup, self.no_backup) {
+            (Some(suffix), _, _) if suffix.is_empty() || suffix.contains(['/', '\\']) => {
+                anyhow::bail!("Invalid backup suffix: '{}'", suffix)
+            }
+            (Some(suffix), _, _) => Ok(Some(suffix)),
+            (None, true, _) => Ok(Some(".bak")),
+            (None, false, true) => Ok(None),
+            (None, false, false) => anyhow::bail!(
+                "--in-place needs an explicit choice: --backup (keep FILE.bak), --backup-suffix SUFFIX or --no-backup"
+            ),
+        }
+    }
+}
+
+/// Output path and format for in-place mode: the input itself, in its own format
+pub fn target(input: &Path, args: &InPlaceArgs) -> Result<(PathBuf, ImageFormat)> {
+    args.suffix()?;
+    let format = ImageReader::open(input)
+        .and_then(|reader| reader.with_guessed_format())
+        .ok()
+        .and_then(|reader| reader.format())
+        .with_context(|| format!("Cannot convert in place, unknown format: {}", input.display()))?;
+    Ok((input.to_path_buf(), format))
+}
+
+/// Refuse to write over the input unless --in-place asked for it
+///
+/// `./a.jpg` and `a.jpg`, symlinks and hard links to the input all count as the same file.
+pub fn check_distinct(input: &Path, output: &Path) -> Result<()> {
+    if same_file(input, output) {
+        anyhow::bail!(
+            "Input and output are the same file: {}; use --in-place with --backup or --no-backup to overwrite it",
+            output.display()
+        );
+    }
+    Ok(())
+}
+
+/// Whether both paths exist and are one file on disk
+fn same_file(a: &Path, b: &Path) -> bool {
+    let (Ok(a_metadata), Ok(b_metadata)) = (std::fs::metadata(a), std::fs::metadata(b)) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        a_metadata.dev() == b_metadata.dev() && a_metadata.ino() == b_metadata.ino()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (a_metadata, b_metadata);
+        matches!((a.canonicalize(), b.canonicalize()), (Ok(a), Ok(b)) if a == b)
+    }
+}
+
+/// Copy the original aside before it is overwritten, per the backup choice
+pub fn backup(input: &Path, args: &InPlaceArgs) -> Result<()> {
+    let Some(suffix) = args.suffix()? else {
+        print_warning(&tr!("Overwriting {} without a backup", input.display()));
+        return Ok(());
+    };
+
+    let path = backup_path(input, suffix);
+    if path.exists() {
+        print_warning(&tr!("Replacing existing backup: {}", path.display()));
+    }
+    std::fs::copy(input, &path)
+        .with_context(|| format!("Failed to back up {} to {}", input.display(), path.display()))?;
+    print_info(&tr!("Original backed up to: {}", path.display()));
+    Ok(())
+}
+
+/// `photo.jpg` + `.bak` -> `photo.jpg.bak`
+fn backup_path(input: &Path, suffix: &str) -> PathBuf {
+    let mut name = input.file_name().map(OsString::from).unwrap_or_default();
+    name.push(suffix);
+    input.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(backup: bool, no_backup: bool, backup_suffix: Option<&str>) -> InPlaceArgs {
+        InPlaceArgs { in_place: true, backup, no_backup, backup_suffix: backup_suffix.map(String::from) }
+    }
+
+    #[test]
+    fn test_backup_choice_is_required() {
+        assert!(args(false, false, None).suffix().is_err());
+        assert_eq!(args(true, false, None).suffix().unwrap(), Some(".bak"));
+        assert_eq!(args(false, false, Some(".orig")).suffix().unwrap(), Some(".orig"));
+        assert_eq!(args(false, true, None).suffix().unwrap(), None);
+        assert!(args(false, false, Some("../x")).suffix().is_err());
+    }
+
+    #[test]
+    fn test_same_file_is_refused() {
+        let dir = std::env::temp_dir().join(format!("imgconv-same-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        let input = dir.join("a.jpg");
+        std::fs::write(&input, b"jpeg").unwrap();
+        assert!(check_distinct(&input, &dir.join("sub/../a.jpg")).is_err());
+        assert!(check_distinct(&input, &dir.join("b.jpg")).is_ok());
+        std::fs::write(dir.join("b.jpg"), b"jpeg").unwrap();
+        assert!(check_distinct(&input, &dir.join("b.jpg")).is_ok());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_backup_path_appends_suffix() {
+        assert_eq!(backup_path(Path::new("shots/a.jpg"), ".bak"), PathBuf::from("shots/a.jpg.bak"));
+    }
+}
@@ -0,0 +1,199 @@
+// File: src\jobs.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Job files: many conversions with per-file options in one parallel run (--jobs-file)
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::i18n::tr;
+use crate::progress;
+use crate::{print_error, print_info, print_success};
+
+#[derive(Args, Debug)]
+pub struct JobsArgs {
+    /// Run the conversions listed in FILE (TOML or JSON), each with its own options
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["input", "pos_input", "output", "pos_output", "clipboard", "files_from"]
+    )]
+    pub jobs_file: Option<PathBuf>,
+
+    /// Jobs converted at the same time (default: number of CPUs)
+    #[arg(long, value_name = "N", requires = "jobs_file", value_parser = clap::value_parser!(u16).range(1..))]
+    pub parallel: Option<u16>,
+}
+
+/// One conversion from a job file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+    pub input: PathBuf,
+    pub output: PathBuf,
+    /// Per-job options as command-line arguments, e.g. ["--quality", "80"]
+    pub options: Vec<String>,
+}
+
+/// Read the jobs in `path`: `[[job]]` tables in TOML, or an array of objects
+/// (optionally under "job"/"jobs") in JSON
+pub fn load(path: &Path) -> Result<Vec<Job>> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read job file: {}", path.display()))?;
+    let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let value: Value = if is_json {
+        serde_json::from_str(&text).with_context(|| format!("Invalid JSON in job file: {}", path.display()))?
+    } else {
+        let table: toml::Table = toml::from_str(&text).with_context(|| format!("Invalid TOML in job file: {}", path.display()))?;
+        serde_json::to_value(table)?
+    };
+    parse(value).with_context(|| format!("Invalid job file: {}", path.display()))
+}
+
+fn parse(value: Value) -> Result<Vec<Job>> {
+    let entries = match value {
+        Value::Array(entries) => entries,
+        Value::Object(mut object) => match object.remove("job").or_else(|| object.remove("jobs")) {
+            Some(Value::Array(entries)) => entries,
+            _ => anyhow::bail!("expected a list of jobs under \"job\""),
+        },
+        _ => anyhow::bail!("expected a list of jobs"),
+    };
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, entry)| job(entry).with_context(|| format!("job {}", i + 1)))
+        .collect()
+}
+
+fn job(entry: Value) -> Result<Job> {
+    let Value::Object(entry) = entry else {
+        anyhow::bail!("expected a table of options");
+    };
+    let path = |key: &str| match entry.get(key) {
+        Some(Value::String(path)) => Ok(PathBuf::from(path)),
+        _ => anyhow::bail!("\"{}\" is missing or not a string", key),
+    };
+    let (input, output) = (path("input")?, path("output")?);
+    let mut options = Vec::new();
+    for (key, value) in entry.iter().filter(|(key, _)| *key != "input" && *key != "output") {
+        // Keys are long option names; "smart_crop" and "smart-crop" both work
+        let flag = format!("--{}", key.replace('_', "-"));
+        let values = match value {
+            Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            match value {
+                Value::Bool(true) => options.push(flag.clone()),
+                Value::Bool(false) => {}
+                Value::String(text) => options.extend([flag.clone(), text.clone()]),
+                Value::Number(number) => options.extend([flag.clone(), number.to_string()]),
+                _ => anyhow::bail!("unsupported value for \"{}\": {}", key, value),
+            }
+        }
+    }
+    Ok(Job { input, output, options })
+}
+
+/// The command line without --jobs-file and --parallel, as shared by every job
+pub fn base_arguments(arguments: &[String]) -> Vec<String> {
+    let mut base = Vec::new();
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        if argument == "--jobs-file" || argument == "--parallel" {
+            arguments.next();
+        } else if !argument.starts_with("--jobs-file=") && !argument.starts_with("--parallel=") {
+            base.push(argument.clone());
+        }
+    }
+    base
+}
+
+/// Run `convert` for every job on up to `parallel` threads; it returns
+/// false for a job skipped as up to date
+///
+/// Like a batch, a failing job is reported and the others carry on; the run
+/// fails at the end if any job did.
+pub fn run<T: Sync>(
+    jobs: &[(Job, T)],
+    parallel: Option<u16>,
+    convert: impl Fn(&Job, &T) -> Result<bool> + Sync,
+) -> Result<()> {
+    if jobs.is_empty() {
+        anyhow::bail!("The job file lists no jobs");
+    }
+    let threads = parallel
+        .map(usize::from)
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+        .min(jobs.len());
+    print_info(&tr!("{} files to convert", jobs.len()));
+    progress::start(jobs.len());
+
+    let next = AtomicUsize::new(0);
+    let counts = Mutex::new((0, 0, 0)); // converted, skipped, failed
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                while let Some((job, settings)) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    progress::file_started(Some(&job.input), &job.output);
+                    match convert(job, settings) {
+                        Ok(true) => {
+                            counts.lock().unwrap().0 += 1;
+                            progress::file_finished(&job.output);
+                        }
+                        Ok(false) => {
+                            counts.lock().unwrap().1 += 1;
+                            progress::file_skipped(&job.output);
+                        }
+                        Err(e) => {
+                            counts.lock().unwrap().2 += 1;
+                            progress::file_failed(Some(&job.input), &e);
+                            print_error(&format!("{}: {:#}", job.input.display(), e));
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    let (converted, skipped, failed) = counts.into_inner().unwrap();
+    progress::done(converted, skipped, failed);
+    if failed > 0 {
+        anyhow::bail!(tr!("{} of {} files failed to convert", failed, jobs.len()));
+    }
+    print_success(&tr!("Batch done: {} converted, {} up to date", converted, skipped));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jobs() {
+        let table: toml::Table = toml::from_str(
+            "[[job]]\ninput = \"a.png\"\noutput = \"a.webp\"\nquality = 70\nresize = \"800x600\"\nlossless = false\n\n\
+             [[job]]\ninput = \"b.png\"\noutput = \"b.jpg\"\nsmart_crop = \"1:1\"\noptimize = true\n",
+        )
+        .unwrap();
+        let jobs = parse(serde_json::to_value(table).unwrap()).unwrap();
+        assert_eq!(jobs[0].options, ["--quality", "70", "--resize", "800x600"]);
+        assert_eq!((jobs[1].input.as_path(), jobs[1].output.as_path()), (Path::new("b.png"), Path::new("b.jpg")));
+        assert_eq!(jobs[1].options, ["--optimize", "--smart-crop", "1:1"]);
+
+        let jobs = parse(serde_json::json!([{"input": "c.png", "output": "c.avif", "quality": 50}])).unwrap();
+        assert_eq!(jobs[0].options, ["--quality", "50"]);
+        let error = parse(serde_json::json!({"jobs": [{"input": "d.png"}]})).unwrap_err();
+        assert_eq!(format!("{:#}", error), "job 1: \"output\" is missing or not a string");
+    }
+
+    #[test]
+    fn test_base_arguments() {
+        let arguments = ["--jobs-file", "j.toml", "-q", "85", "--parallel=2", "-f", "webp"].map(String::from);
+        assert_eq!(base_arguments(&arguments), ["-q", "85", "-f", "webp"]);
+    }
+}
@@ -0,0 +1,129 @@
+// File: src\join.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: `imgconv join` - stitch images side by side or stacked
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use image::{imageops, DynamicImage, GenericImageView, RgbaImage};
+use std::path::PathBuf;
+
+use crate::alpha::flatten_for_format;
+use crate::color::Color;
+use crate::encode::save_image;
+use crate::i18n::tr;
+use crate::{detect_format_from_path, load_image, print_info, print_success};
+
+#[derive(Args, Debug)]
+pub struct JoinArgs {
+    /// Input images followed by the output file
+    #[arg(value_name = "FILES", num_args = 3.., required = true)]
+    files: Vec<PathBuf>,
+
+    /// Stitch left-to-right or top-to-bottom
+    #[arg(long, value_enum, default_value = "horizontal")]
+    direction: Direction,
+
+    /// Cross-axis alignment of images with different sizes
+    #[arg(long, value_enum, default_value = "center")]
+    align: Align,
+
+    /// Space between images (pixels)
+    #[arg(long, default_value = "0", value_name = "PX")]
+    gap: u32,
+
+    /// Color of gaps and uncovered areas
+    #[arg(long, default_value = "transparent", value_name = "COLOR")]
+    background: Color,
+
+    /// Quality for lossy output formats (1-100)
+    #[arg(short, long, default_value = "90", value_name = "NUM", value_parser = clap::value_parser!(u8).range(1..=100))]
+    quality: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Align {
+    Start,
+    Center,
+    End,
+}
+
+pub fn run(args: &JoinArgs) -> Result<()> {
+    let (output, inputs) = args.files.split_last().context("Output file is required")?;
+    let format = detect_format_from_path(output)
+        .with_context(|| format!("Could not determine output format from '{}'", output.display()))?;
+
+    let images = inputs
+        .iter()
+        .map(|path| load_image(path).map(|(img, _)| img))
+        .collect::<Result<Vec<DynamicImage>>>()?;
+
+    let sizes: Vec<(u32, u32)> = images.iter().map(|img| img.dimensions()).collect();
+    let (positions, (width, height)) = layout(&sizes, args.direction, args.align, args.gap);
+    print_info(&tr!("Joining {} images into {}x{}", images.len(), width, height));
+
+    let mut canvas = RgbaImage::from_pixel(width, height, args.background.0);
+    for (img, &(x, y)) in images.iter().zip(&positions) {
+        imageops::overlay(&mut canvas, &img.to_rgba8(), x as i64, y as i64);
+    }
+
+    let joined = flatten_for_format(DynamicImage::ImageRgba8(canvas), format, args.background);
+    save_image(&joined, format, args.quality, output)?;
+    print_success(&tr!("Joined image saved to: {}", output.display()));
+    Ok(())
+}
+
+/// Positions of each image and the total canvas size
+fn layout(sizes: &[(u32, u32)], direction: Direction, align: Align, gap: u32) -> (Vec<(u32, u32)>, (u32, u32)) {
+    // Work in (main axis, cross axis) and swap back for vertical joins
+    let oriented: Vec<(u32, u32)> = sizes
+        .iter()
+        .map(|&(w, h)| if direction == Direction::Horizontal { (w, h) } else { (h, w) })
+        .collect();
+    let cross = oriented.iter().map(|s| s.1).max().unwrap_or(0);
+    let main = oriented.iter().map(|s| s.0).sum::<u32>() + gap * (sizes.len() as u32).saturating_sub(1);
+
+    let mut cursor = 0;
+    let positions = oriented
+        .iter()
+        .map(|&(m, c)| {
+            let offset = match align {
+                Align::Start => 0,
+                Align::Center => (cross - c) / 2,
+                Align::End => cross - c,
+            };
+            let pos = (cursor, offset);
+            cursor += m + gap;
+            if direction == Direction::Horizontal { pos } else { (pos.1, pos.0) }
+        })
+        .collect();
+
+    let size = if direction == Direction::Horizontal { (main, cross) } else { (cross, main) };
+    (positions, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_horizontal_center() {
+        let (positions, size) = layout(&[(10, 20), (5, 10)], Direction::Horizontal, Align::Center, 3);
+        assert_eq!(positions, vec![(0, 0), (13, 5)]);
+        assert_eq!(size, (18, 20));
+    }
+
+    #[test]
+    fn test_vertical_end() {
+        let (positions, size) = layout(&[(10, 20), (4, 10)], Direction::Vertical, Align::End, 0);
+        assert_eq!(positions, vec![(0, 0), (6, 20)]);
+        assert_eq!(size, (10, 30));
+    }
+}
@@ -0,0 +1,186 @@
+// File: src\levels.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Automatic levels (--auto-levels), shadow/highlight recovery (--shadows, --highlights) and brightness/contrast options
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageBuffer, Luma};
+
+use crate::colorspace::with_color_type;
+use crate::geometry::Size;
+use crate::i18n::tr;
+use crate::print_info;
+
+/// Share of pixels --auto-levels lets clip at each end, so a few specks
+/// of pure black or white don't stop the stretch
+const CLIP: f64 = 0.005;
+
+/// Size the luminance is blurred at for the shadow/highlight masks
+const MASK_SIZE: u32 = 256;
+
+#[derive(Args, Debug)]
+pub struct LevelsArgs {
+    /// Stretch each channel so its darkest and lightest tones reach black and
+    /// white, which also removes color casts from fading or underexposure
+    #[arg(long)]
+    pub auto_levels: bool,
+
+    /// Lift (positive) or deepen (negative) the shadows, -100 to 100
+    #[arg(long, value_name = "AMOUNT", allow_hyphen_values = true, value_parser = parse_slider)]
+    pub shadows: Option<f32>,
+
+    /// Brighten (positive) or recover (negative) the highlights, -100 to 100
+    #[arg(long, value_name = "AMOUNT", allow_hyphen_values = true, value_parser = parse_slider)]
+    pub highlights: Option<f32>,
+
+    /// Add this much to every channel, -255 to 255
+    #[arg(long, value_name = "AMOUNT", allow_hyphen_values = true, value_parser = clap::value_parser!(i32).range(-255..=255))]
+    pub brightness: Option<i32>,
+
+    /// Raise (positive) or lower (negative) the contrast by this percentage
+    #[arg(long, value_name = "PERCENT", allow_hyphen_values = true, value_parser = parse_contrast)]
+    pub contrast: Option<f32>,
+}
+
+impl LevelsArgs {
+    /// Whether any tonal adjustment was asked for
+    pub fn is_active(&self) -> bool {
+        self.auto_levels
+            || self.shadows.is_some()
+            || self.highlights.is_some()
+            || self.brightness.is_some()
+            || self.contrast.is_some()
+    }
+}
+
+fn parse_slider(s: &str) -> Result<f32, String> {
+    let amount: f32 = s.parse().map_err(|_| format!("Expected an amount from -100 to 100, got: {}", s))?;
+    if !(-100.0..=100.0).contains(&amount) {
+        return Err(format!("Amount must be between -100 and 100, got: {}", amount));
+    }
+    Ok(amount)
+}
+
+fn parse_contrast(s: &str) -> Result<f32, String> {
+    let percent: f32 = s.parse().map_err(|_| format!("Expected a contrast change in percent, got: {}", s))?;
+    if !(-100.0..=1000.0).contains(&percent) {
+        return Err(format!("Contrast change must be between -100 and 1000 percent, got: {}", percent));
+    }
+    Ok(percent)
+}
+
+/// Apply --auto-levels, then --shadows and --highlights
+pub fn apply(img: DynamicImage, args: &LevelsArgs) -> Result<DynamicImage> {
+    if !args.auto_levels && args.shadows.is_none() && args.highlights.is_none() {
+        return Ok(img);
+    }
+    let color_type = img.color();
+    let mut canvas = img.to_rgba32f();
+
+    if args.auto_levels {
+        let ranges: [(f32, f32); 3] = std::array::from_fn(|channel| {
+            let mut histogram = [0u64; 256];
+            for pixel in canvas.pixels().filter(|pixel| pixel.0[3] > 0.0) {
+                histogram[(pixel.0[channel].clamp(0.0, 1.0) * 255.0).round() as usize] += 1;
+            }
+            let total: u64 = histogram.iter().sum();
+            let clip = (total as f64 * CLIP) as u64;
+            let percentile = |mut levels: Box<dyn Iterator<Item = usize>>| {
+                let mut seen = 0;
+                levels.find(|&level| {
+                    seen += histogram[level];
+                    seen > clip
+                })
+            };
+            let low = percentile(Box::new(0..256)).unwrap_or(0);
+            let high = percentile(Box::new((0..256).rev())).unwrap_or(255);
+            (low as f32 / 255.0, high as f32 / 255.0)
+        });
+        let summary: Vec<String> = ["R", "G", "B"]
+            .iter()
+            .zip(ranges)
+            .map(|(name, (low, high))| format!("{} {}-{}", name, (low * 255.0).round(), (high * 255.0).round()))
+            .collect();
+        print_info(&tr!("Auto levels: {}", summary.join(", ")));
+        for pixel in canvas.pixels_mut() {
+            for (value, (low, high)) in pixel.0.iter_mut().zip(ranges) {
+                // A flat channel has nothing to stretch
+                if high > low {
+                    *value = ((*value - low) / (high - low)).clamp(0.0, 1.0);
+                }
+            }
+        }
+    }
+
+    let shadows = args.shadows.unwrap_or(0.0) / 100.0;
+    let highlights = args.highlights.unwrap_or(0.0) / 100.0;
+    if shadows != 0.0 || highlights != 0.0 {
+        let (width, height) = canvas.dimensions();
+        let luminance = |rgb: &[f32]| 0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2];
+        let lum: ImageBuffer<Luma<f32>, Vec<f32>> = ImageBuffer::from_fn(width, height, |x, y| Luma([luminance(&canvas.get_pixel(x, y).0)]));
+        // The masks follow the blurred surroundings rather than each pixel,
+        // so the local contrast within shadows and highlights survives
+        let (small_width, small_height) = Size { width: MASK_SIZE, height: MASK_SIZE }.fit(width, height);
+        let small = imageops::resize(&lum, small_width, small_height, FilterType::Triangle);
+        let surround = imageops::resize(&imageops::blur(&small, small_width.max(small_height) as f32 / 50.0), width, height, FilterType::Triangle);
+        for ((pixel, lum), surround) in canvas.pixels_mut().zip(lum.pixels()).zip(surround.pixels()) {
+            let (before, surround) = (lum.0[0].clamp(0.0, 1.0), surround.0[0].clamp(0.0, 1.0));
+            let after = tone(before, surround, shadows, highlights);
+            for value in &mut pixel.0[..3] {
+                // Scaling keeps the hue; black has none, so it is lifted evenly
+                *value = if before > 1e-4 { *value * after / before } else { *value + after - before }.clamp(0.0, 1.0);
+            }
+        }
+    }
+    Ok(with_color_type(DynamicImage::ImageRgba32F(canvas), color_type))
+}
+
+/// Adjusted luminance for `lum` in surroundings of luminance `surround`;
+/// `shadows` and `highlights` are from -1 to 1
+fn tone(lum: f32, surround: f32, shadows: f32, highlights: f32) -> f32 {
+    let in_shadow = (1.0 - surround).powi(2);
+    let in_highlight = surround.powi(2);
+    // Shadows lift by up to 3x and deepen down to black
+    let lum = lum * (1.0 + shadows * in_shadow * if shadows > 0.0 { 2.0 } else { 1.0 });
+    if highlights > 0.0 {
+        1.0 - (1.0 - lum).max(0.0) * (1.0 - highlights * in_highlight)
+    } else {
+        // Recovery pulls highlights down by up to half
+        lum * (1.0 + 0.5 * highlights * in_highlight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_auto_levels_stretches_each_channel() {
+        // A dull image with a blue cast: every channel spans a narrow range
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(16, 16, |x, _| Rgb([40 + x as u8 * 8, 50 + x as u8 * 6, 90 + x as u8 * 5])));
+        let args = LevelsArgs { auto_levels: true, shadows: None, highlights: None, brightness: None, contrast: None };
+        let out = apply(img, &args).unwrap().into_rgb8();
+        assert_eq!(out.get_pixel(0, 0).0, [0, 0, 0]);
+        assert_eq!(out.get_pixel(15, 0).0, [255, 255, 255]);
+        assert!(parse_slider("-40").is_ok() && parse_slider("150").is_err());
+    }
+
+    #[test]
+    fn test_shadows_and_highlights() {
+        assert!(tone(0.1, 0.1, 0.5, 0.0) > 0.15);
+        assert_eq!(tone(0.0, 0.0, 1.0, 0.0), 0.0);
+        // Lifting shadows hardly touches highlights, and the other way round
+        assert!((tone(0.9, 0.9, 1.0, 0.0) - 0.9).abs() < 0.05);
+        assert!(tone(0.9, 0.9, 0.0, -1.0) < 0.6);
+        assert!(tone(0.8, 0.9, 0.0, 1.0) > 0.9);
+
+        let dark = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 8, Rgb([30, 20, 10])));
+        let args = LevelsArgs { auto_levels: false, shadows: Some(80.0), highlights: None, brightness: None, contrast: None };
+        let [r, g, b] = apply(dark, &args).unwrap().into_rgb8().get_pixel(4, 4).0;
+        assert!(r > 45 && r > g && g > b, "{:?}", (r, g, b));
+    }
+}
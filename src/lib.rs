@@ -0,0 +1,85 @@
+// File: src\lib.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Library API: configure and run conversions from Rust code
+// License: MIT
+
+//! Convert images from Rust code with the same decoding, resizing and
+//! encoding the `imgconv` command uses.
+//!
+//! ```no_run
+//! use imgconv::{Converter, Resize};
+//!
+//! Converter::new()
+//!     .quality(80)
+//!     .resize(Resize::Fit(1600, 1600))
+//!     .strip_metadata(true)
+//!     .run("photo.png", "photo.jpg")?;
+//! # Ok::<(), imgconv::ImgconvError>(())
+//! ```
+
+use colored::*;
+
+#[cfg(feature = "async")]
+mod async_io;
+mod converter;
+mod error;
+pub mod pipeline;
+mod probe;
+pub mod transport;
+
+// Internals shared with the command-line tool
+#[doc(hidden)]
+pub mod cancel;
+#[doc(hidden)]
+pub mod clipboard;
+#[doc(hidden)]
+pub mod cmyk;
+#[doc(hidden)]
+pub mod colorspace;
+#[doc(hidden)]
+pub mod encode;
+#[doc(hidden)]
+pub mod encoder;
+#[doc(hidden)]
+pub mod exifthumb;
+#[doc(hidden)]
+pub mod formats;
+#[doc(hidden)]
+pub mod geometry;
+#[doc(hidden)]
+pub mod gpu;
+#[doc(hidden)]
+pub mod i18n;
+#[doc(hidden)]
+pub mod resize;
+
+pub use cancel::CancellationToken;
+pub use converter::{convert_stream, Converter, Resize};
+pub use encoder::{AvifOpts, EncoderOptions, GifOpts, JpegOpts, PngOpts, WebpOpts};
+pub use geometry::Gravity;
+pub use error::{BoxError, ImgconvError, Result};
+pub use pipeline::{Op, Pipeline, Rotation};
+pub use probe::{probe, ExifInfo, ImageInfo, ProbeInput};
+pub use resize::Filter;
+pub use transport::{ImageSink, ImageSource};
+
+#[doc(hidden)]
+pub fn print_info(msg: &str) {
+    eprintln!("{} {}", "[INFO]".blue().bold(), msg);
+}
+
+#[doc(hidden)]
+pub fn print_success(msg: &str) {
+    eprintln!("{} {}", "[✓]".green().bold(), msg);
+}
+
+#[doc(hidden)]
+pub fn print_warning(msg: &str) {
+    eprintln!("{} {}", "[!]".yellow().bold(), msg);
+}
+
+#[doc(hidden)]
+pub fn print_error(msg: &str) {
+    eprintln!("{} {}", "[✗]".red().bold(), msg);
+}
@@ -0,0 +1,147 @@
+// File: src\limits.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Resource limits that guard against decompression bombs
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::{ImageDecoder, ImageReader};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::i18n::tr;
+
+/// Used by subcommands and whenever --max-pixels is not given (100 megapixels)
+pub const DEFAULT_MAX_PIXELS: PixelCount = PixelCount(100_000_000);
+
+#[derive(Args, Debug)]
+pub struct LimitArgs {
+    /// Refuse images larger than this before decoding (e.g. 100MP, 5000000; 0 = no limit)
+    #[arg(long, default_value = "100MP", value_name = "PIXELS")]
+    pub max_pixels: PixelCount,
+
+    /// Memory budget for a conversion (e.g. 2G); bigger images are streamed in strips where possible
+    #[arg(long, value_name = "SIZE")]
+    pub max_memory: Option<ByteSize>,
+
+    /// Always convert in strips without decoding the whole image (PNG, TIFF and PNM only)
+    #[arg(long, conflicts_with_all = ["estimate", "clipboard"])]
+    pub stream: bool,
+}
+
+/// A pixel count, written plainly or with a K/M/G (or KP/MP/GP) suffix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelCount(pub u64);
+
+impl FromStr for PixelCount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let upper = s.trim().to_ascii_uppercase();
+        parse_scaled(upper.strip_suffix('P').unwrap_or(&upper), 1000.0)
+            .map(PixelCount)
+            .ok_or_else(|| format!("Invalid pixel count '{}', expected e.g. 100MP or 5000000", s))
+    }
+}
+
+/// A size in bytes with an optional binary K/M/G/T suffix (2G = 2 GiB)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteSize(pub u64);
+
+impl FromStr for ByteSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let upper = s.trim().to_ascii_uppercase();
+        let number = upper.strip_suffix("IB").or_else(|| upper.strip_suffix('B')).unwrap_or(&upper);
+        parse_scaled(number, 1024.0)
+            .map(ByteSize)
+            .ok_or_else(|| format!("Invalid size '{}', expected e.g. 512M or 2G", s))
+    }
+}
+
+/// "1.5M" -> 1.5 * base², with K, M, G and T as the powers of `base`
+fn parse_scaled(number: &str, base: f64) -> Option<u64> {
+    let power = match number.chars().last()? {
+        'K' => 1,
+        'M' => 2,
+        'G' => 3,
+        'T' => 4,
+        _ => 0,
+    };
+    let digits = if power > 0 { &number[..number.len() - 1] } else { number };
+    let value: f64 = digits.trim().parse().ok()?;
+    (value.is_finite() && value >= 0.0).then(|| (value * base.powi(power)).round() as u64)
+}
+
+/// Rough peak memory of converting `path` fully in memory: the decoded
+/// buffer plus about two working copies. None when the header can't be read.
+pub fn estimate_memory(path: &Path) -> Option<u64> {
+    let decoder = ImageReader::open(path).ok()?.with_guessed_format().ok()?.into_decoder().ok()?;
+    Some(decoder.total_bytes().saturating_mul(3))
+}
+
+/// Check the dimensions `path` declares in its header against `max`
+///
+/// Only the header is read, so a tiny file claiming 100000x100000 pixels is
+/// rejected before any pixel buffer is allocated. Files whose header can't be
+/// read are left for the decoder to report.
+pub fn check_pixels(path: &Path, max: PixelCount) -> Result<()> {
+    if max.0 == 0 {
+        return Ok(());
+    }
+    let dimensions = ImageReader::open(path)
+        .ok()
+        .and_then(|reader| reader.with_guessed_format().ok())
+        .and_then(|reader| reader.into_dimensions().ok());
+    match dimensions {
+        Some(dimensions) => check_dimensions(path, dimensions, max),
+        None => Ok(()),
+    }
+}
+
+/// `check_pixels` for dimensions already read, e.g. from an image in memory
+pub fn check_dimensions(path: &Path, (width, height): (u32, u32), max: PixelCount) -> Result<()> {
+    let pixels = width as u64 * height as u64;
+    if max.0 > 0 && pixels > max.0 {
+        anyhow::bail!(tr!(
+            "{} is {}x{} ({} pixels), over the --max-pixels limit of {}",
+            path.display(),
+            width,
+            height,
+            pixels,
+            max.0
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pixel_count() {
+        assert_eq!("100MP".parse(), Ok(PixelCount(100_000_000)));
+        assert_eq!("1.5m".parse(), Ok(PixelCount(1_500_000)));
+        assert_eq!("640K".parse(), Ok(PixelCount(640_000)));
+        assert_eq!("5000000".parse(), Ok(PixelCount(5_000_000)));
+        assert!("lots".parse::<PixelCount>().is_err());
+
+        assert_eq!("2G".parse(), Ok(ByteSize(2 << 30)));
+        assert_eq!("512MiB".parse(), Ok(ByteSize(512 << 20)));
+        assert_eq!("1.5kb".parse(), Ok(ByteSize(1536)));
+        assert_eq!("4096".parse(), Ok(ByteSize(4096)));
+    }
+
+    #[test]
+    fn test_rejects_declared_size() {
+        let path = std::env::temp_dir().join(format!("imgconv-bomb-{}.png", std::process::id()));
+        image::RgbImage::new(100, 100).save(&path).unwrap();
+        assert!(check_pixels(&path, PixelCount(10_000)).is_ok());
+        assert!(check_pixels(&path, PixelCount(9_999)).is_err());
+        assert!(check_pixels(&path, PixelCount(0)).is_ok());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -4,12 +4,95 @@
 // Description: 
 // License: MIT
 
-use clap::{Parser, ValueEnum, ArgAction};
+use clap::{Parser, Subcommand, ValueEnum, ArgAction, CommandFactory, FromArgMatches};
 use clap_version_flag::colorful_version;
 use image::{ImageFormat, ImageReader, GenericImageView, DynamicImage};
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use colored::*;
+use std::sync::{Mutex, Once};
+
+mod alpha;
+mod animation;
+mod annotate;
+mod appicons;
+mod archive;
+mod autoquality;
+mod avatar;
+mod backend;
+mod batch;
+mod bundle;
+mod cache;
+mod chromakey;
+mod color;
+mod confirm;
+mod convolve;
+mod crop;
+mod curves;
+mod dedup;
+mod depth;
+mod desktop;
+mod dispose;
+mod draw;
+mod effects;
+mod estimate;
+mod filter;
+mod explorer;
+mod frame;
+mod inplace;
+mod jobs;
+mod manifest;
+mod limits;
+mod mask;
+mod names;
+mod montage;
+mod noise;
+mod optimize;
+mod history;
+mod hooks;
+mod join;
+mod levels;
+mod palette;
+mod perms;
+mod pick;
+mod post;
+mod priority;
+mod progress;
+mod quantize;
+mod redact;
+mod resume;
+mod s3;
+mod sanity;
+mod scan;
+mod sftp;
+mod sharpen;
+mod sink;
+mod social;
+mod sprites;
+mod stream;
+mod stylize;
+mod summary;
+mod superres;
+mod target;
+mod template;
+mod term;
+mod thumb;
+mod throttle;
+mod tile;
+mod timings;
+mod tonemap;
+#[cfg(feature = "tui")]
+mod tui;
+mod upscale;
+mod verify;
+mod webset;
+mod whitebalance;
+
+use i18n::tr;
+use imgconv::{cancel, clipboard, cmyk, colorspace, encode, encoder, exifthumb, formats, geometry, i18n, resize, transport};
+use imgconv::{print_error, print_info, print_success, print_warning};
+use imgconv::{Op, Pipeline, Resize};
+use encode::{save_bilevel, save_image, save_indexed, supports_quality, write_bytes};
 
 const ABOUT: &str = "
 imgconv - Professional Image Format Converter
@@ -40,6 +123,84 @@ EXAMPLES:
     imgconv -c output_image -e jpg
     imgconv -c output_image.png -e jpg
     
+    # Preview output size for several qualities without writing
+    imgconv input.png output.avif --estimate=40,60,80
+    
+    # Check files for truncation/corruption
+    imgconv verify photos/*.jpg --repair
+    
+    # Inspect pixel colors
+    imgconv pick input.png --at 120,45
+    imgconv pick input.png --region 0,0,10,10 --stats
+    
+    # Caption the image
+    imgconv input.jpg output.jpg --text \"(c) 2025 Example\" --font DejaVuSans.ttf --gravity south
+    
+    # Add a border or a two-band gallery frame
+    imgconv photo.jpg framed.jpg --border 10 --border-color \"#000\"
+    imgconv photo.jpg framed.jpg --frame 12,30 --frame-color \"#f5f5f0\"
+    
+    # Rounded corners or a circular avatar
+    imgconv photo.jpg rounded.png --round-corners 24
+    imgconv face.jpg avatar.png --circle
+    
+    # Flatten transparency onto a color when the target has no alpha
+    imgconv logo.png logo.jpg --background \"#ffffff\"
+    
+    # Drop shadow and vignette
+    imgconv product.png polished.png --shadow \"8x8+0+4#00000080\" --vignette 0.4
+    
+    # Extract or replace the alpha channel
+    imgconv sprite.png sprite.png --extract-alpha sprite_mask.png
+    imgconv sprite.jpg sprite.png --apply-alpha sprite_mask.png
+    
+    # Contact sheet of a photo folder
+    imgconv montage photos/*.jpg sheet.png --columns 5 --cell 256x256 --label filename --font DejaVuSans.ttf
+    
+    # Split into tiles for map/deep-zoom pipelines
+    imgconv tile map.png --grid 4x4
+    imgconv tile map.png --tile-size 512x512 -o \"tiles/{row}/{col}.{ext}\" -f webp
+    
+    # Pack icons into a sprite sheet with a JSON atlas
+    imgconv sprites icons/*.png sheet.png --layout packed --json sheet.json
+    
+    # Stitch screenshots into a comparison strip
+    imgconv join a.png b.png c.png strip.png --direction horizontal --align center --gap 10
+    
+    # Content-aware square crop for thumbnails
+    imgconv photo.jpg thumb.jpg --smart-crop 1:1
+    
+    # Black out an email address and pixelate a face in a screenshot
+    imgconv shot.png shared.png --redact 40,10,300,24 --redact 500,80,120,120,pixelate
+    
+    # Reduce to a 64-color palette before writing a GIF
+    imgconv photo.png photo.gif --quantize 64 --quant-algo wu
+    
+    # Dithered black-and-white PNG, or 16-bit TIFF
+    imgconv scan.png scan_bw.png --bit-depth 1
+    imgconv render.png render.tiff --bit-depth 16
+    
+    # Tone-map an HDR render to a viewable JPEG
+    imgconv render.hdr render.jpg --tonemap aces --exposure 0.5
+    
+    # Downscale in linear light to avoid dark halos
+    imgconv photo.jpg small.webp --resize 800x800 --linear-resize
+    
+    # Palette PNG (PNG8) for UI assets
+    imgconv icon.png icon8.png --png-palette --quantize 64
+    
+    # Export the 8 dominant colors as a GIMP palette
+    imgconv palette logo.png --colors 8 --export logo.gpl
+    
+    # Clean up noise before heavy compression
+    imgconv night.jpg night.avif --denoise 0.5 -q 45
+    
+    # Recompress JPEGs in place, keeping the originals as .bak
+    imgconv photo.jpg --in-place -q 80 --backup
+    
+    # Convert a folder tree, only re-encoding changed files
+    imgconv assets/ dist/ -f webp -r --newer-only
+    
     # Batch conversion pattern
     for f in *.webp; do imgconv \"$f\" \"${f%.webp}.png\"; done
 ";
@@ -84,15 +245,56 @@ impl Format {
     }
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Fully decode files and report truncated or corrupt images
+    Verify(verify::VerifyArgs),
+    /// Print pixel colors at a point or statistics over a region
+    Pick(pick::PickArgs),
+    /// Build a contact sheet of thumbnails
+    Montage(montage::MontageArgs),
+    /// Split an image into tiles
+    Tile(tile::TileArgs),
+    /// Pack images into a sprite sheet with a JSON atlas
+    Sprites(sprites::SpritesArgs),
+    /// Stitch images side by side or stacked
+    Join(join::JoinArgs),
+    /// Extract dominant colors and export them as GPL, ACO or JSON swatches
+    Palette(palette::PaletteArgs),
+    /// Generate iOS and Android app icon sets from one image
+    Appicons(appicons::AppIconsArgs),
+    /// Save a photo's EXIF thumbnail, or rebuild and re-embed it
+    Thumb(thumb::ThumbArgs),
+    /// Add "Convert with imgconv" to the Windows Explorer context menu
+    InstallShellIntegration(explorer::InstallArgs),
+    /// Remove the Windows Explorer context-menu entries
+    UninstallShellIntegration,
+    /// Add imgconv to the Linux application menu and Nautilus/Dolphin right-click menus
+    InstallDesktop(desktop::InstallArgs),
+    /// Remove the Linux desktop entry and file-manager actions
+    UninstallDesktop,
+    /// List supported formats and which options apply to each
+    Formats,
+    /// Repeat the last successful conversion, optionally on a new input
+    Again(history::AgainArgs),
+    /// Browse, preview and batch-convert images in an interactive terminal UI
+    #[cfg(feature = "tui")]
+    Tui(tui::TuiArgs),
+}
+
 #[derive(Parser, Debug)]
 #[command(
     name = "imgconv",
     author = "Hadi Cahyadi <cumulus13@gmail.com>",
     about = ABOUT,
     long_about = None,
-    disable_version_flag = true
+    disable_version_flag = true,
+    args_conflicts_with_subcommands = true
 )]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Input image file
     #[arg(short, long, value_name = "FILE", conflicts_with = "clipboard")]
     input: Option<PathBuf>,
@@ -117,6 +319,183 @@ struct Args {
     #[arg(short, long, default_value = "90", value_name = "NUM")]
     quality: u8,
 
+    /// Whether -q was given rather than defaulted
+    #[arg(skip)]
+    quality_given: bool,
+
+    /// Fail instead of warning on options or extensions that disagree with the output format, and on damaged inputs
+    #[arg(long)]
+    strict: bool,
+
+    /// Print the transform steps the options add, in the order they run
+    #[arg(long)]
+    show_pipeline: bool,
+
+    /// Print projected output size per quality instead of writing (e.g. --estimate=60,80)
+    #[arg(
+        long,
+        value_name = "QUALITIES",
+        num_args = 0..=1,
+        require_equals = true,
+        value_delimiter = ',',
+        default_missing_values = estimate::DEFAULT_CANDIDATES
+    )]
+    estimate: Option<Vec<u8>>,
+
+    #[command(flatten)]
+    target: target::TargetArgs,
+
+    #[command(flatten)]
+    auto_quality: autoquality::AutoQualityArgs,
+
+    #[command(flatten)]
+    optimize: optimize::OptimizeArgs,
+
+    #[command(flatten)]
+    batch: batch::BatchArgs,
+
+    #[command(flatten)]
+    sink: sink::SinkArgs,
+
+    #[command(flatten)]
+    jobs: jobs::JobsArgs,
+
+    #[command(flatten)]
+    filter: filter::FilterArgs,
+
+    #[command(flatten)]
+    dedup: dedup::DedupArgs,
+
+    #[command(flatten)]
+    limits: limits::LimitArgs,
+
+    #[command(flatten)]
+    cache: cache::CacheArgs,
+
+    #[command(flatten)]
+    in_place: inplace::InPlaceArgs,
+
+    #[command(flatten)]
+    dispose: dispose::DisposeArgs,
+
+    #[command(flatten)]
+    confirm: confirm::ConfirmArgs,
+
+    #[command(flatten)]
+    progress: progress::ProgressArgs,
+
+    #[command(flatten)]
+    manifest: manifest::ManifestArgs,
+
+    #[command(flatten)]
+    summary: summary::SummaryArgs,
+
+    #[command(flatten)]
+    hooks: hooks::HookArgs,
+
+    #[command(flatten)]
+    perms: perms::PermsArgs,
+
+    #[command(flatten)]
+    term: term::TermArgs,
+
+    #[command(flatten)]
+    lang: i18n::LangArgs,
+
+    #[command(flatten)]
+    priority: priority::PriorityArgs,
+
+    #[command(flatten)]
+    backend: backend::BackendArgs,
+
+    #[command(flatten)]
+    encoder: encoder::EncoderArgs,
+
+    #[command(flatten)]
+    timings: timings::TimingsArgs,
+
+    #[command(flatten)]
+    resume: resume::ResumeArgs,
+
+    #[command(flatten)]
+    embedded: exifthumb::EmbeddedArgs,
+
+    #[command(flatten)]
+    animation: animation::AnimationArgs,
+
+    #[command(flatten)]
+    web_set: webset::WebSetArgs,
+
+    #[command(flatten)]
+    tone: tonemap::ToneArgs,
+
+    #[command(flatten)]
+    redact: redact::RedactArgs,
+
+    #[command(flatten)]
+    crop: crop::CropArgs,
+
+    #[command(flatten)]
+    resize: resize::ResizeArgs,
+
+    #[command(flatten)]
+    social: social::SocialArgs,
+
+    #[command(flatten)]
+    avatar: avatar::AvatarArgs,
+
+    #[command(flatten)]
+    text: annotate::TextArgs,
+
+    #[command(flatten)]
+    alpha: alpha::AlphaArgs,
+
+    #[command(flatten)]
+    chroma_key: chromakey::ChromaKeyArgs,
+
+    #[command(flatten)]
+    mask: mask::MaskArgs,
+
+    #[command(flatten)]
+    noise: noise::NoiseArgs,
+
+    #[command(flatten)]
+    upscale: upscale::UpscaleArgs,
+
+    #[command(flatten)]
+    scan: scan::ScanArgs,
+
+    #[command(flatten)]
+    white_balance: whitebalance::WhiteBalanceArgs,
+
+    #[command(flatten)]
+    levels: levels::LevelsArgs,
+
+    #[command(flatten)]
+    curves: curves::CurvesArgs,
+
+    #[command(flatten)]
+    sharpen: sharpen::SharpenArgs,
+
+    #[command(flatten)]
+    stylize: stylize::StylizeArgs,
+
+    #[command(flatten)]
+    effects: effects::EffectsArgs,
+
+    #[command(flatten)]
+    frame: frame::FrameArgs,
+
+    #[command(flatten)]
+    quantize: quantize::QuantizeArgs,
+
+    #[command(flatten)]
+    depth: depth::DepthArgs,
+
+    /// Background used when transparency must be flattened
+    #[arg(long, default_value = "#ffffff", value_name = "COLOR")]
+    background: color::Color,
+
     /// Positional input file (alternative to -i)
     #[arg(value_name = "INPUT", conflicts_with = "clipboard")]
     pos_input: Option<PathBuf>,
@@ -131,131 +510,1160 @@ struct Args {
 
 fn main() -> Result<()> {
     let os_args: Vec<String> = std::env::args().collect();
+    term::apply(term::ColorWhen::Auto);
+    cancel_on_ctrl_c();
+    s3::register();
     if os_args.len() == 2 && (os_args[1] == "-V" || os_args[1] == "--version") {
         let version = colorful_version!();
         version.print_and_exit();
     }
 
-    let args = Args::parse();
+    let mut args = parse_args(std::env::args_os());
+    i18n::init(&args.lang);
+    let mut arguments: Vec<String> = os_args[1..].to_vec();
+
+    // A bare `--resume FILE` continues the saved batch with its original arguments
+    if let Some(state) = args.resume.resume.as_ref().filter(|_| {
+        args.input.is_none() && args.pos_input.is_none() && args.batch.files_from.is_none() && args.command.is_none()
+    }) {
+        arguments = resume::State::saved_arguments(state)?;
+        print_info(&tr!("Resuming batch: imgconv {}", arguments.join(" ")));
+        args = parse_args(std::iter::once("imgconv").chain(arguments.iter().map(String::as_str)));
+    }
+
+    // `again` swaps in the arguments of the last recorded conversion
+    if let Some(Command::Again(again_args)) = &args.command {
+        arguments = history::again(again_args)?;
+        if again_args.print {
+            println!("{}", history::command_line(&arguments));
+            return Ok(());
+        }
+        print_info(&tr!("Repeating: {}", history::command_line(&arguments)));
+        args = parse_args(std::iter::once("imgconv").chain(arguments.iter().map(String::as_str)));
+    }
 
+    term::apply(args.term.color);
     if args.version {
         let version = colorful_version!(); 
         version.print_and_exit();
     }
 
+    priority::apply(&args.priority);
+    progress::init(&args.progress)?;
+    backend::check(&args.backend)?;
+    args.encoder.check()?;
+
+    if let Some(command) = &args.command {
+        return match command {
+            Command::Verify(verify_args) => verify::run(verify_args),
+            Command::Pick(pick_args) => pick::run(pick_args),
+            Command::Montage(montage_args) => montage::run(montage_args),
+            Command::Tile(tile_args) => tile::run(tile_args),
+            Command::Sprites(sprites_args) => sprites::run(sprites_args),
+            Command::Join(join_args) => join::run(join_args),
+            Command::Palette(palette_args) => palette::run(palette_args),
+            Command::Appicons(appicons_args) => appicons::run(appicons_args),
+            Command::Thumb(thumb_args) => thumb::run(thumb_args),
+            #[cfg(feature = "tui")]
+            Command::Tui(tui_args) => tui::run(tui_args),
+            Command::InstallShellIntegration(install_args) => explorer::install(install_args),
+            Command::UninstallShellIntegration => explorer::uninstall(),
+            Command::InstallDesktop(install_args) => desktop::install(install_args),
+            Command::UninstallDesktop => desktop::uninstall(),
+            Command::Formats => formats::run(),
+            // Recorded conversions are never subcommands, so this is only reached by `again` itself
+            Command::Again(_) => Ok(()),
+        };
+    }
+
     // Validate quality
     if args.quality == 0 || args.quality > 100 {
-        anyhow::bail!("Quality must be between 1 and 100, got: {}", args.quality);
+        anyhow::bail!(tr!("Quality must be between 1 and 100, got: {}", args.quality));
+    }
+    encoder::set_process(args.encoder.encoders(args.quality));
+
+    if let Some(list) = &args.batch.files_from {
+        let files = batch::read_list(list, args.batch.null)?;
+        let source = if list.as_os_str() == "-" { "stdin".into() } else { list.display().to_string() };
+        print_info(&tr!("Batch converting images listed in: {}", source));
+        return run_batch(&args, Path::new(""), files, &arguments, None);
+    }
+
+    if let Some(path) = &args.jobs.jobs_file {
+        print_info(&tr!("Running jobs from: {}", path.display()));
+        return run_jobs(&args, path, &arguments);
+    }
+
+    if args.clipboard {
+        print_info(&tr!("Reading image from clipboard..."));
+        let mut timer = timings::Timer::start();
+        let (img, detected_input_format) = get_image_from_clipboard()?;
+        timer.lap(timings::Stage::Decode);
+        print_loaded(&img, detected_input_format);
+
+        // Determine output format with smart logic for clipboard mode
+        let (output_path, output_format) = determine_output_from_clipboard(
+            &output_arg(&args)?, 
+            args.format.clone(), 
+            args.extension.as_deref(), 
+            detected_input_format,
+            args.strict
+        )?;
+        check_options(&args, output_format)?;
+        progress::start(1);
+        progress::file_started(None, &output_path);
+        let dimensions = convert(&args, img, None, &output_path, output_format, &mut timer)
+            .inspect_err(|e| progress::failed(None, e))?;
+        progress::file_finished(&output_path);
+        progress::done(1, 0, 0);
+        if args.timings.timings {
+            timer.print(Path::new("clipboard"));
+        }
+        if args.estimate.is_none() {
+            let mut summary = summary::Summary::default();
+            summary.record(summary::Before::read(None), &output_path, Some(dimensions));
+            hooks::on_complete(&args.hooks, None, &output_path)?;
+            if let Some(csv) = &args.summary.csv {
+                summary.write_csv(csv)?;
+            }
+            hooks::notify(&args.hooks, &summary.to_json(None));
+        }
+        if let Some(mut manifest) = manifest::Manifest::new(&args.manifest) {
+            manifest.record(None, &output_path, Some(dimensions), output_format, args.quality)?;
+            manifest.write()?;
+        }
+        record_history(&args, &arguments);
+        return Ok(());
+    }
+
+    let input = args.input.clone()
+        .or_else(|| args.pos_input.clone())
+        .with_context(|| tr!("Input file is required. Usage: imgconv <input> <output> OR imgconv -c <output>"))?;
+
+    // stdin, URLs and registered schemes are read through their transport
+    let fetched = match input.to_str().filter(|spec| transport::is_source(spec)) {
+        Some(spec) => Some(fetch_input(&args, spec)?),
+        None => None,
+    };
+
+    // Validate input exists
+    if fetched.is_none() && !input.exists() {
+        anyhow::bail!(tr!("Input file not found: {}", input.display()));
+    }
+
+    // Directory input converts every image in it (in place or mirrored into the output directory)
+    if fetched.is_none() && input.is_dir() {
+        print_info(&tr!("Batch converting images from: {}", input.display()));
+        return run_batch(&args, &input, batch::collect(&input, args.batch.recursive)?, &arguments, None);
     }
 
-    // Determine input source: clipboard or file
-    let (img, detected_input_format) = if args.clipboard {
-        // Get from clipboard
-        print_info("Reading image from clipboard...");
-        get_image_from_clipboard()?
+    // Archives are read entry by entry and mirrored into the output directory
+    if fetched.is_none() && archive::is_archive(&input) {
+        print_info(&tr!("Batch converting images from archive: {}", input.display()));
+        return run_archive(&args, &input, &arguments);
+    }
+
+    if args.batch.organize_by.is_some() {
+        anyhow::bail!(tr!("--organize-by sorts batch outputs and needs a directory as input"));
+    }
+    if args.filter.is_enabled() {
+        anyhow::bail!(tr!("--min-width, --max-size, --only-format and the other filters select batch inputs and need a directory as input"));
+    }
+    if args.batch.sort.is_some() || args.batch.rename.is_some() {
+        anyhow::bail!(tr!("--sort and --rename order and name batch outputs and need a directory as input"));
+    }
+    if let Some(flag) = args.sink.flag() {
+        anyhow::bail!(tr!("{} collects batch outputs and needs a directory, an archive or --files-from as input", flag));
+    }
+    if args.resume.resume.is_some() {
+        anyhow::bail!(tr!("--resume tracks batch progress and needs a directory or --files-from as input"));
+    }
+    if args.web_set.web_set.is_some() {
+        convert_web_set(&args, &input)?;
+        record_history(&args, &arguments);
+        return Ok(());
+    }
+
+    // stdout and registered schemes get the output from a staged file
+    let mut delivery = match output_arg(&args).ok().as_ref().and_then(|output| output.to_str()) {
+        Some(spec) if !args.in_place.in_place && transport::is_sink(spec) => Some(transport::open_sink(spec)?),
+        _ => None,
+    };
+    let staging = std::env::temp_dir().join(format!("imgconv-output-{}", std::process::id()));
+
+    // Determine output path and format (in place: the input itself)
+    let (output_path, output_format) = if args.in_place.in_place {
+        inplace::target(&input, &args.in_place)?
     } else {
-        // Get from file
-        let input = args.input
-            .or(args.pos_input)
-            .context("Input file is required. Usage: imgconv <input> <output> OR imgconv -c <output>")?;
-
-        // Validate input exists
-        if !input.exists() {
-            anyhow::bail!("Input file not found: {}", input.display());
+        let output = match &delivery {
+            Some(sink) => {
+                std::fs::create_dir_all(&staging)
+                    .with_context(|| tr!("Failed to create staging directory: {}", staging.display()))?;
+                staging.join(Path::new(&sink.name()).file_name().unwrap_or("stdout".as_ref()))
+            }
+            None => output_arg(&args)?,
+        };
+        let format = args.format.clone().or_else(|| avatar::default_format(&args.avatar, &output));
+        let (output_path, output_format) = determine_output(&output, format, args.strict)?;
+        match output_path.file_name() {
+            Some(name) if args.batch.sanitize_names => {
+                (output_path.with_file_name(names::sanitize(&name.to_string_lossy())), output_format)
+            }
+            _ => (output_path, output_format),
         }
+    };
+    if !args.in_place.in_place && fetched.is_none() {
+        inplace::check_distinct(&input, &output_path)?;
+    }
+    check_options(&args, output_format)?;
 
-        // Read image input
-        print_info(&format!("Reading image from: {}", input.display()));
-        let reader = ImageReader::open(&input)
-            .with_context(|| format!("Failed to open input file: {}", input.display()))?
-            .with_guessed_format()
-            .with_context(|| format!("Failed to detect image format from: {}", input.display()))?;
-        
-        let detected_format = reader.format();
-        let img = reader.decode()
-            .context("Failed to decode image")?;
-        
-        (img, detected_format)
+    if args.batch.newer_only && batch::is_up_to_date(&input, &output_path) {
+        print_success(&tr!("Up to date, skipping: {}", output_path.display()));
+        return Ok(());
+    }
+    if args.estimate.is_none() {
+        let mut plan = confirm::Plan::new(&args.in_place, &args.dispose);
+        plan.add(&output_path);
+        plan.confirm(&args.confirm)?;
+    }
+    let before = match &fetched {
+        Some(data) => summary::Before::from_data(&input, data),
+        None => summary::Before::read(Some(&input)),
+    };
+    let mut timer = timings::Timer::start();
+    progress::start(1);
+    progress::file_started(Some(&input), &output_path);
+    let dimensions = match &fetched {
+        Some(data) => convert_data(&args, data, &input, &output_path, output_format, &mut timer).map(Some),
+        None => convert_file(&args, &input, &output_path, output_format, &mut timer),
+    }
+    .inspect_err(|e| progress::failed(Some(&input), e))?;
+    progress::file_finished(&output_path);
+    progress::done(1, 0, 0);
+    if args.timings.timings {
+        timer.print(&input);
+    }
+    if args.estimate.is_none() {
+        let mut summary = summary::Summary::default();
+        summary.record(before, &output_path, dimensions);
+        hooks::on_complete(&args.hooks, Some(&input), &output_path)?;
+        if let Some(csv) = &args.summary.csv {
+            summary.write_csv(csv)?;
+        }
+        hooks::notify(&args.hooks, &summary.to_json(None));
+    }
+    if let Some(mut manifest) = manifest::Manifest::new(&args.manifest) {
+        manifest.record(Some(&input), &output_path, dimensions, output_format, args.quality)?;
+        manifest.write()?;
+    }
+    if let Some(sink) = &mut delivery {
+        let delivered = std::fs::read(&output_path)
+            .with_context(|| tr!("Failed to read {}", output_path.display()))
+            .and_then(|data| Ok(sink.write(&data, output_format)?));
+        std::fs::remove_dir_all(&staging).ok();
+        delivered?;
+    }
+    if fetched.is_none() {
+        let name = input.file_name().map(Path::new).unwrap_or(&input);
+        dispose::apply(&input, name, &output_path, output_format, &args.dispose)?;
+    }
+    record_history(&args, &arguments);
+    Ok(())
+}
+
+/// Read a single input given as `-` (stdin), a URL or a registered scheme,
+/// rejecting options that need the source file on disk
+fn fetch_input(args: &Args, spec: &str) -> Result<Vec<u8>> {
+    let needs_source_file = [
+        (args.in_place.in_place, "--in-place"),
+        (args.dispose.is_enabled(), "--delete-source and --move-source-to"),
+        (args.batch.newer_only, "--newer-only"),
+        (args.cache.cache_dir.is_some(), "--cache-dir"),
+        (args.limits.stream || args.limits.max_memory.is_some(), "--stream and --max-memory"),
+        (args.embedded.embedded_thumbnail, "--embedded-thumbnail"),
+        (args.web_set.web_set.is_some(), "--web-set"),
+    ];
+    if let Some((_, option)) = needs_source_file.iter().find(|(given, _)| *given) {
+        anyhow::bail!(tr!("{} needs a source file on disk and does not work with {} as input", option, spec));
+    }
+    let mut source = transport::open_source(spec)?;
+    print_info(&tr!("Reading image from: {}", source.name()));
+    Ok(source.read()?)
+}
+
+/// Convert every image in the archive `path` into the output directory,
+/// keeping the directory structure inside the archive
+fn run_archive(args: &Args, path: &Path, arguments: &[String]) -> Result<()> {
+    let needs_source_files = [
+        (args.in_place.in_place, "--in-place"),
+        (args.dispose.is_enabled(), "--delete-source and --move-source-to"),
+        (args.resume.resume.is_some(), "--resume"),
+        (args.batch.newer_only, "--newer-only"),
+        (args.cache.cache_dir.is_some(), "--cache-dir"),
+        (args.limits.stream || args.limits.max_memory.is_some(), "--stream and --max-memory"),
+        (args.embedded.embedded_thumbnail, "--embedded-thumbnail"),
+        (
+            matches!(args.batch.organize_by, Some(batch::OrganizeBy::Date | batch::OrganizeBy::Dimensions)),
+            "--organize-by date/dimensions",
+        ),
+        (
+            matches!(args.batch.sort, Some(batch::SortBy::Mtime | batch::SortBy::ExifDate)),
+            "--sort mtime/exif-date",
+        ),
+        (args.filter.needs_files(), "Size, dimension and date filters"),
+    ];
+    if let Some((_, option)) = needs_source_files.iter().find(|(given, _)| *given) {
+        anyhow::bail!(tr!("{} needs source files on disk and does not work with archive input", option));
+    }
+    let files = archive::list(path)?.iter().map(|name| path.join(name)).collect();
+    let mut archive = archive::Archive::open(path)?;
+    run_batch(args, path, files, arguments, Some(archive.entries()?))
+}
+
+/// Convert `files` (found under `root`) in place or mirrored into the output directory
+///
+/// `arguments` is the command line the batch runs with, for --resume state.
+/// With `entries`, the files are entries of the archive `root` rather than
+/// files on disk.
+fn run_batch(
+    args: &Args,
+    root: &Path,
+    files: Vec<PathBuf>,
+    arguments: &[String],
+    mut entries: Option<archive::Entries>,
+) -> Result<()> {
+    if args.estimate.is_some() {
+        anyhow::bail!(tr!("--estimate works on a single file, not a batch"));
+    }
+    if args.web_set.web_set.is_some() {
+        anyhow::bail!(tr!("--web-set works on a single file, not a batch"));
+    }
+    if let Some(template) = &args.batch.rename {
+        batch::file_name(template, Path::new("check.png"), 1, &args.batch)?;
+    }
+    let files = filter::apply(files, &args.filter);
+    // Number the files in the chosen order; TAR entries can only be read in
+    // archive order, so there --sort sets the numbers but not the order
+    let mut sorted = files.clone();
+    if let Some(key) = args.batch.sort {
+        batch::sort(&mut sorted, key);
+    }
+    let seq: HashMap<PathBuf, usize> =
+        sorted.iter().enumerate().map(|(i, source)| (source.clone(), args.batch.seq_start + i)).collect();
+    let files = if entries.is_some() { files } else { sorted };
+    let mut state = args.resume.resume.as_deref().map(|path| resume::State::open(path, arguments)).transpose()?;
+    let files = match &state {
+        Some(state) => files.into_iter().filter(|source| !state.is_done(source)).collect(),
+        None => files,
+    };
+    if files.is_empty() {
+        if let Some(state) = state {
+            return state.finish();
+        }
+    }
+    let mut sink = sink::open(&args.sink)?;
+    let mut seen = dedup::Seen::new(&args.dedup);
+    if let (Some(flag), true) = (args.sink.flag(), seen.as_ref().is_some_and(dedup::Seen::keeps_outputs)) {
+        anyhow::bail!(tr!("{} removes each output once delivered, so only --dedup skip works with it", flag));
+    }
+    let out_dir = match &sink {
+        Some(sink) => Some(sink.staging().to_path_buf()),
+        None if args.in_place.in_place => None,
+        None => Some(output_arg(args)?),
     };
+    let format = args.format.as_ref().map(Format::to_image_format);
+    let mut manifest = manifest::Manifest::new(&args.manifest);
+    let mut report = timings::Report::default();
+    let mut summary = summary::Summary::default();
+    let result = batch::run(
+        &files,
+        &args.batch,
+        |source| match &out_dir {
+            Some(out_dir) => batch::mirror(root, source, out_dir, format, seq[source], &args.batch)
+                .and_then(|(output, format)| inplace::check_distinct(source, &output).map(|_| (output, format))),
+            None => inplace::target(source, &args.in_place),
+        }
+        .and_then(|(output, format)| check_options(args, format).map(|_| (output, format))),
+        |outputs| {
+            let mut plan = confirm::Plan::new(&args.in_place, &args.dispose);
+            outputs.iter().for_each(|output| plan.add(output));
+            plan.confirm(&args.confirm)
+        },
+        |source, output_path, output_format| {
+            let mut timer = timings::Timer::start();
+            let data = match &mut entries {
+                Some(entries) => Some(entries.read(&batch::relative(root, source))?),
+                None => None,
+            };
+            let reused = match &mut seen {
+                Some(seen) => seen.reuse(source, data.as_deref(), output_format, output_path)?,
+                None => dedup::Reused::No,
+            };
+            let before = match &data {
+                Some(data) => summary::Before::from_data(source, data),
+                None => summary::Before::read(Some(source)),
+            };
+            let dimensions = match (reused, &data) {
+                (dedup::Reused::Skipped, _) => {
+                    return state.as_mut().map_or(Ok(()), |state| state.mark_done(source));
+                }
+                (dedup::Reused::Output, _) => image::image_dimensions(output_path).ok(),
+                (dedup::Reused::No, Some(data)) => Some(convert_data(args, data, source, output_path, output_format, &mut timer)?),
+                (dedup::Reused::No, None) => convert_file(args, source, output_path, output_format, &mut timer)?,
+            };
+            if let Some(seen) = &mut seen {
+                seen.record(output_path);
+            }
+            if args.timings.timings {
+                timer.print(source);
+                report.add(&timer);
+            }
+            summary.record(before, output_path, dimensions);
+            hooks::on_complete(&args.hooks, Some(source), output_path)?;
+            if let Some(manifest) = &mut manifest {
+                manifest.record(Some(source), output_path, dimensions, output_format, args.quality)?;
+            }
+            let relative = batch::relative(root, source);
+            dispose::apply(source, &relative, output_path, output_format, &args.dispose)?;
+            if let Some(sink) = &mut sink {
+                sink.put(output_path, output_format)?;
+            }
+            match &mut state {
+                Some(state) => state.mark_done(source),
+                None => Ok(()),
+            }
+        },
+    );
+
+    // Files that did convert are recorded even when others failed
+    if let Some(sink) = &mut sink {
+        sink.finish()?;
+    }
+    if let Some(seen) = &seen {
+        seen.report();
+    }
+    summary.print_table();
+    if let Some(csv) = &args.summary.csv {
+        summary.write_csv(csv)?;
+    }
+    if args.timings.timings {
+        report.print();
+    }
+    if let Some(manifest) = &manifest {
+        manifest.write()?;
+    }
+    if let Some(state) = state {
+        match &result {
+            Ok(()) => state.finish()?,
+            Err(_) => state.suspend(),
+        }
+    }
+    hooks::notify(&args.hooks, &summary.to_json(result.as_ref().err()));
+    if result.is_ok() {
+        record_history(args, arguments);
+    }
+    result
+}
 
+/// The first Ctrl-C stops the conversion at the next file or write, removing
+/// the partial output; a second one quits at once
+fn cancel_on_ctrl_c() {
+    // The handler holds its own clone, so encoders see the token can fire
+    let token = cancel::process().clone();
+    let _ = ctrlc::set_handler(move || {
+        if token.is_cancelled() {
+            std::process::exit(130);
+        }
+        print_warning(&tr!("Cancelling, press Ctrl-C again to quit immediately"));
+        token.cancel();
+    });
+}
+
+/// Convert each job in the job file `path` with the command line's options
+/// plus its own, several jobs at a time
+fn run_jobs(args: &Args, path: &Path, arguments: &[String]) -> Result<()> {
+    let base = jobs::base_arguments(arguments);
+    let mut parsed = Vec::new();
+    for (i, job) in jobs::load(path)?.into_iter().enumerate() {
+        let job_arguments = base
+            .iter()
+            .map(String::as_str)
+            .chain(["--input", &job.input.to_string_lossy(), "--output", &job.output.to_string_lossy()])
+            .chain(job.options.iter().map(String::as_str))
+            .map(String::from)
+            .collect::<Vec<_>>();
+        // A job's own options win over the same options on the command line
+        let job_args = Args::command()
+            .args_override_self(true)
+            .try_get_matches_from(std::iter::once("imgconv".to_string()).chain(job_arguments))
+            .and_then(|matches| Args::from_matches(&matches))
+            .map_err(|e| {
+                // Only the message itself; clap's usage lines refer to the command line
+                let message = e.to_string();
+                let message = message.lines().next().unwrap_or_default().trim_start_matches("error: ").to_string();
+                anyhow::anyhow!(tr!("Job {} ({}): {}", i + 1, job.input.display(), message))
+            })?;
+        let unsupported = [
+            (job_args.command.is_some(), "subcommands"),
+            (job_args.in_place.in_place, "--in-place"),
+            (job_args.sink.flag().is_some(), "--zip-output, --s3, --post-to and --sftp"),
+            (job_args.resume.resume.is_some(), "--resume"),
+            (job_args.estimate.is_some(), "--estimate"),
+            (job_args.web_set.web_set.is_some(), "--web-set"),
+        ];
+        if let Some((_, option)) = unsupported.iter().find(|(given, _)| *given) {
+            anyhow::bail!(tr!("Job {} ({}): {} cannot be used in a job file", i + 1, job.input.display(), option));
+        }
+        parsed.push((job, job_args));
+    }
+
+    let summary = Mutex::new(summary::Summary::default());
+    let manifest = Mutex::new(manifest::Manifest::new(&args.manifest));
+    let result = jobs::run(&parsed, args.jobs.parallel, |job, job_args| {
+        let (output_path, output_format) = determine_output(&job.output, job_args.format.clone(), job_args.strict)?;
+        inplace::check_distinct(&job.input, &output_path)?;
+        check_options(job_args, output_format)?;
+        if job_args.batch.newer_only && batch::is_up_to_date(&job.input, &output_path) {
+            print_info(&tr!("Up to date, skipping: {}", output_path.display()));
+            return Ok(false);
+        }
+        let before = summary::Before::read(Some(&job.input));
+        let mut timer = timings::Timer::start();
+        let dimensions = convert_file(job_args, &job.input, &output_path, output_format, &mut timer)?;
+        if job_args.timings.timings {
+            timer.print(&job.input);
+        }
+        summary.lock().unwrap().record(before, &output_path, dimensions);
+        hooks::on_complete(&job_args.hooks, Some(&job.input), &output_path)?;
+        if let Some(manifest) = manifest.lock().unwrap().as_mut() {
+            manifest.record(Some(&job.input), &output_path, dimensions, output_format, job_args.quality)?;
+        }
+        let name = job.input.file_name().map(Path::new).unwrap_or(&job.input);
+        dispose::apply(&job.input, name, &output_path, output_format, &job_args.dispose)?;
+        Ok(true)
+    });
+
+    let summary = summary.into_inner().unwrap();
+    summary.print_table();
+    if let Some(csv) = &args.summary.csv {
+        summary.write_csv(csv)?;
+    }
+    if let Some(manifest) = manifest.into_inner().unwrap() {
+        manifest.write()?;
+    }
+    hooks::notify(&args.hooks, &summary.to_json(result.as_ref().err()));
+    if result.is_ok() {
+        record_history(args, arguments);
+    }
+    result
+}
+
+/// Remember a successful conversion for `imgconv again`; a history that
+/// cannot be written never fails the conversion
+fn record_history(args: &Args, arguments: &[String]) {
+    if args.estimate.is_some() {
+        return;
+    }
+    let input = args.input.as_deref().or(args.pos_input.as_deref());
+    let output = args.output.as_deref().or(args.pos_output.as_deref());
+    if let Err(e) = history::record(arguments, input, output) {
+        print_warning(&tr!("Could not update history: {}", format!("{:#}", e)));
+    }
+}
+
+fn output_arg(args: &Args) -> Result<PathBuf> {
+    args.output.clone()
+        .or_else(|| args.pos_output.clone())
+        .with_context(|| tr!("Output file is required. Usage: imgconv <input> <output> OR imgconv -c <output>"))
+}
+
+fn print_loaded(img: &DynamicImage, format: Option<ImageFormat>) {
     let (width, height) = img.dimensions();
-    if let Some(fmt) = detected_input_format {
-        print_success(&format!("Image loaded: {}x{} pixels, format: {:?}", width, height, fmt));
+    if let Some(fmt) = format {
+        print_success(&tr!("Image loaded: {}x{} pixels, format: {}", width, height, format!("{:?}", fmt)));
     } else {
-        print_success(&format!("Image loaded: {}x{} pixels", width, height));
+        print_success(&tr!("Image loaded: {}x{} pixels", width, height));
+    }
+}
+
+/// Check the options given against what `format` can do, before anything is decoded
+fn check_options(args: &Args, format: ImageFormat) -> Result<()> {
+    if args.estimate.is_some() {
+        return Ok(());
+    }
+    // The preview is a fraction of the photo's size, so regions given in the
+    // photo's pixels would land in the wrong place (or miss it entirely)
+    if args.embedded.embedded_thumbnail && args.edits_before_resize() {
+        anyhow::bail!(tr!(
+            "--embedded-thumbnail replaces the photo with its small preview and cannot be combined with --redact, --crop, --smart-crop, --apply-alpha or other options that run before resizing"
+        ));
     }
+    let mut given = vec![
+        ("-q", args.quality_given),
+        ("--optimize", args.optimize.optimize),
+        ("--auto-quality", args.auto_quality.auto_quality),
+        ("--backend", args.backend.backend == backend::Backend::Turbo),
+        ("--png-palette", args.quantize.png_palette),
+        ("--bit-depth", args.depth.bit_depth.is_some()),
+        ("--stream", args.limits.stream),
+    ];
+    given.extend(args.encoder.given());
+    formats::check(format, &given, args.strict)
+}
 
-    // Determine output path
-    let output = args.output
-        .or(args.pos_output)
-        .context("Output file is required. Usage: imgconv <input> <output> OR imgconv -c <output>")?;
+/// Parse the command line, noting whether -q was given explicitly
+fn parse_args<I, T>(arguments: I) -> Args
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let matches = Args::command().get_matches_from(arguments);
+    Args::from_matches(&matches).unwrap_or_else(|e| e.exit())
+}
 
-    // Determine output format with smart logic for clipboard mode
-    let (output_path, output_format) = if args.clipboard {
-        determine_output_from_clipboard(
-            &output, 
-            args.format, 
-            args.extension.as_deref(), 
-            detected_input_format
-        )?
-    } else {
-        determine_output(&output, args.format)?
+impl Args {
+    fn from_matches(matches: &clap::ArgMatches) -> Result<Self, clap::Error> {
+        let mut args = Args::from_arg_matches(matches)?;
+        args.quality_given = matches.value_source("quality") == Some(clap::parser::ValueSource::CommandLine);
+        Ok(args)
+    }
+
+    /// Whether a stage that runs ahead of resizing (in source coordinates) was asked for
+    fn edits_before_resize(&self) -> bool {
+        self.tone.is_active()
+            || self.redact.is_active()
+            || self.alpha.is_active()
+            || self.chroma_key.is_active()
+            || self.crop.is_active()
+            || self.scan.is_active()
+    }
+
+    /// Whether any processing stage other than resizing was asked for
+    fn edits(&self) -> bool {
+        self.edits_before_resize()
+            || self.upscale.is_active()
+            || self.social.is_active()
+            || self.avatar.is_active()
+            || self.text.is_active()
+            || self.mask.is_active()
+            || self.noise.is_active()
+            || self.white_balance.is_active()
+            || self.levels.is_active()
+            || self.curves.is_active()
+            || self.sharpen.is_active()
+            || self.stylize.is_active()
+            || self.effects.is_active()
+            || self.frame.is_active()
+            || self.quantize.is_active()
+            || self.depth.is_active()
+    }
+}
+
+/// Read `input` and convert it to `output_path`, going through the cache if enabled
+///
+/// Returns the output dimensions when known (cache hits in encode-only formats are not decoded).
+fn convert_file(
+    args: &Args,
+    input: &Path,
+    output_path: &Path,
+    output_format: ImageFormat,
+    timer: &mut timings::Timer,
+) -> Result<Option<(u32, u32)>> {
+    let cache = match &args.cache.cache_dir {
+        Some(dir) if args.estimate.is_none() => Some(cache::Entry::new(dir, input, &settings(args), output_format)?),
+        _ => None,
     };
 
-    // Convert and save
-    print_info(&format!("Converting to format: {:?}", output_format));
-    
-    // Create parent directory if it doesn't exist
-    if let Some(parent) = output_path.parent() {
-        if !parent.exists() {
+    if let Some(data) = cache.as_ref().and_then(cache::Entry::lookup) {
+        let source_metadata = perms::read(input)?;
+        create_parent_dir(output_path)?;
+        if args.in_place.in_place {
+            inplace::backup(output_path, &args.in_place)?;
+        }
+        write_bytes(output_path, &data)?;
+        perms::apply(&source_metadata, output_path, &args.perms)?;
+        timer.lap(timings::Stage::Encode);
+        print_success(&tr!("Restored from cache: {}", output_path.display()));
+        return Ok(image::image_dimensions(output_path).ok());
+    }
+
+    let needed = args.limits.max_memory.filter(|_| args.estimate.is_none()).and_then(|budget| {
+        limits::estimate_memory(input).filter(|&needed| needed > budget.0)
+    });
+    let dimensions = match needed {
+        _ if args.limits.stream => convert_streaming(args, input, output_path, output_format, None, timer)?,
+        Some(needed) => convert_streaming(args, input, output_path, output_format, Some(needed), timer)?,
+        None => {
+            print_info(&tr!("Reading image from: {}", input.display()));
+            let embedded = match args.embedded.embedded_thumbnail {
+                true => exifthumb::load(input)?,
+                false => None,
+            };
+            let from_thumbnail = embedded.is_some();
+            // Regions given in source pixels need the full-size decode
+            let scaled = match embedded {
+                None if !args.edits_before_resize() => load_jpeg_scaled(input, args)?,
+                _ => None,
+            };
+            let (img, detected_input_format, declared) = match (embedded, scaled) {
+                (Some(img), _) => (img, Some(ImageFormat::Jpeg), None),
+                (None, Some((img, declared))) => (img, Some(ImageFormat::Jpeg), Some(declared)),
+                (None, None) => {
+                    let (img, format) = load_image_within(input, args.limits.max_pixels)?;
+                    (img, format, None)
+                }
+            };
+            timer.lap(timings::Stage::Decode);
+            print_loaded(&img, detected_input_format);
+            if !from_thumbnail {
+                let data = std::fs::read(input).with_context(|| tr!("Failed to read input file: {}", input.display()))?;
+                let decoded = declared.unwrap_or(img.dimensions());
+                sanity::check(input, &data, detected_input_format, decoded, args.strict)?;
+                animation::check(input, std::io::Cursor::new(&data), detected_input_format, &args.animation);
+            }
+            convert(args, img, Some(input), output_path, output_format, timer)?
+        }
+    };
+
+    if let Some(entry) = &cache {
+        entry.store(output_path)?;
+    }
+    Ok(Some(dimensions))
+}
+
+/// Decode an image read into memory (an archive entry, named `name`) and convert it to `output_path`
+fn convert_data(
+    args: &Args,
+    data: &[u8],
+    name: &Path,
+    output_path: &Path,
+    output_format: ImageFormat,
+    timer: &mut timings::Timer,
+) -> Result<(u32, u32)> {
+    print_info(&tr!("Reading image from: {}", name.display()));
+    let reader = || ImageReader::new(std::io::Cursor::new(data)).with_guessed_format();
+    if let Ok(dimensions) = reader()?.into_dimensions() {
+        limits::check_dimensions(name, dimensions, args.limits.max_pixels)?;
+    }
+    let mut reader = reader()?;
+    reader.no_limits();
+    let detected_input_format = reader.format();
+    let img = match detected_input_format {
+        Some(ImageFormat::Jpeg) => cmyk::decode(data)?,
+        _ => None,
+    };
+    let img = match img {
+        Some(img) => img,
+        None => reader.decode().with_context(|| tr!("Failed to decode image"))?,
+    };
+    timer.lap(timings::Stage::Decode);
+    print_loaded(&img, detected_input_format);
+    sanity::check(name, data, detected_input_format, img.dimensions(), args.strict)?;
+    animation::check(name, std::io::Cursor::new(data), detected_input_format, &args.animation);
+    convert(args, img, None, output_path, output_format, timer)
+}
+
+/// Every option that changes the output bytes, for cache keys
+fn settings(args: &Args) -> String {
+    format!(
+        "{} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?}",
+        args.quality, args.target, args.auto_quality, args.optimize, args.background, args.backend, args.encoder, args.embedded,
+        args.tone, args.redact, args.alpha, args.chroma_key, args.crop, args.scan, args.resize, args.upscale, args.social,
+        args.avatar, args.text, args.mask, args.noise, args.white_balance, args.levels, args.curves, args.sharpen, args.stylize,
+        args.effects, args.frame, args.quantize, args.depth
+    )
+}
+
+/// Convert an image strip by strip, for --stream or when it is over the
+/// --max-memory budget (`needed` is then its estimated footprint)
+///
+/// Only plain conversion and shrinking can be streamed; anything else needs
+/// the whole image in memory and is refused rather than risking the budget.
+fn convert_streaming(
+    args: &Args,
+    input: &Path,
+    output_path: &Path,
+    output_format: ImageFormat,
+    needed: Option<u64>,
+    timer: &mut timings::Timer,
+) -> Result<(u32, u32)> {
+    let reason = match needed {
+        Some(needed) => tr!(
+            "{} needs about {}, over the --max-memory budget of {}",
+            input.display(),
+            estimate::format_size(needed),
+            estimate::format_size(args.limits.max_memory.map_or(0, |size| size.0))
+        ),
+        None => tr!("--stream was given"),
+    };
+    let input_format = ImageReader::open(input)
+        .ok()
+        .and_then(|reader| reader.with_guessed_format().ok())
+        .and_then(|reader| reader.format());
+    let input_format = match input_format {
+        Some(format) if stream::can_stream(Some(format), output_format) && !args.edits() => format,
+        _ => anyhow::bail!(tr!(
+            "{}, but streaming only supports PNG, TIFF and PNM input and output with an optional --resize",
+            reason
+        )),
+    };
+
+    limits::check_pixels(input, args.limits.max_pixels)?;
+    print_info(&tr!("{}; converting in strips", reason));
+    if args.resize.resize.is_some() {
+        print_info(&tr!("Streaming resize uses area averaging; --filter and --linear-resize are ignored"));
+    }
+
+    let source_metadata = perms::read(input)?;
+    create_parent_dir(output_path)?;
+    if args.in_place.in_place {
+        inplace::backup(output_path, &args.in_place)?;
+    }
+    let dimensions = stream::convert(input, input_format, output_path, output_format, args.resize.resize)?;
+    perms::apply(&source_metadata, output_path, &args.perms)?;
+    // Decoding, resizing and encoding are interleaved, so it all counts as encoding
+    timer.lap(timings::Stage::Encode);
+    print_success(&tr!("Successfully converted to: {}", output_path.display()));
+    Ok(dimensions)
+}
+
+/// Process `input` once and write its --web-set variants into the output directory
+fn convert_web_set(args: &Args, input: &Path) -> Result<()> {
+    let out_dir = output_arg(args)?;
+    print_info(&tr!("Reading image from: {}", input.display()));
+    let (img, detected_input_format) = load_image_within(input, args.limits.max_pixels)?;
+    print_loaded(&img, detected_input_format);
+    // Variants are resized from the processed image; PNG keeps alpha and depth
+    // until each format's encoder needs them flattened
+    let img = process(args, img, ImageFormat::Png)?;
+    let name = input.file_stem().unwrap_or_default().to_string_lossy();
+    let name = if args.batch.sanitize_names { names::sanitize(&name) } else { name.into_owned() };
+    webset::write(&img, &name, &out_dir, &args.web_set, args.quality, args.resize.filter, args.background)
+}
+
+fn create_parent_dir(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
             std::fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+                .with_context(|| tr!("Failed to create directory: {}", parent.display()))?;
         }
     }
+    Ok(())
+}
+
+/// Apply every processing stage to `img`, ready to encode as `output_format`
+fn process(args: &Args, img: DynamicImage, output_format: ImageFormat) -> Result<DynamicImage> {
+    static SHOWN: Once = Once::new();
+    let pipeline = pipeline(args, output_format);
+    if args.show_pipeline {
+        SHOWN.call_once(|| {
+            print_info(&tr!("Pipeline for {} output:", format!("{:?}", output_format)));
+            eprint!("{}", pipeline);
+        });
+    }
+    pipeline.apply(img).map_err(|error| match error {
+        // Report the step's own error, as before there was a pipeline
+        imgconv::ImgconvError::Transform { source, .. } => anyhow::Error::from_boxed(source),
+        error => error.into(),
+    })
+}
 
-    // Save with appropriate encoder
-    match output_format {
-        ImageFormat::Jpeg => {
-            let file = std::fs::File::create(&output_path)
-                .with_context(|| format!("Failed to create output file: {}", output_path.display()))?;
-            let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(file, args.quality);
-            encoder.encode_image(&img)
-                .context("Failed to encode JPEG image")?;
-            print_success(&format!("JPEG quality: {}", args.quality));
+/// The transform steps `args` ask for, in the order they run: edits in
+/// source coordinates, resizing, effects (the frame last so it sits on the
+/// outer edge), then what the output format needs
+fn pipeline(args: &Args, output_format: ImageFormat) -> Pipeline<'_> {
+    let mut pipeline = Pipeline::new();
+    let tone = match args.tone.tonemap {
+        Some(op) => format!("{:?}, {:+} EV", op, args.tone.exposure),
+        None => "HDR input only, clipped to display range".to_string(),
+    };
+    pipeline.custom("tonemap", tone, move |img| tonemap::apply(img, &args.tone, output_format));
+    if !args.redact.redact.is_empty() {
+        pipeline.custom("redact", format!("{} areas", args.redact.redact.len()), |img| redact::apply(img, &args.redact));
+    }
+    if let Some(path) = &args.alpha.apply_alpha {
+        pipeline.custom("apply-alpha", path.display().to_string(), |img| alpha::apply_mask(img, &args.alpha));
+    }
+    if let Some(key) = args.chroma_key.chroma_key {
+        let detail = format!("{}, fuzz {}%, feather {}", key, args.chroma_key.fuzz * 100.0, args.chroma_key.feather);
+        pipeline.custom("chroma-key", detail, |img| chromakey::apply_chroma_key(img, &args.chroma_key));
+    }
+    if args.chroma_key.remove_bg {
+        pipeline.custom("remove-bg", "external matting model", |img| chromakey::apply_remove_bg(img, &args.chroma_key));
+    }
+    if let Some(rect) = args.crop.crop {
+        pipeline.push(Op::Crop { x: rect.x, y: rect.y, width: rect.width, height: rect.height });
+    }
+    if let Some(aspect) = args.crop.smart_crop {
+        pipeline.custom("smart-crop", format!("{}:{}", aspect.width, aspect.height), |img| crop::apply(img, &args.crop));
+    }
+    if let Some(radius) = args.scan.descreen {
+        pipeline.custom("descreen", format!("radius {}", radius), |img| scan::apply_descreen(img, &args.scan));
+    }
+    if args.scan.deskew {
+        pipeline.custom("deskew", format!("up to {}°", scan::MAX_SKEW), |img| scan::apply_deskew(img, &args.scan));
+    }
+    if let Some(size) = args.resize.resize {
+        pipeline.push(Op::Resize {
+            to: Resize::Fit(size.width, size.height),
+            filter: args.resize.filter,
+            linear: args.resize.linear_resize,
+            gpu: args.resize.gpu,
+        });
+    }
+    if let Some(factor) = args.upscale.upscale {
+        let detail = match &args.upscale.sr_backend {
+            Some(backend) => format!("{}x, {} (else {:?})", factor.0, backend.model, args.upscale.upscale_filter),
+            None => format!("{}x, {:?}", factor.0, args.upscale.upscale_filter),
+        };
+        pipeline.custom("upscale", detail, |img| upscale::apply(img, &args.upscale));
+    }
+    if let Some(preset) = args.social.social {
+        let detail = format!("{:?}, {:?}", preset, args.social.social_fit);
+        pipeline.custom("social", detail, |img| social::apply(img, &args.social, args.resize.filter, args.background));
+    }
+    if let Some(size) = args.avatar.avatar {
+        pipeline.custom("avatar", format!("{}x{}", size, size), |img| avatar::apply(img, &args.avatar, args.resize.filter));
+    }
+    if let Some(strength) = args.noise.denoise {
+        let detail = format!("{} ({:?})", strength, args.noise.denoise_method);
+        pipeline.custom("denoise", detail, |img| noise::apply_denoise(img, &args.noise));
+    }
+    if let Some(mode) = args.white_balance.white_balance {
+        let detail = match mode {
+            whitebalance::WhiteBalance::Auto => "gray world".to_string(),
+            whitebalance::WhiteBalance::Temperature { kelvin, tint } => format!("from {}K, tint {:+}", kelvin, tint),
+        };
+        pipeline.custom("white-balance", detail, |img| whitebalance::apply(img, &args.white_balance));
+    }
+    if args.levels.auto_levels || args.levels.shadows.is_some() || args.levels.highlights.is_some() {
+        let mut detail = Vec::new();
+        if args.levels.auto_levels {
+            detail.push("auto".to_string());
+        }
+        if let Some(amount) = args.levels.shadows {
+            detail.push(format!("shadows {:+}", amount));
+        }
+        if let Some(amount) = args.levels.highlights {
+            detail.push(format!("highlights {:+}", amount));
         }
-        _ => {
-            img.save_with_format(&output_path, output_format)
-                .with_context(|| format!("Failed to save image to: {}", output_path.display()))?;
+        pipeline.custom("levels", detail.join(", "), |img| levels::apply(img, &args.levels));
+    }
+    if args.levels.brightness.is_some() || args.levels.contrast.is_some() {
+        let (brightness, contrast) = (args.levels.brightness.unwrap_or(0), args.levels.contrast.unwrap_or(0.0));
+        pipeline.push(Op::ColorAdjust { brightness, contrast });
+    }
+    if !args.curves.curve.is_empty() || args.curves.solarize.is_some() {
+        let mut detail: Vec<String> = args.curves.curve.iter().map(|curve| {
+            let channel = curve.channel.map_or("rgb", |channel| ["red", "green", "blue"][channel]);
+            format!("{} curve, {} points", channel, curve.points.len())
+        }).collect();
+        if let Some(threshold) = args.curves.solarize {
+            detail.push(format!("solarize above {}", threshold));
         }
+        pipeline.custom("curves", detail.join("; "), |img| curves::apply(img, &args.curves));
+    }
+    if let Some(unsharp) = args.sharpen.unsharp {
+        let detail = format!("radius {}, amount {}, threshold {}", unsharp.radius, unsharp.amount, unsharp.threshold);
+        pipeline.custom("unsharp", detail, |img| sharpen::apply(img, &args.sharpen));
+    }
+    if let Some(method) = args.stylize.edge {
+        pipeline.custom("edge", format!("{:?}", method), |img| stylize::apply_edge(img, &args.stylize));
+    }
+    if args.stylize.emboss {
+        pipeline.custom("emboss", "3x3, lit from the top left", |img| stylize::apply_emboss(img, &args.stylize));
+    }
+    if let Some(strength) = args.effects.vignette {
+        pipeline.custom("vignette", strength.to_string(), |img| effects::apply_vignette(img, &args.effects));
+    }
+    if let Some(amount) = args.noise.add_grain {
+        pipeline.custom("grain", amount.to_string(), |img| noise::apply_grain(img, &args.noise));
+    }
+    if let Some(text) = &args.text.text {
+        let detail = format!("{:?} at {:?}", text, args.text.gravity);
+        pipeline.custom("text", detail, |img| annotate::apply(img, &args.text));
+    }
+    if args.mask.round_corners.is_some() || args.mask.circle {
+        let detail = match args.mask.round_corners {
+            Some(radius) if !args.mask.circle => format!("round corners, radius {}", radius),
+            _ => "circle".to_string(),
+        };
+        pipeline.custom("mask", detail, |img| mask::apply(img, &args.mask));
+    }
+    if let Some(shadow) = &args.effects.shadow {
+        let detail = format!("blur {}, offset {},{}", shadow.blur, shadow.offset_x, shadow.offset_y);
+        pipeline.custom("shadow", detail, |img| effects::apply_shadow(img, &args.effects));
+    }
+    if args.frame.border.is_some() || args.frame.frame.is_some() {
+        let detail = match (args.frame.border, args.frame.frame) {
+            (_, Some(frame)) => format!("frame {}+{}", frame.outer, frame.inner),
+            (Some(border), None) => format!("border {}", border),
+            (None, None) => unreachable!(),
+        };
+        pipeline.custom("frame", detail, |img| frame::apply(img, &args.frame, args.limits.max_pixels));
     }
+    if output_format == ImageFormat::Ico && !args.resize.no_auto_downscale {
+        pipeline.custom("fit-ico", "to 256x256 when larger", move |img| Ok::<_, anyhow::Error>(resize::fit_ico(img, &args.resize, output_format)));
+    }
+    if let Some(path) = &args.alpha.extract_alpha {
+        pipeline.custom("extract-alpha", path.display().to_string(), |img| alpha::extract(&img, &args.alpha).map(|_| img));
+    }
+    if !encode::supports_alpha(output_format) {
+        let detail = format!("onto {} when transparent", args.background);
+        pipeline.custom("flatten", detail, move |img| {
+            Ok::<_, anyhow::Error>(alpha::flatten_for_format(img, output_format, args.background))
+        });
+    }
+    if args.quantize.quantize.is_some() || args.quantize.png_palette {
+        let detail = match args.quantize.quantize {
+            Some(colors) => format!("{} colors, {:?}", colors, args.quantize.quant_algo),
+            None => "checked for --png-palette".to_string(),
+        };
+        pipeline.custom("quantize", detail, move |img| quantize::apply(img, &args.quantize, output_format, args.depth.dither));
+    }
+    let depth = match args.depth.bit_depth {
+        Some(depth) => format!("{:?}, {:?} dither", depth, args.depth.dither),
+        None => "what the format stores".to_string(),
+    };
+    pipeline.custom("depth", depth, move |img| depth::apply(img, &args.depth, output_format, args.background));
+    pipeline
+}
 
-    // Get file size
-    if let Ok(metadata) = std::fs::metadata(&output_path) {
-        let size_kb = metadata.len() / 1024;
-        print_success(&format!("Output size: {} KB", size_kb));
+/// Run the processing pipeline on `img` and write the result to `output_path`, returning its dimensions
+fn convert(
+    args: &Args,
+    img: DynamicImage,
+    input_path: Option<&Path>,
+    output_path: &Path,
+    output_format: ImageFormat,
+    timer: &mut timings::Timer,
+) -> Result<(u32, u32)> {
+    let img = process(args, img, output_format)?;
+    timer.lap(timings::Stage::Transform);
+
+    if let Some(qualities) = &args.estimate {
+        if let Some(q) = qualities.iter().find(|q| **q == 0 || **q > 100) {
+            anyhow::bail!(tr!("Estimate quality must be between 1 and 100, got: {}", q));
+        }
+        print_info(&tr!("Estimating output size for format: {}", format!("{:?}", output_format)));
+        estimate::run(&img, output_format, qualities)?;
+        return Ok(img.dimensions());
     }
 
-    print_success(&format!("Successfully converted to: {}", output_path.display()));
-    Ok(())
+    // Convert and save
+    print_info(&tr!("Converting to format: {}", format!("{:?}", output_format)));
+    
+    // Create parent directory if it doesn't exist
+    create_parent_dir(output_path)?;
+
+    let source_metadata = input_path.map(perms::read).transpose()?;
+    if args.in_place.in_place {
+        inplace::backup(output_path, &args.in_place)?;
+    }
+
+    // Save with appropriate encoder
+    let limit = target::limit(&args.target, &args.social);
+    let quality = args.encoder.encoders(args.quality).quality_for(output_format);
+    let quality = autoquality::quality(&img, output_format, &args.auto_quality, quality)?;
+    let quality = target::quality(&img, output_format, quality, limit)?;
+    if args.depth.is_bilevel() {
+        save_bilevel(&img.to_luma8(), output_format, output_path)?;
+    } else if args.quantize.png_palette {
+        let indexed = quantize::index(&img, &args.quantize, args.depth.dither);
+        save_indexed(&indexed.palette, &indexed.indices, (indexed.width, indexed.height), output_path)?;
+    } else if output_format == ImageFormat::Png && args.optimize.optimize {
+        optimize::save_png(&img, output_path)?;
+    } else if output_format == ImageFormat::Jpeg && backend::use_turbo(&args.backend) {
+        backend::save_jpeg(&img, quality, args.encoder.jpeg_progressive, output_path)?;
+    } else {
+        save_image(&img, output_format, quality, output_path)?;
+    }
+    target::check_size(output_path, output_format, limit);
+    if let Some(metadata) = &source_metadata {
+        perms::apply(metadata, output_path, &args.perms)?;
+    }
+    timer.lap(timings::Stage::Encode);
+    if supports_quality(output_format) {
+        print_success(&tr!("{} quality: {}", format!("{:?}", output_format), quality));
+    }
+
+    print_success(&tr!("Successfully converted to: {}", output_path.display()));
+    Ok(img.dimensions())
+}
+
+/// Open and decode an image file, sniffing the format from its contents
+fn load_image(input: &Path) -> Result<(DynamicImage, Option<ImageFormat>)> {
+    load_image_within(input, limits::DEFAULT_MAX_PIXELS)
+}
+
+/// `load_image` with an explicit pixel limit, checked against the header before decoding
+fn load_image_within(input: &Path, max_pixels: limits::PixelCount) -> Result<(DynamicImage, Option<ImageFormat>)> {
+    limits::check_pixels(input, max_pixels)?;
+    let mut reader = ImageReader::open(input)
+        .with_context(|| tr!("Failed to open input file: {}", input.display()))?
+        .with_guessed_format()
+        .with_context(|| tr!("Failed to detect image format from: {}", input.display()))?;
+    
+    // The pixel limit above replaces the decoder's own allocation cap
+    reader.no_limits();
+
+    let detected_format = reader.format();
+    if detected_format == Some(ImageFormat::Jpeg) {
+        // Four-component (print) JPEGs need their own color conversion
+        let data = std::fs::read(input)
+            .with_context(|| tr!("Failed to read input file: {}", input.display()))?;
+        if let Some(img) = cmyk::decode(&data)? {
+            return Ok((img, detected_format));
+        }
+    }
+    let img = reader.decode()
+        .with_context(|| tr!("Failed to decode image"))?;
+    
+    Ok((img, detected_format))
+}
+
+/// Decode a JPEG straight at 1/2, 1/4 or 1/8 scale when --resize allows it,
+/// along with the size its header declares
+fn load_jpeg_scaled(input: &Path, args: &Args) -> Result<Option<(DynamicImage, (u32, u32))>> {
+    if args.resize.resize.is_none() {
+        return Ok(None);
+    }
+    limits::check_pixels(input, args.limits.max_pixels)?;
+    let data = std::fs::read(input).with_context(|| tr!("Failed to read input file: {}", input.display()))?;
+    if image::guess_format(&data).ok() != Some(ImageFormat::Jpeg) {
+        return Ok(None);
+    }
+    let Some(img) = resize::decode_jpeg_scaled(&data, &args.resize)? else {
+        return Ok(None);
+    };
+    let declared = ImageReader::with_format(std::io::Cursor::new(&data), ImageFormat::Jpeg).into_dimensions()?;
+    Ok(Some((img, declared)))
 }
 
 fn get_image_from_clipboard() -> Result<(DynamicImage, Option<ImageFormat>)> {
     use arboard::Clipboard;
     
     let mut clipboard = Clipboard::new()
-        .context("Failed to access clipboard")?;
-    
-    let img_data = clipboard.get_image()
-        .context("No image found in clipboard. Please copy an image first.")?;
+        .with_context(|| tr!("Failed to access clipboard"))?;
     
-    // Convert RGBA to image format
-    let width = img_data.width;
-    let height = img_data.height;
-    let rgba_data = img_data.bytes;
+    let img_data = match clipboard.get_image() {
+        Ok(img_data) => img_data,
+        // Some Windows apps only offer formats arboard can't convert; read them directly
+        #[cfg(windows)]
+        Err(e) => {
+            return clipboard::windows_fallback()
+                .with_context(|| format!("{} ({})", tr!("No image found in clipboard. Please copy an image first."), e));
+        }
+        #[cfg(not(windows))]
+        Err(arboard::Error::ContentNotAvailable) => {
+            return Err(imgconv::ImgconvError::ClipboardEmpty).with_context(|| tr!("No image found in clipboard. Please copy an image first."));
+        }
+        #[cfg(not(windows))]
+        Err(e) => return Err(e).with_context(|| tr!("No image found in clipboard. Please copy an image first.")),
+    };
     
-    // Create DynamicImage from RGBA data
-    let img = image::RgbaImage::from_raw(width as u32, height as u32, rgba_data.to_vec())
-        .context("Failed to create image from clipboard data")?;
+    // RGBA rows, possibly padded past width * 4 bytes
+    let img = clipboard::rgba_from_raw(img_data.width, img_data.height, &img_data.bytes)?;
     
     let dynamic_img = DynamicImage::ImageRgba8(img);
     
@@ -267,121 +1675,120 @@ fn determine_output_from_clipboard(
     output: &Path,
     explicit_format: Option<Format>,
     extension: Option<&str>,
-    detected_format: Option<ImageFormat>
+    detected_format: Option<ImageFormat>,
+    strict: bool,
 ) -> Result<(PathBuf, ImageFormat)> {
     // Priority:
-    // 1. -e flag (extension) with conversion
-    // 2. -f flag (format)
+    // 1. -f flag (format), correcting a disagreeing -e or output extension
+    // 2. -e flag (extension) with conversion
     // 3. output file extension
     // 4. detected format from clipboard
     
+    if let Some(fmt) = explicit_format {
+        let output = match extension {
+            Some(ext) => with_image_extension(output, ext),
+            None => output.to_path_buf(),
+        };
+        let output_path = path_for_format(&output, &fmt, strict)?;
+        return Ok((output_path, fmt.to_image_format()));
+    }
+
     if let Some(ext) = extension {
         // User specified -e flag, convert to that format
         let target_format = extension_to_format(ext)
-            .with_context(|| format!("Unknown extension: {}", ext))?;
+            .with_context(|| tr!("Unknown extension: {}", ext))?;
         
-        let mut output_path = output.to_path_buf();
-        
-        // If output has different extension, correct it
-        if let Some(current_ext) = output.extension() {
-            if current_ext.to_string_lossy().to_lowercase() != ext.to_lowercase() {
-                print_info(&format!(
+        // If output has a different image extension, correct it
+        let output_path = match output.extension().filter(|_| detect_format_from_path(output).is_some()) {
+            Some(current_ext) if !current_ext.eq_ignore_ascii_case(ext) => {
+                print_info(&tr!(
                     "Correcting extension from .{} to .{} (conversion mode)", 
                     current_ext.to_string_lossy(), 
                     ext
                 ));
-                output_path.set_extension(ext);
+                with_image_extension(output, ext)
             }
-        } else {
-            output_path.set_extension(ext);
-        }
+            Some(_) => output.to_path_buf(),
+            None => with_image_extension(output, ext),
+        };
         
         return Ok((output_path, target_format));
     }
     
-    if let Some(fmt) = explicit_format {
-        // User specified -f flag
-        let output_format = fmt.to_image_format();
-        let output_path = add_extension_if_needed(output, &fmt);
-        return Ok((output_path, output_format));
-    }
-    
-    // Check if output has extension
-    if let Some(output_ext) = output.extension() {
-        let ext_str = output_ext.to_string_lossy().to_lowercase();
+    // Check if output has an image extension (any spelling of the format counts)
+    if let Some(format_from_ext) = detect_format_from_path(output) {
+        let ext_str = output.extension().unwrap_or_default().to_string_lossy().to_lowercase();
         
         // Check if extension matches detected format
-        if let Some(detected) = detected_format {
+        if let Some(detected) = detected_format.filter(|detected| *detected != format_from_ext) {
             let detected_ext = format_to_main_extension(&detected);
-            
-            if ext_str != detected_ext && ext_str != "jpg" && detected_ext != "jpeg" {
-                // Extension doesn't match, correct it
-                print_info(&format!(
-                    "Output extension .{} doesn't match clipboard format .{}, correcting...", 
-                    ext_str, 
-                    detected_ext
-                ));
-                
-                let mut corrected_path = output.to_path_buf();
-                corrected_path.set_extension(detected_ext);
-                return Ok((corrected_path, detected));
-            }
+            // Extension doesn't match, correct it
+            print_info(&tr!(
+                "Output extension .{} doesn't match clipboard format .{}, correcting...", 
+                ext_str, 
+                detected_ext
+            ));
+            return Ok((with_image_extension(output, detected_ext), detected));
         }
         
-        // Try to detect format from output extension
-        if let Some(format_from_ext) = detect_format_from_path(output) {
-            return Ok((output.to_path_buf(), format_from_ext));
-        }
+        return Ok((output.to_path_buf(), format_from_ext));
     }
     
     // Fallback to detected format or PNG
     let final_format = detected_format.unwrap_or(ImageFormat::Png);
     let ext = format_to_main_extension(&final_format);
     
-    let mut output_path = output.to_path_buf();
-    output_path.set_extension(ext);
+    let output_path = with_image_extension(output, ext);
     
-    print_info(&format!("Auto-adding extension: .{}", ext));
+    print_info(&tr!("Auto-adding extension: .{}", ext));
     
     Ok((output_path, final_format))
 }
 
-fn determine_output(output: &Path, format: Option<Format>) -> Result<(PathBuf, ImageFormat)> {
+fn determine_output(output: &Path, format: Option<Format>, strict: bool) -> Result<(PathBuf, ImageFormat)> {
     if let Some(fmt) = format {
         // Format explicitly specified
         let output_format = fmt.to_image_format();
-        let output_path = add_extension_if_needed(output, &fmt);
+        let output_path = path_for_format(output, &fmt, strict)?;
         Ok((output_path, output_format))
     } else {
         // Try to detect from output extension
         if let Some(detected_format) = detect_format_from_path(output) {
             Ok((output.to_path_buf(), detected_format))
         } else {
-            anyhow::bail!(
+            anyhow::bail!(tr!(
                 "Could not determine output format from '{}'. Please specify --format or use a recognized extension",
                 output.display()
-            )
+            ))
         }
     }
 }
 
-fn add_extension_if_needed(path: &Path, format: &Format) -> PathBuf {
-    // If path already has the correct extension, return as-is
-    if let Some(ext) = path.extension() {
-        if let Some(ext_str) = ext.to_str() {
-            let ext_lower = ext_str.to_lowercase();
-            let format_ext = format_to_extension(format);
-            if ext_lower == format_ext || ext_lower == format_ext.replace("jpeg", "jpg") {
-                return path.to_path_buf();
+/// `path` with an extension that matches `format`
+///
+/// Any extension of the format is kept (`.tif`, `.jpeg`, `.pgm`). One that names
+/// a different format loses to -f: it is rewritten with a warning, or refused
+/// with --strict. Without an image extension, one is added.
+fn path_for_format(path: &Path, format: &Format, strict: bool) -> Result<PathBuf> {
+    let wanted = format.to_image_format();
+    let corrected = with_image_extension(path, format_to_extension(format));
+    match detect_format_from_path(path) {
+        Some(current) if current == wanted => Ok(path.to_path_buf()),
+        Some(current) => {
+            let message = tr!(
+                "Output extension .{} is {}, but -f asks for {}",
+                path.extension().unwrap_or_default().to_string_lossy(),
+                format!("{:?}", current),
+                format!("{:?}", wanted)
+            );
+            if strict {
+                anyhow::bail!(tr!("{} (--strict)", message));
             }
+            print_warning(&tr!("{}; writing {}", message, corrected.display()));
+            Ok(corrected)
         }
+        None => Ok(corrected),
     }
-    
-    // Add extension
-    let ext = format_to_extension(format);
-    let mut new_path = path.to_path_buf();
-    new_path.set_extension(ext);
-    new_path
 }
 
 fn format_to_extension(format: &Format) -> &str {
@@ -403,22 +1810,7 @@ fn format_to_extension(format: &Format) -> &str {
 }
 
 fn extension_to_format(ext: &str) -> Option<ImageFormat> {
-    match ext.to_lowercase().as_str() {
-        "png" => Some(ImageFormat::Png),
-        "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
-        "gif" => Some(ImageFormat::Gif),
-        "bmp" => Some(ImageFormat::Bmp),
-        "ico" => Some(ImageFormat::Ico),
-        "tiff" | "tif" => Some(ImageFormat::Tiff),
-        "webp" => Some(ImageFormat::WebP),
-        "avif" => Some(ImageFormat::Avif),
-        "pnm" | "pbm" | "pgm" | "ppm" => Some(ImageFormat::Pnm),
-        "tga" => Some(ImageFormat::Tga),
-        "dds" => Some(ImageFormat::Dds),
-        "hdr" => Some(ImageFormat::Hdr),
-        "ff" => Some(ImageFormat::Farbfeld),
-        _ => None,
-    }
+    formats::from_extension(ext)
 }
 
 fn format_to_main_extension(format: &ImageFormat) -> &str {
@@ -441,31 +1833,25 @@ fn format_to_main_extension(format: &ImageFormat) -> &str {
 }
 
 fn detect_format_from_path(path: &Path) -> Option<ImageFormat> {
-    let ext = path.extension()?.to_str()?.to_lowercase();
-    match ext.as_str() {
-        "png" => Some(ImageFormat::Png),
-        "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
-        "gif" => Some(ImageFormat::Gif),
-        "bmp" => Some(ImageFormat::Bmp),
-        "ico" => Some(ImageFormat::Ico),
-        "tiff" | "tif" => Some(ImageFormat::Tiff),
-        "webp" => Some(ImageFormat::WebP),
-        "avif" => Some(ImageFormat::Avif),
-        "pnm" | "pbm" | "pgm" | "ppm" => Some(ImageFormat::Pnm),
-        "tga" => Some(ImageFormat::Tga),
-        "dds" => Some(ImageFormat::Dds),
-        "hdr" => Some(ImageFormat::Hdr),
-        "ff" => Some(ImageFormat::Farbfeld),
-        _ => None,
-    }
-}
-
-fn print_info(msg: &str) {
-    eprintln!("{} {}", "[INFO]".blue().bold(), msg);
+    extension_to_format(path.extension()?.to_str()?)
 }
 
-fn print_success(msg: &str) {
-    eprintln!("{} {}", "[✓]".green().bold(), msg);
+/// `path` with its image extension swapped for `ext`, in the same case
+///
+/// Other dots are part of the name, so `v1.2.release` gets `.png` appended
+/// rather than becoming `v1.2.png`, while `PHOTO.JPG` becomes `PHOTO.PNG`.
+fn with_image_extension(path: &Path, ext: &str) -> PathBuf {
+    let current = path.extension().and_then(|current| current.to_str());
+    match current.filter(|current| extension_to_format(current).is_some()) {
+        Some(current) if !current.chars().any(|c| c.is_ascii_lowercase()) => path.with_extension(ext.to_uppercase()),
+        Some(_) => path.with_extension(ext),
+        None => {
+            let mut name = path.as_os_str().to_os_string();
+            name.push(".");
+            name.push(ext);
+            PathBuf::from(name)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -492,6 +1878,68 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_path_for_format() {
+        let path = |name: &str, format: Format, strict: bool| {
+            path_for_format(Path::new(name), &format, strict).map(|path| path.display().to_string())
+        };
+        assert_eq!(path("scan.tif", Format::Tiff, true).unwrap(), "scan.tif");
+        assert_eq!(path("photo.JPEG", Format::Jpg, true).unwrap(), "photo.JPEG");
+        assert_eq!(path("out", Format::Webp, true).unwrap(), "out.webp");
+        assert_eq!(path("out.png", Format::Jpeg, false).unwrap(), "out.jpg");
+        assert!(path("out.png", Format::Jpeg, true).is_err());
+    }
+
+    #[test]
+    fn test_extensions_in_any_case() {
+        assert_eq!(detect_format_from_path(Path::new("PHOTO.JPG")), Some(ImageFormat::Jpeg));
+        assert_eq!(detect_format_from_path(Path::new("scan.Tif")), Some(ImageFormat::Tiff));
+        assert_eq!(detect_format_from_path(Path::new("backup.tar.gz")), None);
+        let swap = |name: &str, ext: &str| with_image_extension(Path::new(name), ext).display().to_string();
+        assert_eq!(swap("PHOTO.JPG", "png"), "PHOTO.PNG");
+        assert_eq!(swap("v1.2.release.jpg", "png"), "v1.2.release.png");
+        assert_eq!(swap("v1.2.release", "png"), "v1.2.release.png");
+        assert_eq!(swap("out", "webp"), "out.webp");
+    }
+
+    #[test]
+    fn test_pipeline_order() {
+        let args = Args::parse_from(["imgconv", "a.png", "b.jpg", "--border", "4", "--resize", "10x10", "--smart-crop", "1:1"]);
+        let steps = pipeline(&args, ImageFormat::Jpeg);
+        let names: Vec<&str> = steps.0.iter().map(|op| op.name()).collect();
+        assert_eq!(names, ["tonemap", "smart-crop", "resize", "frame", "flatten", "depth"]);
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::new(40, 20));
+        assert_eq!(process(&args, img, ImageFormat::Jpeg).unwrap().dimensions(), (18, 18));
+
+        // Flags with a typed step use it rather than a custom one
+        let args = Args::parse_from(["imgconv", "a.png", "b.png", "--crop", "0,0,30,20", "--resize", "10x10", "--brightness", "20"]);
+        let steps = pipeline(&args, ImageFormat::Png);
+        assert!(matches!(steps.0[1], Op::Crop { x: 0, y: 0, width: 30, height: 20 }));
+        assert!(matches!(steps.0[2], Op::Resize { to: Resize::Fit(10, 10), linear: false, gpu: false, .. }));
+        assert!(matches!(steps.0[3], Op::ColorAdjust { brightness: 20, .. }));
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(40, 20));
+        let processed = process(&args, img, ImageFormat::Png).unwrap();
+        assert_eq!((processed.dimensions(), processed.to_rgb8().get_pixel(0, 0).0), ((10, 7), [20; 3]));
+    }
+
+    #[test]
+    fn test_active_stages() {
+        let args = |extra: &[&str]| Args::parse_from(["imgconv", "a.png", "b.png"].iter().chain(extra));
+        assert!(!args(&["--resize", "10x10", "--dither", "none", "--exposure", "0"]).edits());
+        assert!(args(&["--vignette", "0.5"]).edits());
+        assert!(!args(&["--vignette", "0.5"]).edits_before_resize());
+        assert!(args(&["--crop", "0,0,5,5"]).edits_before_resize());
+    }
+
+    #[test]
+    fn test_embedded_thumbnail_refuses_source_coordinates() {
+        let args = Args::parse_from(["imgconv", "a.jpg", "b.jpg", "--embedded-thumbnail", "--resize", "100x100"]);
+        assert!(check_options(&args, ImageFormat::Jpeg).is_ok());
+        let args = Args::parse_from(["imgconv", "a.jpg", "b.jpg", "--embedded-thumbnail", "--redact", "10,10,200,80"]);
+        let error = check_options(&args, ImageFormat::Jpeg).unwrap_err().to_string();
+        assert!(error.contains("--redact"), "{}", error);
+    }
+
     #[test]
     fn test_format_to_extension() {
         assert_eq!(format_to_extension(&Format::Png), "png");
@@ -0,0 +1,105 @@
+// File: src\manifest.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: JSON manifest of converted files with output checksums
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::ImageFormat;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::encode::write_bytes;
+use crate::i18n::tr;
+use crate::print_success;
+
+#[derive(Args, Debug)]
+pub struct ManifestArgs {
+    /// Record every converted file (paths, sizes, SHA-256, settings) in a JSON manifest
+    #[arg(long, value_name = "FILE", conflicts_with = "estimate")]
+    pub write_manifest: Option<PathBuf>,
+}
+
+/// Manifest being collected over a run
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    #[serde(skip)]
+    path: PathBuf,
+    imgconv: &'static str,
+    /// Command-line arguments, i.e. the settings shared by every file
+    arguments: Vec<String>,
+    files: Vec<Record>,
+}
+
+#[derive(Debug, Serialize)]
+struct Record {
+    source: Option<PathBuf>,
+    output: PathBuf,
+    width: Option<u32>,
+    height: Option<u32>,
+    source_bytes: Option<u64>,
+    output_bytes: u64,
+    sha256: String,
+    format: String,
+    quality: u8,
+}
+
+impl Manifest {
+    /// Start a manifest if --write-manifest was given
+    pub fn new(args: &ManifestArgs) -> Option<Self> {
+        args.write_manifest.as_ref().map(|path| Self {
+            path: path.clone(),
+            imgconv: env!("CARGO_PKG_VERSION"),
+            arguments: std::env::args().skip(1).collect(),
+            files: Vec::new(),
+        })
+    }
+
+    /// Add a written output; `source` is None for clipboard input
+    pub fn record(
+        &mut self,
+        source: Option<&Path>,
+        output: &Path,
+        dimensions: Option<(u32, u32)>,
+        format: ImageFormat,
+        quality: u8,
+    ) -> Result<()> {
+        let data = std::fs::read(output)
+            .with_context(|| format!("Failed to read output for the manifest: {}", output.display()))?;
+        self.files.push(Record {
+            source: source.map(Path::to_path_buf),
+            output: output.to_path_buf(),
+            width: dimensions.map(|(width, _)| width),
+            height: dimensions.map(|(_, height)| height),
+            source_bytes: source.and_then(|path| std::fs::metadata(path).ok()).map(|m| m.len()),
+            output_bytes: data.len() as u64,
+            sha256: sha256_hex(&data),
+            format: format!("{:?}", format).to_lowercase(),
+            quality,
+        });
+        Ok(())
+    }
+
+    pub fn write(&self) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize manifest")?;
+        write_bytes(&self.path, (json + "\n").as_bytes())?;
+        print_success(&tr!("Manifest with {} files written to: {}", self.files.len(), self.path.display()));
+        Ok(())
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+}
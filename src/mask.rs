@@ -0,0 +1,91 @@
+// File: src\mask.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Rounded-corner and circular alpha masks
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::{DynamicImage, GenericImageView};
+
+use crate::draw::{restore_color_type, with_alpha, Canvas};
+
+#[derive(Args, Debug)]
+pub struct MaskArgs {
+    /// Round the image corners with this radius (pixels)
+    #[arg(long, value_name = "PX", conflicts_with = "circle")]
+    pub round_corners: Option<u32>,
+
+    /// Crop to a centered square and cut out a circle (avatars)
+    #[arg(long)]
+    pub circle: bool,
+}
+
+impl MaskArgs {
+    /// Whether a corner or circle mask is applied
+    pub fn is_active(&self) -> bool {
+        self.round_corners.is_some() || self.circle
+    }
+}
+
+/// Apply the requested mask, returning the image unchanged when none was requested
+pub fn apply(img: DynamicImage, args: &MaskArgs) -> Result<DynamicImage> {
+    let color_type = img.color();
+    let canvas = if args.circle {
+        let (width, height) = img.dimensions();
+        let side = width.min(height);
+        let square = img.crop_imm((width - side) / 2, (height - side) / 2, side, side).to_rgba16();
+        apply_mask(square, side as f32 / 2.0)
+    } else if let Some(radius) = args.round_corners {
+        let (width, height) = img.dimensions();
+        let radius = (radius as f32).min(width.min(height) as f32 / 2.0);
+        apply_mask(img.to_rgba16(), radius)
+    } else {
+        return Ok(img);
+    };
+
+    Ok(restore_color_type(with_alpha(color_type), canvas))
+}
+
+/// Multiply alpha by an anti-aliased rounded-rectangle mask with corner `radius`
+fn apply_mask(mut canvas: Canvas, radius: f32) -> Canvas {
+    let (width, height) = (canvas.width() as f32, canvas.height() as f32);
+
+    for (x, y, pixel) in canvas.enumerate_pixels_mut() {
+        // Sample at the pixel center, measured from the nearest corner circle center
+        let px = x as f32 + 0.5;
+        let py = y as f32 + 0.5;
+        let cx = px.clamp(radius, width - radius);
+        let cy = py.clamp(radius, height - radius);
+        let distance = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
+        let coverage = (radius - distance + 0.5).clamp(0.0, 1.0);
+
+        if coverage < 1.0 {
+            pixel.0[3] = (pixel.0[3] as f32 * coverage).round() as u16;
+        }
+    }
+
+    canvas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn test_circle_mask_corners_transparent() {
+        let masked = apply_mask(Canvas::from_pixel(20, 20, Rgba([65535, 0, 0, 65535])), 10.0);
+        assert_eq!(masked.get_pixel(0, 0).0[3], 0);
+        assert_eq!(masked.get_pixel(10, 10).0[3], 65535);
+        assert_eq!(masked.get_pixel(10, 2).0[3], 65535);
+    }
+
+    #[test]
+    fn test_keeps_depth_and_adds_alpha() {
+        let gray = DynamicImage::ImageLuma16(image::ImageBuffer::from_pixel(20, 20, image::Luma([1000u16])));
+        let out = apply(gray, &MaskArgs { round_corners: Some(5), circle: false }).unwrap();
+        assert_eq!(out.color(), image::ColorType::La16);
+        assert_eq!(out.to_luma_alpha16().get_pixel(10, 10).0, [1000, 65535]);
+    }
+}
@@ -0,0 +1,170 @@
+// File: src\montage.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: `imgconv montage` - contact sheet of thumbnails
+// License: MIT
+
+use ab_glyph::{Font, PxScale, ScaleFont};
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use image::{imageops, DynamicImage, GenericImageView, RgbaImage};
+use std::path::{Path, PathBuf};
+
+use crate::annotate::{draw_line, line_width, load_font};
+use crate::color::Color;
+use crate::encode::save_image;
+use crate::exifthumb;
+use crate::geometry::Size;
+use crate::i18n::tr;
+use crate::{detect_format_from_path, load_image, print_info, print_success, print_warning};
+
+#[derive(Args, Debug)]
+pub struct MontageArgs {
+    /// Input images followed by the output sheet
+    #[arg(value_name = "FILES", num_args = 2.., required = true)]
+    files: Vec<PathBuf>,
+
+    /// Number of thumbnails per row
+    #[arg(long, default_value = "5", value_name = "NUM")]
+    columns: u32,
+
+    /// Size of each thumbnail cell
+    #[arg(long, default_value = "256x256", value_name = "WxH")]
+    cell: Size,
+
+    /// Space between cells and around the sheet (pixels)
+    #[arg(long, default_value = "8", value_name = "PX")]
+    spacing: u32,
+
+    /// Caption drawn under each thumbnail
+    #[arg(long, value_enum, default_value = "none", requires_if("filename", "font"))]
+    label: Label,
+
+    /// TrueType/OpenType font used for labels
+    #[arg(long, value_name = "FILE")]
+    font: Option<PathBuf>,
+
+    /// Label font size in pixels
+    #[arg(long, default_value = "14", value_name = "PX")]
+    font_size: f32,
+
+    /// Sheet background color
+    #[arg(long, default_value = "#ffffff", value_name = "COLOR")]
+    background: Color,
+
+    /// Label color
+    #[arg(long, default_value = "#000000", value_name = "COLOR")]
+    text_color: Color,
+
+    /// Use EXIF-embedded thumbnails when present instead of decoding full images (much faster)
+    #[arg(long)]
+    embedded_thumbnail: bool,
+
+    /// Quality for lossy output formats (1-100)
+    #[arg(short, long, default_value = "90", value_name = "NUM", value_parser = clap::value_parser!(u8).range(1..=100))]
+    quality: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Label {
+    None,
+    Filename,
+}
+
+pub fn run(args: &MontageArgs) -> Result<()> {
+    let (output, inputs) = args.files.split_last().context("Output file is required")?;
+    let format = detect_format_from_path(output)
+        .with_context(|| format!("Could not determine output format from '{}'", output.display()))?;
+    if args.columns == 0 {
+        anyhow::bail!("Columns must be greater than 0");
+    }
+
+    let thumbnails: Vec<(&Path, DynamicImage)> = inputs
+        .iter()
+        .filter_map(|path| match load_thumbnail_source(path, args.embedded_thumbnail) {
+            Ok(img) => Some((path.as_path(), img.thumbnail(args.cell.width, args.cell.height))),
+            Err(e) => {
+                print_warning(&tr!("Skipping {}: {}", path.display(), format!("{:#}", e)));
+                None
+            }
+        })
+        .collect();
+    if thumbnails.is_empty() {
+        anyhow::bail!("No readable images to put on the sheet");
+    }
+    print_info(&tr!("Building contact sheet of {} images", thumbnails.len()));
+
+    let font = match (args.label, &args.font) {
+        (Label::Filename, Some(path)) => Some(load_font(path)?),
+        _ => None,
+    };
+    let scale = PxScale::from(args.font_size);
+    let label_height = font.as_ref().map_or(0, |f| {
+        let scaled = f.as_scaled(scale);
+        (scaled.height() + scaled.line_gap()).ceil() as u32 + args.spacing / 2
+    });
+
+    let columns = args.columns.min(thumbnails.len() as u32);
+    let rows = (thumbnails.len() as u32).div_ceil(columns);
+    let pitch_x = args.cell.width + args.spacing;
+    let pitch_y = args.cell.height + label_height + args.spacing;
+    let mut sheet = RgbaImage::from_pixel(
+        columns * pitch_x + args.spacing,
+        rows * pitch_y + args.spacing,
+        args.background.0,
+    );
+
+    for (i, (path, thumb)) in thumbnails.iter().enumerate() {
+        let cell_x = args.spacing + (i as u32 % columns) * pitch_x;
+        let cell_y = args.spacing + (i as u32 / columns) * pitch_y;
+        let (thumb_w, thumb_h) = thumb.dimensions();
+
+        // Center the thumbnail inside its cell
+        let x = cell_x + (args.cell.width - thumb_w) / 2;
+        let y = cell_y + (args.cell.height - thumb_h) / 2;
+        imageops::overlay(&mut sheet, &thumb.to_rgba8(), x as i64, y as i64);
+
+        if let Some(font) = &font {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let label = fit_label(font, scale, &name, args.cell.width as f32);
+            let width = line_width(font, scale, &label);
+            let baseline = (cell_y + args.cell.height + args.spacing / 2) as f32 + font.as_scaled(scale).ascent();
+            let label_x = cell_x as f32 + (args.cell.width as f32 - width).max(0.0) / 2.0;
+            draw_line(&mut sheet, font, scale, &label, label_x, baseline, args.text_color.0);
+        }
+    }
+
+    let sheet = DynamicImage::ImageRgba8(sheet);
+    save_image(&sheet, format, args.quality, output)?;
+    print_success(&tr!(
+        "Contact sheet saved to: {} ({}x{})",
+        output.display(), sheet.width(), sheet.height()
+    ));
+    Ok(())
+}
+
+/// Shorten a label with an ellipsis until it fits `max_width`
+fn fit_label(font: &ab_glyph::FontVec, scale: PxScale, name: &str, max_width: f32) -> String {
+    if line_width(font, scale, name) <= max_width {
+        return name.to_string();
+    }
+    let mut chars: Vec<char> = name.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let candidate: String = chars.iter().collect::<String>() + "…";
+        if line_width(font, scale, &candidate) <= max_width {
+            return candidate;
+        }
+    }
+    String::new()
+}
+
+/// The full image, or its EXIF thumbnail when asked for and present
+fn load_thumbnail_source(path: &Path, embedded: bool) -> Result<DynamicImage> {
+    if embedded {
+        if let Some(img) = exifthumb::load(path)? {
+            return Ok(img);
+        }
+    }
+    Ok(load_image(path)?.0)
+}
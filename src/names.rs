@@ -0,0 +1,63 @@
+// File: src\names.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: URL-safe output file names
+// License: MIT
+
+use std::path::{Component, Path, PathBuf};
+
+/// Make a file name URL-safe: spaces and other unsafe characters become
+/// dashes, runs collapse, and the extension is lowercased
+///
+/// "Summer Photo (1).JPG" -> "Summer-Photo-1.jpg"
+pub fn sanitize(name: &str) -> String {
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, Some(ext)),
+        _ => (name, None),
+    };
+
+    let mut clean = String::with_capacity(stem.len());
+    for c in stem.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+            clean.push(c);
+        } else if !clean.ends_with('-') {
+            clean.push('-');
+        }
+    }
+    let clean = clean.trim_matches(|c| c == '-' || c == '.');
+    let clean = if clean.is_empty() { "image" } else { clean };
+
+    match ext.map(|e| e.to_ascii_lowercase()).filter(|e| e.chars().all(|c| c.is_ascii_alphanumeric())) {
+        Some(ext) => format!("{}.{}", clean, ext),
+        None => clean.to_string(),
+    }
+}
+
+/// Sanitize every component of a relative path
+pub fn sanitize_path(path: &Path) -> PathBuf {
+    path.components()
+        .map(|component| match component {
+            Component::Normal(name) => PathBuf::from(sanitize(&name.to_string_lossy())),
+            other => PathBuf::from(other.as_os_str()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(sanitize("Summer Photo (1).JPG"), "Summer-Photo-1.jpg");
+        assert_eq!(sanitize("café  menu.PNG"), "caf-menu.png");
+        assert_eq!(sanitize("already_fine.v2.webp"), "already_fine.v2.webp");
+        assert_eq!(sanitize("日本.png"), "image.png");
+        assert_eq!(sanitize(".hidden"), "hidden");
+    }
+
+    #[test]
+    fn test_sanitize_path() {
+        assert_eq!(sanitize_path(Path::new("My Trip/Day 1.JPG")), PathBuf::from("My-Trip/Day-1.jpg"));
+    }
+}
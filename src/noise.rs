@@ -0,0 +1,193 @@
+// File: src\noise.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Noise reduction (bilateral/median) and film grain
+// License: MIT
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use image::DynamicImage;
+
+use crate::draw::{restore_color_type, Canvas};
+use crate::i18n::tr;
+use crate::print_info;
+
+#[derive(Args, Debug)]
+pub struct NoiseArgs {
+    /// Reduce noise before encoding (0.0 - 1.0)
+    #[arg(long, value_name = "STRENGTH")]
+    pub denoise: Option<f32>,
+
+    /// Noise reduction filter used by --denoise
+    #[arg(long, value_enum, default_value = "bilateral", value_name = "METHOD")]
+    pub denoise_method: DenoiseMethod,
+
+    /// Add monochrome film grain (0.0 - 1.0)
+    #[arg(long, value_name = "AMOUNT")]
+    pub add_grain: Option<f32>,
+}
+
+impl NoiseArgs {
+    /// Whether noise is removed or added
+    pub fn is_active(&self) -> bool {
+        self.denoise.is_some() || self.add_grain.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DenoiseMethod {
+    /// Edge-preserving smoothing, good for sensor and compression noise
+    Bilateral,
+    /// Per-channel median, good for salt-and-pepper specks
+    Median,
+}
+
+/// Apply `--denoise`, returning the image unchanged when not requested
+pub fn apply_denoise(img: DynamicImage, args: &NoiseArgs) -> Result<DynamicImage> {
+    let Some(strength) = args.denoise else {
+        return Ok(img);
+    };
+    if !(0.0..=1.0).contains(&strength) {
+        anyhow::bail!("Denoise strength must be between 0.0 and 1.0, got: {}", strength);
+    }
+
+    let color_type = img.color();
+    let canvas = img.to_rgba16();
+    let denoised = match args.denoise_method {
+        // The color range is in 8-bit levels, scaled to the 16-bit canvas
+        DenoiseMethod::Bilateral => bilateral(&canvas, 1.0 + 2.0 * strength, (10.0 + 40.0 * strength) * 257.0),
+        DenoiseMethod::Median => median(&canvas, 1 + (strength * 2.0).round() as u32),
+    };
+    print_info(&tr!("Denoised ({}, strength {})", format!("{:?}", args.denoise_method), strength));
+    Ok(restore_color_type(color_type, denoised))
+}
+
+/// Apply `--add-grain`, returning the image unchanged when not requested
+pub fn apply_grain(img: DynamicImage, args: &NoiseArgs) -> Result<DynamicImage> {
+    let Some(amount) = args.add_grain else {
+        return Ok(img);
+    };
+    if !(0.0..=1.0).contains(&amount) {
+        anyhow::bail!("Grain amount must be between 0.0 and 1.0, got: {}", amount);
+    }
+
+    let color_type = img.color();
+    let mut canvas = img.to_rgba16();
+    // Fixed seed so the same input always gets the same grain
+    let mut rng = SplitMix64(0x1DB3_5EED);
+    let sigma = amount * 32.0 * 257.0;
+    for pixel in canvas.pixels_mut() {
+        let offset = rng.gaussian() * sigma;
+        for c in &mut pixel.0[..3] {
+            *c = (*c as f32 + offset).round().clamp(0.0, 65535.0) as u16;
+        }
+    }
+    Ok(restore_color_type(color_type, canvas))
+}
+
+/// Bilateral filter: neighbors weighted by distance and by color similarity
+fn bilateral(img: &Canvas, sigma_space: f32, sigma_range: f32) -> Canvas {
+    let radius = (sigma_space * 2.0).ceil() as i64;
+    let (width, height) = (img.width() as i64, img.height() as i64);
+    let space_weights: Vec<f32> = (-radius..=radius)
+        .flat_map(|dy| (-radius..=radius).map(move |dx| (dx, dy)))
+        .map(|(dx, dy)| (-((dx * dx + dy * dy) as f32) / (2.0 * sigma_space * sigma_space)).exp())
+        .collect();
+    let range_denominator = 2.0 * sigma_range * sigma_range;
+
+    Canvas::from_fn(img.width(), img.height(), |x, y| {
+        let center = img.get_pixel(x, y).0;
+        let mut sum = [0f32; 3];
+        let mut total = 0f32;
+        let mut i = 0;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let nx = (x as i64 + dx).clamp(0, width - 1) as u32;
+                let ny = (y as i64 + dy).clamp(0, height - 1) as u32;
+                let p = img.get_pixel(nx, ny).0;
+                let diff: f32 = (0..3).map(|c| (p[c] as f32 - center[c] as f32).powi(2)).sum();
+                let weight = space_weights[i] * (-diff / range_denominator).exp();
+                for c in 0..3 {
+                    sum[c] += p[c] as f32 * weight;
+                }
+                total += weight;
+                i += 1;
+            }
+        }
+        let [r, g, b] = sum.map(|v| (v / total).round() as u16);
+        image::Rgba([r, g, b, center[3]])
+    })
+}
+
+/// Median of each color channel over a (2r+1)² window
+fn median(img: &Canvas, radius: u32) -> Canvas {
+    let r = radius as i64;
+    let (width, height) = (img.width() as i64, img.height() as i64);
+    let mut window: [Vec<u16>; 3] = Default::default();
+
+    Canvas::from_fn(img.width(), img.height(), |x, y| {
+        window.iter_mut().for_each(Vec::clear);
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let nx = (x as i64 + dx).clamp(0, width - 1) as u32;
+                let ny = (y as i64 + dy).clamp(0, height - 1) as u32;
+                let p = img.get_pixel(nx, ny).0;
+                for c in 0..3 {
+                    window[c].push(p[c]);
+                }
+            }
+        }
+        let mid = window[0].len() / 2;
+        let [r, g, b] = [0, 1, 2].map(|c| *window[c].select_nth_unstable(mid).1);
+        image::Rgba([r, g, b, img.get_pixel(x, y).0[3]])
+    })
+}
+
+/// Small deterministic PRNG, enough for grain
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_f32(&mut self) -> f32 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        ((z ^ (z >> 31)) >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Approximately standard normal (sum of four uniforms)
+    fn gaussian(&mut self) -> f32 {
+        let sum: f32 = (0..4).map(|_| self.next_f32()).sum();
+        (sum - 2.0) * 3f32.sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn test_median_removes_specks() {
+        let mut img = Canvas::from_pixel(5, 5, Rgba([25700, 25700, 25700, 65535]));
+        img.put_pixel(2, 2, Rgba([65535, 0, 65535, 65535]));
+        let out = median(&img, 1);
+        assert_eq!(*out.get_pixel(2, 2), Rgba([25700, 25700, 25700, 65535]));
+    }
+
+    #[test]
+    fn test_bilateral_keeps_edges() {
+        let img = Canvas::from_fn(8, 8, |x, _| if x < 4 { Rgba([0, 0, 0, 65535]) } else { Rgba([65535, 65535, 65535, 65535]) });
+        let out = bilateral(&img, 2.0, 20.0 * 257.0);
+        assert!(out.get_pixel(3, 4).0[0] < 5 * 257 && out.get_pixel(4, 4).0[0] > 250 * 257);
+    }
+
+    #[test]
+    fn test_grain_is_deterministic() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 8, Rgba([128, 128, 128, 255])));
+        let args = NoiseArgs { denoise: None, denoise_method: DenoiseMethod::Bilateral, add_grain: Some(0.5) };
+        let a = apply_grain(img.clone(), &args).unwrap();
+        assert_eq!(a, apply_grain(img, &args).unwrap());
+        assert!(a.to_rgba8().pixels().any(|p| p.0[0] != 128));
+    }
+}
@@ -0,0 +1,168 @@
+// File: src\optimize.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Lossless PNG optimization: smallest color type, filter and deflate level
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::{DynamicImage, GenericImageView};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::encode::{pack_indices, palette_bits, write_bytes};
+use crate::estimate::format_size;
+use crate::i18n::tr;
+use crate::print_info;
+
+/// Row filters tried for every candidate encoding
+const FILTERS: [png::Filter; 7] = [
+    png::Filter::NoFilter,
+    png::Filter::Sub,
+    png::Filter::Up,
+    png::Filter::Avg,
+    png::Filter::Paeth,
+    png::Filter::Adaptive,
+    png::Filter::MinEntropy,
+];
+
+/// Strongest zlib level
+const DEFLATE_LEVEL: u8 = 9;
+
+#[derive(Args, Debug)]
+pub struct OptimizeArgs {
+    /// Losslessly shrink PNG output: reduce the color type, try every filter at max compression
+    #[arg(long)]
+    pub optimize: bool,
+}
+
+/// Pixel data in one PNG color type, ready to compress
+#[derive(Debug, Clone, PartialEq)]
+struct Candidate {
+    color: png::ColorType,
+    depth: png::BitDepth,
+    palette: Option<Vec<u8>>,
+    trns: Option<Vec<u8>>,
+    data: Vec<u8>,
+}
+
+/// Write `img` as the smallest PNG found, with no ancillary chunks
+///
+/// Only IHDR, PLTE, tRNS, IDAT and IEND are written, so no text, time or
+/// color-profile chunks end up in the file; pixels are unchanged.
+pub fn save_png(img: &DynamicImage, path: &Path) -> Result<()> {
+    let (width, height) = img.dimensions();
+    let mut best: Option<(Vec<u8>, String)> = None;
+    for candidate in candidates(img) {
+        for filter in FILTERS {
+            let data = encode(&candidate, width, height, filter)?;
+            if best.as_ref().is_none_or(|(smallest, _)| data.len() < smallest.len()) {
+                best = Some((data, format!("{:?} {}-bit, {:?} filter", candidate.color, candidate.depth as u8, filter)));
+            }
+        }
+    }
+    let (data, choice) = best.context("No PNG encoding candidates")?;
+    print_info(&tr!("Optimized PNG: {} ({})", format_size(data.len() as u64), choice));
+    write_bytes(path, &data)
+}
+
+fn encode(candidate: &Candidate, width: u32, height: u32, filter: png::Filter) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(candidate.color);
+    encoder.set_depth(candidate.depth);
+    encoder.set_deflate_compression(png::DeflateCompression::Level(DEFLATE_LEVEL));
+    encoder.set_filter(filter);
+    if let Some(palette) = &candidate.palette {
+        encoder.set_palette(palette.clone());
+    }
+    if let Some(trns) = &candidate.trns {
+        encoder.set_trns(trns.clone());
+    }
+    let mut writer = encoder.write_header().context("Failed to encode PNG image")?;
+    writer.write_image_data(&candidate.data).context("Failed to encode PNG image")?;
+    writer.finish().context("Failed to encode PNG image")?;
+    Ok(out)
+}
+
+/// Lossless representations of `img`: the narrowest truecolor/gray type and,
+/// for 256 colors or fewer, a palette
+fn candidates(img: &DynamicImage) -> Vec<Candidate> {
+    let wide = img.color().bytes_per_pixel() / img.color().channel_count() > 1;
+    let rgba16 = wide.then(|| img.to_rgba16());
+    // 16-bit samples are kept only when some value needs the extra precision
+    if let Some(rgba16) = rgba16.filter(|rgba16| rgba16.as_raw().iter().any(|&v| v >> 8 != v & 0xff)) {
+        let opaque = rgba16.pixels().all(|p| p.0[3] == u16::MAX);
+        let gray = rgba16.pixels().all(|p| p.0[0] == p.0[1] && p.0[1] == p.0[2]);
+        let (color, channels) = color_type(gray, opaque);
+        let data = rgba16.pixels()
+            .flat_map(|p| channels.iter().map(move |&c| p.0[c]))
+            .flat_map(u16::to_be_bytes)
+            .collect();
+        return vec![Candidate { color, depth: png::BitDepth::Sixteen, palette: None, trns: None, data }];
+    }
+
+    let rgba = img.to_rgba8();
+    let opaque = rgba.pixels().all(|p| p.0[3] == 255);
+    let gray = rgba.pixels().all(|p| p.0[0] == p.0[1] && p.0[1] == p.0[2]);
+    let (color, channels) = color_type(gray, opaque);
+    let data = rgba.pixels().flat_map(|p| channels.iter().map(move |&c| p.0[c])).collect();
+    let mut candidates = vec![Candidate { color, depth: png::BitDepth::Eight, palette: None, trns: None, data }];
+
+    let mut colors: HashMap<[u8; 4], u8> = HashMap::new();
+    let mut indices = Vec::with_capacity(rgba.len() / 4);
+    for pixel in rgba.pixels() {
+        let next = colors.len();
+        if next == 256 && !colors.contains_key(&pixel.0) {
+            return candidates;
+        }
+        indices.push(*colors.entry(pixel.0).or_insert(next as u8));
+    }
+    let mut palette = vec![[0u8; 4]; colors.len()];
+    for (color, index) in colors {
+        palette[index as usize] = color;
+    }
+    let bits = palette_bits(palette.len());
+    candidates.push(Candidate {
+        color: png::ColorType::Indexed,
+        depth: png::BitDepth::from_u8(bits).unwrap_or(png::BitDepth::Eight),
+        palette: Some(palette.iter().flat_map(|c| [c[0], c[1], c[2]]).collect()),
+        trns: palette.iter().rposition(|c| c[3] < 255).map(|last| palette[..=last].iter().map(|c| c[3]).collect()),
+        data: pack_indices(&indices, rgba.width(), bits),
+    });
+    candidates
+}
+
+/// PNG color type and the RGBA channels it keeps
+fn color_type(gray: bool, opaque: bool) -> (png::ColorType, &'static [usize]) {
+    match (gray, opaque) {
+        (true, true) => (png::ColorType::Grayscale, &[0]),
+        (true, false) => (png::ColorType::GrayscaleAlpha, &[0, 3]),
+        (false, true) => (png::ColorType::Rgb, &[0, 1, 2]),
+        (false, false) => (png::ColorType::Rgba, &[0, 1, 2, 3]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn test_optimized_png_is_lossless_and_reduced() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_fn(40, 30, |x, y| {
+            let v = ((x / 10 + y / 10) * 60) as u8;
+            Rgba([v, v, v, 255])
+        }));
+        let found = candidates(&img);
+        assert_eq!(found[0].color, png::ColorType::Grayscale);
+        assert_eq!(found[1].color, png::ColorType::Indexed);
+        assert_eq!(found[1].depth, png::BitDepth::Four);
+
+        let path = std::env::temp_dir().join(format!("imgconv-optimize-{}.png", std::process::id()));
+        save_png(&img, &path).unwrap();
+        let decoded = image::open(&path).unwrap().to_rgba8();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(decoded, img.to_rgba8());
+    }
+}
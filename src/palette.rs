@@ -0,0 +1,214 @@
+// File: src\palette.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: `imgconv palette` - extract dominant colors and export swatches
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use image::{Rgba, RgbaImage};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::depth::Dither;
+use crate::encode::write_bytes;
+use crate::i18n::tr;
+use crate::quantize::{build_palette, remap, QuantAlgo};
+use crate::{load_image, print_info, print_success};
+
+#[derive(Args, Debug)]
+pub struct PaletteArgs {
+    /// Image to extract colors from
+    #[arg(value_name = "INPUT")]
+    input: PathBuf,
+
+    /// Number of colors to extract (2-256)
+    #[arg(long, default_value = "16", value_name = "N", value_parser = clap::value_parser!(u16).range(2..=256))]
+    colors: u16,
+
+    /// Palette algorithm
+    #[arg(long, value_enum, default_value = "wu", value_name = "ALGO")]
+    algo: QuantAlgo,
+
+    /// Write the palette to a file (.gpl, .aco or .json)
+    #[arg(long, value_name = "FILE")]
+    export: Option<PathBuf>,
+
+    /// Swatch format (defaults to the --export extension)
+    #[arg(long, value_enum, value_name = "FORMAT", requires = "export")]
+    format: Option<SwatchFormat>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SwatchFormat {
+    /// GIMP / Inkscape / Krita palette
+    Gpl,
+    /// Adobe color swatch (Photoshop)
+    Aco,
+    /// JSON array of colors
+    Json,
+}
+
+/// A palette color and the share of pixels closest to it
+#[derive(Debug, Clone, PartialEq)]
+struct Swatch {
+    color: Rgba<u8>,
+    share: f64,
+}
+
+#[derive(Serialize)]
+struct JsonPalette<'a> {
+    name: &'a str,
+    colors: Vec<JsonColor>,
+}
+
+#[derive(Serialize)]
+struct JsonColor {
+    hex: String,
+    rgb: [u8; 3],
+    share: f64,
+}
+
+pub fn run(args: &PaletteArgs) -> Result<()> {
+    let format = match (&args.export, args.format) {
+        (Some(_), Some(format)) => Some(format),
+        (Some(path), None) => Some(format_from_extension(path)?),
+        (None, _) => None,
+    };
+
+    let (img, _) = load_image(&args.input)?;
+    let swatches = extract(&img.to_rgba8(), args.colors as usize, args.algo)?;
+    print_info(&tr!("Extracted {} colors ({})", swatches.len(), format!("{:?}", args.algo)));
+
+    for swatch in &swatches {
+        let [r, g, b, _] = swatch.color.0;
+        let rgb = format!("rgb({}, {}, {})", r, g, b);
+        println!("#{:02x}{:02x}{:02x}  {:<18} {:5.1}%", r, g, b, rgb, swatch.share * 100.0);
+    }
+
+    if let (Some(path), Some(format)) = (&args.export, format) {
+        let name = args.input.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        let data = match format {
+            SwatchFormat::Gpl => to_gpl(&name, &swatches).into_bytes(),
+            SwatchFormat::Aco => to_aco(&swatches),
+            SwatchFormat::Json => to_json(&name, &swatches)?.into_bytes(),
+        };
+        write_bytes(path, &data)?;
+        print_success(&tr!("Palette saved to: {}", path.display()));
+    }
+    Ok(())
+}
+
+fn format_from_extension(path: &Path) -> Result<SwatchFormat> {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    match ext.as_str() {
+        "gpl" => Ok(SwatchFormat::Gpl),
+        "aco" => Ok(SwatchFormat::Aco),
+        "json" => Ok(SwatchFormat::Json),
+        _ => anyhow::bail!("Unknown palette extension '{}', use .gpl, .aco or .json or pass --format", ext),
+    }
+}
+
+/// Quantize the visible pixels and rank the colors by coverage
+fn extract(img: &RgbaImage, colors: usize, algo: QuantAlgo) -> Result<Vec<Swatch>> {
+    // Fully transparent pixels carry no visible color
+    let visible: Vec<u8> = img.pixels().filter(|p| p.0[3] > 0).flat_map(|p| [p.0[0], p.0[1], p.0[2], 255]).collect();
+    let count = visible.len() / 4;
+    if count == 0 {
+        anyhow::bail!("Image has no visible pixels");
+    }
+    let visible = RgbaImage::from_raw(count as u32, 1, visible).context("Failed to collect pixels")?;
+
+    let palette = build_palette(&visible, colors, algo);
+    let mut counts = vec![0usize; palette.len()];
+    for index in remap(&visible, &palette, Dither::None) {
+        counts[index as usize] += 1;
+    }
+
+    let mut swatches: Vec<Swatch> = palette
+        .iter()
+        .zip(counts)
+        .filter(|(_, n)| *n > 0)
+        .map(|(&color, n)| Swatch { color, share: n as f64 / count as f64 })
+        .collect();
+    swatches.sort_by(|a, b| b.share.total_cmp(&a.share));
+    Ok(swatches)
+}
+
+fn to_gpl(name: &str, swatches: &[Swatch]) -> String {
+    let mut out = format!("GIMP Palette\nName: {}\nColumns: 8\n#\n", name);
+    for swatch in swatches {
+        let [r, g, b, _] = swatch.color.0;
+        out.push_str(&format!("{:3} {:3} {:3}\t#{:02x}{:02x}{:02x}\n", r, g, b, r, g, b));
+    }
+    out
+}
+
+/// Adobe .aco: a version 1 block followed by a version 2 block with names
+fn to_aco(swatches: &[Swatch]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for version in [1u16, 2] {
+        out.extend_from_slice(&version.to_be_bytes());
+        out.extend_from_slice(&(swatches.len() as u16).to_be_bytes());
+        for swatch in swatches {
+            let [r, g, b, _] = swatch.color.0;
+            // Color space 0 = RGB, components scaled to 16 bits, fourth unused
+            for value in [0, r as u16 * 257, g as u16 * 257, b as u16 * 257, 0] {
+                out.extend_from_slice(&value.to_be_bytes());
+            }
+            if version == 2 {
+                let name: Vec<u16> = format!("#{:02x}{:02x}{:02x}", r, g, b).encode_utf16().chain([0]).collect();
+                out.extend_from_slice(&(name.len() as u32).to_be_bytes());
+                name.iter().for_each(|unit| out.extend_from_slice(&unit.to_be_bytes()));
+            }
+        }
+    }
+    out
+}
+
+fn to_json(name: &str, swatches: &[Swatch]) -> Result<String> {
+    let palette = JsonPalette {
+        name,
+        colors: swatches
+            .iter()
+            .map(|s| {
+                let [r, g, b, _] = s.color.0;
+                JsonColor {
+                    hex: format!("#{:02x}{:02x}{:02x}", r, g, b),
+                    rgb: [r, g, b],
+                    share: (s.share * 10000.0).round() / 10000.0,
+                }
+            })
+            .collect(),
+    };
+    Ok(serde_json::to_string_pretty(&palette).context("Failed to serialize palette")? + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swatches() -> Vec<Swatch> {
+        vec![
+            Swatch { color: Rgba([255, 0, 0, 255]), share: 0.75 },
+            Swatch { color: Rgba([0, 0, 255, 255]), share: 0.25 },
+        ]
+    }
+
+    #[test]
+    fn test_extract_ranks_by_coverage() {
+        let img = RgbaImage::from_fn(4, 4, |x, _| if x < 3 { Rgba([255, 0, 0, 255]) } else { Rgba([0, 0, 255, 255]) });
+        assert_eq!(extract(&img, 4, QuantAlgo::Wu).unwrap(), swatches());
+    }
+
+    #[test]
+    fn test_swatch_formats() {
+        assert!(to_gpl("logo", &swatches()).ends_with("255   0   0\t#ff0000\n  0   0 255\t#0000ff\n"));
+
+        let aco = to_aco(&swatches());
+        assert_eq!(&aco[..4], &[0, 1, 0, 2]);
+        assert_eq!(&aco[4..14], &[0, 0, 255, 255, 0, 0, 0, 0, 0, 0]);
+        // v1: 4 + 2 * 10 bytes; v2 adds a 4-byte length and 8 UTF-16 units per name
+        assert_eq!(aco.len(), 24 + 4 + 2 * (10 + 4 + 16));
+    }
+}
@@ -0,0 +1,71 @@
+// File: src\perms.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Carry the source file's permissions (and optionally owner) to the output
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use std::fs::Metadata;
+use std::path::Path;
+
+use crate::i18n::tr;
+use crate::print_warning;
+
+#[derive(Args, Debug)]
+pub struct PermsArgs {
+    /// Also copy the source file's owner and group (Unix, needs privileges)
+    #[arg(long)]
+    pub preserve_owner: bool,
+}
+
+/// Read the source's metadata up front; in-place runs replace the file itself
+pub fn read(source: &Path) -> Result<Metadata> {
+    std::fs::metadata(source).with_context(|| format!("Failed to read permissions of: {}", source.display()))
+}
+
+/// Give `output` the permission bits of the source, and its owner if requested
+pub fn apply(source: &Metadata, output: &Path, args: &PermsArgs) -> Result<()> {
+    std::fs::set_permissions(output, source.permissions())
+        .with_context(|| format!("Failed to set permissions on: {}", output.display()))?;
+
+    if args.preserve_owner {
+        copy_owner(source, output);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_owner(metadata: &Metadata, output: &Path) {
+    use std::os::unix::fs::MetadataExt;
+
+    // Unprivileged users may only chown to themselves; not worth failing the conversion
+    if let Err(e) = std::os::unix::fs::chown(output, Some(metadata.uid()), Some(metadata.gid())) {
+        print_warning(&tr!("Could not preserve owner of {} ({})", output.display(), e));
+    }
+}
+
+#[cfg(not(unix))]
+fn copy_owner(_metadata: &Metadata, _output: &Path) {
+    print_warning(&tr!("--preserve-owner is only supported on Unix"));
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_copies_mode_bits() {
+        let dir = std::env::temp_dir().join(format!("imgconv-perms-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (source, output) = (dir.join("in.png"), dir.join("out.png"));
+        std::fs::write(&source, b"").unwrap();
+        std::fs::write(&output, b"").unwrap();
+        std::fs::set_permissions(&source, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+        apply(&read(&source).unwrap(), &output, &PermsArgs { preserve_owner: true }).unwrap();
+        assert_eq!(std::fs::metadata(&output).unwrap().permissions().mode() & 0o777, 0o640);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,148 @@
+// File: src\pick.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: `imgconv pick` - pixel and region color inspector
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::{GenericImageView, Rgba};
+use std::path::PathBuf;
+
+use crate::geometry::{Point, Rect};
+use crate::load_image;
+
+#[derive(Args, Debug)]
+pub struct PickArgs {
+    /// Image to inspect
+    #[arg(value_name = "INPUT")]
+    input: PathBuf,
+
+    /// Print the color of a single pixel
+    #[arg(long, value_name = "X,Y", conflicts_with = "region")]
+    at: Option<Point>,
+
+    /// Print every pixel in a rectangle (or summarize it with --stats)
+    #[arg(long, value_name = "X,Y,W,H")]
+    region: Option<Rect>,
+
+    /// Print per-channel min/max/mean/stddev over the region (or whole image)
+    #[arg(long, conflicts_with = "at")]
+    stats: bool,
+}
+
+pub fn run(args: &PickArgs) -> Result<()> {
+    let (img, _) = load_image(&args.input)?;
+    let (width, height) = img.dimensions();
+
+    if let Some(point) = args.at {
+        if point.x >= width || point.y >= height {
+            anyhow::bail!("Point {},{} is outside the {}x{} image", point.x, point.y, width, height);
+        }
+        println!("{}", describe_pixel(point.x, point.y, img.get_pixel(point.x, point.y)));
+        return Ok(());
+    }
+
+    let region = match args.region {
+        Some(rect) => rect.clamp_to(width, height)
+            .with_context(|| format!("Region is outside the {}x{} image", width, height))?,
+        None if args.stats => Rect { x: 0, y: 0, width, height },
+        None => anyhow::bail!("Specify --at X,Y, --region X,Y,W,H or --stats"),
+    };
+
+    let pixels = (region.y..region.y + region.height)
+        .flat_map(|y| (region.x..region.x + region.width).map(move |x| (x, y)))
+        .map(|(x, y)| (x, y, img.get_pixel(x, y)));
+
+    if args.stats {
+        let stats = RegionStats::collect(pixels.map(|(_, _, p)| p));
+        stats.print(&region);
+    } else {
+        for (x, y, pixel) in pixels {
+            println!("{}", describe_pixel(x, y, pixel));
+        }
+    }
+
+    Ok(())
+}
+
+fn to_hex(rgba: [u8; 4]) -> String {
+    format!("#{:02x}{:02x}{:02x}{:02x}", rgba[0], rgba[1], rgba[2], rgba[3])
+}
+
+fn describe_pixel(x: u32, y: u32, pixel: Rgba<u8>) -> String {
+    let [r, g, b, a] = pixel.0;
+    format!("{},{} rgba({}, {}, {}, {}) {}", x, y, r, g, b, a, to_hex(pixel.0))
+}
+
+/// Running per-channel statistics over RGBA pixels
+struct RegionStats {
+    count: u64,
+    min: [u8; 4],
+    max: [u8; 4],
+    sum: [f64; 4],
+    sum_sq: [f64; 4],
+}
+
+impl RegionStats {
+    fn collect(pixels: impl Iterator<Item = Rgba<u8>>) -> Self {
+        let mut stats = RegionStats { count: 0, min: [u8::MAX; 4], max: [0; 4], sum: [0.0; 4], sum_sq: [0.0; 4] };
+        for pixel in pixels {
+            stats.count += 1;
+            for (c, &value) in pixel.0.iter().enumerate() {
+                stats.min[c] = stats.min[c].min(value);
+                stats.max[c] = stats.max[c].max(value);
+                stats.sum[c] += value as f64;
+                stats.sum_sq[c] += (value as f64).powi(2);
+            }
+        }
+        stats
+    }
+
+    fn mean(&self) -> [f64; 4] {
+        self.sum.map(|s| s / self.count.max(1) as f64)
+    }
+
+    fn stddev(&self) -> [f64; 4] {
+        let mean = self.mean();
+        let mut out = [0.0; 4];
+        for c in 0..4 {
+            let variance = self.sum_sq[c] / self.count.max(1) as f64 - mean[c].powi(2);
+            out[c] = variance.max(0.0).sqrt();
+        }
+        out
+    }
+
+    fn print(&self, region: &Rect) {
+        let mean = self.mean();
+        let stddev = self.stddev();
+        println!("region  {},{},{},{} ({} pixels)", region.x, region.y, region.width, region.height, self.count);
+        for (c, name) in ["R", "G", "B", "A"].iter().enumerate() {
+            println!(
+                "{}       min {:>3}  max {:>3}  mean {:>7.2}  stddev {:>7.2}",
+                name, self.min[c], self.max[c], mean[c], stddev[c]
+            );
+        }
+        println!("mean    {}", to_hex(mean.map(|m| m.round() as u8)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_stats() {
+        let pixels = vec![Rgba([0, 10, 20, 255]), Rgba([100, 10, 40, 255])];
+        let stats = RegionStats::collect(pixels.into_iter());
+        assert_eq!(stats.min, [0, 10, 20, 255]);
+        assert_eq!(stats.max, [100, 10, 40, 255]);
+        assert_eq!(stats.mean(), [50.0, 10.0, 30.0, 255.0]);
+        assert_eq!(stats.stddev()[0], 50.0);
+    }
+
+    #[test]
+    fn test_describe_pixel() {
+        assert_eq!(describe_pixel(1, 2, Rgba([255, 128, 0, 255])), "1,2 rgba(255, 128, 0, 255) #ff8000ff");
+    }
+}
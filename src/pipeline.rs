@@ -0,0 +1,221 @@
+// File: src\pipeline.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Ordered transform pipeline shared by the library and the command line
+// License: MIT
+
+use image::{DynamicImage, GenericImageView};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::error::{BoxError, ImgconvError};
+use crate::geometry::Gravity;
+use crate::resize::{self, Filter};
+use crate::Resize;
+
+/// A step implemented outside this module, such as the command line's effects
+pub type StepFn<'a> = Arc<dyn Fn(DynamicImage) -> Result<DynamicImage, BoxError> + Send + Sync + 'a>;
+
+/// Quarter turns clockwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Cw90,
+    Cw180,
+    Cw270,
+}
+
+/// One transform step
+#[derive(Clone)]
+pub enum Op<'a> {
+    /// Keep the `width` x `height` area whose top-left corner is at `x`,`y`
+    Crop { x: u32, y: u32, width: u32, height: u32 },
+    /// Resize in linear light instead of sRGB when `linear`, and on the GPU
+    /// when `gpu` (falling back to the CPU, see `resize::resize`)
+    Resize { to: Resize, filter: Filter, linear: bool, gpu: bool },
+    Rotate(Rotation),
+    /// `brightness` is added to every channel (-255 to 255); `contrast` is a
+    /// percentage, negative to lower it
+    ColorAdjust { brightness: i32, contrast: f32 },
+    /// Overlay `image` at `gravity`, `margin` pixels from the edges, with
+    /// its alpha scaled by `opacity` (0 to 1)
+    Watermark { image: Arc<DynamicImage>, gravity: Gravity, margin: u32, opacity: f32 },
+    Custom { name: String, detail: String, apply: StepFn<'a> },
+}
+
+impl Op<'_> {
+    /// Short name, as `--show-pipeline` lists it
+    pub fn name(&self) -> &str {
+        match self {
+            Op::Crop { .. } => "crop",
+            Op::Resize { .. } => "resize",
+            Op::Rotate(_) => "rotate",
+            Op::ColorAdjust { .. } => "color-adjust",
+            Op::Watermark { .. } => "watermark",
+            Op::Custom { name, .. } => name,
+        }
+    }
+
+    /// The step's settings
+    pub fn detail(&self) -> String {
+        match self {
+            Op::Crop { x, y, width, height } => format!("{}x{} at {},{}", width, height, x, y),
+            Op::Resize { to, filter, linear, gpu } => {
+                let (fit, width, height) = match to {
+                    Resize::Fit(width, height) => ("fit", width, height),
+                    Resize::Exact(width, height) => ("exactly", width, height),
+                };
+                let space = if *linear { "linear light" } else { "sRGB" };
+                format!("{} {}x{}, {:?}, {}{}", fit, width, height, filter, space, if *gpu { ", GPU" } else { "" })
+            }
+            Op::Rotate(rotation) => format!("{:?}", rotation),
+            Op::ColorAdjust { brightness, contrast } => format!("brightness {:+}, contrast {:+}%", brightness, contrast),
+            Op::Watermark { image, gravity, margin, opacity } => {
+                format!("{}x{} image, {:?}, margin {}, opacity {}", image.width(), image.height(), gravity, margin, opacity)
+            }
+            Op::Custom { detail, .. } => detail.clone(),
+        }
+    }
+
+    pub fn apply(&self, img: DynamicImage) -> Result<DynamicImage, BoxError> {
+        Ok(match self {
+            Op::Crop { x, y, width, height } => {
+                let (img_w, img_h) = img.dimensions();
+                let outside = |start: u32, len: u32, img_len: u32| start.checked_add(len).is_none_or(|end| end > img_len);
+                if outside(*x, *width, img_w) || outside(*y, *height, img_h) || *width == 0 || *height == 0 {
+                    return Err(format!("Crop {} is outside the {}x{} image", self.detail(), img_w, img_h).into());
+                }
+                img.crop_imm(*x, *y, *width, *height)
+            }
+            Op::Resize { to, filter, linear, gpu } => resize::resize(img, *to, *filter, *linear, *gpu),
+            Op::Rotate(Rotation::Cw90) => img.rotate90(),
+            Op::Rotate(Rotation::Cw180) => img.rotate180(),
+            Op::Rotate(Rotation::Cw270) => img.rotate270(),
+            Op::ColorAdjust { brightness, contrast } => {
+                // `brightness` is in 8-bit steps whatever the depth; float
+                // images go through 16 bits, as the image crate clamps them oddly
+                let img = match img {
+                    DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => DynamicImage::ImageRgba16(img.to_rgba16()),
+                    img => img,
+                };
+                let step = if img.color().bytes_per_pixel() > img.color().channel_count() { 257 } else { 1 };
+                let img = if *brightness != 0 { img.brighten(brightness * step) } else { img };
+                if *contrast != 0.0 { img.adjust_contrast(*contrast) } else { img }
+            }
+            Op::Watermark { image, gravity, margin, opacity } => {
+                let mut mark = image.to_rgba8();
+                for pixel in mark.pixels_mut() {
+                    pixel.0[3] = (pixel.0[3] as f32 * opacity.clamp(0.0, 1.0)).round() as u8;
+                }
+                let (x, y) = gravity.place(img.dimensions(), mark.dimensions(), *margin);
+                let mut canvas = img.to_rgba8();
+                image::imageops::overlay(&mut canvas, &mark, x, y);
+                match img.color().has_alpha() {
+                    true => DynamicImage::ImageRgba8(canvas),
+                    false => DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(canvas).to_rgb8()),
+                }
+            }
+            Op::Custom { apply, .. } => apply(img)?,
+        })
+    }
+}
+
+impl fmt::Debug for Op<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", self.name(), self.detail())
+    }
+}
+
+/// Transform steps, run in the order they were added
+#[derive(Clone, Debug, Default)]
+pub struct Pipeline<'a>(pub Vec<Op<'a>>);
+
+impl<'a> Pipeline<'a> {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn then(mut self, op: Op<'a>) -> Self {
+        self.0.push(op);
+        self
+    }
+
+    pub fn push(&mut self, op: Op<'a>) {
+        self.0.push(op);
+    }
+
+    /// Add a step implemented by `apply`, shown as `name` with `detail`
+    pub fn custom<E: Into<BoxError>>(
+        &mut self,
+        name: &str,
+        detail: impl Into<String>,
+        apply: impl Fn(DynamicImage) -> Result<DynamicImage, E> + Send + Sync + 'a,
+    ) {
+        let apply: StepFn<'a> = Arc::new(move |img| apply(img).map_err(Into::into));
+        self.0.push(Op::Custom { name: name.to_string(), detail: detail.into(), apply });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Run every step on `img`, first to last
+    pub fn apply(&self, img: DynamicImage) -> Result<DynamicImage, ImgconvError> {
+        self.0.iter().try_fold(img, |img, op| {
+            op.apply(img).map_err(|source| ImgconvError::Transform { step: op.name().to_string(), source })
+        })
+    }
+}
+
+impl fmt::Display for Pipeline<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return writeln!(f, "  (no transforms)");
+        }
+        for (i, op) in self.0.iter().enumerate() {
+            writeln!(f, "  {:>2}. {:<14} {}", i + 1, op.name(), op.detail())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    #[test]
+    fn test_ops_run_in_order() {
+        let img = DynamicImage::ImageRgb8(RgbImage::new(40, 20));
+        let crop_then_rotate = Pipeline::new()
+            .then(Op::Crop { x: 0, y: 0, width: 30, height: 20 })
+            .then(Op::Rotate(Rotation::Cw90));
+        assert_eq!(crop_then_rotate.apply(img.clone()).unwrap().dimensions(), (20, 30));
+
+        let mut pipeline = Pipeline::new().then(Op::Rotate(Rotation::Cw90));
+        pipeline.custom("halve", "half size", |img: DynamicImage| -> Result<_, BoxError> {
+            Ok(img.resize_exact(img.width() / 2, img.height() / 2, image::imageops::FilterType::Nearest))
+        });
+        assert_eq!(pipeline.apply(img.clone()).unwrap().dimensions(), (10, 20));
+        assert!(pipeline.to_string().contains(" 2. halve"));
+        assert!(Pipeline::new().then(Op::Crop { x: 30, y: 0, width: 20, height: 20 }).apply(img.clone()).is_err());
+        assert!(Pipeline::new().then(Op::Crop { x: u32::MAX, y: 0, width: 2, height: 20 }).apply(img).is_err());
+    }
+
+    #[test]
+    fn test_color_adjust_scales_with_depth() {
+        let op = Op::ColorAdjust { brightness: 51, contrast: 0.0 };
+        let deep = op.apply(DynamicImage::ImageRgb16(image::ImageBuffer::new(2, 2))).unwrap();
+        assert_eq!((deep.color(), deep.to_rgb8().get_pixel(0, 0).0), (image::ColorType::Rgb16, [51; 3]));
+        let shallow = op.apply(DynamicImage::ImageRgb8(RgbImage::new(2, 2))).unwrap();
+        assert_eq!(shallow.to_rgb8().get_pixel(0, 0).0, [51; 3]);
+    }
+
+    #[test]
+    fn test_watermark_keeps_color_type() {
+        let mark = Arc::new(DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(4, 4, image::Rgba([255, 0, 0, 255]))));
+        let op = Op::Watermark { image: mark, gravity: Gravity::Southeast, margin: 1, opacity: 0.5 };
+        let out = op.apply(DynamicImage::ImageRgb8(RgbImage::new(10, 10))).unwrap().to_rgb8();
+        assert_eq!(out.get_pixel(8, 8).0, [128, 0, 0]);
+        assert_eq!(out.get_pixel(0, 0).0, [0, 0, 0]);
+    }
+}
@@ -0,0 +1,175 @@
+// File: src\post.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Upload batch outputs to an HTTP endpoint (--post-to)
+// License: MIT
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use image::ImageFormat;
+use std::ffi::OsStr;
+use std::path::Path;
+
+use crate::i18n::tr;
+use crate::print_success;
+use crate::s3::encode_key;
+use crate::sink::{curl, Sink};
+use crate::template;
+
+#[derive(Args, Debug)]
+pub struct PostArgs {
+    /// Upload each batch output to URL over HTTP; tokens: {path} {name}
+    #[arg(
+        long,
+        value_name = "URL",
+        group = "sink",
+        conflicts_with_all = ["clipboard", "in_place", "output", "pos_output", "estimate", "resume"]
+    )]
+    pub post_to: Option<String>,
+
+    /// HTTP method for --post-to
+    #[arg(long, value_enum, default_value = "post", value_name = "METHOD", requires = "post_to")]
+    pub post_method: Method,
+
+    /// Request body for --post-to: a multipart form or the file's bytes alone
+    #[arg(long, value_enum, default_value = "multipart", value_name = "BODY", requires = "post_to")]
+    pub post_body: Body,
+
+    /// Form field holding the file in multipart uploads
+    #[arg(long, default_value = "file", value_name = "NAME", requires = "post_to")]
+    pub post_field: String,
+
+    /// Extra request header for --post-to, e.g. "Authorization: Bearer TOKEN" (repeatable)
+    #[arg(long = "post-header", value_name = "HEADER", requires = "post_to")]
+    pub post_headers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Method {
+    Post,
+    Put,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Body {
+    /// multipart/form-data with the file in --post-field
+    Multipart,
+    /// The file itself, with its Content-Type
+    Raw,
+}
+
+/// An HTTP endpoint receiving one request per converted file
+pub struct Endpoint {
+    url: String,
+    method: Method,
+    body: Body,
+    field: String,
+    headers: Vec<String>,
+    uploaded: usize,
+}
+
+impl Endpoint {
+    pub fn new(args: &PostArgs, url: &str) -> Result<Self> {
+        // Catch unknown tokens before anything is converted
+        url_for(url, "check.png")?;
+        if let Some(header) = args.post_headers.iter().find(|header| !header.contains(':')) {
+            anyhow::bail!("--post-header expects \"Name: value\", got: {}", header);
+        }
+        Ok(Self {
+            url: url.to_string(),
+            method: args.post_method,
+            body: args.post_body,
+            field: args.post_field.clone(),
+            headers: args.post_headers.clone(),
+            uploaded: 0,
+        })
+    }
+
+    /// Options passed to curl on stdin: headers (which may carry tokens) and the body
+    fn config(&self, output: &Path, name: &str, format: ImageFormat) -> Vec<(&'static str, String)> {
+        let mut config: Vec<(&str, String)> = self.headers.iter().map(|header| ("header", header.clone())).collect();
+        let file_name = name.rsplit('/').next().unwrap_or(name);
+        match self.body {
+            Body::Multipart => config.push((
+                "form",
+                format!(
+                    "{}=@\"{}\";type={};filename=\"{}\"",
+                    self.field,
+                    form_quote(&output.display().to_string()),
+                    format.to_mime_type(),
+                    form_quote(file_name)
+                ),
+            )),
+            Body::Raw => {
+                config.push(("header", format!("Content-Type: {}", format.to_mime_type())));
+                config.push(("upload-file", output.display().to_string()));
+            }
+        }
+        config.push(("request", format!("{:?}", self.method).to_uppercase()));
+        config
+    }
+}
+
+impl Sink for Endpoint {
+    fn put(&mut self, output: &Path, name: &str, format: ImageFormat) -> Result<()> {
+        let url = url_for(&self.url, name)?;
+        let status = curl([OsStr::new(&url)], &self.config(output, name, format))?;
+        if !status.success() {
+            anyhow::bail!("Upload of {} to {} failed (curl exited with {})", name, url, status);
+        }
+        self.uploaded += 1;
+        print_success(&tr!("Uploaded: {} -> {}", name, url));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.uploaded > 0 {
+            print_success(&tr!("Uploaded {} files to {}", self.uploaded, self.url));
+        }
+        Ok(())
+    }
+}
+
+/// `url` with {path} (the output's relative path) and {name} (its file name) filled in, URL-encoded
+fn url_for(url: &str, name: &str) -> Result<String> {
+    let file_name = name.rsplit('/').next().unwrap_or(name);
+    template::render(url, &[("path", encode_key(name)), ("name", encode_key(file_name))])
+}
+
+/// Quote for a file name inside curl's -F syntax
+fn form_quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_options() {
+        assert_eq!(url_for("https://cms/api/media/{path}?n={name}", "a b/c.webp").unwrap(), "https://cms/api/media/a%20b/c.webp?n=c.webp");
+        assert!(url_for("https://cms/{file}", "c.webp").is_err());
+
+        let mut endpoint = Endpoint {
+            url: String::new(),
+            method: Method::Post,
+            body: Body::Multipart,
+            field: "upload".into(),
+            headers: vec!["Authorization: Bearer t".into()],
+            uploaded: 0,
+        };
+        let config = endpoint.config(Path::new("/tmp/s/a b/c.webp"), "a b/c.webp", ImageFormat::WebP);
+        assert_eq!(config[0], ("header", "Authorization: Bearer t".to_string()));
+        assert_eq!(config[1], ("form", "upload=@\"/tmp/s/a b/c.webp\";type=image/webp;filename=\"c.webp\"".to_string()));
+        assert_eq!(config[2], ("request", "POST".to_string()));
+
+        endpoint.body = Body::Raw;
+        endpoint.method = Method::Put;
+        let config = endpoint.config(Path::new("c.png"), "c.png", ImageFormat::Png);
+        assert_eq!(&config[1..], [
+            ("header", "Content-Type: image/png".to_string()),
+            ("upload-file", "c.png".to_string()),
+            ("request", "PUT".to_string()),
+        ]);
+    }
+}
@@ -0,0 +1,66 @@
+// File: src\priority.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Encoder thread cap and process priority for shared machines
+// License: MIT
+
+use clap::Args;
+
+use crate::i18n::tr;
+use crate::{print_info, print_warning};
+
+/// Nice value used by --low-priority (0 = normal, 19 = lowest)
+const LOW_PRIORITY_NICE: i32 = 10;
+
+#[derive(Args, Debug)]
+pub struct PriorityArgs {
+    /// Cap the worker threads of multi-threaded encoders such as AVIF
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    pub encoder_threads: Option<u16>,
+
+    /// Run at lower CPU priority so interactive work stays responsive
+    #[arg(long, visible_alias = "nice")]
+    pub low_priority: bool,
+}
+
+/// Apply both settings to the whole process; call before any encoding starts
+pub fn apply(args: &PriorityArgs) {
+    if let Some(threads) = args.encoder_threads {
+        // The AVIF encoder and the parallel image operations share rayon's
+        // global pool, which is sized from this variable when first used
+        std::env::set_var("RAYON_NUM_THREADS", threads.to_string());
+        print_info(&tr!("Encoder threads limited to {}", threads));
+    }
+    if args.low_priority {
+        lower_priority();
+    }
+}
+
+#[cfg(unix)]
+fn lower_priority() {
+    // Only ever raise the nice value; an already-niced process stays as it is
+    // SAFETY: getpriority/setpriority only touch this process's scheduling value
+    let current = unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) };
+    if current >= LOW_PRIORITY_NICE {
+        return;
+    }
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, LOW_PRIORITY_NICE) } != 0 {
+        print_warning(&tr!("Could not lower process priority ({})", std::io::Error::last_os_error()));
+    }
+}
+
+#[cfg(not(unix))]
+fn lower_priority() {
+    print_warning(&tr!("--low-priority is only supported on Unix"));
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_low_priority_raises_nice() {
+        lower_priority();
+        assert!(unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) } >= LOW_PRIORITY_NICE);
+    }
+}
@@ -0,0 +1,276 @@
+// File: src\probe.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Format detection and header probing (size, color type, frames, EXIF) without decoding pixels
+// License: MIT
+
+use image::{ColorType, ImageDecoder, ImageFormat, ImageReader};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::error::{ImgconvError, Result};
+use crate::exifthumb::{self, Tiff};
+use crate::formats;
+
+/// What `probe` looks at: a file, or an encoded image in memory
+#[derive(Debug, Clone, Copy)]
+pub enum ProbeInput<'a> {
+    Path(&'a Path),
+    Bytes(&'a [u8]),
+}
+
+impl<'a> From<&'a Path> for ProbeInput<'a> {
+    fn from(path: &'a Path) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl<'a> From<&'a PathBuf> for ProbeInput<'a> {
+    fn from(path: &'a PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl<'a> From<&'a str> for ProbeInput<'a> {
+    fn from(path: &'a str) -> Self {
+        Self::Path(Path::new(path))
+    }
+}
+
+impl<'a> From<&'a [u8]> for ProbeInput<'a> {
+    fn from(data: &'a [u8]) -> Self {
+        Self::Bytes(data)
+    }
+}
+
+impl<'a> From<&'a Vec<u8>> for ProbeInput<'a> {
+    fn from(data: &'a Vec<u8>) -> Self {
+        Self::Bytes(data)
+    }
+}
+
+/// What an image's headers say about it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageInfo {
+    pub format: ImageFormat,
+    pub dimensions: (u32, u32),
+    pub color_type: ColorType,
+    /// Animation frames; 1 for still images
+    pub frames: u32,
+    pub exif: ExifInfo,
+}
+
+/// The commonly needed EXIF fields; all None without EXIF data
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExifInfo {
+    /// 1 (upright) to 8, as stored in the Orientation tag
+    pub orientation: Option<u16>,
+    pub make: Option<String>,
+    pub model: Option<String>,
+    /// "YYYY:MM:DD HH:MM:SS" (DateTimeOriginal, else DateTime)
+    pub date_taken: Option<String>,
+}
+
+/// Identify an image and read its headers without decoding the pixels
+///
+/// The format comes from the magic bytes, falling back to the file extension
+/// for formats without a signature (such as TGA). Files are read through a
+/// buffer, and only as far as the headers and frame markers go.
+pub fn probe<'a>(input: impl Into<ProbeInput<'a>>) -> Result<ImageInfo> {
+    match input.into() {
+        ProbeInput::Path(path) => {
+            let file = File::open(path).map_err(ImgconvError::io(format!("Failed to read {}", path.display())))?;
+            let extension = path.extension().and_then(|ext| ext.to_str()).and_then(formats::from_extension);
+            probe_reader(BufReader::new(file), extension, &path.display().to_string())
+        }
+        ProbeInput::Bytes(data) => probe_reader(Cursor::new(data), None, "the image data"),
+    }
+}
+
+fn probe_reader<R: BufRead + Seek>(mut reader: R, extension: Option<ImageFormat>, input: &str) -> Result<ImageInfo> {
+    let read_error = || ImgconvError::io(format!("Failed to read {}", input));
+    let format = image::guess_format(reader.fill_buf().map_err(read_error())?).ok().or(extension).ok_or_else(|| {
+        ImgconvError::UnsupportedFormat(format!("Cannot tell the image format of {}", input))
+    })?;
+    let frames = frames(&mut reader, format).and_then(|frames| reader.rewind().map(|()| frames)).map_err(read_error())?;
+    let decode_error = |source: image::ImageError| ImgconvError::Decode { input: input.to_string(), source: source.into() };
+    let mut decoder = ImageReader::with_format(reader, format).into_decoder().map_err(decode_error)?;
+    let exif = decoder.exif_metadata().ok().flatten().map(|exif| exif_info(&exif)).unwrap_or_default();
+    Ok(ImageInfo {
+        format,
+        dimensions: decoder.dimensions(),
+        color_type: decoder.color_type(),
+        frames,
+        exif,
+    })
+}
+
+fn exif_info(exif: &[u8]) -> ExifInfo {
+    let Some((_, tiff)) = Tiff::find(exif) else {
+        return ExifInfo::default();
+    };
+    let mut info = ExifInfo { date_taken: exifthumb::date_in(exif), ..Default::default() };
+    let Some(entries) = tiff.u32_at(4).and_then(|ifd0| tiff.entries(ifd0 as usize)) else {
+        return info;
+    };
+    for (tag, pos) in entries {
+        match tag {
+            0x010F => info.make = tiff.ascii(pos),
+            0x0110 => info.model = tiff.ascii(pos),
+            0x0112 => info.orientation = tiff.u16_at(pos + 8).filter(|value| (1..=8).contains(value)),
+            _ => {}
+        }
+    }
+    info
+}
+
+/// Count the frames from the container's structure, without decoding any
+fn frames<R: BufRead + Seek>(reader: &mut R, format: ImageFormat) -> std::io::Result<u32> {
+    let counted = match format {
+        ImageFormat::Png => png_frames(reader)?,
+        ImageFormat::Gif => gif_frames(reader)?,
+        ImageFormat::WebP => webp_frames(reader)?,
+        _ => None,
+    };
+    Ok(counted.unwrap_or(1).max(1))
+}
+
+/// `buf.len()` bytes, or None at the end of the input
+fn read_exact_or_end<R: Read>(reader: &mut R, buf: &mut [u8]) -> std::io::Result<Option<()>> {
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(Some(())),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// One byte, or None at the end of the input
+fn read_byte<R: Read>(reader: &mut R) -> std::io::Result<Option<u8>> {
+    let mut byte = [0];
+    Ok(read_exact_or_end(reader, &mut byte)?.map(|()| byte[0]))
+}
+
+/// The frame count an APNG's acTL chunk announces
+fn png_frames<R: Read + Seek>(reader: &mut R) -> std::io::Result<Option<u32>> {
+    reader.seek(SeekFrom::Start(8))?;
+    let mut header = [0; 8];
+    while read_exact_or_end(reader, &mut header)?.is_some() {
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        match &header[4..] {
+            b"acTL" => {
+                let mut frames = [0; 4];
+                return Ok(read_exact_or_end(reader, &mut frames)?.map(|()| u32::from_be_bytes(frames)));
+            }
+            // acTL must come before the image data
+            b"IDAT" => return Ok(None),
+            // The data and its CRC
+            _ => reader.seek(SeekFrom::Current(length as i64 + 4))?,
+        };
+    }
+    Ok(None)
+}
+
+/// Count the image descriptors, skipping color tables and data sub-blocks
+fn gif_frames<R: Read + Seek>(reader: &mut R) -> std::io::Result<Option<u32>> {
+    let table = |flags: u8| if flags & 0x80 != 0 { 3 << ((flags & 7) + 1) } else { 0 };
+    let mut screen = [0; 13];
+    if read_exact_or_end(reader, &mut screen)?.is_none() {
+        return Ok(None);
+    }
+    reader.seek(SeekFrom::Current(table(screen[10])))?;
+    let mut frames = 0;
+    loop {
+        match read_byte(reader)? {
+            Some(0x2C) => {
+                frames += 1;
+                // Descriptor, local color table, then the LZW minimum code size
+                let mut descriptor = [0; 9];
+                if read_exact_or_end(reader, &mut descriptor)?.is_none() {
+                    return Ok(Some(frames));
+                }
+                reader.seek(SeekFrom::Current(table(descriptor[8]) + 1))?;
+            }
+            Some(0x21) => {
+                read_byte(reader)?;
+            }
+            // The trailer, or a truncated file: count what is there
+            _ => return Ok(Some(frames)),
+        }
+        while let Some(length) = read_byte(reader)? {
+            if length == 0 {
+                break;
+            }
+            reader.seek(SeekFrom::Current(length as i64))?;
+        }
+    }
+}
+
+/// Count the ANMF chunks of an animated WebP
+fn webp_frames<R: Read + Seek>(reader: &mut R) -> std::io::Result<Option<u32>> {
+    reader.seek(SeekFrom::Start(12))?;
+    let mut header = [0; 8];
+    let mut frames = 0;
+    while read_exact_or_end(reader, &mut header)?.is_some() {
+        if &header[..4] == b"ANMF" {
+            frames += 1;
+        }
+        let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as i64;
+        reader.seek(SeekFrom::Current(length + length % 2))?;
+    }
+    Ok(Some(frames))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::gif::GifEncoder;
+    use image::{Frame, RgbaImage};
+
+    #[test]
+    fn test_probe_bytes_and_paths() {
+        let mut png = Vec::new();
+        image::RgbImage::new(30, 20).write_to(&mut Cursor::new(&mut png), ImageFormat::Png).unwrap();
+        let info = probe(&png).unwrap();
+        assert_eq!((info.format, info.dimensions, info.color_type, info.frames), (ImageFormat::Png, (30, 20), ColorType::Rgb8, 1));
+        assert_eq!(info.exif, ExifInfo::default());
+
+        let mut gif = Vec::new();
+        let mut encoder = GifEncoder::new(&mut gif);
+        for _ in 0..3 {
+            encoder.encode_frame(Frame::new(RgbaImage::new(4, 4))).unwrap();
+        }
+        drop(encoder);
+        assert_eq!(probe(&gif).unwrap().frames, 3);
+        assert!(matches!(probe(b"not an image".as_slice()), Err(ImgconvError::UnsupportedFormat(_))));
+
+        // TGA has no magic bytes, so its extension names the format
+        let path = std::env::temp_dir().join(format!("imgconv-probe-{}.tga", std::process::id()));
+        image::RgbImage::new(5, 6).save(&path).unwrap();
+        assert_eq!(probe(&path).unwrap().dimensions, (5, 6));
+        std::fs::remove_file(&path).unwrap();
+
+        // Files are counted through the buffered reader, the same as bytes
+        let path = path.with_extension("gif");
+        std::fs::write(&path, &gif).unwrap();
+        assert_eq!(probe(&path).unwrap().frames, 3);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_exif_info() {
+        // Little-endian TIFF: IFD0 with Make "Acme" (inline), Model "Cam 10" and Orientation 6
+        let mut tiff = b"II\x2a\x00\x08\x00\x00\x00".to_vec();
+        tiff.extend_from_slice(&[3, 0]);
+        tiff.extend_from_slice(&[0x0F, 0x01, 2, 0, 4, 0, 0, 0, b'A', b'c', b'm', b'e']);
+        tiff.extend_from_slice(&[0x10, 0x01, 2, 0, 7, 0, 0, 0, 50, 0, 0, 0]);
+        tiff.extend_from_slice(&[0x12, 0x01, 3, 0, 1, 0, 0, 0, 6, 0, 0, 0]);
+        tiff.extend_from_slice(&[0, 0, 0, 0]);
+        tiff.extend_from_slice(b"Cam 10\0");
+        let info = exif_info(&tiff);
+        assert_eq!(info.make.as_deref(), Some("Acme"));
+        assert_eq!(info.model.as_deref(), Some("Cam 10"));
+        assert_eq!(info.orientation, Some(6));
+        assert_eq!(exif_info(b"junk"), ExifInfo::default());
+    }
+}
@@ -0,0 +1,167 @@
+// File: src\progress.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Newline-delimited JSON progress events for GUI front-ends (--progress-json)
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::timings::Stage;
+
+static STREAM: Mutex<Option<Stream>> = Mutex::new(None);
+
+#[derive(Args, Debug)]
+pub struct ProgressArgs {
+    /// Write JSON progress events, one per line, to file descriptor FD (1 = stdout, 2 = stderr)
+    #[arg(long, value_name = "FD")]
+    pub progress_json: Option<i32>,
+}
+
+struct Stream {
+    out: Box<dyn Write + Send>,
+    /// Files in the run and files finished (converted, skipped or failed) so far
+    total: usize,
+    finished: usize,
+}
+
+impl Stream {
+    fn send(&mut self, event: Value) {
+        // A front-end that went away must not stop the conversion
+        let _ = writeln!(self.out, "{}", event).and_then(|()| self.out.flush());
+    }
+
+    fn percent(&self, file_fraction: f64) -> f64 {
+        let total = self.total.max(1) as f64;
+        ((self.finished as f64 + file_fraction) / total * 100.0).min(100.0)
+    }
+}
+
+/// Open the --progress-json descriptor; events are dropped when it is not given
+pub fn init(args: &ProgressArgs) -> Result<()> {
+    let Some(fd) = args.progress_json else {
+        return Ok(());
+    };
+    let out = open(fd)?;
+    *STREAM.lock().unwrap_or_else(|e| e.into_inner()) = Some(Stream { out, total: 1, finished: 0 });
+    Ok(())
+}
+
+fn open(fd: i32) -> Result<Box<dyn Write + Send>> {
+    match fd {
+        1 => Ok(Box::new(std::io::stdout())),
+        2 => Ok(Box::new(std::io::stderr())),
+        #[cfg(unix)]
+        fd if fd > 2 => {
+            use std::os::unix::io::FromRawFd;
+            // SAFETY: fcntl only queries the descriptor; ownership passes to the File
+            // once it is known to be open, and it stays open until the process exits
+            if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+                anyhow::bail!("--progress-json: file descriptor {} is not open", fd);
+            }
+            Ok(Box::new(unsafe { std::fs::File::from_raw_fd(fd) }))
+        }
+        _ => anyhow::bail!("--progress-json: unsupported file descriptor {} (use 1 for stdout or 2 for stderr)", fd),
+    }
+}
+
+fn with_stream(f: impl FnOnce(&mut Stream)) {
+    if let Some(stream) = STREAM.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        f(stream);
+    }
+}
+
+/// `{"event":"start","total":N}` before the first file
+pub fn start(total: usize) {
+    with_stream(|stream| {
+        stream.total = total;
+        stream.finished = 0;
+        stream.send(json!({ "event": "start", "total": total }));
+    });
+}
+
+/// A file begins converting (`input` is None for the clipboard)
+pub fn file_started(input: Option<&Path>, output: &Path) {
+    with_stream(|stream| {
+        let event = json!({
+            "event": "file_started",
+            "index": stream.finished,
+            "total": stream.total,
+            "input": input,
+            "output": output,
+            "percent": stream.percent(0.0),
+        });
+        stream.send(event);
+    });
+}
+
+/// A stage of the current file is done
+pub fn stage(stage: Stage) {
+    with_stream(|stream| {
+        let (name, fraction) = match stage {
+            Stage::Decode => ("decode", 0.4),
+            Stage::Transform => ("transform", 0.6),
+            Stage::Encode => ("encode", 1.0),
+        };
+        let event = json!({ "event": "progress", "stage": name, "index": stream.finished, "percent": stream.percent(fraction) });
+        stream.send(event);
+    });
+}
+
+/// The current file was written
+pub fn file_finished(output: &Path) {
+    with_stream(|stream| {
+        stream.finished += 1;
+        let bytes = std::fs::metadata(output).map(|m| m.len()).ok();
+        let event = json!({ "event": "file_finished", "output": output, "bytes": bytes, "percent": stream.percent(0.0) });
+        stream.send(event);
+    });
+}
+
+/// The current file was skipped as up to date
+pub fn file_skipped(output: &Path) {
+    with_stream(|stream| {
+        stream.finished += 1;
+        stream.send(json!({ "event": "file_skipped", "output": output, "percent": stream.percent(0.0) }));
+    });
+}
+
+/// The current file failed; a batch carries on with the next one
+pub fn file_failed(input: Option<&Path>, error: &anyhow::Error) {
+    with_stream(|stream| {
+        stream.finished += 1;
+        let event = json!({ "event": "error", "input": input, "message": format!("{:#}", error), "percent": stream.percent(0.0) });
+        stream.send(event);
+    });
+}
+
+/// A single-file run failed: its error, then the closing `done`
+pub fn failed(input: Option<&Path>, error: &anyhow::Error) {
+    file_failed(input, error);
+    done(0, 0, 1);
+}
+
+/// `{"event":"done",...}` after the last file
+pub fn done(converted: usize, skipped: usize, failed: usize) {
+    with_stream(|stream| {
+        stream.send(json!({ "event": "done", "converted": converted, "skipped": skipped, "failed": failed }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_counts_finished_files_and_stage() {
+        let stream = Stream { out: Box::new(std::io::sink()), total: 4, finished: 1 };
+        assert_eq!(stream.percent(0.0), 25.0);
+        assert!((stream.percent(0.4) - 35.0).abs() < 1e-9);
+        let single = Stream { out: Box::new(std::io::sink()), total: 0, finished: 1 };
+        assert_eq!(single.percent(1.0), 100.0);
+    }
+}
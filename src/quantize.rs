@@ -0,0 +1,437 @@
+// File: src\quantize.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Color quantization (NeuQuant, median cut, Wu) and palette indexing
+// License: MIT
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use color_quant::NeuQuant;
+use image::{ColorType, DynamicImage, ImageFormat, Rgba, RgbaImage};
+use std::collections::HashMap;
+
+use crate::depth::{self, Dither};
+use crate::draw::restore_color_type;
+use crate::i18n::tr;
+use crate::print_info;
+
+#[derive(Args, Debug)]
+pub struct QuantizeArgs {
+    /// Reduce the image to at most N colors (2-256)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(2..=256))]
+    pub quantize: Option<u16>,
+
+    /// Palette algorithm used by --quantize and --png-palette
+    #[arg(long, value_enum, default_value = "wu", value_name = "ALGO")]
+    pub quant_algo: QuantAlgo,
+
+    /// Write an indexed (PNG8) palette image, using --quantize colors or 256
+    #[arg(long, conflicts_with = "bit_depth")]
+    pub png_palette: bool,
+}
+
+impl QuantizeArgs {
+    /// Whether colors are reduced or checked for a palette
+    pub fn is_active(&self) -> bool {
+        self.quantize.is_some() || self.png_palette
+    }
+}
+
+/// Palette plus one index per pixel, ready for an indexed encoder
+pub struct IndexedImage {
+    pub width: u32,
+    pub height: u32,
+    pub palette: Vec<Rgba<u8>>,
+    pub indices: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QuantAlgo {
+    /// Neural-net quantizer, good for photos
+    Neuquant,
+    /// Recursive box splitting at the median
+    MedianCut,
+    /// Variance-minimizing box splitting (Xiaolin Wu)
+    Wu,
+}
+
+/// Apply `--quantize`, returning the image unchanged when not requested
+///
+/// With `--png-palette` the image is left alone here and indexed by `index` at save time.
+pub fn apply(img: DynamicImage, args: &QuantizeArgs, format: ImageFormat, dither: Dither) -> Result<DynamicImage> {
+    if args.png_palette {
+        if format != ImageFormat::Png {
+            anyhow::bail!("--png-palette requires PNG output, got {:?}", format);
+        }
+        return Ok(img);
+    }
+    let Some(colors) = args.quantize else {
+        return Ok(img);
+    };
+
+    // Palette entries are 8-bit, a deeper container would only add size
+    depth::warn_reduction(img.color(), "--quantize");
+    let color_type = match img.color() {
+        ColorType::L16 => ColorType::L8,
+        ColorType::La16 => ColorType::La8,
+        ColorType::Rgb16 | ColorType::Rgb32F => ColorType::Rgb8,
+        ColorType::Rgba16 | ColorType::Rgba32F => ColorType::Rgba8,
+        other => other,
+    };
+    let mut canvas = img.to_rgba8();
+    let palette = build_palette(&canvas, colors as usize, args.quant_algo);
+    let indices = remap(&canvas, &palette, dither);
+    for (pixel, &index) in canvas.pixels_mut().zip(&indices) {
+        *pixel = palette[index as usize];
+    }
+
+    print_info(&tr!("Quantized to {} colors ({})", palette.len(), format!("{:?}", args.quant_algo)));
+    Ok(restore_color_type(color_type, DynamicImage::ImageRgba8(canvas).into_rgba16()))
+}
+
+/// Build the palette and per-pixel indices for `--png-palette`
+pub fn index(img: &DynamicImage, args: &QuantizeArgs, dither: Dither) -> IndexedImage {
+    depth::warn_reduction(img.color(), "--png-palette");
+    let canvas = img.to_rgba8();
+    let colors = args.quantize.unwrap_or(256) as usize;
+    let palette = build_palette(&canvas, colors, args.quant_algo);
+    let indices = remap(&canvas, &palette, dither);
+    print_info(&tr!("Indexed PNG with {} colors ({})", palette.len(), format!("{:?}", args.quant_algo)));
+    IndexedImage { width: canvas.width(), height: canvas.height(), palette, indices }
+}
+
+/// Build a palette of at most `colors` entries
+pub fn build_palette(img: &RgbaImage, colors: usize, algo: QuantAlgo) -> Vec<Rgba<u8>> {
+    let mut palette = match algo {
+        QuantAlgo::Neuquant => {
+            let quant = NeuQuant::new(10, colors, img.as_raw());
+            quant.color_map_rgba().chunks_exact(4).map(|c| Rgba([c[0], c[1], c[2], c[3]])).collect()
+        }
+        QuantAlgo::MedianCut => median_cut(img, colors),
+        QuantAlgo::Wu => wu(img, colors),
+    };
+    palette.sort_by_key(|c| c.0);
+    palette.dedup();
+    palette
+}
+
+/// Palette index for every pixel, optionally diffusing the error (Floyd-Steinberg)
+pub fn remap(img: &RgbaImage, palette: &[Rgba<u8>], dither: Dither) -> Vec<u8> {
+    let width = img.width() as usize;
+    let mut cache: HashMap<[u8; 4], u8> = HashMap::new();
+    let mut lookup = |color: [u8; 4]| *cache.entry(color).or_insert_with(|| nearest(palette, Rgba(color)) as u8);
+
+    if dither == Dither::None {
+        return img.pixels().map(|p| lookup(p.0)).collect();
+    }
+
+    // Error carried into the current and the next row
+    let mut current = vec![[0f32; 4]; width + 2];
+    let mut next = vec![[0f32; 4]; width + 2];
+    let mut indices = Vec::with_capacity(width * img.height() as usize);
+    for row in img.rows() {
+        for (x, pixel) in row.enumerate() {
+            let wanted: [f32; 4] = std::array::from_fn(|c| (pixel.0[c] as f32 + current[x + 1][c]).clamp(0.0, 255.0));
+            let index = lookup(wanted.map(|v| v.round() as u8));
+            indices.push(index);
+
+            let chosen = palette[index as usize].0;
+            for c in 0..4 {
+                let error = wanted[c] - chosen[c] as f32;
+                current[x + 2][c] += error * 7.0 / 16.0;
+                next[x][c] += error * 3.0 / 16.0;
+                next[x + 1][c] += error * 5.0 / 16.0;
+                next[x + 2][c] += error / 16.0;
+            }
+        }
+        std::mem::swap(&mut current, &mut next);
+        next.iter_mut().for_each(|e| *e = [0.0; 4]);
+    }
+    indices
+}
+
+/// Index of the palette entry closest to `color` (squared RGBA distance)
+pub fn nearest(palette: &[Rgba<u8>], color: Rgba<u8>) -> usize {
+    let distance = |entry: &Rgba<u8>| -> u32 {
+        entry.0.iter().zip(color.0).map(|(&a, b)| (a as i32 - b as i32).pow(2) as u32).sum()
+    };
+    (0..palette.len()).min_by_key(|&i| distance(&palette[i])).unwrap_or(0)
+}
+
+/// Median cut over the RGBA histogram: split the box with the widest channel at its weighted median
+fn median_cut(img: &RgbaImage, colors: usize) -> Vec<Rgba<u8>> {
+    let mut histogram: HashMap<[u8; 4], u64> = HashMap::new();
+    for pixel in img.pixels() {
+        *histogram.entry(pixel.0).or_insert(0) += 1;
+    }
+    let mut boxes: Vec<Vec<([u8; 4], u64)>> = vec![histogram.into_iter().collect()];
+
+    while boxes.len() < colors {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| {
+                let (channel, range) = widest_channel(b);
+                (range, i, channel)
+            })
+            .max();
+        let Some((_, index, channel)) = widest else {
+            break;
+        };
+
+        let mut colors_in_box = boxes.swap_remove(index);
+        colors_in_box.sort_by_key(|(c, _)| c[channel]);
+        let total: u64 = colors_in_box.iter().map(|(_, n)| n).sum();
+        let mut seen = 0;
+        let mut split = 1;
+        for (i, (_, n)) in colors_in_box.iter().enumerate() {
+            seen += n;
+            if seen * 2 >= total {
+                split = (i + 1).clamp(1, colors_in_box.len() - 1);
+                break;
+            }
+        }
+        let upper = colors_in_box.split_off(split);
+        boxes.push(colors_in_box);
+        boxes.push(upper);
+    }
+
+    boxes
+        .iter()
+        .map(|b| {
+            let total: u64 = b.iter().map(|(_, n)| n).sum();
+            let mut sum = [0u64; 4];
+            for (color, n) in b {
+                for (s, &v) in sum.iter_mut().zip(color) {
+                    *s += v as u64 * n;
+                }
+            }
+            Rgba(sum.map(|s| ((s + total / 2) / total) as u8))
+        })
+        .collect()
+}
+
+/// Channel with the largest value range in a box, and that range
+fn widest_channel(colors: &[([u8; 4], u64)]) -> (usize, u8) {
+    (0..4)
+        .map(|channel| {
+            let min = colors.iter().map(|(c, _)| c[channel]).min().unwrap_or(0);
+            let max = colors.iter().map(|(c, _)| c[channel]).max().unwrap_or(0);
+            (channel, max - min)
+        })
+        .max_by_key(|&(channel, range)| (range, std::cmp::Reverse(channel)))
+        .unwrap_or((0, 0))
+}
+
+/// Histogram resolution per channel for Wu's algorithm (5 bits + 1 for prefix sums)
+const WU_SIDE: usize = 33;
+
+/// Cumulative color moments over the 33x33x33 RGB histogram
+struct Moments {
+    weight: Vec<f64>,
+    red: Vec<f64>,
+    green: Vec<f64>,
+    blue: Vec<f64>,
+    alpha: Vec<f64>,
+    squares: Vec<f64>,
+}
+
+/// Half-open RGB box in histogram coordinates: (r0, r1] x (g0, g1] x (b0, b1]
+#[derive(Debug, Clone, Copy)]
+struct WuBox {
+    lo: [usize; 3],
+    hi: [usize; 3],
+}
+
+fn wu_index(r: usize, g: usize, b: usize) -> usize {
+    (r * WU_SIDE + g) * WU_SIDE + b
+}
+
+impl Moments {
+    fn new(img: &RgbaImage) -> Moments {
+        let size = WU_SIDE * WU_SIDE * WU_SIDE;
+        let mut m = Moments {
+            weight: vec![0.0; size],
+            red: vec![0.0; size],
+            green: vec![0.0; size],
+            blue: vec![0.0; size],
+            alpha: vec![0.0; size],
+            squares: vec![0.0; size],
+        };
+        for pixel in img.pixels() {
+            let [r, g, b, a] = pixel.0;
+            let i = wu_index((r >> 3) as usize + 1, (g >> 3) as usize + 1, (b >> 3) as usize + 1);
+            let (r, g, b) = (r as f64, g as f64, b as f64);
+            m.weight[i] += 1.0;
+            m.red[i] += r;
+            m.green[i] += g;
+            m.blue[i] += b;
+            m.alpha[i] += a as f64;
+            m.squares[i] += r * r + g * g + b * b;
+        }
+
+        // Turn the histogram into 3D prefix sums, one axis at a time
+        for table in [&mut m.weight, &mut m.red, &mut m.green, &mut m.blue, &mut m.alpha, &mut m.squares] {
+            for axis in 0..3 {
+                for r in 1..WU_SIDE {
+                    for g in 1..WU_SIDE {
+                        for b in 1..WU_SIDE {
+                            let prev = match axis {
+                                0 => wu_index(r - 1, g, b),
+                                1 => wu_index(r, g - 1, b),
+                                _ => wu_index(r, g, b - 1),
+                            };
+                            table[wu_index(r, g, b)] += table[prev];
+                        }
+                    }
+                }
+            }
+        }
+        m
+    }
+
+    /// Sum of a moment table over a box (inclusion-exclusion on the prefix sums)
+    fn volume(table: &[f64], cube: &WuBox) -> f64 {
+        let ([r0, g0, b0], [r1, g1, b1]) = (cube.lo, cube.hi);
+        table[wu_index(r1, g1, b1)] - table[wu_index(r1, g1, b0)] - table[wu_index(r1, g0, b1)]
+            + table[wu_index(r1, g0, b0)]
+            - table[wu_index(r0, g1, b1)]
+            + table[wu_index(r0, g1, b0)]
+            + table[wu_index(r0, g0, b1)]
+            - table[wu_index(r0, g0, b0)]
+    }
+
+    /// (weight, red, green, blue) sums of a box
+    fn sums(&self, cube: &WuBox) -> [f64; 4] {
+        [
+            Self::volume(&self.weight, cube),
+            Self::volume(&self.red, cube),
+            Self::volume(&self.green, cube),
+            Self::volume(&self.blue, cube),
+        ]
+    }
+
+    fn variance(&self, cube: &WuBox) -> f64 {
+        let [w, r, g, b] = self.sums(cube);
+        if w <= 1.0 {
+            return 0.0;
+        }
+        Self::volume(&self.squares, cube) - (r * r + g * g + b * b) / w
+    }
+
+    /// Split a box where it most reduces the total variance, None if it cannot be split
+    fn cut(&self, cube: &WuBox) -> Option<(WuBox, WuBox)> {
+        let whole = self.sums(cube);
+        let mut best: Option<(f64, usize, usize)> = None;
+
+        for axis in 0..3 {
+            for pos in cube.lo[axis] + 1..cube.hi[axis] {
+                let mut first = *cube;
+                first.hi[axis] = pos;
+                let half = self.sums(&first);
+                let other: Vec<f64> = whole.iter().zip(half).map(|(w, h)| w - h).collect();
+                if half[0] <= 0.0 || other[0] <= 0.0 {
+                    continue;
+                }
+                let score = (half[1].powi(2) + half[2].powi(2) + half[3].powi(2)) / half[0]
+                    + (other[1].powi(2) + other[2].powi(2) + other[3].powi(2)) / other[0];
+                if best.is_none_or(|(s, _, _)| score > s) {
+                    best = Some((score, axis, pos));
+                }
+            }
+        }
+
+        let (_, axis, pos) = best?;
+        let (mut first, mut second) = (*cube, *cube);
+        first.hi[axis] = pos;
+        second.lo[axis] = pos;
+        Some((first, second))
+    }
+}
+
+/// Xiaolin Wu's quantizer in RGB; alpha is the mean of each box's pixels
+fn wu(img: &RgbaImage, colors: usize) -> Vec<Rgba<u8>> {
+    let moments = Moments::new(img);
+    let mut boxes = vec![WuBox { lo: [0; 3], hi: [WU_SIDE - 1; 3] }];
+    let mut variances = vec![moments.variance(&boxes[0])];
+
+    while boxes.len() < colors {
+        let Some((index, _)) = variances
+            .iter()
+            .enumerate()
+            .filter(|(_, &v)| v > 0.0)
+            .max_by(|a, b| a.1.total_cmp(b.1))
+        else {
+            break;
+        };
+        match moments.cut(&boxes[index]) {
+            Some((first, second)) => {
+                boxes[index] = first;
+                variances[index] = moments.variance(&first);
+                boxes.push(second);
+                variances.push(moments.variance(&second));
+            }
+            None => variances[index] = 0.0,
+        }
+    }
+
+    boxes
+        .iter()
+        .filter_map(|cube| {
+            let [w, r, g, b] = moments.sums(cube);
+            let a = Moments::volume(&moments.alpha, cube);
+            (w > 0.0).then(|| Rgba([r, g, b, a].map(|v| (v / w).round().clamp(0.0, 255.0) as u8)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_tone() -> RgbaImage {
+        RgbaImage::from_fn(16, 16, |x, _| {
+            if x < 8 { Rgba([250, 10, 10, 255]) } else { Rgba([10, 10, 250, 255]) }
+        })
+    }
+
+    #[test]
+    fn test_palettes_respect_limit() {
+        let img = RgbaImage::from_fn(32, 32, |x, y| Rgba([(x * 8) as u8, (y * 8) as u8, 128, 255]));
+        for algo in [QuantAlgo::Neuquant, QuantAlgo::MedianCut, QuantAlgo::Wu] {
+            let palette = build_palette(&img, 16, algo);
+            assert!(!palette.is_empty() && palette.len() <= 16, "{:?}: {}", algo, palette.len());
+        }
+    }
+
+    #[test]
+    fn test_dither_mixes_palette() {
+        // 25% gray over a black/white palette: roughly a quarter of pixels white
+        let img = RgbaImage::from_pixel(16, 16, Rgba([64, 64, 64, 255]));
+        let palette = [Rgba([0, 0, 0, 255]), Rgba([255, 255, 255, 255])];
+        let white = remap(&img, &palette, Dither::FloydSteinberg).iter().filter(|&&i| i == 1).count();
+        assert!((48..=80).contains(&white), "white pixels: {}", white);
+        assert!(remap(&img, &palette, Dither::None).iter().all(|&i| i == 0));
+    }
+
+    #[test]
+    fn test_any_depth_comes_out_8bit() {
+        let args = QuantizeArgs { quantize: Some(4), quant_algo: QuantAlgo::Wu, png_palette: false };
+        for img in [DynamicImage::new_rgba16(4, 4), DynamicImage::new_rgb32f(4, 4), DynamicImage::new_luma16(4, 4)] {
+            let out = apply(img, &args, ImageFormat::Png, Dither::None).unwrap();
+            assert!(!crate::draw::is_high_depth(out.color()), "{:?}", out.color());
+        }
+    }
+
+    #[test]
+    fn test_exact_colors_survive() {
+        for algo in [QuantAlgo::MedianCut, QuantAlgo::Wu] {
+            let img = two_tone();
+            let palette = build_palette(&img, 4, algo);
+            let indices = remap(&img, &palette, Dither::FloydSteinberg);
+            assert_eq!(palette[indices[0] as usize], Rgba([250, 10, 10, 255]), "{:?}", algo);
+            assert_eq!(palette[indices[15] as usize], Rgba([10, 10, 250, 255]), "{:?}", algo);
+        }
+    }
+}
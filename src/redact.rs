@@ -0,0 +1,157 @@
+// File: src\redact.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Blur, pixelate or black out rectangular regions
+// License: MIT
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use image::{imageops, DynamicImage, GenericImageView, Rgba};
+use std::str::FromStr;
+
+use crate::draw::{restore_color_type, Canvas};
+use crate::geometry::Rect;
+use crate::i18n::tr;
+use crate::{print_info, print_warning};
+
+#[derive(Args, Debug)]
+pub struct RedactArgs {
+    /// Redact a region; MODE is solid (default), pixelate or blur. Repeatable
+    #[arg(long, value_name = "X,Y,W,H[,MODE]")]
+    pub redact: Vec<Redaction>,
+}
+
+impl RedactArgs {
+    /// Whether any region is to be redacted
+    pub fn is_active(&self) -> bool {
+        !self.redact.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RedactMode {
+    Solid,
+    Pixelate,
+    Blur,
+}
+
+/// Region and method given as "X,Y,W,H[,MODE]"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Redaction {
+    pub rect: Rect,
+    pub mode: RedactMode,
+}
+
+impl FromStr for Redaction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (rect, mode) = match s.rsplit_once(',') {
+            Some((rect, mode)) if mode.trim().chars().all(|c| c.is_ascii_alphabetic()) => {
+                (rect, RedactMode::from_str(mode.trim(), true).map_err(|_| {
+                    format!("Unknown redaction mode '{}', expected solid, pixelate or blur", mode.trim())
+                })?)
+            }
+            _ => (s, RedactMode::Solid),
+        };
+        Ok(Redaction { rect: rect.parse()?, mode })
+    }
+}
+
+/// Apply every `--redact` region, returning the image unchanged when none are given
+pub fn apply(img: DynamicImage, args: &RedactArgs) -> Result<DynamicImage> {
+    if args.redact.is_empty() {
+        return Ok(img);
+    }
+
+    let (width, height) = img.dimensions();
+    let color_type = img.color();
+    let mut canvas = img.to_rgba16();
+
+    for redaction in &args.redact {
+        let Some(rect) = redaction.rect.clamp_to(width, height) else {
+            print_warning(&tr!(
+                "Redaction region {},{},{},{} is outside the {}x{} image",
+                redaction.rect.x, redaction.rect.y, redaction.rect.width, redaction.rect.height, width, height
+            ));
+            continue;
+        };
+        match redaction.mode {
+            RedactMode::Solid => solid(&mut canvas, rect),
+            RedactMode::Pixelate => pixelate(&mut canvas, rect),
+            RedactMode::Blur => blur(&mut canvas, rect),
+        }
+    }
+
+    print_info(&tr!("Redacted {} region(s)", args.redact.len()));
+    Ok(restore_color_type(color_type, canvas))
+}
+
+/// Replace every pixel with opaque black so nothing of the original survives
+fn solid(canvas: &mut Canvas, rect: Rect) {
+    for y in rect.y..rect.y + rect.height {
+        for x in rect.x..rect.x + rect.width {
+            canvas.put_pixel(x, y, Rgba([0, 0, 0, u16::MAX]));
+        }
+    }
+}
+
+/// Average blocks of roughly a tenth of the region's longest side
+fn pixelate(canvas: &mut Canvas, rect: Rect) {
+    let block = (rect.width.max(rect.height) / 10).max(4);
+
+    for by in (rect.y..rect.y + rect.height).step_by(block as usize) {
+        for bx in (rect.x..rect.x + rect.width).step_by(block as usize) {
+            let bw = block.min(rect.x + rect.width - bx);
+            let bh = block.min(rect.y + rect.height - by);
+
+            let mut sum = [0u64; 4];
+            for y in by..by + bh {
+                for x in bx..bx + bw {
+                    for (total, value) in sum.iter_mut().zip(canvas.get_pixel(x, y).0) {
+                        *total += value as u64;
+                    }
+                }
+            }
+            let count = (bw * bh) as u64;
+            let average = Rgba(sum.map(|total| ((total + count / 2) / count) as u16));
+
+            for y in by..by + bh {
+                for x in bx..bx + bw {
+                    canvas.put_pixel(x, y, average);
+                }
+            }
+        }
+    }
+}
+
+/// Heavy gaussian blur confined to the region
+fn blur(canvas: &mut Canvas, rect: Rect) {
+    let sigma = (rect.width.min(rect.height) as f32 / 4.0).max(3.0);
+    let region = imageops::crop_imm(canvas, rect.x, rect.y, rect.width, rect.height).to_image();
+    imageops::replace(canvas, &imageops::blur(&region, sigma), rect.x as i64, rect.y as i64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_redaction() {
+        let r: Redaction = "10,20,30,40,pixelate".parse().unwrap();
+        assert_eq!(r.rect, Rect { x: 10, y: 20, width: 30, height: 40 });
+        assert_eq!(r.mode, RedactMode::Pixelate);
+        assert_eq!("1,2,3,4".parse::<Redaction>().unwrap().mode, RedactMode::Solid);
+        assert!("1,2,3,4,smudge".parse::<Redaction>().is_err());
+        assert!("1,2,3".parse::<Redaction>().is_err());
+    }
+
+    #[test]
+    fn test_solid_overwrites_alpha() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(8, 8, Rgba([200, 100, 50, 0])));
+        let args = RedactArgs { redact: vec!["2,2,10,10,solid".parse().unwrap()] };
+        let out = apply(img, &args).unwrap().to_rgba8();
+        assert_eq!(*out.get_pixel(7, 7), Rgba([0, 0, 0, 255]));
+        assert_eq!(*out.get_pixel(1, 1), Rgba([200, 100, 50, 0]));
+    }
+}
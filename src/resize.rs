@@ -0,0 +1,260 @@
+// File: src\resize.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Resizing with selectable filters and optional linear-light scaling
+// License: MIT
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageFormat, RgbaImage};
+use std::sync::Once;
+
+use crate::colorspace::{self, Colorspace};
+use crate::geometry::Size;
+use crate::gpu;
+use crate::Resize;
+use crate::i18n::tr;
+use crate::{print_info, print_warning};
+
+#[derive(Args, Debug)]
+pub struct ResizeArgs {
+    /// Scale to fit within WxH, keeping the aspect ratio
+    #[arg(long, value_name = "WxH")]
+    pub resize: Option<Size>,
+
+    /// Resampling filter used by --resize
+    #[arg(long, value_enum, default_value = "lanczos3", value_name = "FILTER")]
+    pub filter: Filter,
+
+    /// Resize in linear light instead of gamma-encoded sRGB (avoids dark halos)
+    #[arg(long, requires = "resize")]
+    pub linear_resize: bool,
+
+    /// Resize on the GPU through wgpu, falling back to the CPU (needs --features gpu)
+    #[arg(long, requires = "resize")]
+    pub gpu: bool,
+
+    /// Fail on images over 256x256 when writing ICO instead of shrinking them to fit
+    #[arg(long)]
+    pub no_auto_downscale: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Filter {
+    Nearest,
+    Triangle,
+    CatmullRom,
+    Gaussian,
+    Lanczos3,
+}
+
+impl Filter {
+    pub fn to_filter_type(self) -> FilterType {
+        match self {
+            Filter::Nearest => FilterType::Nearest,
+            Filter::Triangle => FilterType::Triangle,
+            Filter::CatmullRom => FilterType::CatmullRom,
+            Filter::Gaussian => FilterType::Gaussian,
+            Filter::Lanczos3 => FilterType::Lanczos3,
+        }
+    }
+}
+
+/// Apply `--resize`, returning the image unchanged when not requested
+pub fn apply(img: DynamicImage, args: &ResizeArgs) -> Result<DynamicImage> {
+    Ok(match args.resize {
+        Some(size) => resize(img, Resize::Fit(size.width, size.height), args.filter, args.linear_resize, args.gpu),
+        None => img,
+    })
+}
+
+/// Resize `img` with `filter`, in linear light when `linear`, and on the GPU
+/// when `gpu` unless it can't be used there
+pub fn resize(img: DynamicImage, to: Resize, filter: Filter, linear: bool, gpu: bool) -> DynamicImage {
+    let space = if linear { Colorspace::Linear } else { Colorspace::Srgb };
+    let (width, height) = img.dimensions();
+    let (target_w, target_h) = match to {
+        Resize::Fit(fit_w, fit_h) => Size { width: fit_w, height: fit_h }.fit(width, height),
+        Resize::Exact(width, height) => (width, height),
+    };
+    let resized = match gpu {
+        true => on_gpu(&img, (target_w, target_h), filter, linear),
+        false => None,
+    }
+    .unwrap_or_else(|| colorspace::run_in(space, img, |img| img.resize_exact(target_w, target_h, filter.to_filter_type())));
+
+    print_info(&tr!(
+        "Resized {}x{} -> {}x{} ({}, {})",
+        width,
+        height,
+        resized.width(),
+        resized.height(),
+        format!("{:?}", filter),
+        if linear { "linear light" } else { "sRGB" }
+    ));
+    resized
+}
+
+/// --gpu: `img` resized on the GPU, or None to resize it on the CPU, with a
+/// warning the first time the GPU can't be used
+fn on_gpu(img: &DynamicImage, (width, height): (u32, u32), filter: Filter, linear: bool) -> Option<DynamicImage> {
+    static ANNOUNCED: Once = Once::new();
+    static FALLBACK: Once = Once::new();
+    let resized = gpu::adapter().and_then(|adapter| {
+        ANNOUNCED.call_once(|| print_info(&tr!("Resizing on the GPU: {}", adapter)));
+        gpu::resize(img, width, height, filter.to_filter_type(), linear)
+    });
+    match resized {
+        Ok(resized) => Some(resized),
+        Err(e) => {
+            FALLBACK.call_once(|| print_warning(&tr!("Resizing on the CPU instead: {}", format!("{:#}", e))));
+            None
+        }
+    }
+}
+
+/// Largest width and height an ICO entry can have
+const ICO_MAX: u32 = 256;
+
+/// Shrink an image too big for an ICO entry to fit 256x256, centred on a
+/// transparent square so the icon keeps its aspect ratio
+pub fn fit_ico(img: DynamicImage, args: &ResizeArgs, format: ImageFormat) -> DynamicImage {
+    let (width, height) = img.dimensions();
+    if format != ImageFormat::Ico || args.no_auto_downscale || (width <= ICO_MAX && height <= ICO_MAX) {
+        return img;
+    }
+    let space = if args.linear_resize { Colorspace::Linear } else { Colorspace::Srgb };
+    let filter = args.filter.to_filter_type();
+    let resized = colorspace::run_in(space, img, |img| img.resize(ICO_MAX, ICO_MAX, filter)).to_rgba8();
+    let mut canvas = RgbaImage::new(ICO_MAX, ICO_MAX);
+    let (x, y) = ((ICO_MAX - resized.width()) / 2, (ICO_MAX - resized.height()) / 2);
+    image::imageops::overlay(&mut canvas, &resized, x.into(), y.into());
+    print_info(&tr!(
+        "ICO entries are at most {}: downscaled {}x{} -> {}x{}, padded to {} (--no-auto-downscale to keep the size)",
+        format!("{0}x{0}", ICO_MAX),
+        width,
+        height,
+        resized.width(),
+        resized.height(),
+        format!("{0}x{0}", ICO_MAX)
+    ));
+    DynamicImage::ImageRgba8(canvas)
+}
+
+/// Decode a JPEG at 1/2, 1/4 or 1/8 of its size when the --resize target
+/// allows it, through the decoder's reduced-size IDCT
+///
+/// Only the reduced image is ever allocated and most of the IDCT work is
+/// skipped, which is where shrinking large photos spends its time and
+/// memory. The largest factor that still leaves the image at least as big as
+/// the target in either orientation is used, so the real filter still sets
+/// the quality. None means a full decode is needed: no factor fits, the
+/// resize is in linear light (scaling in the DCT averages in sRGB), or the
+/// JPEG isn't 8-bit gray or color.
+pub fn decode_jpeg_scaled(data: &[u8], args: &ResizeArgs) -> Result<Option<DynamicImage>> {
+    let Some(size) = args.resize.filter(|_| !args.linear_resize) else {
+        return Ok(None);
+    };
+    let mut decoder = jpeg_decoder::Decoder::new(data);
+    if decoder.read_info().is_err() {
+        return Ok(None);
+    }
+    let Some(info) = decoder.info() else {
+        return Ok(None);
+    };
+    let (width, height) = (info.width as u32, info.height as u32);
+    // EXIF orientation may still swap the sides, so both fits must be met
+    let (fit_w, fit_h) = size.fit(width, height);
+    let (swapped_h, swapped_w) = size.fit(height, width);
+    let (target_w, target_h) = (fit_w.max(swapped_w), fit_h.max(swapped_h));
+    let Some(factor) = [8, 4, 2].into_iter().find(|f| width / f >= target_w && height / f >= target_h) else {
+        return Ok(None);
+    };
+    let (scaled_w, scaled_h) = decoder.scale(width.div_ceil(factor) as u16, height.div_ceil(factor) as u16)?;
+    let (scaled_w, scaled_h) = (scaled_w as u32, scaled_h as u32);
+    let pixels = decoder.decode()?;
+    let img = match info.pixel_format {
+        jpeg_decoder::PixelFormat::L8 => image::GrayImage::from_raw(scaled_w, scaled_h, pixels).map(DynamicImage::ImageLuma8),
+        jpeg_decoder::PixelFormat::RGB24 => image::RgbImage::from_raw(scaled_w, scaled_h, pixels).map(DynamicImage::ImageRgb8),
+        _ => return Ok(None),
+    };
+    let Some(img) = img else {
+        anyhow::bail!(tr!("The JPEG decoder returned a truncated {}x{} image", scaled_w, scaled_h));
+    };
+    print_info(&tr!("Decoded at 1/{} scale: {}x{} -> {}x{}", factor, width, height, scaled_w, scaled_h));
+    Ok(Some(img))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn checkerboard() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(64, 32, |x, y| {
+            if (x + y) % 2 == 0 { Rgb([255, 255, 255]) } else { Rgb([0, 0, 0]) }
+        }))
+    }
+
+    #[test]
+    fn test_resize_keeps_aspect() {
+        let args = ResizeArgs { resize: Some(Size { width: 32, height: 32 }), filter: Filter::Triangle, linear_resize: false, gpu: false, no_auto_downscale: false };
+        assert_eq!(apply(checkerboard(), &args).unwrap().dimensions(), (32, 16));
+    }
+
+    #[test]
+    fn test_linear_resize_is_brighter() {
+        // Averaging black and white in linear light gives ~188, not 128
+        let mut args = ResizeArgs { resize: Some(Size { width: 8, height: 4 }), filter: Filter::Triangle, linear_resize: false, gpu: false, no_auto_downscale: false };
+        let gamma = apply(checkerboard(), &args).unwrap().to_rgb8().get_pixel(4, 2).0[0];
+        args.linear_resize = true;
+        let linear = apply(checkerboard(), &args).unwrap().to_rgb8().get_pixel(4, 2).0[0];
+        assert!((120..=136).contains(&gamma), "gamma: {}", gamma);
+        assert!((180..=196).contains(&linear), "linear: {}", linear);
+    }
+
+    #[test]
+    fn test_fit_ico_pads_to_square() {
+        let args = ResizeArgs { resize: None, filter: Filter::Triangle, linear_resize: false, gpu: false, no_auto_downscale: false };
+        let img = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(600, 300, image::Rgb([200, 0, 0])));
+        let icon = fit_ico(img.clone(), &args, ImageFormat::Ico).to_rgba8();
+        assert_eq!(icon.dimensions(), (256, 256));
+        assert_eq!(icon.get_pixel(128, 128).0, [200, 0, 0, 255]);
+        assert_eq!(icon.get_pixel(128, 10).0[3], 0);
+        assert_eq!(fit_ico(img.clone(), &args, ImageFormat::Png).dimensions(), (600, 300));
+        let args = ResizeArgs { no_auto_downscale: true, ..args };
+        assert_eq!(fit_ico(img, &args, ImageFormat::Ico).dimensions(), (600, 300));
+    }
+
+    /// `img` encoded as a JPEG
+    fn jpeg(img: &DynamicImage) -> Vec<u8> {
+        let mut data = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut data, ImageFormat::Jpeg).unwrap();
+        data.into_inner()
+    }
+
+    #[test]
+    fn test_scaled_jpeg_decode_stays_above_target() {
+        let photo = DynamicImage::ImageRgb8(RgbImage::from_fn(64, 40, |x, _| if x < 32 { Rgb([200, 40, 40]) } else { Rgb([40, 40, 200]) }));
+        let mut args = ResizeArgs { resize: Some(Size { width: 10, height: 10 }), filter: Filter::Triangle, linear_resize: false, gpu: false, no_auto_downscale: false };
+        // 64x40 -> 10x6, or 10x16 if rotated: 1/4 gives 16x10, 1/8 would give 8x5
+        let reduced = decode_jpeg_scaled(&jpeg(&photo), &args).unwrap().unwrap();
+        assert_eq!(reduced.dimensions(), (16, 10));
+        let pixel = reduced.to_rgb8().get_pixel(2, 5).0;
+        assert!(pixel[0] > 170 && pixel[2] < 70, "{:?}", pixel);
+
+        args.resize = Some(Size { width: 40, height: 40 });
+        assert!(decode_jpeg_scaled(&jpeg(&photo), &args).unwrap().is_none());
+        args = ResizeArgs { resize: Some(Size { width: 10, height: 10 }), linear_resize: true, ..args };
+        assert!(decode_jpeg_scaled(&jpeg(&photo), &args).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_scaled_jpeg_decode_keeps_gray() {
+        let gray = DynamicImage::ImageLuma8(image::GrayImage::from_pixel(50, 50, image::Luma([90])));
+        let args = ResizeArgs { resize: Some(Size { width: 12, height: 12 }), filter: Filter::Triangle, linear_resize: false, gpu: false, no_auto_downscale: false };
+        let reduced = decode_jpeg_scaled(&jpeg(&gray), &args).unwrap().unwrap();
+        assert_eq!((reduced.color(), reduced.dimensions()), (image::ColorType::L8, (13, 13)));
+        assert!(decode_jpeg_scaled(b"not a jpeg", &args).unwrap().is_none());
+    }
+}
@@ -0,0 +1,123 @@
+// File: src\resume.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Batch progress state so interrupted runs can pick up where they stopped
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::encode::write_bytes;
+use crate::i18n::tr;
+use crate::{print_info, print_success};
+
+#[derive(Args, Debug)]
+pub struct ResumeArgs {
+    /// Track batch progress in FILE and skip finished files when rerun
+    /// (`--resume FILE` alone continues with the saved arguments)
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["estimate", "clipboard"])]
+    pub resume: Option<PathBuf>,
+}
+
+/// Progress of one batch, saved after every converted file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct State {
+    #[serde(skip)]
+    path: PathBuf,
+    /// Command-line arguments the batch was started with
+    arguments: Vec<String>,
+    /// Sources whose output has been fully written
+    done: BTreeSet<PathBuf>,
+}
+
+impl State {
+    /// Arguments saved in the state file at `path`
+    pub fn saved_arguments(path: &Path) -> Result<Vec<String>> {
+        Ok(Self::load(path)?.arguments)
+    }
+
+    /// Continue the batch recorded at `path`, or start a new one
+    ///
+    /// A state file written for different arguments is refused, since its
+    /// finished files would not match what this run produces.
+    pub fn open(path: &Path, arguments: &[String]) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self { path: path.to_path_buf(), arguments: arguments.to_vec(), done: BTreeSet::new() });
+        }
+        let state = Self::load(path)?;
+        if state.arguments != arguments {
+            anyhow::bail!(
+                "{} belongs to a batch started with different arguments ({}); run `imgconv --resume {}` \
+                 to continue it, or delete it to start over",
+                path.display(),
+                state.arguments.join(" "),
+                path.display()
+            );
+        }
+        print_info(&tr!("Resuming: {} files already converted", state.done.len()));
+        Ok(state)
+    }
+
+    fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path).with_context(|| format!("Failed to read state file: {}", path.display()))?;
+        let mut state: Self = serde_json::from_slice(&data)
+            .with_context(|| format!("Invalid state file: {}", path.display()))?;
+        state.path = path.to_path_buf();
+        Ok(state)
+    }
+
+    pub fn is_done(&self, source: &Path) -> bool {
+        self.done.contains(source)
+    }
+
+    /// Record `source` as finished; outputs are written atomically, so a
+    /// file counts as done only once its output is complete
+    pub fn mark_done(&mut self, source: &Path) -> Result<()> {
+        self.done.insert(source.to_path_buf());
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize batch state")?;
+        write_bytes(&self.path, (json + "\n").as_bytes())
+    }
+
+    /// Some files failed: keep the state so a rerun retries just those
+    pub fn suspend(self) {
+        print_info(&tr!(
+            "Progress saved; run `imgconv --resume {}` to retry the remaining files",
+            self.path.display()
+        ));
+    }
+
+    /// The batch completed: the state file is no longer needed
+    pub fn finish(self) -> Result<()> {
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)
+                .with_context(|| format!("Failed to remove state file: {}", self.path.display()))?;
+        }
+        print_success(&tr!("Batch complete, removed state file: {}", self.path.display()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_round_trip() {
+        let path = std::env::temp_dir().join(format!("imgconv-resume-{}.json", std::process::id()));
+        let arguments = vec!["in".to_string(), "out".to_string(), "--resume".to_string()];
+        let mut state = State::open(&path, &arguments).unwrap();
+        state.mark_done(Path::new("in/a.png")).unwrap();
+
+        let state = State::open(&path, &arguments).unwrap();
+        assert!(state.is_done(Path::new("in/a.png")));
+        assert!(!state.is_done(Path::new("in/b.png")));
+        assert_eq!(State::saved_arguments(&path).unwrap(), arguments);
+        assert!(State::open(&path, &arguments[..2]).is_err());
+
+        state.finish().unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,299 @@
+// File: src\s3.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Upload batch outputs to S3-compatible object storage (--s3)
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::ImageFormat;
+use imgconv::transport::{self, ImageSink};
+use imgconv::ImgconvError;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::i18n::tr;
+use crate::print_success;
+use crate::sink::{curl, Sink};
+
+#[derive(Args, Debug)]
+pub struct S3Args {
+    /// Upload batch outputs to S3-compatible storage, e.g. s3://bucket/prefix/ (credentials from the AWS environment or ~/.aws)
+    #[arg(
+        long,
+        value_name = "URL",
+        group = "sink",
+        conflicts_with_all = ["clipboard", "in_place", "output", "pos_output", "estimate", "resume"]
+    )]
+    pub s3: Option<String>,
+}
+
+/// AWS credentials as found in the environment or the shared credentials file
+#[derive(Debug, Clone, PartialEq)]
+struct Credentials {
+    key: String,
+    secret: String,
+    token: Option<String>,
+}
+
+/// An `s3://bucket/prefix/` destination, uploaded to with curl's SigV4 signing
+pub struct Bucket {
+    bucket: String,
+    prefix: String,
+    region: String,
+    /// AWS_ENDPOINT_URL(_S3) for S3-compatible services (path-style URLs)
+    endpoint: Option<String>,
+    credentials: Credentials,
+    uploaded: usize,
+}
+
+impl Bucket {
+    pub fn new(url: &str) -> Result<Self> {
+        let (bucket, prefix) = parse_url(url)?;
+        let profile = env("AWS_PROFILE").unwrap_or_else(|| "default".into());
+        let credentials = credentials(&profile)?;
+        let config = aws_file("AWS_CONFIG_FILE", "config")
+            .map(|text| ini_section(&text, &config_section(&profile)))
+            .unwrap_or_default();
+        let region = env("AWS_REGION")
+            .or_else(|| env("AWS_DEFAULT_REGION"))
+            .or_else(|| config.get("region").cloned())
+            .unwrap_or_else(|| "us-east-1".into());
+        let endpoint = env("AWS_ENDPOINT_URL_S3")
+            .or_else(|| env("AWS_ENDPOINT_URL"))
+            .or_else(|| config.get("endpoint_url").cloned());
+        Ok(Self { bucket, prefix, region, endpoint, credentials, uploaded: 0 })
+    }
+
+    /// Object URL for `key`: path-style on a custom endpoint, virtual-hosted on AWS
+    fn object_url(&self, key: &str) -> String {
+        let key = encode_key(key);
+        match &self.endpoint {
+            Some(endpoint) => format!("{}/{}/{}", endpoint.trim_end_matches('/'), self.bucket, key),
+            None => format!("https://{}.s3.{}.amazonaws.com/{}", self.bucket, self.region, key),
+        }
+    }
+}
+
+impl Sink for Bucket {
+    fn put(&mut self, output: &Path, name: &str, format: ImageFormat) -> Result<()> {
+        let key = format!("{}{}", self.prefix, name);
+        let url = self.object_url(&key);
+        let data = std::fs::read(output).with_context(|| format!("Failed to read {}", output.display()))?;
+        let mut config = vec![
+            ("user", format!("{}:{}", self.credentials.key, self.credentials.secret)),
+            ("header", format!("Content-Type: {}", format.to_mime_type())),
+            ("header", format!("x-amz-content-sha256: {:x}", Sha256::digest(&data))),
+        ];
+        if let Some(token) = &self.credentials.token {
+            config.push(("header", format!("x-amz-security-token: {}", token)));
+        }
+        let sigv4 = format!("aws:amz:{}:s3", self.region);
+        let arguments = [OsStr::new("--aws-sigv4"), sigv4.as_ref(), "--upload-file".as_ref(), output.as_os_str(), url.as_ref()];
+        let status = curl(arguments, &config)?;
+        if !status.success() {
+            anyhow::bail!("Upload to s3://{}/{} failed (curl exited with {})", self.bucket, key, status);
+        }
+        self.uploaded += 1;
+        print_success(&tr!("Uploaded: s3://{}/{}", self.bucket, key));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.uploaded > 0 {
+            print_success(&tr!("Uploaded {} files to s3://{}/{}", self.uploaded, self.bucket, self.prefix));
+        }
+        Ok(())
+    }
+}
+
+/// One `s3://bucket/key` object, the output of a single-file conversion
+struct Object {
+    spec: String,
+    bucket: Bucket,
+    key: String,
+}
+
+impl ImageSink for Object {
+    fn name(&self) -> String {
+        self.spec.clone()
+    }
+
+    fn write(&mut self, data: &[u8], format: ImageFormat) -> imgconv::Result<()> {
+        let uploaded = stage(data).context("Failed to stage the upload").and_then(|staged| {
+            let uploaded = self.bucket.put(&staged, &self.key, format);
+            std::fs::remove_file(&staged).ok();
+            uploaded
+        });
+        uploaded.map_err(|source| ImgconvError::Transport { name: self.spec.clone(), source: source.into() })
+    }
+}
+
+/// Numbers the staged uploads, as parallel jobs may upload at once
+static STAGED: AtomicUsize = AtomicUsize::new(0);
+
+/// Write the data to a new temporary file for curl to upload
+///
+/// The file is created fresh (never opened if it exists), so a file or
+/// symlink someone else planted under the name is not written through.
+fn stage(data: &[u8]) -> std::io::Result<PathBuf> {
+    loop {
+        let path = std::env::temp_dir().join(format!("imgconv-s3-{}-{}", std::process::id(), STAGED.fetch_add(1, Ordering::Relaxed)));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                if let Err(e) = file.write_all(data) {
+                    std::fs::remove_file(&path).ok();
+                    return Err(e);
+                }
+                return Ok(path);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Let `s3://bucket/key` name a single output
+pub fn register() {
+    transport::register_sink("s3", |spec| {
+        let open = || -> Result<Object> {
+            let (bucket, key) = spec.strip_prefix("s3://").and_then(|rest| rest.rsplit_once('/')).filter(|(_, key)| !key.is_empty())
+                .with_context(|| format!("Expected s3://bucket/key, got: {}", spec))?;
+            Ok(Object { spec: spec.to_string(), bucket: Bucket::new(&format!("s3://{}", bucket))?, key: key.to_string() })
+        };
+        match open() {
+            Ok(object) => Ok(Box::new(object)),
+            Err(source) => Err(ImgconvError::Transport { name: spec.to_string(), source: source.into() }),
+        }
+    });
+}
+
+/// Bucket and key prefix (empty or ending in "/") of an s3:// URL
+fn parse_url(url: &str) -> Result<(String, String)> {
+    let rest = url.strip_prefix("s3://").with_context(|| format!("--s3 expects s3://bucket/prefix/, got: {}", url))?;
+    let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+    if bucket.is_empty() {
+        anyhow::bail!("--s3 URL has no bucket: {}", url);
+    }
+    let prefix = prefix.trim_matches('/');
+    let prefix = if prefix.is_empty() { String::new() } else { format!("{}/", prefix) };
+    Ok((bucket.to_string(), prefix))
+}
+
+/// Keys from AWS_ACCESS_KEY_ID/AWS_SECRET_ACCESS_KEY, else the profile in ~/.aws/credentials
+fn credentials(profile: &str) -> Result<Credentials> {
+    if let (Some(key), Some(secret)) = (env("AWS_ACCESS_KEY_ID"), env("AWS_SECRET_ACCESS_KEY")) {
+        return Ok(Credentials { key, secret, token: env("AWS_SESSION_TOKEN") });
+    }
+    let mut section = aws_file("AWS_SHARED_CREDENTIALS_FILE", "credentials")
+        .map(|text| ini_section(&text, profile))
+        .unwrap_or_default();
+    match (section.remove("aws_access_key_id"), section.remove("aws_secret_access_key")) {
+        (Some(key), Some(secret)) => Ok(Credentials { key, secret, token: section.remove("aws_session_token") }),
+        _ => anyhow::bail!(
+            "No S3 credentials: set AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY or add profile [{}] to ~/.aws/credentials",
+            profile
+        ),
+    }
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Contents of an AWS shared file: $VARIABLE, else ~/.aws/NAME
+fn aws_file(variable: &str, name: &str) -> Option<String> {
+    let path = std::env::var_os(variable).map(PathBuf::from).or_else(|| {
+        let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+        Some(Path::new(&home).join(".aws").join(name))
+    })?;
+    std::fs::read_to_string(path).ok()
+}
+
+/// The config file names profiles "[profile NAME]", except the default one
+fn config_section(profile: &str) -> String {
+    if profile == "default" {
+        profile.to_string()
+    } else {
+        format!("profile {}", profile)
+    }
+}
+
+/// Key/value pairs of `[section]` in an INI file
+fn ini_section(text: &str, section: &str) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let mut inside = false;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            inside = name.trim() == section;
+        } else if let Some((key, value)) = line.split_once('=').filter(|_| inside) {
+            values.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    values
+}
+
+/// URI-encode an object key the way SigV4 expects, keeping the "/" separators
+pub fn encode_key(key: &str) -> String {
+    let mut encoded = String::new();
+    for byte in key.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url_and_keys() {
+        assert_eq!(parse_url("s3://assets/web/img").unwrap(), ("assets".into(), "web/img/".into()));
+        assert_eq!(parse_url("s3://assets").unwrap(), ("assets".into(), String::new()));
+        assert!(parse_url("https://assets/").is_err());
+        assert_eq!(encode_key("web/My Trip/ü+1.webp"), "web/My%20Trip/%C3%BC%2B1.webp");
+
+        let credentials = Credentials { key: "k".into(), secret: "s".into(), token: None };
+        let mut bucket = Bucket {
+            bucket: "assets".into(),
+            prefix: String::new(),
+            region: "eu-west-1".into(),
+            endpoint: None,
+            credentials,
+            uploaded: 0,
+        };
+        assert_eq!(bucket.object_url("a b.png"), "https://assets.s3.eu-west-1.amazonaws.com/a%20b.png");
+        bucket.endpoint = Some("http://localhost:9000/".into());
+        assert_eq!(bucket.object_url("x/a.png"), "http://localhost:9000/assets/x/a.png");
+    }
+
+    #[test]
+    fn test_ini_section() {
+        let text = "[default]\nregion = us-east-1\n\n[profile cdn]\n# comment\nregion=eu-central-1\nendpoint_url = http://minio:9000\n";
+        let cdn = ini_section(text, &config_section("cdn"));
+        assert_eq!(cdn.get("region").map(String::as_str), Some("eu-central-1"));
+        assert_eq!(cdn.get("endpoint_url").map(String::as_str), Some("http://minio:9000"));
+        assert_eq!(ini_section(text, &config_section("default")).len(), 1);
+    }
+
+    #[test]
+    fn test_stage_uses_fresh_files() {
+        let first = stage(b"one").unwrap();
+        let second = stage(b"two").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read(&first).unwrap(), b"one");
+        std::fs::remove_file(&first).unwrap();
+        std::fs::remove_file(&second).unwrap();
+    }
+}
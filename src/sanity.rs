@@ -0,0 +1,122 @@
+// File: src\sanity.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Compare a decoded image with what its file claims, to catch truncated or malformed inputs
+// License: MIT
+
+use anyhow::Result;
+use image::{ImageFormat, ImageReader};
+use std::io::Cursor;
+use std::path::Path;
+
+use crate::i18n::tr;
+use crate::print_warning;
+
+/// Warn about (or with `strict`, refuse) an input whose decoded image
+/// disagrees with its file: another size than the header states, missing end
+/// markers, or fewer animation frames than announced
+///
+/// Decoders often fill a truncated image with gray instead of failing, so
+/// this is what tells a damaged upload from a good one.
+pub fn check(name: &Path, data: &[u8], format: Option<ImageFormat>, decoded: (u32, u32), strict: bool) -> Result<()> {
+    for issue in inspect(data, format, decoded) {
+        if strict {
+            anyhow::bail!(tr!("{}: {} (--strict)", name.display(), issue));
+        }
+        print_warning(&tr!("{}: {}", name.display(), issue));
+    }
+    Ok(())
+}
+
+fn inspect(data: &[u8], format: Option<ImageFormat>, decoded: (u32, u32)) -> Vec<String> {
+    let Some(format) = format else {
+        return Vec::new();
+    };
+    let mut issues = Vec::new();
+    if let Ok(claimed) = ImageReader::with_format(Cursor::new(data), format).into_dimensions() {
+        if claimed != decoded {
+            issues.push(tr!(
+                "decoded {}x{}, but the header says {}x{}",
+                decoded.0, decoded.1, claimed.0, claimed.1
+            ));
+        }
+    }
+    match format {
+        ImageFormat::Png => issues.extend(png_issues(data)),
+        ImageFormat::Jpeg if !jpeg_has_end(data) => {
+            issues.push(tr!("no end-of-image marker after the image data, the file looks truncated"))
+        }
+        ImageFormat::Gif if data.last() != Some(&0x3B) => {
+            issues.push(tr!("no GIF trailer at the end, the file looks truncated"))
+        }
+        _ => {}
+    }
+    issues
+}
+
+/// Walk the chunks: the file must reach IEND, and an APNG must hold the
+/// number of frames its acTL chunk announces
+fn png_issues(data: &[u8]) -> Vec<String> {
+    let be32 = |bytes: &[u8]| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let (mut pos, mut announced, mut frames, mut ended) = (8, None, 0, false);
+    while let Some(header) = data.get(pos..pos + 8) {
+        let length = be32(&header[..4]) as usize;
+        match &header[4..] {
+            b"acTL" => announced = data.get(pos + 8..pos + 12).map(be32),
+            b"fcTL" => frames += 1,
+            b"IEND" => {
+                ended = true;
+                break;
+            }
+            _ => {}
+        }
+        pos += 12 + length;
+    }
+    let mut issues = Vec::new();
+    if !ended {
+        issues.push(tr!("no IEND chunk, the file looks truncated"));
+    }
+    match announced {
+        Some(announced) if announced != frames => {
+            issues.push(tr!("the animation announces {} frames, but {} are present", announced, frames))
+        }
+        _ => {}
+    }
+    issues
+}
+
+/// Whether an end-of-image marker follows the last start-of-scan
+fn jpeg_has_end(data: &[u8]) -> bool {
+    // Entropy-coded data escapes 0xFF as FF 00, so these pairs are real markers
+    let scan = data.windows(2).rposition(|pair| pair == [0xFF, 0xDA]).unwrap_or(0);
+    data[scan..].windows(2).any(|pair| pair == [0xFF, 0xD9])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(format: ImageFormat) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgb8(image::RgbImage::new(16, 8)).write_to(&mut buffer, format).unwrap();
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn test_intact_files_pass() {
+        for format in [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::Gif, ImageFormat::Bmp] {
+            assert!(inspect(&encode(format), Some(format), (16, 8)).is_empty(), "{:?}", format);
+        }
+        assert_eq!(inspect(&encode(ImageFormat::Png), Some(ImageFormat::Png), (8, 8)).len(), 1);
+    }
+
+    #[test]
+    fn test_truncated_files_are_flagged() {
+        for format in [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::Gif] {
+            let data = encode(format);
+            let issues = inspect(&data[..data.len() - 12], Some(format), (16, 8));
+            assert!(issues.iter().any(|issue| issue.contains("truncated")), "{:?}", format);
+        }
+        assert!(check(Path::new("a.png"), &encode(ImageFormat::Png)[..40], Some(ImageFormat::Png), (16, 8), true).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+// File: src\scan.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Cleanup for scanned documents: halftone removal (--descreen) and straightening (--deskew)
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GenericImageView, Rgba, Rgba32FImage};
+
+use crate::colorspace::with_color_type;
+use crate::i18n::tr;
+use crate::print_info;
+use crate::sharpen::{unsharp_mask, Unsharp};
+
+/// Largest skew --deskew looks for, in degrees either way
+pub const MAX_SKEW: f32 = 15.0;
+
+/// Skew is measured on a copy no larger than this, which is plenty for the angle
+const DETECT_SIZE: u32 = 800;
+
+#[derive(Args, Debug)]
+pub struct ScanArgs {
+    /// Remove halftone dots (moiré) from scanned print by blurring over
+    /// RADIUS pixels, about the dot spacing (e.g. 2 for a 600 dpi magazine scan)
+    #[arg(long, value_name = "RADIUS", value_parser = parse_radius)]
+    pub descreen: Option<f32>,
+
+    /// Straighten a crooked scan, measuring the skew from its text lines and edges
+    #[arg(long)]
+    pub deskew: bool,
+}
+
+impl ScanArgs {
+    /// Whether descreening or deskewing was asked for
+    pub fn is_active(&self) -> bool {
+        self.descreen.is_some() || self.deskew
+    }
+}
+
+fn parse_radius(s: &str) -> Result<f32, String> {
+    let radius: f32 = s.parse().map_err(|_| format!("Expected a radius in pixels, got: {}", s))?;
+    if !(0.5..=20.0).contains(&radius) {
+        return Err(format!("Descreen radius must be between 0.5 and 20, got: {}", radius));
+    }
+    Ok(radius)
+}
+
+/// Apply --descreen, returning the image unchanged when not requested
+///
+/// The blur removes the dot pattern; a wide, thresholded unsharp mask then
+/// brings back the edges of text and shapes without the dots.
+pub fn apply_descreen(img: DynamicImage, args: &ScanArgs) -> Result<DynamicImage> {
+    let Some(radius) = args.descreen else {
+        return Ok(img);
+    };
+    let color_type = img.color();
+    let blurred = with_color_type(DynamicImage::ImageRgba32F(imageops::blur(&img.to_rgba32f(), radius)), color_type);
+    Ok(unsharp_mask(blurred, Unsharp { radius: radius * 2.0, amount: 0.6, threshold: 6 }))
+}
+
+/// Apply --deskew, returning the image unchanged when not requested or
+/// when no skew is found
+pub fn apply_deskew(img: DynamicImage, args: &ScanArgs) -> Result<DynamicImage> {
+    if !args.deskew {
+        return Ok(img);
+    }
+    let Some(angle) = detect_skew(&img) else {
+        print_info(&tr!("No skew found"));
+        return Ok(img);
+    };
+    print_info(&tr!("Deskewed by {}°", format!("{:.2}", -angle)));
+    let color_type = img.color();
+    Ok(with_color_type(DynamicImage::ImageRgba32F(rotate(&img.to_rgba32f(), angle)), color_type))
+}
+
+/// The angle in degrees (clockwise, as y points down) that lines of the
+/// document run at, found as the projection that stacks its dark pixels
+/// into the sharpest rows; None for a blank page or a straight one
+fn detect_skew(img: &DynamicImage) -> Option<f32> {
+    let (width, height) = img.dimensions();
+    let small = if width.max(height) > DETECT_SIZE { img.resize(DETECT_SIZE, DETECT_SIZE, FilterType::Triangle) } else { img.clone() };
+    let gray = small.to_luma8();
+    let mean = gray.pixels().map(|p| p.0[0] as f64).sum::<f64>() / gray.len().max(1) as f64;
+    // Ink: anything clearly darker than the page on average
+    let threshold = mean * 0.75;
+    let ink: Vec<(f32, f32)> =
+        gray.enumerate_pixels().filter(|(_, _, p)| (p.0[0] as f64) < threshold).map(|(x, y, _)| (x as f32, y as f32)).collect();
+    if ink.len() < 32 || ink.len() as u64 * 2 > gray.len() as u64 {
+        return None;
+    }
+    // Projected rows stay within ±(width + height), so they are offset by that
+    let rows = gray.width() + gray.height();
+    let score = |angle: f32| {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let mut bins = vec![0u32; rows as usize * 2];
+        for &(x, y) in &ink {
+            bins[((y * cos - x * sin).round() as i64 + rows as i64) as usize] += 1;
+        }
+        bins.iter().map(|&n| (n as u64).pow(2)).sum::<u64>()
+    };
+    // Neighbouring angles often tie on a small copy; the middle of the tie wins
+    let best = |angles: &mut dyn Iterator<Item = f32>| {
+        let scored: Vec<(f32, u64)> = angles.map(|angle| (angle, score(angle))).collect();
+        let top = scored.iter().map(|&(_, score)| score).max().unwrap_or(0);
+        let tied: Vec<f32> = scored.iter().filter(|&&(_, score)| score == top).map(|&(angle, _)| angle).collect();
+        tied.iter().sum::<f32>() / tied.len() as f32
+    };
+    // A coarse sweep in whole degrees, then twentieths around the best
+    let coarse = best(&mut (-MAX_SKEW as i32..=MAX_SKEW as i32).map(|degree| degree as f32));
+    let fine = best(&mut (-20..=20).map(|step| coarse + step as f32 * 0.05));
+    (fine.abs() >= 0.05).then_some(fine)
+}
+
+/// Rotate `img` by `degrees` clockwise about its center, keeping the size;
+/// the corners uncovered are filled with the average color of the border
+fn rotate(img: &Rgba32FImage, degrees: f32) -> Rgba32FImage {
+    let (width, height) = img.dimensions();
+    let border: Vec<&Rgba<f32>> = img.enumerate_pixels().filter(|(x, y, _)| *x == 0 || *y == 0 || *x == width - 1 || *y == height - 1).map(|(_, _, p)| p).collect();
+    let mut fill = [0f32; 4];
+    for pixel in &border {
+        for (sum, value) in fill.iter_mut().zip(pixel.0) {
+            *sum += value / border.len() as f32;
+        }
+    }
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (cx, cy) = ((width as f32 - 1.0) / 2.0, (height as f32 - 1.0) / 2.0);
+    Rgba32FImage::from_fn(width, height, |x, y| {
+        let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+        let (sx, sy) = (cx + dx * cos - dy * sin, cy + dx * sin + dy * cos);
+        if sx < 0.0 || sy < 0.0 || sx > width as f32 - 1.0 || sy > height as f32 - 1.0 {
+            return Rgba(fill);
+        }
+        // Bilinear between the four source pixels around (sx, sy)
+        let (x0, y0) = (sx.floor() as u32, sy.floor() as u32);
+        let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+        let (fx, fy) = (sx - x0 as f32, sy - y0 as f32);
+        let (a, b, c, d) = (img.get_pixel(x0, y0).0, img.get_pixel(x1, y0).0, img.get_pixel(x0, y1).0, img.get_pixel(x1, y1).0);
+        Rgba(std::array::from_fn(|i| {
+            (a[i] * (1.0 - fx) + b[i] * fx) * (1.0 - fy) + (c[i] * (1.0 - fx) + d[i] * fx) * fy
+        }))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    /// A white page with dark text-like lines sloping down by `degrees`
+    fn page(degrees: f32) -> DynamicImage {
+        let slope = degrees.to_radians().tan();
+        DynamicImage::ImageLuma8(GrayImage::from_fn(240, 160, |x, y| {
+            let offset = y as f32 - x as f32 * slope;
+            let on_line = (20.0..140.0).contains(&offset) && offset.rem_euclid(16.0) < 3.0 && (20..220).contains(&x);
+            Luma([if on_line { 20 } else { 245 }])
+        }))
+    }
+
+    #[test]
+    fn test_deskew_straightens_lines() {
+        let skewed = page(3.0);
+        let angle = detect_skew(&skewed).unwrap();
+        assert!((angle - 3.0).abs() < 0.2, "{}", angle);
+        assert_eq!(detect_skew(&page(0.0)), None);
+
+        let args = ScanArgs { descreen: None, deskew: true };
+        let straight = apply_deskew(skewed, &args).unwrap();
+        assert_eq!(straight.color(), image::ColorType::L8);
+        assert!(detect_skew(&straight).is_none_or(|angle| angle.abs() < 0.2));
+    }
+
+    #[test]
+    fn test_descreen_removes_dots() {
+        // A 50% halftone: alternating black and white pixels
+        let dots = DynamicImage::ImageLuma8(GrayImage::from_fn(32, 32, |x, y| Luma([if (x + y) % 2 == 0 { 0 } else { 255 }])));
+        let args = ScanArgs { descreen: Some(1.5), deskew: false };
+        let out = apply_descreen(dots, &args).unwrap().into_luma8();
+        // The corners see the pattern mirrored by the edge, so only the inside is even
+        let inside = out.enumerate_pixels().filter(|(x, y, _)| (2..30).contains(x) && (2..30).contains(y));
+        assert!(inside.clone().all(|(_, _, p)| (118..=138).contains(&p.0[0])), "{:?}", inside.map(|p| p.2.0[0]).min());
+        assert!(parse_radius("0.1").is_err());
+    }
+}
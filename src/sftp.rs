@@ -0,0 +1,145 @@
+// File: src\sftp.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Ship batch outputs to a remote server over SFTP (--sftp)
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::ImageFormat;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::i18n::tr;
+use crate::print_success;
+use crate::sink::Sink;
+
+#[derive(Args, Debug)]
+pub struct SftpArgs {
+    /// Upload batch outputs over SFTP to user@host:/path/ (key-based login, uses the ssh client)
+    #[arg(
+        long,
+        value_name = "TARGET",
+        group = "sink",
+        conflicts_with_all = ["clipboard", "in_place", "output", "pos_output", "estimate", "resume"]
+    )]
+    pub sftp: Option<String>,
+}
+
+/// A remote directory reached through the system `sftp` client
+pub struct Remote {
+    /// `user@host` (or a host alias from ~/.ssh/config)
+    host: String,
+    /// Remote directory, empty for the login directory
+    dir: String,
+    /// Remote directories already created during this run
+    created: HashSet<String>,
+    /// Socket of the shared SSH connection (Unix), so each file doesn't log in again
+    control: Option<PathBuf>,
+    uploaded: usize,
+}
+
+impl Remote {
+    pub fn new(target: &str) -> Result<Self> {
+        let (host, dir) = parse_target(target)?;
+        let control = cfg!(unix).then(|| std::env::temp_dir().join(format!("imgconv-ssh-{}", std::process::id())));
+        Ok(Self { host, dir, created: HashSet::new(), control, uploaded: 0 })
+    }
+
+    /// Batch commands creating any missing parent directories of `path`, then uploading `output` to it
+    fn commands(&mut self, output: &Path, path: &str) -> String {
+        let mut commands = String::new();
+        for (end, _) in path.match_indices('/').filter(|(end, _)| *end > 0) {
+            let parent = &path[..end];
+            if self.created.insert(parent.to_string()) {
+                // A leading "-" tells sftp to carry on when the directory already exists
+                commands += &format!("-mkdir {}\n", quote(parent));
+            }
+        }
+        commands += &format!("put {} {}\n", quote(&output.display().to_string()), quote(path));
+        commands
+    }
+
+    fn sftp(&self) -> Command {
+        let mut sftp = Command::new("sftp");
+        sftp.args(["-q", "-b", "-"]);
+        if let Some(control) = &self.control {
+            sftp.args(["-o", "ControlMaster=auto", "-o", "ControlPersist=60"]);
+            sftp.arg("-o").arg(format!("ControlPath={}", control.display()));
+        }
+        sftp.arg(&self.host);
+        sftp
+    }
+}
+
+impl Sink for Remote {
+    fn put(&mut self, output: &Path, name: &str, _format: ImageFormat) -> Result<()> {
+        let path = if self.dir.is_empty() { name.to_string() } else { format!("{}/{}", self.dir.trim_end_matches('/'), name) };
+        let commands = self.commands(output, &path);
+        let mut child = self
+            .sftp()
+            .stdin(Stdio::piped())
+            .spawn()
+            .context("Could not run sftp (is the OpenSSH client installed and on PATH?)")?;
+        child.stdin.take().context("sftp stdin unavailable")?.write_all(commands.as_bytes())
+            .context("Failed to send commands to sftp")?;
+        let status = child.wait().context("sftp did not finish")?;
+        if !status.success() {
+            anyhow::bail!("Upload of {} to {}:{} failed (sftp exited with {})", name, self.host, path, status);
+        }
+        self.uploaded += 1;
+        print_success(&tr!("Uploaded: {}:{}", self.host, path));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if let Some(control) = self.control.as_ref().filter(|control| control.exists()) {
+            // Close the shared connection now rather than after ControlPersist
+            Command::new("ssh")
+                .arg("-o")
+                .arg(format!("ControlPath={}", control.display()))
+                .args(["-O", "exit", &self.host])
+                .stderr(Stdio::null())
+                .status()
+                .ok();
+        }
+        if self.uploaded > 0 {
+            print_success(&tr!("Uploaded {} files to {}:{}", self.uploaded, self.host, self.dir));
+        }
+        Ok(())
+    }
+}
+
+/// Host and remote directory of `user@host:/path/`
+fn parse_target(target: &str) -> Result<(String, String)> {
+    match target.split_once(':') {
+        Some((host, dir)) if !host.is_empty() => Ok((host.to_string(), dir.to_string())),
+        _ => anyhow::bail!("--sftp expects user@host:/path/, got: {}", target),
+    }
+}
+
+/// Quote an argument of an sftp batch command
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_commands() {
+        assert_eq!(parse_target("render@farm:/srv/frames/").unwrap(), ("render@farm".into(), "/srv/frames/".into()));
+        assert_eq!(parse_target("farm:").unwrap(), ("farm".into(), String::new()));
+        assert!(parse_target("/srv/frames").is_err());
+
+        let mut remote = Remote::new("farm:/srv").unwrap();
+        let commands = remote.commands(Path::new("/tmp/s/shot 1/a.png"), "/srv/shot 1/a.png");
+        assert_eq!(commands, "-mkdir \"/srv\"\n-mkdir \"/srv/shot 1\"\nput \"/tmp/s/shot 1/a.png\" \"/srv/shot 1/a.png\"\n");
+        let commands = remote.commands(Path::new("b.png"), "/srv/shot 1/b.png");
+        assert_eq!(commands, "put \"b.png\" \"/srv/shot 1/b.png\"\n");
+        assert_eq!(remote.commands(Path::new("c.png"), "c.png"), "put \"c.png\" \"c.png\"\n");
+    }
+}
@@ -0,0 +1,121 @@
+// File: src\sharpen.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Output sharpening with an unsharp mask (--unsharp radius,amount,threshold)
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::{imageops, DynamicImage};
+use std::str::FromStr;
+
+use crate::colorspace::with_color_type;
+
+#[derive(Args, Debug)]
+pub struct SharpenArgs {
+    /// Unsharp mask as RADIUS,AMOUNT[,THRESHOLD], e.g. "0.8,0.6,2" for screen
+    /// or "2,1.2,4" for print; the threshold (0-255) spares smooth areas
+    #[arg(long, value_name = "RADIUS,AMOUNT[,THRESHOLD]")]
+    pub unsharp: Option<Unsharp>,
+}
+
+impl SharpenArgs {
+    /// Whether --unsharp was given
+    pub fn is_active(&self) -> bool {
+        self.unsharp.is_some()
+    }
+}
+
+/// Parsed `--unsharp` specification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unsharp {
+    /// Blur radius (Gaussian sigma) in pixels: the size of the edges enhanced
+    pub radius: f32,
+    /// How much of the difference to the blurred image is added back (1.0 = 100%)
+    pub amount: f32,
+    /// Differences up to this many levels are left alone
+    pub threshold: u8,
+}
+
+impl FromStr for Unsharp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Expected RADIUS,AMOUNT[,THRESHOLD] such as 1.5,0.8,3, got: {}", s);
+        let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+        let (radius, amount, threshold) = match parts[..] {
+            [radius, amount] => (radius, amount, "0"),
+            [radius, amount, threshold] => (radius, amount, threshold),
+            _ => return Err(invalid()),
+        };
+        let unsharp = Unsharp {
+            radius: radius.parse().map_err(|_| invalid())?,
+            amount: amount.parse().map_err(|_| invalid())?,
+            threshold: threshold.parse().map_err(|_| invalid())?,
+        };
+        if !(0.1..=50.0).contains(&unsharp.radius) {
+            return Err(format!("Unsharp radius must be between 0.1 and 50, got: {}", unsharp.radius));
+        }
+        if !(0.0..=10.0).contains(&unsharp.amount) {
+            return Err(format!("Unsharp amount must be between 0 and 10, got: {}", unsharp.amount));
+        }
+        Ok(unsharp)
+    }
+}
+
+/// Apply --unsharp, returning the image unchanged when not requested
+pub fn apply(img: DynamicImage, args: &SharpenArgs) -> Result<DynamicImage> {
+    let Some(unsharp) = args.unsharp else {
+        return Ok(img);
+    };
+    Ok(unsharp_mask(img, unsharp))
+}
+
+/// Sharpen `img` with an unsharp mask
+///
+/// Each color channel moves away from its blurred value by `amount` times
+/// the difference, where that difference exceeds `threshold`; alpha is kept.
+pub fn unsharp_mask(img: DynamicImage, unsharp: Unsharp) -> DynamicImage {
+    let color_type = img.color();
+    let mut canvas = img.to_rgba32f();
+    let blurred = imageops::blur(&canvas, unsharp.radius);
+    let threshold = unsharp.threshold as f32 / 255.0;
+    for (pixel, soft) in canvas.pixels_mut().zip(blurred.pixels()) {
+        for (value, soft) in pixel.0.iter_mut().zip(soft.0).take(3) {
+            let detail = *value - soft;
+            if detail.abs() > threshold {
+                *value = (*value + unsharp.amount * detail).clamp(0.0, 1.0);
+            }
+        }
+    }
+    with_color_type(DynamicImage::ImageRgba32F(canvas), color_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    #[test]
+    fn test_parse_unsharp() {
+        assert_eq!("1.5,0.8,3".parse::<Unsharp>().unwrap(), Unsharp { radius: 1.5, amount: 0.8, threshold: 3 });
+        assert_eq!("2,1".parse::<Unsharp>().unwrap().threshold, 0);
+        assert!("2".parse::<Unsharp>().is_err());
+        assert!("0,1".parse::<Unsharp>().is_err());
+        assert!("1,1,300".parse::<Unsharp>().is_err());
+    }
+
+    #[test]
+    fn test_unsharp_raises_edge_contrast() {
+        let step = GrayImage::from_fn(16, 4, |x, _| Luma([if x < 8 { 80 } else { 160 }]));
+        let sharpen = |threshold| {
+            let args = SharpenArgs { unsharp: Some(Unsharp { radius: 1.5, amount: 1.0, threshold }) };
+            apply(DynamicImage::ImageLuma8(step.clone()), &args).unwrap().into_luma8()
+        };
+        let out = sharpen(0);
+        assert!(out.get_pixel(7, 0).0[0] < 80 && out.get_pixel(8, 0).0[0] > 160);
+        assert_eq!(out.get_pixel(0, 0).0[0], 80);
+        // Edges weaker than the threshold are left alone
+        assert_eq!(sharpen(100), step);
+    }
+}
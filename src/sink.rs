@@ -0,0 +1,176 @@
+// File: src\sink.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Destinations for batch outputs other than an output directory (--zip-output, --s3, --post-to, --sftp)
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::ImageFormat;
+use std::ffi::OsStr;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+
+use crate::{bundle, post, s3, sftp};
+
+#[derive(Args, Debug)]
+pub struct SinkArgs {
+    #[command(flatten)]
+    pub zip: bundle::BundleArgs,
+
+    #[command(flatten)]
+    pub s3: s3::S3Args,
+
+    #[command(flatten)]
+    pub post: post::PostArgs,
+
+    #[command(flatten)]
+    pub sftp: sftp::SftpArgs,
+}
+
+impl SinkArgs {
+    /// The sink option given, for error messages
+    pub fn flag(&self) -> Option<&'static str> {
+        if self.zip.zip_output.is_some() {
+            Some("--zip-output")
+        } else if self.s3.s3.is_some() {
+            Some("--s3")
+        } else if self.post.post_to.is_some() {
+            Some("--post-to")
+        } else if self.sftp.sftp.is_some() {
+            Some("--sftp")
+        } else {
+            None
+        }
+    }
+}
+
+/// Receives each converted file of a batch
+pub trait Sink {
+    /// Deliver `output`; `name` is its path under the staging directory, with forward slashes
+    fn put(&mut self, output: &Path, name: &str, format: ImageFormat) -> Result<()>;
+
+    /// Called once after the last file, also when some files failed
+    fn finish(&mut self) -> Result<()>;
+}
+
+/// A sink and the staging directory its files are written to first
+///
+/// Every output is encoded into the staging directory as usual, handed to
+/// the sink and deleted, so only one converted file is on disk at a time.
+pub struct Staged {
+    staging: PathBuf,
+    sink: Box<dyn Sink>,
+}
+
+/// The sink selected by `args`, if any
+pub fn open(args: &SinkArgs) -> Result<Option<Staged>> {
+    let (staging, sink): (PathBuf, Box<dyn Sink>) = if let Some(path) = &args.zip.zip_output {
+        let mut staging = path.as_os_str().to_owned();
+        staging.push(".parts");
+        (staging.into(), Box::new(bundle::Bundle::new(path)))
+    } else if let Some(url) = &args.s3.s3 {
+        (temp_staging(), Box::new(s3::Bucket::new(url)?))
+    } else if let Some(url) = &args.post.post_to {
+        (temp_staging(), Box::new(post::Endpoint::new(&args.post, url)?))
+    } else if let Some(target) = &args.sftp.sftp {
+        (temp_staging(), Box::new(sftp::Remote::new(target)?))
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(Staged { staging, sink }))
+}
+
+fn temp_staging() -> PathBuf {
+    std::env::temp_dir().join(format!("imgconv-staging-{}", std::process::id()))
+}
+
+impl Staged {
+    /// Where outputs are written before `put` hands them to the sink
+    pub fn staging(&self) -> &Path {
+        &self.staging
+    }
+
+    /// Hand `output` (written under `staging`) to the sink and remove it
+    pub fn put(&mut self, output: &Path, format: ImageFormat) -> Result<()> {
+        let name = entry_name(output.strip_prefix(&self.staging).unwrap_or(output));
+        self.sink.put(output, &name, format)?;
+        std::fs::remove_file(output).with_context(|| format!("Failed to remove staged file: {}", output.display()))
+    }
+
+    pub fn finish(&mut self) -> Result<()> {
+        self.sink.finish()
+    }
+}
+
+impl Drop for Staged {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.staging).ok();
+    }
+}
+
+/// Run curl for an upload, passing `config` (option and value pairs such as
+/// `("header", ...)`) on stdin so credentials never show up in the process list
+pub fn curl<'a>(arguments: impl IntoIterator<Item = &'a OsStr>, config: &[(&str, String)]) -> Result<ExitStatus> {
+    let mut child = Command::new("curl")
+        .args(["-fsS", "-o", if cfg!(windows) { "NUL" } else { "/dev/null" }, "--config", "-"])
+        .args(arguments)
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("Could not run curl (is it installed and on PATH?)")?;
+    let config: String = config
+        .iter()
+        .map(|(option, value)| format!("{} = \"{}\"\n", option, value.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect();
+    child.stdin.take().context("curl stdin unavailable")?.write_all(config.as_bytes())
+        .context("Failed to pass the upload settings to curl")?;
+    child.wait().context("curl did not finish")
+}
+
+/// A relative path with forward slashes, as archive entry names and object keys use
+fn entry_name(relative: &Path) -> String {
+    relative
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Recorder(std::rc::Rc<std::cell::RefCell<Vec<String>>>);
+
+    impl Sink for Recorder {
+        fn put(&mut self, output: &Path, name: &str, _format: ImageFormat) -> Result<()> {
+            self.0.borrow_mut().push(format!("{}={}", name, std::fs::read_to_string(output)?));
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<()> {
+            self.0.borrow_mut().push("finish".into());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_staged_hands_over_and_cleans_up() {
+        let log = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let mut staged = Staged { staging: temp_staging(), sink: Box::new(Recorder(log.clone())) };
+        let output = staged.staging().join("sub").join("a.webp");
+        std::fs::create_dir_all(output.parent().unwrap()).unwrap();
+        std::fs::write(&output, "x").unwrap();
+        staged.put(&output, ImageFormat::WebP).unwrap();
+        staged.finish().unwrap();
+        assert!(!output.exists());
+        let staging = staged.staging().to_path_buf();
+        drop(staged);
+        assert!(!staging.exists());
+        assert_eq!(*log.borrow(), ["sub/a.webp=x", "finish"]);
+    }
+}
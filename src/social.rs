@@ -0,0 +1,128 @@
+// File: src\social.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Social media size presets (dimensions and upload size limits)
+// License: MIT
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use image::{imageops, DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+use crate::color::Color;
+use crate::crop::smart_crop_rect;
+use crate::i18n::tr;
+use crate::resize::Filter;
+use crate::{print_info, print_warning};
+
+const MB: u64 = 1024 * 1024;
+
+#[derive(Args, Debug)]
+pub struct SocialArgs {
+    /// Crop/resize to a platform's image size and keep the file under its upload limit
+    #[arg(long, value_enum, value_name = "PRESET", conflicts_with_all = ["resize", "smart_crop"])]
+    pub social: Option<Preset>,
+
+    /// How --social reaches the exact size: crop the most detailed region or pad with --background
+    #[arg(long, value_enum, default_value = "crop", value_name = "MODE", requires = "social")]
+    pub social_fit: SocialFit,
+}
+
+impl SocialArgs {
+    /// Whether a --social preset was chosen
+    pub fn is_active(&self) -> bool {
+        self.social.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Preset {
+    /// Open Graph link preview (Facebook, LinkedIn, Slack): 1200x630, 8 MB
+    Og,
+    /// X/Twitter summary_large_image card: 1200x628, 5 MB
+    TwitterCard,
+    /// Instagram square post: 1080x1080, 8 MB
+    InstagramSquare,
+    /// YouTube video thumbnail: 1280x720, 2 MB
+    YoutubeThumb,
+}
+
+impl Preset {
+    /// Required width and height
+    pub fn size(self) -> (u32, u32) {
+        match self {
+            Preset::Og => (1200, 630),
+            Preset::TwitterCard => (1200, 628),
+            Preset::InstagramSquare => (1080, 1080),
+            Preset::YoutubeThumb => (1280, 720),
+        }
+    }
+
+    /// Largest file the platform accepts
+    pub fn max_bytes(self) -> u64 {
+        match self {
+            Preset::Og | Preset::InstagramSquare => 8 * MB,
+            Preset::TwitterCard => 5 * MB,
+            Preset::YoutubeThumb => 2 * MB,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SocialFit {
+    /// Fill the frame, cropping around the most detailed region
+    Crop,
+    /// Fit the whole image and fill the borders with --background
+    Pad,
+}
+
+/// Bring `img` to the preset's exact size, returning it unchanged when no preset is set
+pub fn apply(img: DynamicImage, args: &SocialArgs, filter: Filter, background: Color) -> Result<DynamicImage> {
+    let Some(preset) = args.social else {
+        return Ok(img);
+    };
+    let (width, height) = preset.size();
+    let (src_w, src_h) = img.dimensions();
+    if src_w < width && src_h < height {
+        print_warning(&tr!("Source is {}x{}, smaller than {}x{}; it will be upscaled", src_w, src_h, width, height));
+    }
+
+    let img = match args.social_fit {
+        SocialFit::Crop => {
+            let rect = smart_crop_rect(&img, width as f64 / height as f64);
+            img.crop_imm(rect.x, rect.y, rect.width, rect.height)
+                .resize_exact(width, height, filter.to_filter_type())
+        }
+        SocialFit::Pad => {
+            let fitted = img.resize(width, height, filter.to_filter_type()).to_rgba8();
+            let mut canvas = RgbaImage::from_pixel(width, height, Rgba(background.0 .0));
+            let x = (width - fitted.width()) as i64 / 2;
+            let y = (height - fitted.height()) as i64 / 2;
+            imageops::overlay(&mut canvas, &fitted, x, y);
+            DynamicImage::ImageRgba8(canvas)
+        }
+    };
+    print_info(&tr!("Social preset {}: {}x{} ({})", format!("{:?}", preset), width, height, format!("{:?}", args.social_fit)));
+    Ok(img)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_reach_exact_size() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(400, 300));
+        for fit in [SocialFit::Crop, SocialFit::Pad] {
+            let args = SocialArgs { social: Some(Preset::Og), social_fit: fit };
+            let out = apply(img.clone(), &args, Filter::Triangle, Color(Rgba([255, 0, 0, 255]))).unwrap();
+            assert_eq!(out.dimensions(), (1200, 630));
+        }
+
+        let args = SocialArgs { social: Some(Preset::InstagramSquare), social_fit: SocialFit::Pad };
+        let out = apply(img, &args, Filter::Triangle, Color(Rgba([255, 0, 0, 255]))).unwrap().to_rgba8();
+        // 4:3 fits as 1080x810, leaving bands top and bottom
+        assert_eq!(out.get_pixel(540, 0).0, [255, 0, 0, 255]);
+        assert_eq!(out.get_pixel(0, 540).0, [0, 0, 0, 255]);
+        assert_eq!(out.get_pixel(540, 1079).0, [255, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,181 @@
+// File: src\sprites.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: `imgconv sprites` - pack images into a sheet with a JSON atlas
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use image::{imageops, DynamicImage, GenericImageView, RgbaImage};
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::encode::{save_image, write_bytes};
+use crate::i18n::tr;
+use crate::{detect_format_from_path, load_image, print_info, print_success, print_warning};
+
+#[derive(Args, Debug)]
+pub struct SpritesArgs {
+    /// Input images followed by the output sheet
+    #[arg(value_name = "FILES", num_args = 2.., required = true)]
+    files: Vec<PathBuf>,
+
+    /// Uniform grid cells or tightly packed shelves
+    #[arg(long, value_enum, default_value = "packed")]
+    layout: Layout,
+
+    /// Transparent gap between sprites (pixels)
+    #[arg(long, default_value = "2", value_name = "PX")]
+    padding: u32,
+
+    /// Write a JSON atlas with each sprite's coordinates
+    #[arg(long, value_name = "FILE")]
+    json: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Layout {
+    Grid,
+    Packed,
+}
+
+#[derive(Serialize)]
+struct Atlas {
+    image: String,
+    size: AtlasSize,
+    frames: Vec<Frame>,
+}
+
+#[derive(Serialize)]
+struct AtlasSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+struct Frame {
+    name: String,
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+pub fn run(args: &SpritesArgs) -> Result<()> {
+    let (output, inputs) = args.files.split_last().context("Output file is required")?;
+    let format = detect_format_from_path(output)
+        .with_context(|| format!("Could not determine output format from '{}'", output.display()))?;
+
+    let sprites: Vec<(String, DynamicImage)> = inputs
+        .iter()
+        .filter_map(|path| match load_image(path) {
+            Ok((img, _)) => Some((path.file_name().unwrap_or_default().to_string_lossy().into_owned(), img)),
+            Err(e) => {
+                print_warning(&tr!("Skipping {}: {}", path.display(), format!("{:#}", e)));
+                None
+            }
+        })
+        .collect();
+    if sprites.is_empty() {
+        anyhow::bail!("No readable images to pack");
+    }
+
+    let sizes: Vec<(u32, u32)> = sprites.iter().map(|(_, img)| img.dimensions()).collect();
+    let (positions, (sheet_w, sheet_h)) = match args.layout {
+        Layout::Grid => grid_layout(&sizes, args.padding),
+        Layout::Packed => shelf_layout(&sizes, args.padding),
+    };
+    print_info(&tr!("Packing {} sprites into {}x{} sheet", sprites.len(), sheet_w, sheet_h));
+
+    let mut sheet = RgbaImage::new(sheet_w, sheet_h);
+    let mut frames = Vec::with_capacity(sprites.len());
+    for ((name, img), &(x, y)) in sprites.iter().zip(&positions) {
+        imageops::replace(&mut sheet, &img.to_rgba8(), x as i64, y as i64);
+        frames.push(Frame { name: name.clone(), x, y, w: img.width(), h: img.height() });
+    }
+
+    save_image(&DynamicImage::ImageRgba8(sheet), format, 100, output)?;
+    print_success(&tr!("Sprite sheet saved to: {}", output.display()));
+
+    if let Some(json_path) = &args.json {
+        let atlas = Atlas {
+            image: output.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+            size: AtlasSize { w: sheet_w, h: sheet_h },
+            frames,
+        };
+        let json = serde_json::to_string_pretty(&atlas).context("Failed to serialize atlas")?;
+        write_bytes(json_path, (json + "\n").as_bytes())?;
+        print_success(&tr!("Atlas saved to: {}", json_path.display()));
+    }
+
+    Ok(())
+}
+
+/// Uniform cells sized to the largest sprite, in a roughly square grid
+fn grid_layout(sizes: &[(u32, u32)], padding: u32) -> (Vec<(u32, u32)>, (u32, u32)) {
+    let cell_w = sizes.iter().map(|s| s.0).max().unwrap_or(0) + padding;
+    let cell_h = sizes.iter().map(|s| s.1).max().unwrap_or(0) + padding;
+    let columns = (sizes.len() as f64).sqrt().ceil().max(1.0) as u32;
+    let rows = (sizes.len() as u32).div_ceil(columns);
+
+    let positions = (0..sizes.len() as u32)
+        .map(|i| (padding + (i % columns) * cell_w, padding + (i / columns) * cell_h))
+        .collect();
+    (positions, (padding + columns * cell_w, padding + rows * cell_h))
+}
+
+/// Shelf packing: tallest sprites first, rows filled up to a near-square width
+fn shelf_layout(sizes: &[(u32, u32)], padding: u32) -> (Vec<(u32, u32)>, (u32, u32)) {
+    let area: u64 = sizes.iter().map(|&(w, h)| (w + padding) as u64 * (h + padding) as u64).sum();
+    let widest = sizes.iter().map(|s| s.0).max().unwrap_or(0);
+    let max_width = ((area as f64).sqrt().ceil() as u32).max(widest) + padding;
+
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by(|&a, &b| sizes[b].1.cmp(&sizes[a].1).then(sizes[b].0.cmp(&sizes[a].0)));
+
+    let mut positions = vec![(0, 0); sizes.len()];
+    let (mut x, mut y, mut shelf_h, mut sheet_w) = (padding, padding, 0, 0);
+    for i in order {
+        let (w, h) = sizes[i];
+        if x > padding && x + w + padding > max_width {
+            y += shelf_h + padding;
+            x = padding;
+            shelf_h = 0;
+        }
+        positions[i] = (x, y);
+        x += w + padding;
+        shelf_h = shelf_h.max(h);
+        sheet_w = sheet_w.max(x);
+    }
+
+    (positions, (sheet_w, y + shelf_h + padding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(a: (u32, u32, u32, u32), b: (u32, u32, u32, u32)) -> bool {
+        a.0 < b.0 + b.2 && b.0 < a.0 + a.2 && a.1 < b.1 + b.3 && b.1 < a.1 + a.3
+    }
+
+    #[test]
+    fn test_shelf_layout_no_overlap() {
+        let sizes = [(32, 32), (64, 16), (16, 48), (32, 32), (8, 8)];
+        let (positions, (w, h)) = shelf_layout(&sizes, 2);
+        let rects: Vec<_> = positions.iter().zip(&sizes).map(|(&(x, y), &(sw, sh))| (x, y, sw, sh)).collect();
+        for (i, a) in rects.iter().enumerate() {
+            assert!(a.0 + a.2 <= w && a.1 + a.3 <= h);
+            for b in &rects[i + 1..] {
+                assert!(!overlaps(*a, *b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid_layout() {
+        let (positions, size) = grid_layout(&[(10, 10), (20, 5), (5, 5)], 0);
+        assert_eq!(positions, vec![(0, 0), (20, 0), (0, 10)]);
+        assert_eq!(size, (40, 20));
+    }
+}
@@ -0,0 +1,501 @@
+// File: src\stream.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Row-by-row conversion for images too large to hold in memory
+// License: MIT
+
+use anyhow::{Context, Result};
+use image::ImageFormat;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, Write};
+use std::path::Path;
+use tiff::encoder::colortype::{self, ColorType};
+use tiff::encoder::{TiffEncoder, TiffKind};
+
+use crate::encode::write_file;
+use crate::geometry::Size;
+use crate::i18n::tr;
+use crate::print_warning;
+
+/// Whether the streaming path can handle this format pair
+pub fn can_stream(input: Option<ImageFormat>, output: ImageFormat) -> bool {
+    let streamable = |format| matches!(format, ImageFormat::Png | ImageFormat::Tiff | ImageFormat::Pnm);
+    input.is_some_and(streamable) && streamable(output)
+}
+
+/// Convert `input` to `output` a row at a time, shrinking to fit `resize`
+///
+/// Only a few rows (one strip for TIFF) are ever held in memory. Resizing
+/// uses area averaging (the --filter choice needs whole-image access) and
+/// can only shrink.
+pub fn convert(
+    input: &Path,
+    input_format: ImageFormat,
+    output: &Path,
+    format: ImageFormat,
+    resize: Option<Size>,
+) -> Result<(u32, u32)> {
+    let file = File::open(input).with_context(|| format!("Failed to open input file: {}", input.display()))?;
+    let reader = BufReader::new(file);
+    let mut source: Box<dyn RowSource> = match input_format {
+        ImageFormat::Png => Box::new(PngRows::open(reader)?),
+        ImageFormat::Tiff => Box::new(TiffRows::open(reader)?),
+        ImageFormat::Pnm => Box::new(PnmRows::open(reader)?),
+        _ => anyhow::bail!("{:?} input cannot be streamed", input_format),
+    };
+    let Header { width, height, channels } = source.header();
+    let (out_w, out_h) = match resize {
+        Some(size) => size.fit(width, height),
+        None => (width, height),
+    };
+    if out_w > width || out_h > height {
+        anyhow::bail!("Streaming conversion can only shrink images, not enlarge them");
+    }
+
+    write_file(output, |writer| {
+        encode(writer, format, (out_w, out_h), channels, |emit| {
+            let mut scaler = AreaScaler::new((width, height), (out_w, out_h), channels);
+            while let Some(row) = source.next_row()? {
+                scaler.push(row, emit)?;
+            }
+            scaler.finish(emit)
+        })
+    })?;
+    Ok((out_w, out_h))
+}
+
+/// Size and 8-bit channel count (1 to 4: gray, gray+alpha, RGB, RGBA) of a source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Header {
+    width: u32,
+    height: u32,
+    channels: usize,
+}
+
+/// Decoder that hands out one row of 8-bit samples at a time
+trait RowSource {
+    fn header(&self) -> Header;
+    fn next_row(&mut self) -> Result<Option<&[u8]>>;
+}
+
+/// PNG rows normalised to 8 bits per sample
+struct PngRows {
+    reader: png::Reader<BufReader<File>>,
+    header: Header,
+}
+
+impl PngRows {
+    fn open(file: BufReader<File>) -> Result<Self> {
+        // Only a row buffer is allocated, so the decoder's own budget can go
+        let mut decoder = png::Decoder::new_with_limits(file, png::Limits { bytes: usize::MAX });
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let reader = decoder.read_info().context("Failed to read PNG header")?;
+
+        let info = reader.info();
+        if info.interlaced {
+            anyhow::bail!("Interlaced PNGs cannot be streamed row by row");
+        }
+        if info.bit_depth == png::BitDepth::Sixteen {
+            print_warning(&tr!("Streaming reduces 16-bit samples to 8 bits"));
+        }
+        let header = Header {
+            width: info.width,
+            height: info.height,
+            channels: reader.output_color_type().0.samples(),
+        };
+        Ok(Self { reader, header })
+    }
+}
+
+impl RowSource for PngRows {
+    fn header(&self) -> Header {
+        self.header
+    }
+
+    fn next_row(&mut self) -> Result<Option<&[u8]>> {
+        Ok(self.reader.next_row().context("Failed to decode PNG row")?.map(|row| row.data()))
+    }
+}
+
+/// TIFF rows, decoded one strip at a time
+struct TiffRows {
+    decoder: tiff::decoder::Decoder<BufReader<File>>,
+    header: Header,
+    strip: Vec<u8>,
+    next_strip: u32,
+    offset: usize,
+}
+
+impl TiffRows {
+    fn open(file: BufReader<File>) -> Result<Self> {
+        let mut decoder = tiff::decoder::Decoder::new(file)
+            .context("Failed to read TIFF header")?
+            .with_limits(tiff::decoder::Limits::unlimited());
+        if decoder.get_chunk_type() != tiff::decoder::ChunkType::Strip {
+            anyhow::bail!("Tiled TIFFs cannot be streamed, only striped ones");
+        }
+        let planar = decoder.find_tag_unsigned::<u16>(tiff::tags::Tag::PlanarConfiguration).ok().flatten();
+        if planar == Some(2) {
+            anyhow::bail!("Planar TIFFs cannot be streamed, only interleaved ones");
+        }
+
+        let (channels, depth) = match decoder.colortype().context("Failed to read TIFF color type")? {
+            tiff::ColorType::Gray(depth) => (1, depth),
+            tiff::ColorType::GrayA(depth) => (2, depth),
+            tiff::ColorType::RGB(depth) => (3, depth),
+            tiff::ColorType::RGBA(depth) => (4, depth),
+            other => anyhow::bail!("{:?} TIFFs cannot be streamed", other),
+        };
+        match depth {
+            8 => {}
+            16 => print_warning(&tr!("Streaming reduces 16-bit samples to 8 bits")),
+            _ => anyhow::bail!("{}-bit TIFFs cannot be streamed", depth),
+        }
+        let (width, height) = decoder.dimensions().context("Failed to read TIFF dimensions")?;
+        let header = Header { width, height, channels };
+        Ok(Self { decoder, header, strip: Vec::new(), next_strip: 0, offset: 0 })
+    }
+}
+
+impl RowSource for TiffRows {
+    fn header(&self) -> Header {
+        self.header
+    }
+
+    fn next_row(&mut self) -> Result<Option<&[u8]>> {
+        let row_len = self.header.width as usize * self.header.channels;
+        if self.offset >= self.strip.len() {
+            if self.next_strip >= self.decoder.strip_count().context("Failed to read TIFF strips")? {
+                return Ok(None);
+            }
+            self.strip = match self.decoder.read_chunk(self.next_strip).context("Failed to decode TIFF strip")? {
+                tiff::decoder::DecodingResult::U8(data) => data,
+                tiff::decoder::DecodingResult::U16(data) => data.iter().map(|v| (v >> 8) as u8).collect(),
+                _ => anyhow::bail!("Unsupported TIFF sample format"),
+            };
+            self.next_strip += 1;
+            self.offset = 0;
+        }
+        let row = self.strip.get(self.offset..self.offset + row_len).context("TIFF strip is truncated")?;
+        self.offset += row_len;
+        Ok(Some(row))
+    }
+}
+
+/// Binary PGM/PPM/PAM rows with a maxval of 255
+struct PnmRows {
+    reader: BufReader<File>,
+    header: Header,
+    row: Vec<u8>,
+    remaining: u32,
+}
+
+impl PnmRows {
+    fn open(mut reader: BufReader<File>) -> Result<Self> {
+        let header = read_pnm_header(&mut reader)?;
+        let row = vec![0; header.width as usize * header.channels];
+        Ok(Self { reader, header, row, remaining: header.height })
+    }
+}
+
+impl RowSource for PnmRows {
+    fn header(&self) -> Header {
+        self.header
+    }
+
+    fn next_row(&mut self) -> Result<Option<&[u8]>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.reader.read_exact(&mut self.row).context("PNM pixel data is truncated")?;
+        self.remaining -= 1;
+        Ok(Some(&self.row))
+    }
+}
+
+/// Parse a P5/P6/P7 header, leaving `reader` at the first pixel byte
+fn read_pnm_header(reader: &mut impl BufRead) -> Result<Header> {
+    let magic = pnm_token(reader)?;
+    let (width, height, channels, maxval) = match magic.as_str() {
+        "P5" | "P6" => {
+            let mut number = || -> Result<u32> {
+                pnm_token(reader)?.parse().context("Invalid number in PNM header")
+            };
+            let (width, height, maxval) = (number()?, number()?, number()?);
+            (width, height, if magic == "P5" { 1 } else { 3 }, maxval)
+        }
+        "P7" => {
+            let (mut width, mut height, mut depth, mut maxval) = (0, 0, 0, 0);
+            loop {
+                let key = pnm_token(reader)?;
+                if key == "ENDHDR" {
+                    break;
+                }
+                let mut line = String::new();
+                reader.read_line(&mut line).context("Failed to read PAM header")?;
+                let value = || line.trim().parse::<u32>().context("Invalid number in PAM header");
+                match key.as_str() {
+                    "WIDTH" => width = value()?,
+                    "HEIGHT" => height = value()?,
+                    "DEPTH" => depth = value()?,
+                    "MAXVAL" => maxval = value()?,
+                    _ => {} // TUPLTYPE follows from DEPTH
+                }
+            }
+            (width, height, depth as usize, maxval)
+        }
+        _ => anyhow::bail!("Only binary PGM, PPM and PAM files can be streamed"),
+    };
+    if maxval != 255 {
+        anyhow::bail!("Only 8-bit PNM files (maxval 255) can be streamed");
+    }
+    if width == 0 || height == 0 || !(1..=4).contains(&channels) {
+        anyhow::bail!("Invalid PNM header");
+    }
+    Ok(Header { width, height, channels })
+}
+
+/// Next whitespace-delimited header token, skipping comments; consumes the
+/// single whitespace byte that ends it
+fn pnm_token(reader: &mut impl BufRead) -> Result<String> {
+    let mut token = String::new();
+    let mut byte = [0u8];
+    loop {
+        reader.read_exact(&mut byte).context("PNM header is truncated")?;
+        match byte[0] {
+            b'#' if token.is_empty() => {
+                reader.read_line(&mut String::new()).context("PNM header is truncated")?;
+            }
+            b if b.is_ascii_whitespace() => {
+                if !token.is_empty() {
+                    return Ok(token);
+                }
+            }
+            b => token.push(b as char),
+        }
+    }
+}
+
+/// Encode rows from `rows` as PNG, TIFF or PNM, one row of 8-bit samples at a time
+fn encode<W: Write + Seek>(
+    mut writer: W,
+    format: ImageFormat,
+    (width, height): (u32, u32),
+    channels: usize,
+    rows: impl FnOnce(&mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()>,
+) -> Result<()> {
+    match format {
+        ImageFormat::Png => {
+            let mut encoder = png::Encoder::new(writer, width, height);
+            encoder.set_color(match channels {
+                1 => png::ColorType::Grayscale,
+                2 => png::ColorType::GrayscaleAlpha,
+                3 => png::ColorType::Rgb,
+                _ => png::ColorType::Rgba,
+            });
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut png = encoder.write_header().context("Failed to encode PNG image")?;
+            let mut stream = png.stream_writer().context("Failed to encode PNG image")?;
+            rows(&mut |row| stream.write_all(row).context("Failed to encode PNG image"))?;
+            stream.finish().context("Failed to encode PNG image")
+        }
+        ImageFormat::Tiff => {
+            // Classic TIFF offsets are 32-bit; switch to BigTIFF near 4 GiB
+            let size = width as u64 * height as u64 * channels.max(3) as u64;
+            let result = if size < (4u64 << 30) - (16 << 20) {
+                let encoder = TiffEncoder::new(writer).context("Failed to encode TIFF image")?;
+                encode_tiff(encoder, (width, height), channels, rows)
+            } else {
+                let encoder = TiffEncoder::new_big(writer).context("Failed to encode TIFF image")?;
+                encode_tiff(encoder, (width, height), channels, rows)
+            };
+            result.context("Failed to encode TIFF image")
+        }
+        ImageFormat::Pnm => {
+            // PGM/PPM for opaque images, PAM when there is alpha (as the image crate does)
+            let header = match channels {
+                1 => format!("P5\n{} {}\n255\n", width, height),
+                3 => format!("P6\n{} {}\n255\n", width, height),
+                _ => format!(
+                    "P7\nWIDTH {}\nHEIGHT {}\nDEPTH {}\nMAXVAL 255\nTUPLTYPE {}\nENDHDR\n",
+                    width,
+                    height,
+                    channels,
+                    if channels == 2 { "GRAYSCALE_ALPHA" } else { "RGB_ALPHA" }
+                ),
+            };
+            writer.write_all(header.as_bytes()).context("Failed to encode PNM image")?;
+            rows(&mut |row| writer.write_all(row).context("Failed to encode PNM image"))
+        }
+        _ => anyhow::bail!("{:?} output cannot be streamed", format),
+    }
+}
+
+fn encode_tiff<W: Write + Seek, K: TiffKind>(
+    encoder: TiffEncoder<W, K>,
+    size: (u32, u32),
+    channels: usize,
+    rows: impl FnOnce(&mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()>,
+) -> Result<()> {
+    match channels {
+        1 => write_strips::<colortype::Gray8, _, _>(encoder, size, 1, rows, |row, out| out.extend_from_slice(row)),
+        3 => write_strips::<colortype::RGB8, _, _>(encoder, size, 3, rows, |row, out| out.extend_from_slice(row)),
+        // The TIFF encoder has no gray+alpha type, so that is widened to RGBA
+        2 => write_strips::<colortype::RGBA8, _, _>(encoder, size, 4, rows, |row, out| {
+            row.chunks_exact(2).for_each(|p| out.extend_from_slice(&[p[0], p[0], p[0], p[1]]))
+        }),
+        _ => write_strips::<colortype::RGBA8, _, _>(encoder, size, 4, rows, |row, out| out.extend_from_slice(row)),
+    }
+}
+
+/// Collect rows into strips of about 64 KB and write them out as they fill
+fn write_strips<C: ColorType<Inner = u8>, W: Write + Seek, K: TiffKind>(
+    mut encoder: TiffEncoder<W, K>,
+    (width, height): (u32, u32),
+    samples: usize,
+    rows: impl FnOnce(&mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()>,
+    widen: impl Fn(&[u8], &mut Vec<u8>),
+) -> Result<()> {
+    let mut image = encoder.new_image::<C>(width, height)?;
+    let rows_per_strip = (65_536 / (width as usize * samples)).clamp(1, height as usize);
+    image.rows_per_strip(rows_per_strip as u32)?;
+
+    let mut strip = Vec::with_capacity(rows_per_strip * width as usize * samples);
+    rows(&mut |row| {
+        widen(row, &mut strip);
+        if strip.len() as u64 == image.next_strip_sample_count() {
+            image.write_strip(&strip)?;
+            strip.clear();
+        }
+        Ok(())
+    })?;
+    image.finish()?;
+    Ok(())
+}
+
+/// Box-filter downscaler fed one source row at a time
+struct AreaScaler {
+    src: (u32, u32),
+    dst: (u32, u32),
+    channels: usize,
+    /// Output column each source column lands in, and how many land there
+    column: Vec<usize>,
+    span: Vec<u64>,
+    sums: Vec<u64>,
+    rows: u64,
+    next_src_row: u32,
+    out_row: u32,
+    out: Vec<u8>,
+}
+
+impl AreaScaler {
+    fn new(src: (u32, u32), dst: (u32, u32), channels: usize) -> Self {
+        let column: Vec<usize> = (0..src.0 as u64).map(|x| (x * dst.0 as u64 / src.0 as u64) as usize).collect();
+        let mut span = vec![0u64; dst.0 as usize];
+        column.iter().for_each(|&c| span[c] += 1);
+        Self {
+            src,
+            dst,
+            channels,
+            column,
+            span,
+            sums: vec![0; dst.0 as usize * channels],
+            rows: 0,
+            next_src_row: 0,
+            out_row: 0,
+            out: vec![0; dst.0 as usize * channels],
+        }
+    }
+
+    fn push(&mut self, row: &[u8], emit: &mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()> {
+        if self.src == self.dst {
+            return emit(row);
+        }
+        let target = (self.next_src_row as u64 * self.dst.1 as u64 / self.src.1 as u64) as u32;
+        if target != self.out_row && self.rows > 0 {
+            self.flush(emit)?;
+        }
+        self.out_row = target;
+
+        let channels = self.channels;
+        for (x, pixel) in row.chunks_exact(channels).enumerate() {
+            let base = self.column[x] * channels;
+            for (c, &v) in pixel.iter().enumerate() {
+                self.sums[base + c] += v as u64;
+            }
+        }
+        self.rows += 1;
+        self.next_src_row += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self, emit: &mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()> {
+        if self.rows > 0 {
+            self.flush(emit)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self, emit: &mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()> {
+        for (i, (sum, out)) in self.sums.iter_mut().zip(self.out.iter_mut()).enumerate() {
+            let count = self.span[i / self.channels] * self.rows;
+            *out = ((*sum + count / 2) / count) as u8;
+            *sum = 0;
+        }
+        self.rows = 0;
+        emit(&self.out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_area_scaler_averages_blocks() {
+        // 4x2 gray -> 2x1: each output pixel averages a 2x2 block
+        let rows: [&[u8]; 2] = [&[0, 100, 200, 200], &[100, 200, 0, 0]];
+        let mut scaler = AreaScaler::new((4, 2), (2, 1), 1);
+        let mut out = Vec::new();
+        let mut collect = |r: &[u8]| {
+            out.push(r.to_vec());
+            Ok(())
+        };
+        for row in rows {
+            scaler.push(row, &mut collect).unwrap();
+        }
+        scaler.finish(&mut collect).unwrap();
+        assert_eq!(out, vec![vec![100, 100]]);
+    }
+
+    #[test]
+    fn test_stream_round_trips() {
+        let dir = std::env::temp_dir().join(format!("imgconv-stream-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (png_in, tiff, pnm, png_out) = (dir.join("in.png"), dir.join("a.tiff"), dir.join("b.pam"), dir.join("c.png"));
+        let img = image::RgbaImage::from_fn(40, 20, |x, y| image::Rgba([x as u8, y as u8, 7, 200]));
+        img.save(&png_in).unwrap();
+
+        // PNG -> TIFF -> PAM -> PNG keeps every pixel
+        assert_eq!(convert(&png_in, ImageFormat::Png, &tiff, ImageFormat::Tiff, None).unwrap(), (40, 20));
+        assert_eq!(convert(&tiff, ImageFormat::Tiff, &pnm, ImageFormat::Pnm, None).unwrap(), (40, 20));
+        assert_eq!(convert(&pnm, ImageFormat::Pnm, &png_out, ImageFormat::Png, None).unwrap(), (40, 20));
+        assert_eq!(image::open(&tiff).unwrap().to_rgba8(), img);
+        assert_eq!(image::open(&png_out).unwrap().to_rgba8(), img);
+
+        let size = Size { width: 10, height: 10 };
+        assert_eq!(convert(&png_in, ImageFormat::Png, &png_out, ImageFormat::Png, Some(size)).unwrap(), (10, 5));
+        assert_eq!(image::image_dimensions(&png_out).unwrap(), (10, 5));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_pnm_header() {
+        let mut data: &[u8] = b"P6\n# scanner\n3000 2000\n255\n\x01";
+        assert_eq!(read_pnm_header(&mut data).unwrap(), Header { width: 3000, height: 2000, channels: 3 });
+        assert_eq!(data, b"\x01");
+        let mut pam: &[u8] = b"P7\nWIDTH 4\nHEIGHT 2\nDEPTH 2\nMAXVAL 255\nTUPLTYPE GRAYSCALE_ALPHA\nENDHDR\n";
+        assert_eq!(read_pnm_header(&mut pam).unwrap(), Header { width: 4, height: 2, channels: 2 });
+        assert!(read_pnm_header(&mut &b"P6 2 2 65535\n"[..]).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+// File: src\stylize.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Edge detection (--edge sobel|canny) and emboss (--emboss)
+// License: MIT
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use image::{DynamicImage, GrayAlphaImage, GrayImage, LumaA};
+use std::collections::VecDeque;
+
+use crate::colorspace::with_color_type;
+use crate::convolve::{convolve, convolve_plane, Kernel};
+
+/// Blur ahead of Canny edge detection, so noise doesn't become edges
+const CANNY_SIGMA: f32 = 1.4;
+
+/// Canny keeps edges at least this share of the strongest gradient, and
+/// weaker ones down to half that where they connect to them
+const CANNY_HIGH: f32 = 0.2;
+
+#[derive(Args, Debug)]
+pub struct StylizeArgs {
+    /// Replace the image with its edges, white on black, e.g. for masks
+    #[arg(long, value_enum, value_name = "METHOD", conflicts_with = "emboss")]
+    pub edge: Option<EdgeMethod>,
+
+    /// Relief effect, as if the image were pressed into the paper
+    #[arg(long)]
+    pub emboss: bool,
+}
+
+impl StylizeArgs {
+    /// Whether an edge or emboss effect was chosen
+    pub fn is_active(&self) -> bool {
+        self.edge.is_some() || self.emboss
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EdgeMethod {
+    /// Gradient strength: soft edges, brighter where the contrast is higher
+    Sobel,
+    /// Thin, connected one-pixel lines
+    Canny,
+}
+
+/// Apply --edge, returning the image unchanged when not requested
+///
+/// The result is grayscale, keeping any alpha channel.
+pub fn apply_edge(img: DynamicImage, args: &StylizeArgs) -> Result<DynamicImage> {
+    let Some(method) = args.edge else {
+        return Ok(img);
+    };
+    let (width, height) = (img.width(), img.height());
+    let luma: Vec<f32> = img.to_rgba32f().pixels().map(|p| 0.2126 * p.0[0] + 0.7152 * p.0[1] + 0.0722 * p.0[2]).collect();
+    let edges = match method {
+        // A full black to white step gives 4
+        EdgeMethod::Sobel => gradients(&luma, width, height).0.iter().map(|magnitude| (magnitude / 4.0).min(1.0)).collect(),
+        EdgeMethod::Canny => canny(&luma, width, height),
+    };
+    let to_u8 = |value: f32| (value * 255.0).round().clamp(0.0, 255.0) as u8;
+    Ok(if img.color().has_alpha() {
+        let alpha = img.to_luma_alpha8();
+        DynamicImage::ImageLumaA8(GrayAlphaImage::from_fn(width, height, |x, y| {
+            LumaA([to_u8(edges[(y * width + x) as usize]), alpha.get_pixel(x, y).0[1]])
+        }))
+    } else {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, y| image::Luma([to_u8(edges[(y * width + x) as usize])])))
+    })
+}
+
+/// Apply --emboss, returning the image unchanged when not requested
+pub fn apply_emboss(img: DynamicImage, args: &StylizeArgs) -> Result<DynamicImage> {
+    if !args.emboss {
+        return Ok(img);
+    }
+    // Lit from the top left; the weights sum to 1, so flat areas keep their color
+    let kernel = Kernel::new(3, vec![-2.0, -1.0, 0.0, -1.0, 1.0, 1.0, 0.0, 1.0, 2.0]).expect("3x3 kernel");
+    let color_type = img.color();
+    let mut embossed = convolve(&img.to_rgba32f(), &kernel);
+    embossed.pixels_mut().for_each(|pixel| pixel.0.iter_mut().for_each(|value| *value = value.clamp(0.0, 1.0)));
+    Ok(with_color_type(DynamicImage::ImageRgba32F(embossed), color_type))
+}
+
+/// Sobel gradient magnitude and direction (radians) of each value
+fn gradients(plane: &[f32], width: u32, height: u32) -> (Vec<f32>, Vec<f32>) {
+    let gx = convolve_plane(plane, width, height, &Kernel::sobel_x());
+    let gy = convolve_plane(plane, width, height, &Kernel::sobel_y());
+    gx.iter().zip(&gy).map(|(&x, &y)| (x.hypot(y), y.atan2(x))).unzip()
+}
+
+/// Canny edges: 1.0 on an edge, 0.0 elsewhere
+fn canny(plane: &[f32], width: u32, height: u32) -> Vec<f32> {
+    let smooth = convolve_plane(plane, width, height, &Kernel::gaussian(CANNY_SIGMA));
+    let (magnitude, direction) = gradients(&smooth, width, height);
+    let at = |x: i64, y: i64| {
+        if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+            0.0
+        } else {
+            magnitude[(y * width as i64 + x) as usize]
+        }
+    };
+    // Thin the edges to the pixels that peak across them
+    let mut thin = vec![0f32; magnitude.len()];
+    for (i, value) in thin.iter_mut().enumerate() {
+        let (x, y) = ((i as u32 % width) as i64, (i as u32 / width) as i64);
+        let octant = ((direction[i].to_degrees() + 180.0 + 22.5) / 45.0) as i64 % 4;
+        let (dx, dy) = [(1, 0), (1, 1), (0, 1), (-1, 1)][octant as usize];
+        if magnitude[i] >= at(x + dx, y + dy) && magnitude[i] >= at(x - dx, y - dy) {
+            *value = magnitude[i];
+        }
+    }
+    let strongest = thin.iter().cloned().fold(0.0, f32::max);
+    let (high, low) = (strongest * CANNY_HIGH, strongest * CANNY_HIGH / 2.0);
+    // Follow weak edges out from the strong ones
+    let mut edges = vec![0f32; thin.len()];
+    let mut queue: VecDeque<usize> = (0..thin.len()).filter(|&i| strongest > 0.0 && thin[i] >= high).collect();
+    queue.iter().for_each(|&i| edges[i] = 1.0);
+    while let Some(i) = queue.pop_front() {
+        let (x, y) = ((i as u32 % width) as i64, (i as u32 / width) as i64);
+        for (dx, dy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 0 || ny < 0 || nx >= width as i64 || ny >= height as i64 {
+                continue;
+            }
+            let n = (ny * width as i64 + nx) as usize;
+            if edges[n] == 0.0 && thin[n] >= low {
+                edges[n] = 1.0;
+                queue.push_back(n);
+            }
+        }
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Luma, Rgb, RgbImage};
+
+    /// A dark square in the middle of a light image
+    fn square() -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(24, 24, |x, y| Luma([if (8..16).contains(&x) && (8..16).contains(&y) { 30 } else { 220 }])))
+    }
+
+    #[test]
+    fn test_edges() {
+        let sobel = apply_edge(square(), &StylizeArgs { edge: Some(EdgeMethod::Sobel), emboss: false }).unwrap().into_luma8();
+        assert!(sobel.get_pixel(8, 12).0[0] > 150);
+        assert_eq!((sobel.get_pixel(2, 2).0[0], sobel.get_pixel(12, 12).0[0]), (0, 0));
+
+        let canny = apply_edge(square(), &StylizeArgs { edge: Some(EdgeMethod::Canny), emboss: false }).unwrap().into_luma8();
+        // One line along each side of the square: the row through it crosses two edges
+        let row: Vec<u8> = (0..24).map(|x| canny.get_pixel(x, 12).0[0]).collect();
+        assert_eq!(row.windows(2).filter(|pair| pair[0] == 0 && pair[1] == 255).count(), 2, "{:?}", row);
+        assert_eq!(canny.get_pixel(12, 12).0[0], 0);
+    }
+
+    #[test]
+    fn test_emboss_keeps_flat_areas() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(6, 6, Rgb([90, 140, 200])));
+        let out = apply_emboss(img, &StylizeArgs { edge: None, emboss: true }).unwrap();
+        assert_eq!(out.color(), image::ColorType::Rgb8);
+        assert!(out.into_rgb8().pixels().all(|p| p.0 == [90, 140, 200]));
+    }
+}
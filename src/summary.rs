@@ -0,0 +1,256 @@
+// File: src\summary.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Before/after size and dimension report per file and per batch, with CSV export
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use std::path::{Path, PathBuf};
+
+use crate::encode::write_bytes;
+use crate::estimate::format_size;
+use crate::i18n::tr;
+use crate::{print_info, print_success};
+
+/// Batches with more files than this print only the total row
+const MAX_TABLE_ROWS: usize = 50;
+
+#[derive(Args, Debug)]
+pub struct SummaryArgs {
+    /// Export the before/after size report of every converted file as CSV
+    #[arg(long, value_name = "FILE", conflicts_with = "estimate")]
+    pub csv: Option<PathBuf>,
+}
+
+/// Size and dimensions of a source, read before it is converted (and
+/// possibly overwritten or removed)
+#[derive(Debug, Clone, Default)]
+pub struct Before {
+    source: Option<PathBuf>,
+    bytes: Option<u64>,
+    dimensions: Option<(u32, u32)>,
+}
+
+impl Before {
+    /// Measure `source`; None for clipboard input
+    pub fn read(source: Option<&Path>) -> Self {
+        let Some(source) = source else {
+            return Self::default();
+        };
+        Self {
+            source: Some(source.to_path_buf()),
+            bytes: std::fs::metadata(source).ok().map(|m| m.len()),
+            dimensions: image::image_dimensions(source).ok(),
+        }
+    }
+
+    /// Measure a source read into memory, such as an archive entry
+    pub fn from_data(source: &Path, data: &[u8]) -> Self {
+        let dimensions = image::ImageReader::new(std::io::Cursor::new(data))
+            .with_guessed_format()
+            .ok()
+            .and_then(|reader| reader.into_dimensions().ok());
+        Self { source: Some(source.to_path_buf()), bytes: Some(data.len() as u64), dimensions }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Row {
+    before: Before,
+    output: PathBuf,
+    bytes: u64,
+    dimensions: Option<(u32, u32)>,
+}
+
+impl Row {
+    fn saved(&self) -> Option<f64> {
+        saved(self.before.bytes?, self.bytes)
+    }
+
+    fn dimensions(&self) -> String {
+        match (self.before.dimensions, self.dimensions) {
+            (Some(before), Some(after)) if before != after => {
+                format!("{}x{} -> {}x{}", before.0, before.1, after.0, after.1)
+            }
+            (_, Some(after)) => format!("{}x{}", after.0, after.1),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Conversions collected over a run
+#[derive(Debug, Default)]
+pub struct Summary {
+    rows: Vec<Row>,
+}
+
+impl Summary {
+    /// Print the before/after line for a finished conversion and keep it for the report
+    pub fn record(&mut self, before: Before, output: &Path, dimensions: Option<(u32, u32)>) {
+        let Ok(metadata) = std::fs::metadata(output) else {
+            return;
+        };
+        let row = Row { before, output: output.to_path_buf(), bytes: metadata.len(), dimensions };
+        let sizes = match row.before.bytes {
+            Some(bytes) => format!("{} -> {}", format_size(bytes), format_size(row.bytes)),
+            None => format_size(row.bytes),
+        };
+        let dimensions = row.dimensions();
+        let dimensions = if dimensions.is_empty() { String::new() } else { format!(", {}", dimensions) };
+        match row.saved() {
+            Some(saved) => print_success(&format!("{}: {}{}", describe(saved), sizes, dimensions)),
+            None => print_info(&tr!("Output: {}{}", sizes, dimensions)),
+        }
+        self.rows.push(row);
+    }
+
+    /// Aligned per-file table (for small batches) with a total row
+    pub fn print_table(&self) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let names: Vec<String> = self.rows.iter().map(display_name).collect();
+        let total_label = format!("Total ({} files)", self.rows.len());
+        let width = names.iter().map(|n| n.chars().count()).chain([total_label.len(), 4]).max().unwrap_or(4);
+
+        println!("{:<width$}  {:>10}  {:>10}  {:>7}  Dimensions", "File", "Before", "After", "Saved");
+        if self.rows.len() <= MAX_TABLE_ROWS {
+            for (row, name) in self.rows.iter().zip(&names) {
+                println!(
+                    "{:<width$}  {:>10}  {:>10}  {:>7}  {}",
+                    name,
+                    row.before.bytes.map(format_size).unwrap_or_default(),
+                    format_size(row.bytes),
+                    row.saved().map(|s| format!("{:.1}%", s)).unwrap_or_default(),
+                    row.dimensions()
+                );
+            }
+        } else {
+            println!("... {} files, see --csv for each one", self.rows.len());
+        }
+
+        let before: u64 = self.rows.iter().filter_map(|row| row.before.bytes).sum();
+        let after: u64 = self.rows.iter().map(|row| row.bytes).sum();
+        println!(
+            "{:<width$}  {:>10}  {:>10}  {:>7}",
+            total_label,
+            format_size(before),
+            format_size(after),
+            saved(before, after).map(|s| format!("{:.1}%", s)).unwrap_or_default()
+        );
+    }
+
+    /// Webhook payload: totals, every file, and a one-line `text` that
+    /// chat webhooks (Slack, Mattermost) display as the message
+    pub fn to_json(&self, error: Option<&anyhow::Error>) -> serde_json::Value {
+        let before: u64 = self.rows.iter().filter_map(|row| row.before.bytes).sum();
+        let after: u64 = self.rows.iter().map(|row| row.bytes).sum();
+        let mut text = format!("imgconv: converted {} files, {} -> {}", self.rows.len(), format_size(before), format_size(after));
+        if let Some(saved) = saved(before, after) {
+            text += &format!(" ({})", describe(saved).to_lowercase());
+        }
+        if let Some(error) = error {
+            text += &format!("; finished with errors: {}", error);
+        }
+        let files: Vec<serde_json::Value> = self.rows.iter().map(|row| {
+            serde_json::json!({
+                "source": row.before.source,
+                "output": row.output,
+                "source_bytes": row.before.bytes,
+                "output_bytes": row.bytes,
+            })
+        }).collect();
+        serde_json::json!({
+            "text": text,
+            "status": if error.is_some() { "failed" } else { "ok" },
+            "error": error.map(|e| e.to_string()),
+            "converted": self.rows.len(),
+            "source_bytes": before,
+            "output_bytes": after,
+            "files": files,
+        })
+    }
+
+    /// Write every row to `path` as CSV
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        write_bytes(path, self.to_csv().as_bytes())?;
+        print_success(&tr!("Size report saved to: {}", path.display()));
+        Ok(())
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "source,output,source_bytes,output_bytes,saved_percent,source_width,source_height,output_width,output_height\n",
+        );
+        let opt = |value: Option<u64>| value.map(|v| v.to_string()).unwrap_or_default();
+        for row in &self.rows {
+            let source = row.before.source.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+            let fields = [
+                csv_field(&source),
+                csv_field(&row.output.display().to_string()),
+                opt(row.before.bytes),
+                row.bytes.to_string(),
+                row.saved().map(|s| format!("{:.2}", s)).unwrap_or_default(),
+                opt(row.before.dimensions.map(|d| d.0 as u64)),
+                opt(row.before.dimensions.map(|d| d.1 as u64)),
+                opt(row.dimensions.map(|d| d.0 as u64)),
+                opt(row.dimensions.map(|d| d.1 as u64)),
+            ];
+            csv += &fields.join(",");
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Percentage of `before` saved by shrinking it to `after` (negative when it grew)
+fn saved(before: u64, after: u64) -> Option<f64> {
+    (before > 0).then(|| (before as f64 - after as f64) / before as f64 * 100.0)
+}
+
+fn describe(saved: f64) -> String {
+    if saved >= 0.0 {
+        tr!("Saved {}%", format!("{:.1}", saved))
+    } else {
+        tr!("Grew {}%", format!("{:.1}", -saved))
+    }
+}
+
+fn display_name(row: &Row) -> String {
+    match &row.before.source {
+        Some(source) => source.display().to_string(),
+        None => row.output.display().to_string(),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_rows_and_savings() {
+        let row = Row {
+            before: Before { source: Some("in, 1.png".into()), bytes: Some(1000), dimensions: Some((40, 20)) },
+            output: "out.jpg".into(),
+            bytes: 250,
+            dimensions: Some((20, 10)),
+        };
+        assert_eq!(row.saved(), Some(75.0));
+        assert_eq!(row.dimensions(), "40x20 -> 20x10");
+        let summary = Summary { rows: vec![row] };
+        assert_eq!(summary.to_csv().lines().nth(1), Some("\"in, 1.png\",out.jpg,1000,250,75.00,40,20,20,10"));
+        assert_eq!(describe(-12.5), "Grew 12.5%");
+        let json = summary.to_json(None);
+        assert_eq!(json["text"], "imgconv: converted 1 files, 1.0 KB -> 0.2 KB (saved 75.0%)");
+        assert_eq!(json["files"][0]["output"], "out.jpg");
+    }
+}
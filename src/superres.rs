@@ -0,0 +1,130 @@
+// File: src\superres.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: AI super-resolution for --upscale through an external Real-ESRGAN program
+// License: MIT
+
+use anyhow::{Context, Result};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Once;
+
+use crate::colorspace::with_color_type;
+use crate::i18n::tr;
+use crate::print_warning;
+
+/// The Real-ESRGAN program run when IMGCONV_REALESRGAN doesn't name another
+const DEFAULT_PROGRAM: &str = "realesrgan-ncnn-vulkan";
+
+const DEFAULT_MODEL: &str = "realesrgan-x4plus";
+
+/// Numbers the scratch directories, as parallel jobs may upscale at once
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// The fallback is announced once per run rather than for every file
+static FALLBACK_WARNED: Once = Once::new();
+
+/// Super-resolution backend given as "realesrgan[:MODEL]"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrBackend {
+    pub model: String,
+}
+
+impl FromStr for SrBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, model) = s.split_once(':').unwrap_or((s, DEFAULT_MODEL));
+        if !name.eq_ignore_ascii_case("realesrgan") {
+            return Err(format!("Unknown super-resolution backend '{}', expected realesrgan[:MODEL]", name));
+        }
+        if model.is_empty() || model.contains(['/', '\\']) {
+            return Err(format!("Expected a model name such as {}, got: {}", DEFAULT_MODEL, model));
+        }
+        Ok(SrBackend { model: model.to_string() })
+    }
+}
+
+impl SrBackend {
+    /// The factor the model is trained for, else the one closest to `factor`
+    fn native_scale(&self, factor: f32) -> u32 {
+        match self.model.as_str() {
+            model if model.contains("x4") => 4,
+            model if model.contains("x2") => 2,
+            _ => (factor.ceil() as u32).clamp(2, 4),
+        }
+    }
+}
+
+/// Enlarge `img` to `size` with the model, or None (after a warning) when
+/// the program is missing or fails, so the caller can use its own filter
+pub fn upscale(img: &DynamicImage, size: (u32, u32), factor: f32, backend: &SrBackend) -> Option<DynamicImage> {
+    let program = std::env::var_os("IMGCONV_REALESRGAN").map(PathBuf::from).unwrap_or_else(|| DEFAULT_PROGRAM.into());
+    match run(img, size, factor, backend, &program) {
+        Ok(upscaled) => Some(upscaled),
+        Err(e) => {
+            FALLBACK_WARNED.call_once(|| {
+                print_warning(&tr!("Super-resolution unavailable ({}); using the --upscale-filter instead", format!("{:#}", e)))
+            });
+            None
+        }
+    }
+}
+
+fn run(img: &DynamicImage, size: (u32, u32), factor: f32, backend: &SrBackend, program: &Path) -> Result<DynamicImage> {
+    let dir = std::env::temp_dir().join(format!("imgconv-sr-{}-{}", std::process::id(), RUNS.fetch_add(1, Ordering::Relaxed)));
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let result = (|| {
+        let (input, output) = (dir.join("in.png"), dir.join("out.png"));
+        // The model works on 8-bit pixels
+        let source = if img.color().has_alpha() { DynamicImage::ImageRgba8(img.to_rgba8()) } else { DynamicImage::ImageRgb8(img.to_rgb8()) };
+        source.save(&input).context("Failed to write the model's input")?;
+        let scale = backend.native_scale(factor).to_string();
+        let result = Command::new(program)
+            .arg("-i")
+            .arg(&input)
+            .arg("-o")
+            .arg(&output)
+            .args(["-n", &backend.model, "-s", &scale, "-f", "png"])
+            .stdin(Stdio::null())
+            .output()
+            .with_context(|| format!("could not run {}", program.display()))?;
+        if !result.status.success() {
+            let message = String::from_utf8_lossy(&result.stderr).trim().lines().last().unwrap_or_default().to_string();
+            anyhow::bail!("{} failed: {}", program.display(), message);
+        }
+        image::open(&output).context("Failed to read the model's output")
+    })();
+    let _ = std::fs::remove_dir_all(&dir);
+    let upscaled = result?;
+    // Models enlarge by a fixed factor; the rest is made up with Lanczos
+    let upscaled = if upscaled.dimensions() == size { upscaled } else { upscaled.resize_exact(size.0, size.1, FilterType::Lanczos3) };
+    Ok(with_color_type(upscaled, img.color()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_backend() {
+        assert_eq!("realesrgan".parse::<SrBackend>().unwrap().model, DEFAULT_MODEL);
+        let anime = "realesrgan:realesr-animevideov3".parse::<SrBackend>().unwrap();
+        assert_eq!((anime.native_scale(2.0), anime.native_scale(3.5)), (2, 4));
+        assert_eq!("realesrgan".parse::<SrBackend>().unwrap().native_scale(2.0), 4);
+        assert!("waifu2x".parse::<SrBackend>().is_err());
+        assert!("realesrgan:../model".parse::<SrBackend>().is_err());
+    }
+
+    #[test]
+    fn test_missing_program_falls_back() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(4, 4));
+        let backend = SrBackend { model: DEFAULT_MODEL.to_string() };
+        let missing = PathBuf::from("imgconv-no-such-program");
+        assert!(run(&img, (8, 8), 2.0, &backend, &missing).is_err());
+    }
+}
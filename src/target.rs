@@ -0,0 +1,84 @@
+// File: src\target.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Output file size budgets met by searching the encoder quality
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::{DynamicImage, ImageFormat};
+use std::path::Path;
+
+use crate::encode::supports_quality;
+use crate::estimate::{format_size, quality_within};
+use crate::i18n::tr;
+use crate::limits::ByteSize;
+use crate::social::SocialArgs;
+use crate::{print_info, print_warning};
+
+#[derive(Args, Debug)]
+pub struct TargetArgs {
+    /// Use the highest quality whose output fits in SIZE (e.g. 200KB, 1.5M); JPEG and AVIF
+    #[arg(long, value_name = "SIZE", conflicts_with = "estimate")]
+    pub target_size: Option<ByteSize>,
+}
+
+/// The tightest size budget from --target-size and a --social preset
+pub fn limit(args: &TargetArgs, social: &SocialArgs) -> Option<u64> {
+    let preset = social.social.map(|preset| preset.max_bytes());
+    match (args.target_size.map(|size| size.0), preset) {
+        (Some(target), Some(preset)) => Some(target.min(preset)),
+        (target, preset) => target.or(preset),
+    }
+}
+
+/// Quality to encode `img` with so the output fits in `limit` bytes
+///
+/// The search starts at `quality` and only ever lowers it. Lossless formats
+/// are returned unchanged; `check_size` reports them after saving.
+pub fn quality(img: &DynamicImage, format: ImageFormat, quality: u8, limit: Option<u64>) -> Result<u8> {
+    let Some(limit) = limit.filter(|_| supports_quality(format)) else {
+        return Ok(quality);
+    };
+    match quality_within(img, format, quality, limit)? {
+        Some((fitted, size)) => {
+            print_info(&tr!("Quality {} fits the {} budget ({})", fitted, format_size(limit), format_size(size)));
+            Ok(fitted)
+        }
+        None => anyhow::bail!(tr!("Cannot fit the image in {}, even at quality 1; resize it first", format_size(limit))),
+    }
+}
+
+/// Warn when the saved output is over budget (only possible for lossless formats)
+pub fn check_size(path: &Path, format: ImageFormat, limit: Option<u64>) {
+    let Some(limit) = limit else {
+        return;
+    };
+    if let Ok(metadata) = std::fs::metadata(path) {
+        if metadata.len() > limit {
+            print_warning(&tr!(
+                "{} is {}, over the {} budget; {} is lossless here, save as JPEG or AVIF to let the quality adapt",
+                path.display(),
+                format_size(metadata.len()),
+                format_size(limit),
+                format!("{:?}", format)
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::social::{Preset, SocialFit};
+
+    #[test]
+    fn test_limit_takes_tightest_budget() {
+        let target = TargetArgs { target_size: Some(ByteSize(200 * 1024)) };
+        let youtube = SocialArgs { social: Some(Preset::YoutubeThumb), social_fit: SocialFit::Crop };
+        let none = SocialArgs { social: None, social_fit: SocialFit::Crop };
+        assert_eq!(limit(&target, &youtube), Some(200 * 1024));
+        assert_eq!(limit(&TargetArgs { target_size: None }, &youtube), Some(2 * 1024 * 1024));
+        assert_eq!(limit(&TargetArgs { target_size: None }, &none), None);
+    }
+}
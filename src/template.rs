@@ -0,0 +1,50 @@
+// File: src\template.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Output filename templates ("{name}_{row}_{col}.{ext}")
+// License: MIT
+
+use anyhow::Result;
+
+/// Replace `{token}` placeholders with values; unknown tokens are an error
+pub fn render(template: &str, vars: &[(&str, String)]) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| anyhow::anyhow!("Unclosed '{{' in output template: {}", template))?;
+        let token = &after[..end];
+
+        let value = vars
+            .iter()
+            .find(|(key, _)| *key == token)
+            .map(|(_, value)| value)
+            .ok_or_else(|| {
+                let known: Vec<String> = vars.iter().map(|(key, _)| format!("{{{}}}", key)).collect();
+                anyhow::anyhow!("Unknown token {{{}}} in output template, available: {}", token, known.join(" "))
+            })?;
+        out.push_str(value);
+        rest = &after[end + 1..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let vars = [("name", "map".to_string()), ("row", "02".to_string()), ("ext", "png".to_string())];
+        assert_eq!(render("{name}_{row}.{ext}", &vars).unwrap(), "map_02.png");
+        assert_eq!(render("tiles/static.png", &vars).unwrap(), "tiles/static.png");
+        assert!(render("{name}_{col}", &vars).is_err());
+        assert!(render("{name", &vars).is_err());
+    }
+}
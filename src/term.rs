@@ -0,0 +1,56 @@
+// File: src\term.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: When to color terminal output (--color, NO_COLOR, TTY detection)
+// License: MIT
+
+use clap::{Args, ValueEnum};
+use std::io::IsTerminal;
+
+#[derive(Args, Debug)]
+pub struct TermArgs {
+    /// Color the [INFO]/[✓]/[!] markers: auto (only on a terminal, honoring NO_COLOR), always, never
+    #[arg(long, value_enum, default_value = "auto", value_name = "WHEN", global = true)]
+    pub color: ColorWhen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorWhen {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Switch colored output on or off for the rest of the process
+///
+/// Messages go to stderr, so auto mode checks stderr rather than stdout.
+pub fn apply(when: ColorWhen) {
+    let enabled = match when {
+        ColorWhen::Always => true,
+        ColorWhen::Never => false,
+        ColorWhen::Auto => auto_enabled(
+            std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()),
+            std::env::var("CLICOLOR_FORCE").is_ok_and(|v| v != "0"),
+            std::io::stderr().is_terminal() && std::env::var("TERM").map_or(true, |term| term != "dumb"),
+        ),
+    };
+    colored::control::set_override(enabled);
+}
+
+/// CLICOLOR_FORCE wins over NO_COLOR (https://no-color.org), which wins over TTY detection
+fn auto_enabled(no_color: bool, force: bool, terminal: bool) -> bool {
+    force || (!no_color && terminal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_color_precedence() {
+        assert!(auto_enabled(false, false, true));
+        assert!(!auto_enabled(false, false, false));
+        assert!(!auto_enabled(true, false, true));
+        assert!(auto_enabled(true, true, false));
+    }
+}
@@ -0,0 +1,173 @@
+// File: src\throttle.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Pace batch conversions: I/O rate cap and pausing while the machine is busy
+// License: MIT
+
+use clap::Args;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::i18n::tr;
+use crate::print_info;
+
+/// Share of CPU time used by other processes above which --idle-only pauses
+const BUSY_PERCENT: f64 = 25.0;
+
+/// How long CPU use is sampled, how long to wait before checking again while
+/// busy, and how long an idle reading is trusted
+const SAMPLE: Duration = Duration::from_secs(2);
+const RECHECK: Duration = Duration::from_secs(10);
+const IDLE_VALID: Duration = Duration::from_secs(30);
+
+#[derive(Args, Debug)]
+pub struct ThrottleArgs {
+    /// Limit batch disk traffic (sources read plus outputs written) to this many MB/s
+    #[arg(long, value_name = "MB/S")]
+    pub throttle_mbps: Option<f64>,
+
+    /// Pause a batch between files while other programs keep the CPU busy (Linux)
+    #[arg(long)]
+    pub idle_only: bool,
+}
+
+/// Paces a batch between files
+pub struct Throttle {
+    /// Bytes per second, from --throttle-mbps
+    limit: Option<f64>,
+    idle_only: bool,
+    started: Instant,
+    /// Time spent waiting for idle, which doesn't count towards the rate
+    paused: Duration,
+    /// When the system was last found idle
+    idle_at: Option<Instant>,
+    bytes: u64,
+}
+
+impl Throttle {
+    pub fn new(args: &ThrottleArgs) -> Self {
+        let limit = args.throttle_mbps.filter(|mbps| *mbps > 0.0).map(|mbps| mbps * 1_000_000.0);
+        let idle_only = args.idle_only && idle_supported();
+        Self { limit, idle_only, started: Instant::now(), paused: Duration::ZERO, idle_at: None, bytes: 0 }
+    }
+
+    /// Before each file: with --idle-only, wait until other processes leave the CPU mostly idle
+    pub fn wait_for_idle(&mut self) {
+        if !self.idle_only || self.idle_at.is_some_and(|at| at.elapsed() < IDLE_VALID) {
+            return;
+        }
+        let waiting = Instant::now();
+        let mut announced = false;
+        while let Some(busy) = others_cpu_percent(SAMPLE).filter(|busy| *busy > BUSY_PERCENT) {
+            if !announced {
+                print_info(&tr!("System busy ({}% CPU), pausing the batch", format!("{:.0}", busy)));
+                announced = true;
+            }
+            std::thread::sleep(RECHECK);
+        }
+        if announced {
+            print_info(&tr!("System idle again, resuming the batch"));
+        }
+        self.paused += waiting.elapsed();
+        self.idle_at = Some(Instant::now());
+    }
+
+    /// After each file: count its source and output sizes and sleep as long
+    /// as needed to stay under --throttle-mbps
+    pub fn account(&mut self, source: &Path, output: &Path) {
+        let Some(limit) = self.limit else {
+            return;
+        };
+        let size = |path: &Path| std::fs::metadata(path).map_or(0, |m| m.len());
+        self.bytes += size(source) + size(output);
+        let due = Duration::from_secs_f64(self.bytes as f64 / limit);
+        let spent = self.started.elapsed().saturating_sub(self.paused);
+        if let Some(ahead) = due.checked_sub(spent) {
+            std::thread::sleep(ahead);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn idle_supported() -> bool {
+    true
+}
+
+#[cfg(not(target_os = "linux"))]
+fn idle_supported() -> bool {
+    crate::print_warning(&tr!("--idle-only is only supported on Linux"));
+    false
+}
+
+/// CPU use of every process except this one over `window`, as a percentage
+/// of all cores; None when it can't be measured
+#[cfg(target_os = "linux")]
+fn others_cpu_percent(window: Duration) -> Option<f64> {
+    let before = (system_ticks()?, own_ticks()?);
+    std::thread::sleep(window);
+    let after = (system_ticks()?, own_ticks()?);
+    let total = after.0 .0.saturating_sub(before.0 .0);
+    let busy = after.0 .1.saturating_sub(before.0 .1);
+    let own = after.1.saturating_sub(before.1);
+    (total > 0).then(|| busy.saturating_sub(own) as f64 * 100.0 / total as f64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn others_cpu_percent(_window: Duration) -> Option<f64> {
+    None
+}
+
+/// Total and busy CPU ticks of all cores, from /proc/stat
+#[cfg(target_os = "linux")]
+fn system_ticks() -> Option<(u64, u64)> {
+    parse_stat(&std::fs::read_to_string("/proc/stat").ok()?)
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_stat(stat: &str) -> Option<(u64, u64)> {
+    let ticks: Vec<u64> = stat.lines().next()?.strip_prefix("cpu ")?.split_whitespace().filter_map(|n| n.parse().ok()).collect();
+    // user nice system idle iowait irq softirq steal; guest time is already in user
+    let total: u64 = ticks.iter().take(8).sum();
+    let idle = ticks.get(3)? + ticks.get(4).unwrap_or(&0);
+    Some((total, total - idle))
+}
+
+/// CPU ticks used by this process (all threads), from /proc/self/stat
+#[cfg(target_os = "linux")]
+fn own_ticks() -> Option<u64> {
+    parse_own(&std::fs::read_to_string("/proc/self/stat").ok()?)
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_own(stat: &str) -> Option<u64> {
+    // The command name may contain spaces, so count fields after its closing ")"
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    // utime and stime are fields 14 and 15, i.e. 12 and 13 after the name
+    Some(fields.get(11)?.parse::<u64>().ok()? + fields.get(12)?.parse::<u64>().ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_stat() {
+        let stat = "cpu  100 5 50 800 20 3 2 0 10 0\ncpu0 50 2 25 400 10 1 1 0 5 0\n";
+        assert_eq!(parse_stat(stat), Some((980, 160)));
+        let own = "4242 (my prog) S 1 4242 4242 0 -1 4194304 500 0 0 0 37 11 0 0 20 0 1 0 100";
+        assert_eq!(parse_own(own), Some(48));
+    }
+
+    #[test]
+    fn test_throttle_sleeps_to_stay_under_limit() {
+        let dir = std::env::temp_dir().join(format!("imgconv-throttle-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("a.bin");
+        std::fs::write(&file, vec![0; 50_000]).unwrap();
+        let mut throttle = Throttle::new(&ThrottleArgs { throttle_mbps: Some(1.0), idle_only: false });
+        let started = Instant::now();
+        throttle.account(&file, &file);
+        assert!(started.elapsed() >= Duration::from_millis(90));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
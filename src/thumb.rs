@@ -0,0 +1,110 @@
+// File: src\thumb.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: `imgconv thumb` - save or rebuild the EXIF-embedded thumbnail of a photo
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use std::path::PathBuf;
+
+use crate::encode::{encode_image, save_image, write_bytes};
+use crate::exifthumb;
+use crate::i18n::tr;
+use crate::{detect_format_from_path, print_success};
+
+/// Quality of regenerated thumbnails; they are tiny either way
+const THUMBNAIL_QUALITY: u8 = 85;
+
+#[derive(Args, Debug)]
+#[command(group(clap::ArgGroup::new("mode").required(true).args(["from_exif", "regenerate"])))]
+pub struct ThumbArgs {
+    /// Photo to read the thumbnail from, or to give a new one
+    #[arg(value_name = "INPUT")]
+    input: PathBuf,
+
+    /// The thumbnail file for --from-exif; the updated photo for --regenerate
+    /// (default: the photo itself)
+    #[arg(value_name = "OUTPUT")]
+    output: Option<PathBuf>,
+
+    /// Save the embedded EXIF thumbnail without decoding the photo
+    #[arg(long, requires = "output")]
+    from_exif: bool,
+
+    /// Rebuild the EXIF thumbnail from the photo (e.g. after edits) and embed it
+    #[arg(long)]
+    regenerate: bool,
+
+    /// Longest side of the regenerated thumbnail in pixels
+    #[arg(long, default_value = "160", value_name = "PX", requires = "regenerate",
+          value_parser = clap::value_parser!(u32).range(16..=512))]
+    size: u32,
+}
+
+pub fn run(args: &ThumbArgs) -> Result<()> {
+    if args.from_exif {
+        extract(args)
+    } else {
+        regenerate(args)
+    }
+}
+
+/// --from-exif: copy the thumbnail out, re-encoding only for a non-JPEG output
+fn extract(args: &ThumbArgs) -> Result<()> {
+    let output = args.output.as_ref().context("--from-exif needs an OUTPUT file")?;
+    let data = exifthumb::read(&args.input)?
+        .with_context(|| format!("{} has no embedded EXIF thumbnail; use --regenerate to add one", args.input.display()))?;
+    let format = detect_format_from_path(output).unwrap_or(ImageFormat::Jpeg);
+    if format == ImageFormat::Jpeg {
+        write_bytes(output, &data)?;
+    } else {
+        let img = image::load_from_memory_with_format(&data, ImageFormat::Jpeg)
+            .with_context(|| format!("Failed to decode the embedded thumbnail of {}", args.input.display()))?;
+        save_image(&img, format, THUMBNAIL_QUALITY, output)?;
+    }
+    print_success(&tr!("Saved the {} byte EXIF thumbnail of {} to {}", data.len(), args.input.display(), output.display()));
+    Ok(())
+}
+
+/// --regenerate: shrink the photo into a new thumbnail and write it into its EXIF data
+fn regenerate(args: &ThumbArgs) -> Result<()> {
+    let jpeg = std::fs::read(&args.input).with_context(|| format!("Failed to read input file: {}", args.input.display()))?;
+    // Thumbnails are stored unrotated like the photo, so EXIF orientation isn't applied
+    let img = image::load_from_memory_with_format(&jpeg, ImageFormat::Jpeg)
+        .with_context(|| format!("{} is not a readable JPEG", args.input.display()))?;
+    let thumbnail = img.resize(args.size, args.size, FilterType::Lanczos3);
+    let mut data = std::io::Cursor::new(Vec::new());
+    encode_image(&DynamicImage::ImageRgb8(thumbnail.to_rgb8()), ImageFormat::Jpeg, THUMBNAIL_QUALITY, &mut data)?;
+    let updated = exifthumb::embed(&jpeg, data.get_ref())?;
+    let output = args.output.as_ref().unwrap_or(&args.input);
+    write_bytes(output, &updated)?;
+    print_success(&tr!(
+        "Embedded a new {}x{} thumbnail ({} bytes) in {}",
+        thumbnail.width(), thumbnail.height(), data.get_ref().len(), output.display()
+    ));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regenerate_then_extract() {
+        let dir = std::env::temp_dir().join(format!("imgconv-thumb-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (photo, thumb) = (dir.join("photo.jpg"), dir.join("thumb.png"));
+        DynamicImage::new_rgb8(400, 300).save(&photo).unwrap();
+
+        let args = |from_exif, output: Option<PathBuf>| ThumbArgs { input: photo.clone(), output, from_exif, regenerate: !from_exif, size: 160 };
+        assert!(run(&args(true, Some(thumb.clone()))).is_err());
+        run(&args(false, None)).unwrap();
+        run(&args(true, Some(thumb.clone()))).unwrap();
+        assert_eq!(image::open(&thumb).unwrap().width(), 160);
+        assert_eq!(image::open(&thumb).unwrap().height(), 120);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+// File: src\tile.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: `imgconv tile` - split an image into tiles
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::GenericImageView;
+use std::path::PathBuf;
+
+use crate::encode::save_image;
+use crate::geometry::{Rect, Size};
+use crate::i18n::tr;
+use crate::{format_to_main_extension, load_image, print_info, print_success, template, Format};
+
+#[derive(Args, Debug)]
+pub struct TileArgs {
+    /// Image to split
+    #[arg(value_name = "INPUT")]
+    input: PathBuf,
+
+    /// Split into COLSxROWS equal tiles
+    #[arg(long, value_name = "COLSxROWS", conflicts_with = "tile_size", required_unless_present = "tile_size")]
+    grid: Option<Size>,
+
+    /// Split into tiles of this size (edge tiles may be smaller)
+    #[arg(long, value_name = "WxH")]
+    tile_size: Option<Size>,
+
+    /// Output template; tokens: {name} {row} {col} {index} {x} {y} {ext}
+    #[arg(short, long, default_value = "{name}_{row}_{col}.{ext}", value_name = "TEMPLATE")]
+    output: String,
+
+    /// Tile format (defaults to the input format)
+    #[arg(short, long, value_name = "FORMAT")]
+    format: Option<Format>,
+
+    /// Quality for lossy formats (1-100)
+    #[arg(short, long, default_value = "90", value_name = "NUM", value_parser = clap::value_parser!(u8).range(1..=100))]
+    quality: u8,
+}
+
+pub fn run(args: &TileArgs) -> Result<()> {
+    let (img, detected_format) = load_image(&args.input)?;
+    let (width, height) = img.dimensions();
+
+    let format = match &args.format {
+        Some(fmt) => fmt.to_image_format(),
+        None => detected_format.filter(|f| f.writing_enabled())
+            .context("Input format cannot be written, please specify --format")?,
+    };
+
+    let tiles = match (args.grid, args.tile_size) {
+        (Some(grid), _) => grid_tiles(width, height, grid.width, grid.height)?,
+        (None, Some(size)) => sized_tiles(width, height, size.width, size.height),
+        (None, None) => anyhow::bail!("Specify --grid COLSxROWS or --tile-size WxH"),
+    };
+    print_info(&tr!("Splitting {}x{} image into {} tiles", width, height, tiles.len()));
+
+    let name = args.input.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+    let ext = format_to_main_extension(&format).to_string();
+    let rows = tiles.iter().map(|(row, _, _)| row).max().copied().unwrap_or(0) + 1;
+    let cols = tiles.iter().map(|(_, col, _)| col).max().copied().unwrap_or(0) + 1;
+    let (row_digits, col_digits) = (rows.to_string().len(), cols.to_string().len());
+    let index_digits = tiles.len().to_string().len();
+
+    for (index, (row, col, rect)) in tiles.iter().enumerate() {
+        let vars = [
+            ("name", name.clone()),
+            ("row", format!("{:0width$}", row, width = row_digits)),
+            ("col", format!("{:0width$}", col, width = col_digits)),
+            ("index", format!("{:0width$}", index, width = index_digits)),
+            ("x", rect.x.to_string()),
+            ("y", rect.y.to_string()),
+            ("ext", ext.clone()),
+        ];
+        let path = PathBuf::from(template::render(&args.output, &vars)?);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        let tile = img.crop_imm(rect.x, rect.y, rect.width, rect.height);
+        save_image(&tile, format, args.quality, &path)?;
+    }
+
+    print_success(&tr!("Wrote {} tiles ({} rows x {} columns)", tiles.len(), rows, cols));
+    Ok(())
+}
+
+/// Split into `cols` x `rows` tiles, spreading remainder pixels over the first tiles
+fn grid_tiles(width: u32, height: u32, cols: u32, rows: u32) -> Result<Vec<(u32, u32, Rect)>> {
+    if cols > width || rows > height {
+        anyhow::bail!("Grid {}x{} is larger than the {}x{} image", cols, rows, width, height);
+    }
+    let edges = |total: u32, parts: u32| -> Vec<u32> { (0..=parts).map(|i| total * i / parts).collect() };
+    let xs = edges(width, cols);
+    let ys = edges(height, rows);
+
+    let mut tiles = Vec::new();
+    for row in 0..rows as usize {
+        for col in 0..cols as usize {
+            let rect = Rect { x: xs[col], y: ys[row], width: xs[col + 1] - xs[col], height: ys[row + 1] - ys[row] };
+            tiles.push((row as u32, col as u32, rect));
+        }
+    }
+    Ok(tiles)
+}
+
+/// Split into fixed-size tiles; the last row/column holds the remainder
+fn sized_tiles(width: u32, height: u32, tile_w: u32, tile_h: u32) -> Vec<(u32, u32, Rect)> {
+    let mut tiles = Vec::new();
+    for (row, y) in (0..height).step_by(tile_h as usize).enumerate() {
+        for (col, x) in (0..width).step_by(tile_w as usize).enumerate() {
+            let rect = Rect { x, y, width: tile_w.min(width - x), height: tile_h.min(height - y) };
+            tiles.push((row as u32, col as u32, rect));
+        }
+    }
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_tiles_cover_image() {
+        let tiles = grid_tiles(10, 7, 3, 2).unwrap();
+        assert_eq!(tiles.len(), 6);
+        let area: u32 = tiles.iter().map(|(_, _, r)| r.width * r.height).sum();
+        assert_eq!(area, 70);
+        assert!(grid_tiles(2, 2, 3, 1).is_err());
+    }
+
+    #[test]
+    fn test_sized_tiles_remainder() {
+        let tiles = sized_tiles(1000, 600, 512, 512);
+        assert_eq!(tiles.len(), 4);
+        assert_eq!(tiles[3], (1, 1, Rect { x: 512, y: 512, width: 488, height: 88 }));
+    }
+}
@@ -0,0 +1,144 @@
+// File: src\timings.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Per-stage timing report (decode, transform, encode) and peak memory
+// License: MIT
+
+use clap::Args;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::estimate::format_size;
+use crate::i18n::tr;
+use crate::print_info;
+use crate::progress;
+
+#[derive(Args, Debug)]
+pub struct TimingsArgs {
+    /// Report decode, transform and encode time (and peak memory) per file and for the batch
+    #[arg(long)]
+    pub timings: bool,
+}
+
+/// Part of a conversion that time is charged to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Decode,
+    Transform,
+    /// Encoding plus writing the file
+    Encode,
+}
+
+const STAGES: [(Stage, &str); 3] = [(Stage::Decode, "decode"), (Stage::Transform, "transform"), (Stage::Encode, "encode")];
+
+/// Stopwatch splitting one conversion into stages
+#[derive(Debug, Clone)]
+pub struct Timer {
+    last: Instant,
+    spent: [Duration; 3],
+}
+
+impl Timer {
+    pub fn start() -> Self {
+        Self { last: Instant::now(), spent: [Duration::ZERO; 3] }
+    }
+
+    /// Charge the time since the previous lap (or the start) to `stage`
+    pub fn lap(&mut self, stage: Stage) {
+        let now = Instant::now();
+        self.spent[stage as usize] += now - self.last;
+        self.last = now;
+        progress::stage(stage);
+    }
+
+    /// One line for `source`, e.g. "decode 12.0 ms, transform 3.1 ms, encode 40.2 ms, total 55.3 ms"
+    pub fn print(&self, source: &Path) {
+        print_info(&tr!("Timings for {}: {}{}", source.display(), breakdown(&self.spent, false), rss_suffix()));
+    }
+}
+
+/// Stage times summed over a batch
+#[derive(Debug, Default)]
+pub struct Report {
+    files: usize,
+    spent: [Duration; 3],
+}
+
+impl Report {
+    pub fn add(&mut self, timer: &Timer) {
+        self.files += 1;
+        for (total, spent) in self.spent.iter_mut().zip(timer.spent) {
+            *total += spent;
+        }
+    }
+
+    /// Totals with each stage's share, to show whether decoding, processing
+    /// or encoding (quality settings, I/O) dominates
+    pub fn print(&self) {
+        if self.files > 0 {
+            print_info(&tr!("Timings for {} files: {}{}", self.files, breakdown(&self.spent, true), rss_suffix()));
+        }
+    }
+}
+
+fn breakdown(spent: &[Duration; 3], shares: bool) -> String {
+    let total: Duration = spent.iter().sum();
+    let mut parts: Vec<String> = STAGES
+        .iter()
+        .map(|&(stage, name)| {
+            let time = spent[stage as usize];
+            if shares && !total.is_zero() {
+                format!("{} {} ({:.0}%)", name, format_duration(time), time.as_secs_f64() / total.as_secs_f64() * 100.0)
+            } else {
+                format!("{} {}", name, format_duration(time))
+            }
+        })
+        .collect();
+    parts.push(format!("total {}", format_duration(total)));
+    parts.join(", ")
+}
+
+fn rss_suffix() -> String {
+    peak_rss().map(|bytes| format!(", peak RSS {}", format_size(bytes))).unwrap_or_default()
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+    } else {
+        format!("{:.2} s", duration.as_secs_f64())
+    }
+}
+
+/// Highest resident memory of this process so far, where the OS reports it
+#[cfg(unix)]
+fn peak_rss() -> Option<u64> {
+    // SAFETY: getrusage only fills in the zeroed struct we pass
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+        return None;
+    }
+    // Linux reports kilobytes, macOS bytes
+    let max = usage.ru_maxrss as u64;
+    Some(if cfg!(target_os = "macos") { max } else { max * 1024 })
+}
+
+#[cfg(not(unix))]
+fn peak_rss() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breakdown_shares() {
+        let spent = [Duration::from_millis(200), Duration::from_millis(300), Duration::from_millis(1500)];
+        assert_eq!(
+            breakdown(&spent, true),
+            "decode 200.0 ms (10%), transform 300.0 ms (15%), encode 1.50 s (75%), total 2.00 s"
+        );
+        assert_eq!(breakdown(&[Duration::ZERO; 3], true), "decode 0.0 ms, transform 0.0 ms, encode 0.0 ms, total 0.0 ms");
+    }
+}
@@ -0,0 +1,122 @@
+// File: src\tonemap.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: HDR to SDR tone mapping (Reinhard, Hable, ACES)
+// License: MIT
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use image::{ColorType, DynamicImage, ImageFormat, Rgba32FImage};
+
+use crate::colorspace::linear_to_srgb;
+use crate::encode::supports_16bit;
+use crate::i18n::tr;
+use crate::{print_info, print_warning};
+
+#[derive(Args, Debug)]
+pub struct ToneArgs {
+    /// Tone-map HDR/EXR input when writing a low dynamic range format
+    #[arg(long, value_enum, value_name = "OPERATOR")]
+    pub tonemap: Option<ToneMap>,
+
+    /// Exposure adjustment in stops applied before tone mapping
+    #[arg(long, default_value = "0", value_name = "EV", allow_hyphen_values = true)]
+    pub exposure: f32,
+}
+
+impl ToneArgs {
+    /// Whether --tonemap or --exposure was given (HDR input is clipped either way)
+    pub fn is_active(&self) -> bool {
+        self.tonemap.is_some() || self.exposure != 0.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ToneMap {
+    /// x / (1 + x), soft highlight roll-off
+    Reinhard,
+    /// Filmic curve from Uncharted 2
+    Hable,
+    /// ACES filmic fit, punchy contrast
+    Aces,
+}
+
+/// Map float images to display range when `format` cannot store HDR values
+pub fn apply(img: DynamicImage, args: &ToneArgs, format: ImageFormat) -> Result<DynamicImage> {
+    let is_float = matches!(img.color(), ColorType::Rgb32F | ColorType::Rgba32F);
+    if !is_float || matches!(format, ImageFormat::Hdr | ImageFormat::OpenExr) {
+        if args.tonemap.is_some() || args.exposure != 0.0 {
+            print_warning(&tr!("--tonemap/--exposure only apply to HDR input written to a non-HDR format"));
+        }
+        return Ok(img);
+    }
+
+    match args.tonemap {
+        Some(op) => print_info(&tr!("Tone mapping with {} at {} EV", format!("{:?}", op), format!("{:+}", args.exposure))),
+        None => print_warning(&tr!("HDR input is clipped to display range, use --tonemap for better highlights")),
+    }
+
+    let gain = 2f32.powf(args.exposure);
+    let mut canvas: Rgba32FImage = img.to_rgba32f();
+    for pixel in canvas.pixels_mut() {
+        for c in &mut pixel.0[..3] {
+            let exposed = (*c * gain).max(0.0);
+            let mapped = match args.tonemap {
+                Some(op) => map(op, exposed),
+                None => exposed,
+            };
+            *c = linear_to_srgb(mapped.clamp(0.0, 1.0));
+        }
+    }
+
+    // Display-referred output, 16-bit where the format allows it
+    let has_alpha = img.color().has_alpha();
+    let mapped = DynamicImage::ImageRgba32F(canvas);
+    Ok(match (supports_16bit(format), has_alpha) {
+        (true, true) => DynamicImage::ImageRgba16(mapped.to_rgba16()),
+        (true, false) => DynamicImage::ImageRgb16(mapped.to_rgb16()),
+        (false, true) => DynamicImage::ImageRgba8(mapped.to_rgba8()),
+        (false, false) => DynamicImage::ImageRgb8(mapped.to_rgb8()),
+    })
+}
+
+/// Apply a tone curve to a linear scene value, result in 0.0-1.0
+fn map(op: ToneMap, x: f32) -> f32 {
+    match op {
+        ToneMap::Reinhard => x / (1.0 + x),
+        ToneMap::Hable => {
+            const WHITE: f32 = 11.2;
+            hable(x * 2.0) / hable(WHITE)
+        }
+        ToneMap::Aces => (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14),
+    }
+}
+
+fn hable(x: f32) -> f32 {
+    let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+    ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operators_compress_highlights() {
+        for op in [ToneMap::Reinhard, ToneMap::Hable, ToneMap::Aces] {
+            assert!(map(op, 0.0).abs() < 0.01, "{:?}", op);
+            let bright = map(op, 50.0);
+            assert!(bright > 0.9, "{:?}: {}", op, bright);
+            assert!(map(op, 0.5) < map(op, 1.0), "{:?} is not monotonic", op);
+        }
+    }
+
+    #[test]
+    fn test_sdr_output() {
+        let args = ToneArgs { tonemap: Some(ToneMap::Reinhard), exposure: 1.0 };
+        let img = DynamicImage::ImageRgb32F(image::Rgb32FImage::from_pixel(1, 1, image::Rgb([4.0, 0.5, 0.0])));
+        let out = apply(img, &args, ImageFormat::Jpeg).unwrap().to_rgb8();
+        let [r, g, b] = out.get_pixel(0, 0).0;
+        assert!(r > 230 && g > 150 && g < 220 && b == 0, "{:?}", (r, g, b));
+    }
+}
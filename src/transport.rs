@@ -0,0 +1,313 @@
+// File: src\transport.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: ImageSource/ImageSink traits, the built-in transports and a registry for custom ones
+// License: MIT
+
+use image::ImageFormat;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use crate::cancel::CancellationToken;
+use crate::converter::write_atomic;
+use crate::error::{ImgconvError, Result};
+
+/// Where an encoded input image comes from
+pub trait ImageSource {
+    /// Shown in messages and errors
+    fn name(&self) -> String;
+
+    /// The whole encoded image
+    fn read(&mut self) -> Result<Vec<u8>>;
+}
+
+/// Where an encoded output image goes
+pub trait ImageSink {
+    /// Shown in messages; its extension picks the output format when none is set
+    fn name(&self) -> String;
+
+    fn write(&mut self, data: &[u8], format: ImageFormat) -> Result<()>;
+}
+
+/// Opens a transport from the rest of a `scheme://...` spec (the whole spec is passed)
+///
+/// Factories are shared so they run outside the registry lock, and may
+/// themselves look up or register transports.
+pub type SourceFactory = Arc<dyn Fn(&str) -> Result<Box<dyn ImageSource>> + Send + Sync>;
+pub type SinkFactory = Arc<dyn Fn(&str) -> Result<Box<dyn ImageSink>> + Send + Sync>;
+
+static SOURCES: Mutex<Vec<(String, SourceFactory)>> = Mutex::new(Vec::new());
+static SINKS: Mutex<Vec<(String, SinkFactory)>> = Mutex::new(Vec::new());
+
+/// Read `scheme://...` inputs with `factory`, replacing a built-in or earlier one
+pub fn register_source(scheme: &str, factory: impl Fn(&str) -> Result<Box<dyn ImageSource>> + Send + Sync + 'static) {
+    let mut sources = SOURCES.lock().unwrap();
+    sources.retain(|(known, _)| !known.eq_ignore_ascii_case(scheme));
+    sources.push((scheme.to_string(), Arc::new(factory)));
+}
+
+/// Write `scheme://...` outputs with `factory`, replacing an earlier one
+pub fn register_sink(scheme: &str, factory: impl Fn(&str) -> Result<Box<dyn ImageSink>> + Send + Sync + 'static) {
+    let mut sinks = SINKS.lock().unwrap();
+    sinks.retain(|(known, _)| !known.eq_ignore_ascii_case(scheme));
+    sinks.push((scheme.to_string(), Arc::new(factory)));
+}
+
+fn scheme(spec: &str) -> Option<&str> {
+    spec.split_once("://").map(|(scheme, _)| scheme).filter(|scheme| scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)))
+}
+
+fn is_url(spec: &str) -> bool {
+    matches!(scheme(spec).map(str::to_ascii_lowercase).as_deref(), Some("http" | "https"))
+}
+
+/// Whether `spec` names something other than a file to read: `-` (stdin),
+/// `clipboard:`, an http(s) URL or a registered scheme
+pub fn is_source(spec: &str) -> bool {
+    let registered = |scheme: &str| SOURCES.lock().unwrap().iter().any(|(known, _)| known.eq_ignore_ascii_case(scheme));
+    spec == "-" || spec == "clipboard:" || is_url(spec) || scheme(spec).is_some_and(registered)
+}
+
+/// Whether `spec` names something other than a file to write: `-` (stdout) or a registered scheme
+pub fn is_sink(spec: &str) -> bool {
+    let registered = |scheme: &str| SINKS.lock().unwrap().iter().any(|(known, _)| known.eq_ignore_ascii_case(scheme));
+    spec == "-" || scheme(spec).is_some_and(registered)
+}
+
+/// The source `spec` names; anything that isn't a transport is a file path
+pub fn open_source(spec: &str) -> Result<Box<dyn ImageSource>> {
+    if let Some(scheme) = scheme(spec) {
+        let factory = SOURCES.lock().unwrap().iter().find(|(known, _)| known.eq_ignore_ascii_case(scheme)).map(|(_, factory)| factory.clone());
+        if let Some(factory) = factory {
+            return factory(spec);
+        }
+    }
+    Ok(match spec {
+        "-" => Box::new(ReaderSource::stdin()),
+        "clipboard:" => Box::new(ClipboardSource),
+        spec if is_url(spec) => Box::new(UrlSource(spec.to_string())),
+        path => Box::new(FileSource(path.into())),
+    })
+}
+
+/// The sink `spec` names; anything that isn't a transport is a file path
+pub fn open_sink(spec: &str) -> Result<Box<dyn ImageSink>> {
+    if let Some(scheme) = scheme(spec) {
+        let factory = SINKS.lock().unwrap().iter().find(|(known, _)| known.eq_ignore_ascii_case(scheme)).map(|(_, factory)| factory.clone());
+        if let Some(factory) = factory {
+            return factory(spec);
+        }
+    }
+    Ok(match spec {
+        "-" => Box::new(WriterSink::stdout()),
+        path => Box::new(FileSink(path.into())),
+    })
+}
+
+pub struct FileSource(pub PathBuf);
+
+impl ImageSource for FileSource {
+    fn name(&self) -> String {
+        self.0.display().to_string()
+    }
+
+    fn read(&mut self) -> Result<Vec<u8>> {
+        std::fs::read(&self.0).map_err(ImgconvError::io(format!("Failed to read {}", self.0.display())))
+    }
+}
+
+/// Writes the file atomically, so a failure leaves no partial output
+pub struct FileSink(pub PathBuf);
+
+impl ImageSink for FileSink {
+    fn name(&self) -> String {
+        self.0.display().to_string()
+    }
+
+    fn write(&mut self, data: &[u8], _format: ImageFormat) -> Result<()> {
+        write_atomic(&self.0, data, &CancellationToken::new())
+    }
+}
+
+/// An image held in memory
+pub struct MemorySource(pub Vec<u8>);
+
+impl ImageSource for MemorySource {
+    fn name(&self) -> String {
+        "memory".to_string()
+    }
+
+    fn read(&mut self) -> Result<Vec<u8>> {
+        Ok(std::mem::take(&mut self.0))
+    }
+}
+
+/// Collects the output in memory
+#[derive(Default)]
+pub struct MemorySink {
+    pub data: Vec<u8>,
+    pub format: Option<ImageFormat>,
+}
+
+impl ImageSink for MemorySink {
+    fn name(&self) -> String {
+        "memory".to_string()
+    }
+
+    fn write(&mut self, data: &[u8], format: ImageFormat) -> Result<()> {
+        self.data = data.to_vec();
+        self.format = Some(format);
+        Ok(())
+    }
+}
+
+/// Everything a reader yields, such as stdin or a socket
+pub struct ReaderSource<R> {
+    name: String,
+    reader: R,
+}
+
+impl<R: Read> ReaderSource<R> {
+    pub fn new(name: &str, reader: R) -> Self {
+        Self { name: name.to_string(), reader }
+    }
+}
+
+impl ReaderSource<std::io::Stdin> {
+    pub fn stdin() -> Self {
+        Self::new("stdin", std::io::stdin())
+    }
+}
+
+impl<R: Read> ImageSource for ReaderSource<R> {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn read(&mut self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.reader.read_to_end(&mut data).map_err(ImgconvError::io(format!("Failed to read {}", self.name)))?;
+        Ok(data)
+    }
+}
+
+/// Writes the output to a writer, such as stdout or a socket
+pub struct WriterSink<W> {
+    name: String,
+    writer: W,
+}
+
+impl<W: Write> WriterSink<W> {
+    pub fn new(name: &str, writer: W) -> Self {
+        Self { name: name.to_string(), writer }
+    }
+}
+
+impl WriterSink<std::io::Stdout> {
+    pub fn stdout() -> Self {
+        Self::new("stdout", std::io::stdout())
+    }
+}
+
+impl<W: Write> ImageSink for WriterSink<W> {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn write(&mut self, data: &[u8], _format: ImageFormat) -> Result<()> {
+        self.writer
+            .write_all(data)
+            .and_then(|_| self.writer.flush())
+            .map_err(ImgconvError::io(format!("Failed to write {}", self.name)))
+    }
+}
+
+/// An http(s) URL, downloaded with curl
+pub struct UrlSource(pub String);
+
+impl ImageSource for UrlSource {
+    fn name(&self) -> String {
+        self.0.clone()
+    }
+
+    fn read(&mut self) -> Result<Vec<u8>> {
+        let output = Command::new("curl")
+            .args(["-fsSL", "--", &self.0])
+            .stdin(Stdio::null())
+            .output()
+            .map_err(ImgconvError::io("Could not run curl (is it installed and on PATH?)".to_string()))?;
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(ImgconvError::Transport { name: self.0.clone(), source: message.into() });
+        }
+        Ok(output.stdout)
+    }
+}
+
+/// The image on the clipboard, as PNG
+pub struct ClipboardSource;
+
+impl ImageSource for ClipboardSource {
+    fn name(&self) -> String {
+        "clipboard".to_string()
+    }
+
+    fn read(&mut self) -> Result<Vec<u8>> {
+        let transport = |source: arboard::Error| ImgconvError::Transport { name: "clipboard".to_string(), source: source.into() };
+        let image = match arboard::Clipboard::new().map_err(transport)?.get_image() {
+            Ok(image) => image,
+            Err(arboard::Error::ContentNotAvailable) => return Err(ImgconvError::ClipboardEmpty),
+            Err(error) => return Err(transport(error)),
+        };
+        let rgba = crate::clipboard::rgba_from_raw(image.width, image.height, &image.bytes)
+            .map_err(|source| ImgconvError::Decode { input: "clipboard".to_string(), source: source.into() })?;
+        let mut png = std::io::Cursor::new(Vec::new());
+        rgba.write_to(&mut png, ImageFormat::Png)
+            .map_err(|source| ImgconvError::Encode { format: ImageFormat::Png, source: source.into() })?;
+        Ok(png.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Upper;
+
+    impl ImageSource for Upper {
+        fn name(&self) -> String {
+            "upper".to_string()
+        }
+
+        fn read(&mut self) -> Result<Vec<u8>> {
+            Ok(b"UPPER".to_vec())
+        }
+    }
+
+    #[test]
+    fn test_open_by_spec() {
+        assert!(is_source("-") && is_source("https://example.com/a.png") && !is_source("a.png"));
+        assert!(is_sink("-") && !is_sink("out.png") && !is_sink("test-sink://x"));
+        assert_eq!(open_source("photos/a.png").unwrap().name(), "photos/a.png");
+        assert_eq!(open_sink("-").unwrap().name(), "stdout");
+
+        register_source("test-upper", |_| Ok(Box::new(Upper)));
+        assert!(is_source("TEST-UPPER://anything"));
+        assert_eq!(open_source("test-upper://x").unwrap().read().unwrap(), b"UPPER");
+    }
+
+    #[test]
+    fn test_factory_may_use_the_registry() {
+        // A factory that delegates to another scheme must not deadlock on the registry
+        register_source("test-alias", |spec| {
+            assert!(is_source("test-upper://x"));
+            register_sink("test-alias-sink", |_| Ok(Box::new(WriterSink::stdout())));
+            open_source(&spec.replacen("test-alias", "test-upper", 1))
+        });
+        register_source("test-upper", |_| Ok(Box::new(Upper)));
+        assert_eq!(open_source("test-alias://x").unwrap().read().unwrap(), b"UPPER");
+        assert!(is_sink("test-alias-sink://y"));
+    }
+}
@@ -0,0 +1,464 @@
+// File: src\tui.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Interactive terminal UI: browse, preview, select and batch-convert images
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::RgbImage;
+use ratatui::buffer::Buffer;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Gauge, List, ListItem, ListState, Paragraph, Widget};
+use ratatui::{DefaultTerminal, Frame};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use crate::batch::collect;
+use crate::estimate::format_size;
+use crate::with_image_extension;
+
+/// Output formats offered by `f`, as passed to `--format`
+const FORMATS: [&str; 8] = ["webp", "jpeg", "png", "avif", "gif", "bmp", "tiff", "ico"];
+
+/// `--resize` values offered by `r`; empty keeps the original size
+const RESIZES: [&str; 5] = ["", "3840x2160", "1920x1080", "1280x1280", "800x800"];
+
+/// Images larger than this are listed but not previewed
+const MAX_PREVIEW_PIXELS: u64 = 64_000_000;
+
+/// Log lines kept in the progress pane
+const MAX_LOG: usize = 200;
+
+#[derive(Args, Debug)]
+pub struct TuiArgs {
+    /// Directory to browse
+    #[arg(default_value = ".", value_name = "DIR")]
+    pub dir: PathBuf,
+
+    /// Where converted files are written (default: DIR/converted)
+    #[arg(long, value_name = "DIR")]
+    pub out: Option<PathBuf>,
+
+    /// Include images in subdirectories
+    #[arg(short, long)]
+    pub recursive: bool,
+}
+
+struct Entry {
+    path: PathBuf,
+    bytes: u64,
+    dimensions: Option<(u32, u32)>,
+    selected: bool,
+    status: Status,
+}
+
+#[derive(Clone, PartialEq)]
+enum Status {
+    Idle,
+    Running,
+    Done,
+    Failed,
+}
+
+/// Messages from the conversion thread
+enum Progress {
+    Started(usize),
+    Finished(usize, std::result::Result<String, String>),
+    Done,
+}
+
+struct App {
+    root: PathBuf,
+    out: PathBuf,
+    entries: Vec<Entry>,
+    list: ListState,
+    format: usize,
+    quality: u8,
+    resize: usize,
+    preview: Option<(usize, Option<RgbImage>)>,
+    log: Vec<String>,
+    /// Files finished, total and failed in the current run
+    progress: Option<(usize, usize, usize)>,
+    worker: Option<Receiver<Progress>>,
+}
+
+pub fn run(args: &TuiArgs) -> Result<()> {
+    let entries: Vec<Entry> = collect(&args.dir, args.recursive)?
+        .into_iter()
+        .map(|path| Entry {
+            bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            dimensions: image::image_dimensions(&path).ok(),
+            path,
+            selected: false,
+            status: Status::Idle,
+        })
+        .collect();
+    if entries.is_empty() {
+        anyhow::bail!("No images found in: {}", args.dir.display());
+    }
+
+    let mut app = App {
+        root: args.dir.clone(),
+        out: args.out.clone().unwrap_or_else(|| args.dir.join("converted")),
+        entries,
+        list: ListState::default().with_selected(Some(0)),
+        format: 0,
+        quality: 90,
+        resize: 0,
+        preview: None,
+        log: Vec::new(),
+        progress: None,
+        worker: None,
+    };
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl App {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            self.load_preview();
+            terminal.draw(|frame| self.draw(frame)).context("Failed to draw the terminal UI")?;
+            self.drain_progress();
+            if !event::poll(Duration::from_millis(100))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let busy = self.worker.is_some();
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc if !busy => return Ok(()),
+                KeyCode::Up | KeyCode::Char('k') => self.list.select_previous(),
+                KeyCode::Down | KeyCode::Char('j') => self.list.select_next(),
+                KeyCode::Home => self.list.select_first(),
+                KeyCode::End => self.list.select_last(),
+                KeyCode::Char(' ') => {
+                    if let Some(entry) = self.list.selected().and_then(|i| self.entries.get_mut(i)) {
+                        entry.selected = !entry.selected;
+                    }
+                    self.list.select_next();
+                }
+                KeyCode::Char('a') => {
+                    let all = self.entries.iter().all(|e| e.selected);
+                    self.entries.iter_mut().for_each(|e| e.selected = !all);
+                }
+                KeyCode::Char('f') if !busy => self.format = (self.format + 1) % FORMATS.len(),
+                KeyCode::Char('+') | KeyCode::Char('=') if !busy => self.quality = (self.quality + 5).min(100),
+                KeyCode::Char('-') if !busy => self.quality = self.quality.saturating_sub(5).max(5),
+                KeyCode::Char('r') if !busy => self.resize = (self.resize + 1) % RESIZES.len(),
+                KeyCode::Enter if !busy => self.start(),
+                _ => {}
+            }
+            // The list wraps past its end otherwise
+            if let Some(i) = self.list.selected() {
+                self.list.select(Some(i.min(self.entries.len() - 1)));
+            }
+        }
+    }
+
+    /// Convert the selected files (or the highlighted one) in a background thread
+    fn start(&mut self) {
+        let mut jobs: Vec<usize> = (0..self.entries.len()).filter(|&i| self.entries[i].selected).collect();
+        if jobs.is_empty() {
+            jobs.extend(self.list.selected());
+        }
+        let exe = match std::env::current_exe() {
+            Ok(exe) => exe,
+            Err(e) => return self.push_log(format!("Cannot locate imgconv: {}", e)),
+        };
+        let commands: Vec<(usize, Vec<String>)> = jobs
+            .iter()
+            .map(|&i| {
+                let output = output_path(&self.out, &self.root, &self.entries[i].path, FORMATS[self.format]);
+                (i, convert_args(&self.entries[i].path, &output, FORMATS[self.format], self.quality, RESIZES[self.resize]))
+            })
+            .collect();
+        for &i in &jobs {
+            self.entries[i].status = Status::Idle;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for (index, args) in commands {
+                let _ = sender.send(Progress::Started(index));
+                let result = match Command::new(&exe).args(&args).output() {
+                    Ok(output) => {
+                        let stderr = String::from_utf8_lossy(&output.stderr);
+                        let mut lines = stderr.lines().filter(|line| !line.trim().is_empty());
+                        if output.status.success() {
+                            Ok(lines.next_back().unwrap_or_default().to_string())
+                        } else {
+                            Err(lines.find(|line| line.starts_with("Error:")).unwrap_or("failed").to_string())
+                        }
+                    }
+                    Err(e) => Err(e.to_string()),
+                };
+                let _ = sender.send(Progress::Finished(index, result));
+            }
+            let _ = sender.send(Progress::Done);
+        });
+        self.progress = Some((0, jobs.len(), 0));
+        self.worker = Some(receiver);
+        self.push_log(format!("Converting {} files to {} in {}", jobs.len(), FORMATS[self.format], self.out.display()));
+    }
+
+    fn drain_progress(&mut self) {
+        let Some(receiver) = &self.worker else {
+            return;
+        };
+        let messages: Vec<Progress> = receiver.try_iter().collect();
+        for message in messages {
+            match message {
+                Progress::Started(i) => self.entries[i].status = Status::Running,
+                Progress::Finished(i, result) => {
+                    let name = self.display_name(i);
+                    let (status, line) = match result {
+                        Ok(line) => (Status::Done, format!("{}: {}", name, line)),
+                        Err(line) => (Status::Failed, format!("{}: {}", name, line)),
+                    };
+                    if let Some((done, _, failed)) = &mut self.progress {
+                        *done += 1;
+                        *failed += usize::from(status == Status::Failed);
+                    }
+                    self.entries[i].status = status;
+                    self.push_log(line);
+                }
+                Progress::Done => {
+                    self.worker = None;
+                    let (done, _, failed) = self.progress.unwrap_or_default();
+                    self.push_log(format!("Finished: {} converted, {} failed", done - failed, failed));
+                }
+            }
+        }
+    }
+
+    fn push_log(&mut self, line: String) {
+        self.log.push(line);
+        if self.log.len() > MAX_LOG {
+            self.log.remove(0);
+        }
+    }
+
+    fn display_name(&self, index: usize) -> String {
+        let path = &self.entries[index].path;
+        path.strip_prefix(&self.root).unwrap_or(path).display().to_string()
+    }
+
+    /// Decode the highlighted image once, downscaled for the preview pane
+    fn load_preview(&mut self) {
+        let Some(index) = self.list.selected() else {
+            return;
+        };
+        if self.preview.as_ref().is_some_and(|(loaded, _)| *loaded == index) {
+            return;
+        }
+        let entry = &self.entries[index];
+        let small_enough = entry.dimensions.is_some_and(|(w, h)| w as u64 * h as u64 <= MAX_PREVIEW_PIXELS);
+        let thumbnail = small_enough
+            .then(|| image::open(&entry.path).ok())
+            .flatten()
+            .map(|img| img.thumbnail(256, 256).to_rgb8());
+        self.preview = Some((index, thumbnail));
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, options, progress, help] = Layout::vertical([
+            Constraint::Min(8),
+            Constraint::Length(3),
+            Constraint::Length(8),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list_area, side] = Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(main);
+        let [preview_area, details_area] = Layout::vertical([Constraint::Min(4), Constraint::Length(6)]).areas(side);
+
+        let items: Vec<ListItem> = (0..self.entries.len())
+            .map(|i| {
+                let entry = &self.entries[i];
+                let mark = match entry.status {
+                    Status::Running => "…",
+                    Status::Done => "✓",
+                    Status::Failed => "✗",
+                    Status::Idle if entry.selected => "x",
+                    Status::Idle => " ",
+                };
+                let dimensions = entry.dimensions.map(|(w, h)| format!("{}x{}", w, h)).unwrap_or_default();
+                let style = match entry.status {
+                    Status::Done => Style::default().fg(Color::Green),
+                    Status::Failed => Style::default().fg(Color::Red),
+                    _ if entry.selected => Style::default().fg(Color::Yellow),
+                    _ => Style::default(),
+                };
+                ListItem::new(format!("[{}] {}  {}  {}", mark, self.display_name(i), dimensions, format_size(entry.bytes)))
+                    .style(style)
+            })
+            .collect();
+        let selected = self.entries.iter().filter(|e| e.selected).count();
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(" Images ({} selected of {}) ", selected, self.entries.len())))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let preview_block = Block::bordered().title(" Preview ");
+        let inner = preview_block.inner(preview_area);
+        frame.render_widget(preview_block, preview_area);
+        match self.preview.as_ref().and_then(|(_, img)| img.as_ref()) {
+            Some(img) => frame.render_widget(HalfBlocks(img), inner),
+            None => frame.render_widget(Paragraph::new("No preview"), inner),
+        }
+
+        let details = self.list.selected().map(|i| {
+            let entry = &self.entries[i];
+            vec![
+                Line::from(format!("File:   {}", entry.path.display())),
+                Line::from(format!(
+                    "Format: {}",
+                    crate::detect_format_from_path(&entry.path).map(|f| format!("{:?}", f)).unwrap_or_default()
+                )),
+                Line::from(format!(
+                    "Size:   {}",
+                    entry.dimensions.map(|(w, h)| format!("{}x{} pixels", w, h)).unwrap_or_else(|| "unreadable".into())
+                )),
+                Line::from(format!("Bytes:  {}", format_size(entry.bytes))),
+            ]
+        });
+        frame.render_widget(
+            Paragraph::new(details.unwrap_or_default()).block(Block::bordered().title(" Details ")),
+            details_area,
+        );
+
+        let resize = if RESIZES[self.resize].is_empty() { "original" } else { RESIZES[self.resize] };
+        frame.render_widget(
+            Paragraph::new(format!(
+                "Format: {}   Quality: {}   Resize: {}   Output: {}",
+                FORMATS[self.format],
+                self.quality,
+                resize,
+                self.out.display()
+            ))
+            .block(Block::bordered().title(" Options ")),
+            options,
+        );
+
+        let progress_block = Block::bordered().title(" Progress ");
+        let inner = progress_block.inner(progress);
+        frame.render_widget(progress_block, progress);
+        let [gauge_area, log_area] = Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).areas(inner);
+        if let Some((done, total, _)) = self.progress {
+            let ratio = if total == 0 { 1.0 } else { done as f64 / total as f64 };
+            frame.render_widget(
+                Gauge::default().ratio(ratio).label(format!("{}/{}", done, total)).gauge_style(Style::default().fg(Color::Cyan)),
+                gauge_area,
+            );
+        }
+        let visible = log_area.height as usize;
+        let start = self.log.len().saturating_sub(visible);
+        frame.render_widget(Paragraph::new(self.log[start..].join("\n")), log_area);
+
+        frame.render_widget(
+            Paragraph::new("↑/↓ move  space select  a all  f format  +/- quality  r resize  enter convert  q quit")
+                .style(Style::default().add_modifier(Modifier::DIM)),
+            help,
+        );
+    }
+}
+
+/// Image drawn with `▀`: each cell shows two pixels, top as foreground and
+/// bottom as background
+struct HalfBlocks<'a>(&'a RgbImage);
+
+impl Widget for HalfBlocks<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let fitted = fit(self.0, area.width as u32, area.height as u32 * 2);
+        let (width, height) = fitted.dimensions();
+        for y in 0..height.div_ceil(2) {
+            for x in 0..width {
+                let top = fitted.get_pixel(x, y * 2).0;
+                let bottom = if y * 2 + 1 < height { fitted.get_pixel(x, y * 2 + 1).0 } else { top };
+                if let Some(cell) = buf.cell_mut((area.x + x as u16, area.y + y as u16)) {
+                    cell.set_char('▀')
+                        .set_fg(Color::Rgb(top[0], top[1], top[2]))
+                        .set_bg(Color::Rgb(bottom[0], bottom[1], bottom[2]));
+                }
+            }
+        }
+    }
+}
+
+/// Scale `img` to fit within `width` x `height`, keeping the aspect ratio
+fn fit(img: &RgbImage, width: u32, height: u32) -> RgbImage {
+    let (w, h) = img.dimensions();
+    if width == 0 || height == 0 || w == 0 || h == 0 {
+        return RgbImage::new(0, 0);
+    }
+    let scale = (width as f64 / w as f64).min(height as f64 / h as f64);
+    let (fw, fh) = (((w as f64 * scale) as u32).max(1), ((h as f64 * scale) as u32).max(1));
+    if (fw, fh) == (w, h) {
+        return img.clone();
+    }
+    image::imageops::resize(img, fw, fh, image::imageops::FilterType::Triangle)
+}
+
+/// Output for `source`: its path relative to the browsed directory, under
+/// `out` with the new extension
+fn output_path(out: &Path, root: &Path, source: &Path, format: &str) -> PathBuf {
+    let relative = source.strip_prefix(root).unwrap_or(source);
+    let ext = if format == "jpeg" { "jpg" } else { format };
+    with_image_extension(&out.join(relative), ext)
+}
+
+/// Arguments for one `imgconv` run converting `source`
+fn convert_args(source: &Path, output: &Path, format: &str, quality: u8, resize: &str) -> Vec<String> {
+    let mut args = vec![
+        source.display().to_string(),
+        output.display().to_string(),
+        "--format".into(),
+        format.into(),
+        "--quality".into(),
+        quality.to_string(),
+        "--color".into(),
+        "never".into(),
+    ];
+    if !resize.is_empty() {
+        args.extend(["--resize".into(), resize.into()]);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_path_and_arguments() {
+        let output = output_path(Path::new("shots/converted"), Path::new("shots"), Path::new("shots/trip/a.png"), "jpeg");
+        assert_eq!(output, Path::new("shots/converted/trip/a.jpg"));
+        let args = convert_args(Path::new("shots/trip/a.png"), &output, "jpeg", 80, "800x800");
+        assert_eq!(args[2..6], ["--format", "jpeg", "--quality", "80"]);
+        assert_eq!(args[8..], ["--resize", "800x800"]);
+    }
+
+    #[test]
+    fn test_half_block_preview() {
+        let img = RgbImage::from_fn(2, 2, |_, y| if y < 1 { image::Rgb([255, 0, 0]) } else { image::Rgb([0, 0, 255]) });
+        let area = Rect::new(0, 0, 2, 1);
+        let mut buf = Buffer::empty(area);
+        HalfBlocks(&img).render(area, &mut buf);
+        let cell = &buf[(0, 0)];
+        assert_eq!(cell.symbol(), "▀");
+        assert_eq!(cell.fg, Color::Rgb(255, 0, 0));
+        assert_eq!(cell.bg, Color::Rgb(0, 0, 255));
+    }
+}
@@ -0,0 +1,393 @@
+// File: src\upscale.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Enlarging by a factor (--upscale) with photo and pixel-art filters
+// License: MIT
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, Rgba, Rgba32FImage, RgbaImage};
+use std::str::FromStr;
+
+use crate::colorspace::with_color_type;
+use crate::i18n::tr;
+use crate::print_info;
+use crate::superres::{self, SrBackend};
+
+/// Largest --upscale factor, beyond which outputs get unreasonably large
+const MAX_FACTOR: f32 = 16.0;
+
+#[derive(Args, Debug)]
+pub struct UpscaleArgs {
+    /// Enlarge by a factor, e.g. 2x, 3x or 1.5x
+    #[arg(long, value_name = "FACTOR")]
+    pub upscale: Option<Factor>,
+
+    /// Filter for --upscale: lanczos3 or mitchell for photos, xbr, scale2x or nearest for pixel art
+    #[arg(long, value_enum, default_value = "lanczos3", value_name = "FILTER", requires = "upscale")]
+    pub upscale_filter: UpscaleFilter,
+
+    /// Upscale with an AI model, realesrgan[:MODEL] (runs realesrgan-ncnn-vulkan);
+    /// --upscale-filter is used when it isn't available
+    #[arg(long, value_name = "BACKEND[:MODEL]", requires = "upscale")]
+    pub sr_backend: Option<SrBackend>,
+}
+
+impl UpscaleArgs {
+    /// Whether --upscale was given
+    pub fn is_active(&self) -> bool {
+        self.upscale.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum UpscaleFilter {
+    /// Sharpest for photos, with slight ringing at hard edges
+    Lanczos3,
+    /// Mitchell-Netravali cubic: softer, without ringing
+    Mitchell,
+    /// Blocky pixels, exactly as they were
+    Nearest,
+    /// xBR pixel art scaling: diagonal edges are found from the colors
+    /// around each corner and redrawn as anti-aliased lines; any whole factor
+    Xbr,
+    /// Scale2x/Scale3x: pixel art with smoothed diagonals and no new colors
+    Scale2x,
+}
+
+/// Scale factor given as "2x", "2" or "150%"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Factor(pub f32);
+
+impl FromStr for Factor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Expected a factor such as 2x, 1.5x or 150%, got: {}", s);
+        let s = s.trim();
+        let factor = match s.strip_suffix('%') {
+            Some(percent) => percent.parse::<f32>().map_err(|_| invalid())? / 100.0,
+            None => s.trim_end_matches(['x', 'X']).parse::<f32>().map_err(|_| invalid())?,
+        };
+        if !(1.0..=MAX_FACTOR).contains(&factor) {
+            return Err(format!("Upscale factor must be between 1x and {}x, got: {}", MAX_FACTOR, s));
+        }
+        Ok(Factor(factor))
+    }
+}
+
+impl Factor {
+    /// `(width, height)` enlarged by this factor
+    pub fn apply(self, (width, height): (u32, u32)) -> (u32, u32) {
+        let scale = |side: u32| ((side as f32 * self.0).round() as u32).max(1);
+        (scale(width), scale(height))
+    }
+}
+
+/// Apply --upscale, returning the image unchanged when not requested
+pub fn apply(img: DynamicImage, args: &UpscaleArgs) -> Result<DynamicImage> {
+    let Some(factor) = args.upscale else {
+        return Ok(img);
+    };
+    let (width, height) = img.dimensions();
+    let (new_width, new_height) = factor.apply((width, height));
+    let enhanced = args.sr_backend.as_ref().and_then(|backend| {
+        superres::upscale(&img, (new_width, new_height), factor.0, backend).map(|img| (img, backend.model.clone()))
+    });
+    let (upscaled, method) = match enhanced {
+        Some(enhanced) => enhanced,
+        None => (classic(img, factor, args.upscale_filter)?, format!("{:?}", args.upscale_filter)),
+    };
+    print_info(&tr!("Upscaled {}x{} -> {}x{} ({})", width, height, upscaled.width(), upscaled.height(), method));
+    Ok(upscaled)
+}
+
+/// Enlarge with one of the built-in filters
+fn classic(img: DynamicImage, factor: Factor, filter: UpscaleFilter) -> Result<DynamicImage> {
+    let (new_width, new_height) = factor.apply(img.dimensions());
+    Ok(match filter {
+        UpscaleFilter::Lanczos3 => img.resize_exact(new_width, new_height, FilterType::Lanczos3),
+        UpscaleFilter::Nearest => img.resize_exact(new_width, new_height, FilterType::Nearest),
+        UpscaleFilter::Mitchell => {
+            with_color_type(DynamicImage::ImageRgba32F(mitchell(&img.to_rgba32f(), new_width, new_height)), img.color())
+        }
+        UpscaleFilter::Xbr => with_color_type(DynamicImage::ImageRgba8(xbr(&img.to_rgba8(), factor)?), img.color()),
+        UpscaleFilter::Scale2x => with_color_type(DynamicImage::ImageRgba8(scale_x(img.to_rgba8(), factor)?), img.color()),
+    })
+}
+
+/// Mitchell-Netravali cubic (B = C = 1/3)
+fn mitchell_weight(x: f32) -> f32 {
+    const B: f32 = 1.0 / 3.0;
+    const C: f32 = 1.0 / 3.0;
+    let x = x.abs();
+    let value = if x < 1.0 {
+        (12.0 - 9.0 * B - 6.0 * C) * x.powi(3) + (-18.0 + 12.0 * B + 6.0 * C) * x.powi(2) + (6.0 - 2.0 * B)
+    } else if x < 2.0 {
+        (-B - 6.0 * C) * x.powi(3) + (6.0 * B + 30.0 * C) * x.powi(2) + (-12.0 * B - 48.0 * C) * x + (8.0 * B + 24.0 * C)
+    } else {
+        0.0
+    };
+    value / 6.0
+}
+
+/// Enlarge with the Mitchell filter, one axis at a time
+fn mitchell(img: &Rgba32FImage, width: u32, height: u32) -> Rgba32FImage {
+    let horizontal = resample_axis(img, width, true);
+    resample_axis(&horizontal, height, false)
+}
+
+/// Resample `img` to `size` along one axis; the kernel keeps its 2-pixel
+/// reach because the image only grows
+fn resample_axis(img: &Rgba32FImage, size: u32, horizontal: bool) -> Rgba32FImage {
+    let (width, height) = img.dimensions();
+    let source = if horizontal { width } else { height };
+    let scale = source as f32 / size as f32;
+    // Source positions and weights for each output position along the axis
+    let taps: Vec<Vec<(u32, f32)>> = (0..size)
+        .map(|out| {
+            let center = (out as f32 + 0.5) * scale - 0.5;
+            let first = center.floor() as i64 - 1;
+            let taps: Vec<(u32, f32)> = (first..first + 4)
+                .map(|pos| (pos.clamp(0, source as i64 - 1) as u32, mitchell_weight(pos as f32 - center)))
+                .collect();
+            let total: f32 = taps.iter().map(|(_, weight)| weight).sum();
+            taps.into_iter().map(|(pos, weight)| (pos, weight / total)).collect()
+        })
+        .collect();
+    let (out_width, out_height) = if horizontal { (size, height) } else { (width, size) };
+    Rgba32FImage::from_fn(out_width, out_height, |x, y| {
+        let mut sum = [0f32; 4];
+        for &(pos, weight) in &taps[if horizontal { x } else { y } as usize] {
+            let pixel = if horizontal { img.get_pixel(pos, y) } else { img.get_pixel(x, pos) };
+            for (total, value) in sum.iter_mut().zip(pixel.0) {
+                *total += value * weight;
+            }
+        }
+        Rgba(sum.map(|value| value.clamp(0.0, 1.0)))
+    })
+}
+
+/// Scale2x for 2x, Scale3x for 3x, and passes of both for 4x, 6x, 8x and 9x
+fn scale_x(img: RgbaImage, factor: Factor) -> Result<RgbaImage> {
+    let whole = (factor.0.fract() == 0.0).then_some(factor.0 as u32);
+    let passes: &[u32] = match whole {
+        Some(1) => &[],
+        Some(2) => &[2],
+        Some(3) => &[3],
+        Some(4) => &[2, 2],
+        Some(6) => &[2, 3],
+        Some(8) => &[2, 2, 2],
+        Some(9) => &[3, 3],
+        _ => anyhow::bail!(tr!(
+            "--upscale-filter scale2x needs a factor of 2x, 3x, 4x, 6x, 8x or 9x, got {}x (xbr takes any whole factor)",
+            factor.0
+        )),
+    };
+    Ok(passes.iter().fold(img, |img, &pass| if pass == 2 { scale2x(&img) } else { scale3x(&img) }))
+}
+
+/// The 3x3 neighbourhood of (x, y), edges repeated, row by row
+fn neighbours(img: &RgbaImage, x: u32, y: u32) -> [Rgba<u8>; 9] {
+    let (width, height) = img.dimensions();
+    std::array::from_fn(|i| {
+        let nx = (x as i64 + i as i64 % 3 - 1).clamp(0, width as i64 - 1) as u32;
+        let ny = (y as i64 + i as i64 / 3 - 1).clamp(0, height as i64 - 1) as u32;
+        *img.get_pixel(nx, ny)
+    })
+}
+
+fn scale2x(img: &RgbaImage) -> RgbaImage {
+    let mut out = RgbaImage::new(img.width() * 2, img.height() * 2);
+    for (x, y, _) in img.enumerate_pixels() {
+        let [_, b, _, d, e, f, _, h, _] = neighbours(img, x, y);
+        let block = if b != h && d != f {
+            [if d == b { d } else { e }, if b == f { f } else { e }, if d == h { d } else { e }, if h == f { f } else { e }]
+        } else {
+            [e; 4]
+        };
+        for (i, pixel) in block.into_iter().enumerate() {
+            out.put_pixel(x * 2 + i as u32 % 2, y * 2 + i as u32 / 2, pixel);
+        }
+    }
+    out
+}
+
+fn scale3x(img: &RgbaImage) -> RgbaImage {
+    let mut out = RgbaImage::new(img.width() * 3, img.height() * 3);
+    for (x, y, _) in img.enumerate_pixels() {
+        let [a, b, c, d, e, f, g, h, i] = neighbours(img, x, y);
+        let pick = |take: bool, pixel: Rgba<u8>| if take { pixel } else { e };
+        let block = if b != h && d != f {
+            [
+                pick(d == b, d),
+                pick((d == b && e != c) || (b == f && e != a), b),
+                pick(b == f, f),
+                pick((d == b && e != g) || (d == h && e != a), d),
+                e,
+                pick((b == f && e != i) || (h == f && e != c), f),
+                pick(d == h, d),
+                pick((d == h && e != i) || (h == f && e != g), h),
+                pick(h == f, f),
+            ]
+        } else {
+            [e; 9]
+        };
+        for (n, pixel) in block.into_iter().enumerate() {
+            out.put_pixel(x * 3 + n as u32 % 3, y * 3 + n as u32 / 3, pixel);
+        }
+    }
+    out
+}
+
+/// How far apart two colors look: luma counts most, as in xBR, then the
+/// two chroma channels and alpha
+fn distance(p: Rgba<u8>, q: Rgba<u8>) -> f32 {
+    let yuv = |p: Rgba<u8>| {
+        let [r, g, b] = [0, 1, 2].map(|c| p.0[c] as f32);
+        [0.299 * r + 0.587 * g + 0.114 * b, -0.169 * r - 0.331 * g + 0.5 * b, 0.5 * r - 0.419 * g - 0.081 * b]
+    };
+    let (a, b) = (yuv(p), yuv(q));
+    48.0 * (a[0] - b[0]).abs() + 7.0 * (a[1] - b[1]).abs() + 6.0 * (a[2] - b[2]).abs() + 48.0 * (p.0[3] as f32 - q.0[3] as f32).abs()
+}
+
+/// Share of the square at (`x`, `y`) with side `side` (within a pixel, measured
+/// from one of its corners) that lies across the diagonal cutting that corner
+/// off through the middle of its two sides
+fn corner_coverage(x: f32, y: f32, side: f32) -> f32 {
+    let t = 0.5 - x - y;
+    if t <= 0.0 {
+        0.0
+    } else if t <= side {
+        t * t / (2.0 * side * side)
+    } else if t <= 2.0 * side {
+        1.0 - (2.0 * side - t).powi(2) / (2.0 * side * side)
+    } else {
+        1.0
+    }
+}
+
+/// xBR (level 1) in a single pass for any whole factor
+///
+/// For each corner of pixel E, with F and H its neighbours on either side
+/// of the corner and I the pixel diagonally past it, xBR weighs the color
+/// changes in a 5x5 window along the F-H diagonal against those along E-I.
+/// When the image is smoother along F-H, an edge runs across the corner and
+/// the corner is cut off in the color of F or H, whichever is closer to E.
+/// The cut goes through the middle of the pixel's two sides, and each output
+/// pixel is blended by the share of it the cut covers, which is the 2xBR
+/// half blend at 2x.
+fn xbr(img: &RgbaImage, factor: Factor) -> Result<RgbaImage> {
+    if factor.0.fract() != 0.0 {
+        anyhow::bail!(tr!("--upscale-filter xbr needs a whole factor such as 2x, 3x or 4x, got {}x", factor.0));
+    }
+    let n = factor.0 as u32;
+    if n == 1 {
+        return Ok(img.clone());
+    }
+    let (width, height) = img.dimensions();
+    let side = 1.0 / n as f32;
+    let mut out = RgbaImage::new(width * n, height * n);
+    for (x, y, &e) in img.enumerate_pixels() {
+        let at = |dx: i64, dy: i64| {
+            let nx = (x as i64 + dx).clamp(0, width as i64 - 1) as u32;
+            let ny = (y as i64 + dy).clamp(0, height as i64 - 1) as u32;
+            *img.get_pixel(nx, ny)
+        };
+        let mut edges = Vec::new();
+        for (right, bottom) in [(false, false), (true, false), (false, true), (true, true)] {
+            // Offsets as seen from the bottom right corner, mirrored onto this one
+            let (sx, sy) = (if right { 1 } else { -1 }, if bottom { 1 } else { -1 });
+            let p = |dx: i64, dy: i64| at(dx * sx, dy * sy);
+            let (f, h, i) = (p(1, 0), p(0, 1), p(1, 1));
+            if f == e || h == e {
+                continue;
+            }
+            let along_fh = distance(e, p(1, -1)) + distance(e, p(-1, 1)) + distance(i, p(2, 0)) + distance(i, p(0, 2))
+                + 4.0 * distance(h, f);
+            let along_ei = distance(h, p(-1, 0)) + distance(h, p(1, 2)) + distance(f, p(2, 1)) + distance(f, p(0, -1))
+                + 4.0 * distance(e, i);
+            if along_fh < along_ei {
+                let color = if distance(e, f) <= distance(e, h) { f } else { h };
+                edges.push((color, right, bottom));
+            }
+        }
+        for (i, j) in (0..n).flat_map(|j| (0..n).map(move |i| (i, j))) {
+            let mut pixel = e.0.map(|value| value as f32);
+            for (color, right, bottom) in &edges {
+                // Measured from the corner, whichever one it is
+                let sx = if *right { n - 1 - i } else { i } as f32 * side;
+                let sy = if *bottom { n - 1 - j } else { j } as f32 * side;
+                let coverage = corner_coverage(sx, sy, side);
+                for ((value, target), original) in pixel.iter_mut().zip(color.0).zip(e.0) {
+                    *value += (target as f32 - original as f32) * coverage;
+                }
+            }
+            out.put_pixel(x * n + i, y * n + j, Rgba(pixel.map(|value| value.round().clamp(0.0, 255.0) as u8)));
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factor() {
+        assert_eq!("2x".parse::<Factor>().unwrap(), Factor(2.0));
+        assert_eq!("150%".parse::<Factor>().unwrap(), Factor(1.5));
+        assert!("0.5x".parse::<Factor>().is_err());
+        assert!("big".parse::<Factor>().is_err());
+        assert_eq!(Factor(1.5).apply((10, 3)), (15, 5));
+    }
+
+    #[test]
+    fn test_scale2x_smooths_diagonals() {
+        // A diagonal line: black on the main diagonal of a white 3x3
+        let white = Rgba([255, 255, 255, 255]);
+        let black = Rgba([0, 0, 0, 255]);
+        let img = RgbaImage::from_fn(3, 3, |x, y| if x == y { black } else { white });
+        let out = scale2x(&img);
+        assert_eq!(out.dimensions(), (6, 6));
+        // The white pixel above the center gets its lower left quarter filled
+        // in, where nearest neighbour would leave a staircase
+        assert_eq!(*out.get_pixel(2, 1), black);
+        assert_eq!(*out.get_pixel(3, 1), white);
+        assert_eq!(*out.get_pixel(2, 2), black);
+        assert!(scale_x(img, Factor(2.5)).is_err());
+    }
+
+    #[test]
+    fn test_xbr_antialiases_diagonals() {
+        let white = Rgba([255, 255, 255, 255]);
+        let black = Rgba([0, 0, 0, 255]);
+        let img = RgbaImage::from_fn(3, 3, |x, y| if x == y { black } else { white });
+        // 2xBR: the white pixel above the center gets its lower left corner
+        // blended half way towards the line, the rest stays white
+        let out = xbr(&img, Factor(2.0)).unwrap();
+        assert_eq!(out.dimensions(), (6, 6));
+        assert_eq!(out.get_pixel(2, 1).0, [128, 128, 128, 255]);
+        assert_eq!(*out.get_pixel(3, 1), white);
+        assert_eq!(*out.get_pixel(2, 0), white);
+        // Factors scale2x can't do work in one pass, and the corner is filled in fully
+        let out = xbr(&img, Factor(5.0)).unwrap();
+        assert_eq!(out.dimensions(), (15, 15));
+        assert_eq!(*out.get_pixel(5, 4), black);
+        assert_eq!(*out.get_pixel(9, 0), white);
+        assert!(xbr(&img, Factor(2.5)).is_err());
+        // Straight edges have no corner to cut
+        let img = RgbaImage::from_fn(4, 4, |x, _| if x < 2 { black } else { white });
+        assert!(xbr(&img, Factor(3.0)).unwrap().pixels().all(|p| *p == black || *p == white));
+    }
+
+    #[test]
+    fn test_mitchell_keeps_flat_areas() {
+        let args = UpscaleArgs { upscale: Some(Factor(2.5)), upscale_filter: UpscaleFilter::Mitchell, sr_backend: None };
+        let img = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(4, 4, image::Rgb([90, 120, 200])));
+        let out = apply(img, &args).unwrap();
+        assert_eq!(out.dimensions(), (10, 10));
+        assert!(out.to_rgb8().pixels().all(|p| p.0 == [90, 120, 200]));
+    }
+}
@@ -0,0 +1,179 @@
+// File: src\verify.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: `imgconv verify` - full decode check with optional salvage
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::Args;
+use image::{ColorType, DynamicImage, ImageBuffer, ImageDecoder, ImageError, ImageFormat, ImageReader};
+use std::path::{Path, PathBuf};
+
+use crate::encode::save_image;
+use crate::i18n::tr;
+use crate::{detect_format_from_path, format_to_main_extension};
+use crate::{print_error, print_info, print_success, print_warning};
+
+/// Quality used when re-encoding salvaged lossy images
+const REPAIR_QUALITY: u8 = 95;
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    /// Image files to check
+    #[arg(value_name = "FILES", required = true)]
+    files: Vec<PathBuf>,
+
+    /// Re-encode whatever decoded successfully to <name>.repaired.<ext>
+    #[arg(long)]
+    repair: bool,
+}
+
+/// Outcome of fully decoding one file
+enum Verdict {
+    Ok { width: u32, height: u32, format: ImageFormat },
+    Corrupt { error: String, salvage: Option<(DynamicImage, ImageFormat)> },
+}
+
+pub fn run(args: &VerifyArgs) -> Result<()> {
+    let mut failed = 0;
+
+    for path in &args.files {
+        match check_file(path) {
+            Ok(Verdict::Ok { width, height, format }) => {
+                print_success(&tr!("{}: OK ({}x{}, {})", path.display(), width, height, format!("{:?}", format)));
+            }
+            Ok(Verdict::Corrupt { error, salvage }) => {
+                failed += 1;
+                print_error(&format!("{}: {}", path.display(), error));
+                match salvage {
+                    Some((img, format)) if args.repair => {
+                        // Decode-only formats are salvaged as PNG
+                        let format = if format.writing_enabled() { format } else { ImageFormat::Png };
+                        let repaired = repaired_path(path, format);
+                        save_image(&img, format, REPAIR_QUALITY, &repaired)?;
+                        print_success(&tr!("Salvaged image written to: {}", repaired.display()));
+                    }
+                    Some(_) => print_info(&tr!("Partially decodable, use --repair to salvage")),
+                    None if args.repair => print_warning(&tr!("Nothing salvageable, header could not be read")),
+                    None => {}
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                print_error(&format!("{}: {:#}", path.display(), e));
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!(tr!("{} of {} files failed verification", failed, args.files.len()));
+    }
+    print_success(&tr!("All {} files verified", args.files.len()));
+    Ok(())
+}
+
+/// Fully decode a freshly written output, failing if it is truncated or corrupt
+///
+/// Returns false without checking when this build cannot decode the format
+/// (e.g. AVIF, which is encode-only).
+pub fn ensure_decodes(path: &Path) -> Result<bool> {
+    let reader = ImageReader::open(path)
+        .with_context(|| format!("Failed to open file: {}", path.display()))?
+        .with_guessed_format()
+        .with_context(|| format!("Failed to detect image format from: {}", path.display()))?;
+    if let Err(ImageError::Unsupported(_)) = reader.into_decoder() {
+        return Ok(false);
+    }
+
+    match check_file(path)? {
+        Verdict::Ok { .. } => Ok(true),
+        Verdict::Corrupt { error, .. } => anyhow::bail!("Output failed verification: {}: {}", path.display(), error),
+    }
+}
+
+fn check_file(path: &Path) -> Result<Verdict> {
+    let reader = ImageReader::open(path)
+        .with_context(|| format!("Failed to open file: {}", path.display()))?
+        .with_guessed_format()
+        .with_context(|| format!("Failed to detect image format from: {}", path.display()))?;
+
+    let Some(format) = reader.format() else {
+        return Ok(Verdict::Corrupt { error: "Unrecognized image format".to_string(), salvage: None });
+    };
+
+    let decoder = match reader.into_decoder() {
+        Ok(decoder) => decoder,
+        Err(e) => return Ok(Verdict::Corrupt { error: e.to_string(), salvage: None }),
+    };
+
+    let (width, height) = decoder.dimensions();
+    let color = decoder.color_type();
+    let total_bytes = usize::try_from(decoder.total_bytes())
+        .context("Image is too large to verify on this platform")?;
+
+    // Rows decoded before a failure stay in the buffer, the rest stays zeroed
+    let mut buf = vec![0u8; total_bytes];
+    match decoder.read_image(&mut buf) {
+        Ok(()) => Ok(Verdict::Ok { width, height, format }),
+        Err(e) => Ok(Verdict::Corrupt {
+            error: e.to_string(),
+            salvage: buffer_to_image(width, height, color, buf).map(|img| (img, format)),
+        }),
+    }
+}
+
+/// Rebuild a DynamicImage from a raw decoder buffer in native endianness
+fn buffer_to_image(width: u32, height: u32, color: ColorType, buf: Vec<u8>) -> Option<DynamicImage> {
+    let u16s = |buf: &[u8]| -> Vec<u16> {
+        buf.chunks_exact(2).map(|c| u16::from_ne_bytes([c[0], c[1]])).collect()
+    };
+    let f32s = |buf: &[u8]| -> Vec<f32> {
+        buf.chunks_exact(4).map(|c| f32::from_ne_bytes([c[0], c[1], c[2], c[3]])).collect()
+    };
+
+    let img = match color {
+        ColorType::L8 => DynamicImage::ImageLuma8(ImageBuffer::from_raw(width, height, buf)?),
+        ColorType::La8 => DynamicImage::ImageLumaA8(ImageBuffer::from_raw(width, height, buf)?),
+        ColorType::Rgb8 => DynamicImage::ImageRgb8(ImageBuffer::from_raw(width, height, buf)?),
+        ColorType::Rgba8 => DynamicImage::ImageRgba8(ImageBuffer::from_raw(width, height, buf)?),
+        ColorType::L16 => DynamicImage::ImageLuma16(ImageBuffer::from_raw(width, height, u16s(&buf))?),
+        ColorType::La16 => DynamicImage::ImageLumaA16(ImageBuffer::from_raw(width, height, u16s(&buf))?),
+        ColorType::Rgb16 => DynamicImage::ImageRgb16(ImageBuffer::from_raw(width, height, u16s(&buf))?),
+        ColorType::Rgba16 => DynamicImage::ImageRgba16(ImageBuffer::from_raw(width, height, u16s(&buf))?),
+        ColorType::Rgb32F => DynamicImage::ImageRgb32F(ImageBuffer::from_raw(width, height, f32s(&buf))?),
+        ColorType::Rgba32F => DynamicImage::ImageRgba32F(ImageBuffer::from_raw(width, height, f32s(&buf))?),
+        _ => return None,
+    };
+    Some(img)
+}
+
+/// photo.jpg -> photo.repaired.jpg (keeping the original extension when it matches)
+fn repaired_path(path: &Path, format: ImageFormat) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = match path.extension() {
+        Some(ext) if detect_format_from_path(path) == Some(format) => ext.to_string_lossy().into_owned(),
+        _ => format_to_main_extension(&format).to_string(),
+    };
+    path.with_file_name(format!("{}.repaired.{}", stem, ext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repaired_path() {
+        assert_eq!(
+            repaired_path(Path::new("dir/photo.JPG"), ImageFormat::Jpeg),
+            Path::new("dir/photo.repaired.JPG")
+        );
+        assert_eq!(repaired_path(Path::new("scan.xyz"), ImageFormat::Png), Path::new("scan.repaired.png"));
+    }
+
+    #[test]
+    fn test_buffer_to_image_keeps_partial_rows() {
+        let img = buffer_to_image(2, 2, ColorType::Rgb8, vec![255; 12]).unwrap();
+        assert_eq!(img.to_rgb8().get_pixel(1, 1).0, [255, 255, 255]);
+        assert!(buffer_to_image(2, 2, ColorType::Rgb8, vec![0; 3]).is_none());
+    }
+}
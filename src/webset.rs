@@ -0,0 +1,195 @@
+// File: src\webset.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: Responsive web image sets (one file per width and format) with a <picture> snippet
+// License: MIT
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use std::path::Path;
+
+use crate::alpha::flatten_for_format;
+use crate::color::Color;
+use crate::encode::{save_image, write_bytes};
+use crate::i18n::tr;
+use crate::resize::Filter;
+use crate::{format_to_main_extension, print_info, print_success, print_warning};
+
+#[derive(Args, Debug)]
+pub struct WebSetArgs {
+    /// Write a responsive set at these widths into the output directory, plus a <picture> snippet
+    #[arg(
+        long,
+        value_name = "WIDTHS",
+        value_delimiter = ',',
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with_all = ["resize", "social", "format", "estimate", "clipboard", "in_place"]
+    )]
+    pub web_set: Option<Vec<u32>>,
+
+    /// Formats of the web set, preferred first; the last one is the <img> fallback
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "avif,webp", value_name = "FORMATS", requires = "web_set")]
+    pub web_formats: Vec<WebFormat>,
+
+    /// `sizes` attribute of the snippet (how wide the image is displayed)
+    #[arg(long, default_value = "100vw", value_name = "SIZES", requires = "web_set")]
+    pub web_sizes: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WebFormat {
+    Avif,
+    Webp,
+    Jpeg,
+    Png,
+}
+
+impl WebFormat {
+    fn to_image_format(self) -> ImageFormat {
+        match self {
+            WebFormat::Avif => ImageFormat::Avif,
+            WebFormat::Webp => ImageFormat::WebP,
+            WebFormat::Jpeg => ImageFormat::Jpeg,
+            WebFormat::Png => ImageFormat::Png,
+        }
+    }
+}
+
+/// One written variant: file name and pixel size
+#[derive(Debug, Clone, PartialEq)]
+struct Variant {
+    file: String,
+    width: u32,
+    height: u32,
+}
+
+/// Resize the processed `img` to every width in each format and write the
+/// files and `<name>.html` into `out_dir`
+///
+/// Widths above the source are skipped rather than upscaled.
+pub fn write(
+    img: &DynamicImage,
+    name: &str,
+    out_dir: &Path,
+    args: &WebSetArgs,
+    quality: u8,
+    filter: Filter,
+    background: Color,
+) -> Result<()> {
+    let Some(requested) = &args.web_set else {
+        return Ok(());
+    };
+    let (width, height) = img.dimensions();
+    let widths = widths(requested, width);
+    if widths.len() < requested.len() {
+        print_warning(&tr!("Source is only {}px wide; larger widths are not upscaled", width));
+    }
+    std::fs::create_dir_all(out_dir)
+        .with_context(|| tr!("Failed to create directory: {}", out_dir.display()))?;
+
+    let mut sets = Vec::new();
+    for format in &args.web_formats {
+        let format = format.to_image_format();
+        let mut variants = Vec::new();
+        for &target in &widths {
+            let target_height = scaled_height(width, height, target);
+            let variant = match target == width {
+                true => img.clone(),
+                false => img.resize_exact(target, target_height, filter.to_filter_type()),
+            };
+            let variant = flatten_for_format(variant, format, background);
+            let file = format!("{}-{}.{}", name, target, format_to_main_extension(&format));
+            save_image(&variant, format, quality, &out_dir.join(&file))?;
+            print_info(&tr!("Wrote {} ({}x{})", file, target, target_height));
+            variants.push(Variant { file, width: target, height: target_height });
+        }
+        sets.push((format, variants));
+    }
+
+    let html = snippet(&sets, &args.web_sizes);
+    let html_path = out_dir.join(format!("{}.html", name));
+    write_bytes(&html_path, html.as_bytes())?;
+    print!("{}", html);
+    print_success(&tr!(
+        "Wrote {} images and {} to: {}",
+        widths.len() * args.web_formats.len(),
+        html_path.file_name().unwrap_or_default().to_string_lossy(),
+        out_dir.display()
+    ));
+    Ok(())
+}
+
+/// Requested widths that fit the source, ascending; the source width alone
+/// when none do
+fn widths(requested: &[u32], source_width: u32) -> Vec<u32> {
+    let mut widths: Vec<u32> = requested.iter().copied().filter(|&w| w <= source_width).collect();
+    widths.sort_unstable();
+    widths.dedup();
+    if widths.is_empty() {
+        widths.push(source_width);
+    }
+    widths
+}
+
+fn scaled_height(width: u32, height: u32, target: u32) -> u32 {
+    ((height as u64 * target as u64 + width as u64 / 2) / width as u64).max(1) as u32
+}
+
+/// `<picture>` with a `<source>` per preferred format and the last format as
+/// the `<img>`, sized to the largest variant to avoid layout shift
+fn snippet(sets: &[(ImageFormat, Vec<Variant>)], sizes: &str) -> String {
+    let srcset = |variants: &[Variant]| {
+        variants.iter().map(|v| format!("{} {}w", v.file, v.width)).collect::<Vec<_>>().join(", ")
+    };
+    let Some(((_, fallback), sources)) = sets.split_last() else {
+        return String::new();
+    };
+    let mut html = String::from("<picture>\n");
+    for (format, variants) in sources {
+        html += &format!(
+            "  <source type=\"{}\" srcset=\"{}\" sizes=\"{}\">\n",
+            format.to_mime_type(),
+            srcset(variants),
+            sizes
+        );
+    }
+    if let Some(largest) = fallback.last() {
+        html += &format!(
+            "  <img src=\"{}\" srcset=\"{}\" sizes=\"{}\" width=\"{}\" height=\"{}\" alt=\"\" loading=\"lazy\" decoding=\"async\">\n",
+            largest.file,
+            srcset(fallback),
+            sizes,
+            largest.width,
+            largest.height
+        );
+    }
+    html + "</picture>\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_widths_skip_upscaling() {
+        assert_eq!(widths(&[1920, 320, 640, 320], 1000), vec![320, 640]);
+        assert_eq!(widths(&[1920], 800), vec![800]);
+        assert_eq!(scaled_height(1920, 1080, 320), 180);
+    }
+
+    #[test]
+    fn test_snippet_sources_and_fallback() {
+        let variants = |ext: &str| {
+            vec![
+                Variant { file: format!("hero-320.{}", ext), width: 320, height: 180 },
+                Variant { file: format!("hero-640.{}", ext), width: 640, height: 360 },
+            ]
+        };
+        let html = snippet(&[(ImageFormat::Avif, variants("avif")), (ImageFormat::WebP, variants("webp"))], "100vw");
+        assert!(html.contains("<source type=\"image/avif\" srcset=\"hero-320.avif 320w, hero-640.avif 640w\" sizes=\"100vw\">"));
+        assert!(html.contains("<img src=\"hero-640.webp\" srcset=\"hero-320.webp 320w, hero-640.webp 640w\""));
+        assert!(html.contains("width=\"640\" height=\"360\""));
+        assert!(!html.contains("image/webp"));
+    }
+}
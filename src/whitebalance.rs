@@ -0,0 +1,151 @@
+// File: src\whitebalance.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-16
+// Description: White balance correction (--white-balance), gray-world or from a light temperature
+// License: MIT
+
+use anyhow::Result;
+use clap::Args;
+use image::DynamicImage;
+use std::str::FromStr;
+
+use crate::colorspace::{from_linear, srgb_to_linear, to_linear};
+use crate::i18n::tr;
+use crate::print_info;
+
+/// The white point corrections aim for (daylight, D65)
+const NEUTRAL_KELVIN: f32 = 6500.0;
+
+/// Gains beyond this factor would mostly amplify noise in a nearly empty channel
+const MAX_GAIN: f32 = 4.0;
+
+#[derive(Args, Debug)]
+pub struct WhiteBalanceArgs {
+    /// Remove a color cast: auto (gray world) or temperature:K[:TINT] for the
+    /// light the photo was taken in, e.g. temperature:3200 for tungsten
+    #[arg(long, value_name = "MODE")]
+    pub white_balance: Option<WhiteBalance>,
+}
+
+impl WhiteBalanceArgs {
+    /// Whether --white-balance was given
+    pub fn is_active(&self) -> bool {
+        self.white_balance.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WhiteBalance {
+    /// Scale the channels so the image averages to gray
+    Auto,
+    /// Neutralize light of `kelvin`; a positive `tint` (-100 to 100) removes
+    /// a green cast, a negative one a magenta cast
+    Temperature { kelvin: f32, tint: f32 },
+}
+
+impl FromStr for WhiteBalance {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(WhiteBalance::Auto);
+        }
+        let invalid = || format!("Expected auto or temperature:K[:TINT] (e.g. temperature:3200:+10), got: {}", s);
+        let spec = s.strip_prefix("temperature:").ok_or_else(invalid)?;
+        let (kelvin, tint) = match spec.split_once(':') {
+            Some((kelvin, tint)) => (kelvin, tint.parse::<f32>().map_err(|_| invalid())?),
+            None => (spec, 0.0),
+        };
+        let kelvin = kelvin.trim_end_matches(['K', 'k']).parse::<f32>().map_err(|_| invalid())?;
+        if !(1000.0..=40000.0).contains(&kelvin) {
+            return Err(format!("Temperature must be between 1000K and 40000K, got: {}K", kelvin));
+        }
+        if !(-100.0..=100.0).contains(&tint) {
+            return Err(format!("Tint must be between -100 and 100, got: {}", tint));
+        }
+        Ok(WhiteBalance::Temperature { kelvin, tint })
+    }
+}
+
+/// Correct the color cast with per-channel gains in linear light
+pub fn apply(img: DynamicImage, args: &WhiteBalanceArgs) -> Result<DynamicImage> {
+    let Some(mode) = args.white_balance else {
+        return Ok(img);
+    };
+    let color_type = img.color();
+    if !color_type.has_color() {
+        return Ok(img);
+    }
+    let mut canvas = to_linear(&img);
+    let gains = match mode {
+        WhiteBalance::Auto => {
+            let mut sums = [0f64; 3];
+            for pixel in canvas.pixels().filter(|pixel| pixel.0[3] > 0.0) {
+                for (sum, value) in sums.iter_mut().zip(pixel.0) {
+                    *sum += value as f64;
+                }
+            }
+            if sums.iter().any(|sum| *sum <= 0.0) {
+                return Ok(img);
+            }
+            sums.map(|sum| (sums[1] / sum) as f32)
+        }
+        WhiteBalance::Temperature { kelvin, tint } => {
+            let (light, neutral) = (blackbody(kelvin), blackbody(NEUTRAL_KELVIN));
+            let gains: [f32; 3] = std::array::from_fn(|c| neutral[c] / light[c]);
+            let gains = gains.map(|gain| gain / gains[1]);
+            [gains[0], 1.0 - tint / 200.0, gains[2]]
+        }
+    };
+    let gains = gains.map(|gain| gain.clamp(1.0 / MAX_GAIN, MAX_GAIN));
+    print_info(&tr!("White balance gains: R {}, G {}, B {}", format!("{:.2}", gains[0]), format!("{:.2}", gains[1]), format!("{:.2}", gains[2])));
+    for pixel in canvas.pixels_mut() {
+        for (value, gain) in pixel.0.iter_mut().zip(gains) {
+            *value *= gain;
+        }
+    }
+    Ok(from_linear(canvas, color_type))
+}
+
+/// Linear RGB of a black body at `kelvin`, from Tanner Helland's fit of the
+/// Planckian locus in sRGB
+fn blackbody(kelvin: f32) -> [f32; 3] {
+    let t = kelvin / 100.0;
+    let red = if t <= 66.0 { 255.0 } else { 329.699 * (t - 60.0).powf(-0.133_205) };
+    let green = if t <= 66.0 { 99.470_8 * t.ln() - 161.119_57 } else { 288.122_17 * (t - 60.0).powf(-0.075_514_85) };
+    let blue = match t {
+        t if t >= 66.0 => 255.0,
+        t if t <= 19.0 => 0.0,
+        t => 138.517_73 * (t - 10.0).ln() - 305.044_8,
+    };
+    // Keep a trace of every channel so the gains stay finite
+    [red, green, blue].map(|v| srgb_to_linear((v / 255.0).clamp(0.01, 1.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_gray_world_removes_cast() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(4, 4, |x, _| Rgb([60 + x as u8 * 40, 50 + x as u8 * 30, 30 + x as u8 * 20])));
+        let out = apply(img, &WhiteBalanceArgs { white_balance: Some(WhiteBalance::Auto) }).unwrap().to_rgb32f();
+        let mean = |c: usize| out.pixels().map(|p| srgb_to_linear(p.0[c])).sum::<f32>();
+        assert!((mean(0) - mean(1)).abs() < 0.05 && (mean(2) - mean(1)).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_temperature() {
+        assert_eq!("auto".parse::<WhiteBalance>().unwrap(), WhiteBalance::Auto);
+        assert_eq!("temperature:3200:+10".parse::<WhiteBalance>().unwrap(), WhiteBalance::Temperature { kelvin: 3200.0, tint: 10.0 });
+        assert!("temperature:200".parse::<WhiteBalance>().is_err());
+        assert!("warm".parse::<WhiteBalance>().is_err());
+
+        // Tungsten light is orange, so correcting for it cools a neutral gray
+        let gray = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([128, 128, 128])));
+        let args = WhiteBalanceArgs { white_balance: Some(WhiteBalance::Temperature { kelvin: 3200.0, tint: 0.0 }) };
+        let pixel = apply(gray, &args).unwrap().to_rgb8().get_pixel(0, 0).0;
+        assert!(pixel[2] > pixel[1] && pixel[1] > pixel[0], "{:?}", pixel);
+    }
+}